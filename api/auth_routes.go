@@ -0,0 +1,89 @@
+package api
+
+import (
+	"errors"
+	"net/url"
+	"path"
+	"strings"
+
+	"github.com/xdevplatform/xurl/store"
+)
+
+// defaultAuthRoutes maps API paths, optionally for one method only, to the
+// auth types that suit them, in order of preference. In a Path, "*" stands for
+// one path segment and a trailing "/**" for everything below the prefix.
+var defaultAuthRoutes = []store.AuthRoute{
+	// Full-archive search, counts, and the filtered/sampled streams are
+	// app-only endpoints.
+	{Path: "/2/tweets/search/all", Auth: "app"},
+	{Path: "/2/tweets/counts/all", Auth: "app"},
+	{Path: "/2/tweets/search/stream", Auth: "app"},
+	{Path: "/2/tweets/search/stream/**", Auth: "app"},
+	{Path: "/2/tweets/sample/stream", Auth: "app"},
+	{Path: "/2/tweets/sample10/stream", Auth: "app"},
+	// Posts are public to read, but creating and deleting them acts as a user.
+	{Method: "GET", Path: "/2/tweets/*", Auth: "app,oauth2,oauth1"},
+	{Method: "POST", Path: "/2/tweets", Auth: "oauth2,oauth1"},
+	{Method: "DELETE", Path: "/2/tweets/*", Auth: "oauth2,oauth1"},
+	// Legacy v1.1 media upload predates OAuth2 user tokens.
+	{Path: "/1.1/media/**", Auth: "oauth1,oauth2"},
+	// Endpoints that act as or on behalf of the authenticated user.
+	{Path: "/2/users/me", Auth: "oauth2,oauth1"},
+	{Path: "/2/users/*/bookmarks", Auth: "oauth2"},
+	{Path: "/2/users/*/bookmarks/**", Auth: "oauth2"},
+	{Path: "/2/users/*/timelines/reverse_chronological", Auth: "oauth2,oauth1"},
+	// DM endpoints reject app-only auth outright; see requiresUserContext.
+	{Path: "/2/dm_*", Auth: "oauth2,oauth1"},
+	{Path: "/2/dm_*/**", Auth: "oauth2,oauth1"},
+}
+
+// errDMUserContext explains how to get a token the DM endpoints accept.
+var errDMUserContext = errors.New("DM endpoints require user-context OAuth2 with dm.* scopes; run `xurl auth oauth2 --scope-preset dm`")
+
+// errAppOnlyRefused is the error for app-only auth under --require-user-context.
+var errAppOnlyRefused = errors.New("--require-user-context refuses app-only auth; log in with `xurl auth oauth2` or pass --auth oauth2 or --auth oauth1")
+
+// requiresUserContext reports whether rawURL is a DM endpoint (/2/dm_*), which
+// the API rejects with a bare 403 when called with an app-only bearer token.
+func requiresUserContext(rawURL string) bool {
+	u, err := url.Parse(rawURL)
+	if err != nil {
+		return false
+	}
+	return routeMatches("/2/dm_*", u.Path) || routeMatches("/2/dm_*/**", u.Path)
+}
+
+// routeAuthPreference returns the auth preference list (e.g. "oauth2,oauth1")
+// for method and the path of rawURL, checking the user's routes before the
+// built-in ones. It returns "" for routes neither table knows.
+func routeAuthPreference(method, rawURL string, userRoutes []store.AuthRoute) string {
+	u, err := url.Parse(rawURL)
+	if err != nil {
+		return ""
+	}
+	for _, routes := range [][]store.AuthRoute{userRoutes, defaultAuthRoutes} {
+		for _, route := range routes {
+			if (route.Method == "" || strings.EqualFold(route.Method, method)) && routeMatches(route.Path, u.Path) {
+				return route.Auth
+			}
+		}
+	}
+	return ""
+}
+
+// routeMatches reports whether p matches pattern, where "*" matches one path
+// segment and a trailing "/**" matches one or more further segments.
+func routeMatches(pattern, p string) bool {
+	prefix, subtree := strings.CutSuffix(pattern, "/**")
+	want := strings.Split(strings.Trim(prefix, "/"), "/")
+	got := strings.Split(strings.Trim(p, "/"), "/")
+	if subtree && len(got) <= len(want) || !subtree && len(got) != len(want) {
+		return false
+	}
+	for i, segment := range want {
+		if matched, _ := path.Match(segment, got[i]); !matched {
+			return false
+		}
+	}
+	return true
+}
@@ -0,0 +1,202 @@
+package api
+
+import (
+	"os"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/xdevplatform/xurl/auth"
+	"github.com/xdevplatform/xurl/config"
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+	"github.com/xdevplatform/xurl/store"
+)
+
+func TestRouteMatches(t *testing.T) {
+	tests := []struct {
+		pattern string
+		path    string
+		want    bool
+	}{
+		{"/2/tweets/search/all", "/2/tweets/search/all", true},
+		{"/2/tweets/search/all", "/2/tweets/search/all/", true},
+		{"/2/tweets/search/all", "/2/tweets/search/recent", false},
+		{"/2/users/*/bookmarks", "/2/users/123/bookmarks", true},
+		{"/2/users/*/bookmarks", "/2/users/123/bookmarks/456", false},
+		{"/1.1/media/**", "/1.1/media/upload.json", true},
+		{"/1.1/media/**", "/1.1/media/metadata/create.json", true},
+		{"/1.1/media/**", "/1.1/media", false},
+		{"/2/dm_conversations/**", "/2/dm_events", false},
+		{"/2/dm_*", "/2/dm_conversations", true},
+		{"/2/dm_*/**", "/2/dm_conversations/with/123/dm_events", true},
+	}
+
+	for _, tt := range tests {
+		assert.Equal(t, tt.want, routeMatches(tt.pattern, tt.path), "%s vs %s", tt.pattern, tt.path)
+	}
+}
+
+func TestGetAuthHeaderUsesRouteTable(t *testing.T) {
+	cfg := &config.Config{APIBaseURL: "https://api.x.com"}
+	future := uint64(time.Now().Add(time.Hour).Unix())
+
+	// newClient stores an OAuth2 user token, OAuth1 tokens, and/or an app-only
+	// bearer, as requested.
+	newClient := func(t *testing.T, oauth2, oauth1, bearer bool, routes ...store.AuthRoute) *ApiClient {
+		tokenStore, tempDir := createTempTokenStore(t)
+		t.Cleanup(func() { os.RemoveAll(tempDir) })
+		if oauth2 {
+			require.NoError(t, tokenStore.SaveOAuth2Token("alice", "alice-access", "alice-refresh", future))
+		}
+		if oauth1 {
+			require.NoError(t, tokenStore.SaveOAuth1Tokens("at", "ts", "ck", "cs"))
+		}
+		if bearer {
+			require.NoError(t, tokenStore.SaveBearerToken("app-bearer"))
+		}
+		tokenStore.AuthRoutes = routes
+		return NewApiClient(cfg, auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore))
+	}
+
+	t.Run("full-archive search uses app-only auth", func(t *testing.T) {
+		client := newClient(t, true, true, true)
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/tweets/search/all?query=go", "", "", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer app-bearer", header)
+	})
+
+	t.Run("legacy media upload prefers OAuth1", func(t *testing.T) {
+		client := newClient(t, true, true, false)
+		header, err := client.getAuthHeader("POST", "https://upload.x.com/1.1/media/upload.json", "", "", nil)
+		require.NoError(t, err)
+		assert.True(t, strings.HasPrefix(header, "OAuth "), "got %q", header)
+	})
+
+	t.Run("user-context route skips app-only auth", func(t *testing.T) {
+		client := newClient(t, false, true, true)
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "", "", nil)
+		require.NoError(t, err)
+		assert.True(t, strings.HasPrefix(header, "OAuth "), "got %q", header)
+	})
+
+	t.Run("route without matching credentials falls back to the heuristic", func(t *testing.T) {
+		client := newClient(t, false, false, true)
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/users/123/bookmarks", "", "", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer app-bearer", header)
+	})
+
+	t.Run("DM routes refuse app-only auth", func(t *testing.T) {
+		client := newClient(t, false, false, true)
+		for _, endpoint := range []string{"/2/dm_events", "/2/dm_conversations", "/2/dm_conversations/with/123/messages"} {
+			_, err := client.getAuthHeader("POST", "https://api.x.com"+endpoint, "", "", nil)
+			require.Error(t, err, endpoint)
+			assert.True(t, xurlErrors.IsAuthError(err))
+			assert.Contains(t, err.Error(), "xurl auth oauth2 --scope-preset dm")
+		}
+
+		_, err := client.getAuthHeader("GET", "https://api.x.com/2/dm_events", "oauth2,app", "", nil)
+		assert.ErrorContains(t, err, "DM endpoints require user-context OAuth2")
+
+		client = newClient(t, true, false, true)
+		_, err = client.getAuthHeader("GET", "https://api.x.com/2/dm_events", "app", "", nil)
+		assert.ErrorContains(t, err, "DM endpoints require user-context OAuth2", "even when asked for explicitly")
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/dm_events", "", "", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer alice-access", header)
+	})
+
+	t.Run("post reads use app-only auth, writes user context", func(t *testing.T) {
+		client := newClient(t, true, true, true)
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/tweets/1", "", "", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer app-bearer", header)
+
+		for _, request := range []struct{ method, endpoint string }{{"POST", "/2/tweets"}, {"DELETE", "/2/tweets/1"}} {
+			header, err := client.getAuthHeader(request.method, "https://api.x.com"+request.endpoint, "", "", nil)
+			require.NoError(t, err)
+			assert.Equal(t, "Bearer alice-access", header, request.method)
+		}
+
+		client = newClient(t, true, false, false)
+		header, err = client.getAuthHeader("GET", "https://api.x.com/2/tweets/1", "", "", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer alice-access", header, "without a bearer token reads fall back to OAuth2")
+	})
+
+	t.Run("unknown routes keep the heuristic", func(t *testing.T) {
+		client := newClient(t, true, true, true)
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/tweets?ids=1", "", "", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer alice-access", header)
+	})
+
+	t.Run("explicit username skips the table", func(t *testing.T) {
+		client := newClient(t, true, true, true)
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/tweets/search/all", "", "alice", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer alice-access", header)
+	})
+
+	t.Run("configured routes take precedence", func(t *testing.T) {
+		client := newClient(t, true, true, true,
+			store.AuthRoute{Path: "/2/tweets/search/**", Auth: "oauth1"},
+		)
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/tweets/search/all", "", "", nil)
+		require.NoError(t, err)
+		assert.True(t, strings.HasPrefix(header, "OAuth "), "got %q", header)
+	})
+
+	t.Run("configured routes can be limited to a method", func(t *testing.T) {
+		client := newClient(t, true, true, true,
+			store.AuthRoute{Method: "post", Path: "/2/users/*/likes", Auth: "oauth1"},
+			store.AuthRoute{Path: "/2/users/*/likes", Auth: "app"},
+		)
+		header, err := client.getAuthHeader("POST", "https://api.x.com/2/users/1/likes", "", "", nil)
+		require.NoError(t, err)
+		assert.True(t, strings.HasPrefix(header, "OAuth "), "got %q", header)
+
+		header, err = client.getAuthHeader("GET", "https://api.x.com/2/users/1/likes", "", "", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer app-bearer", header)
+	})
+
+	t.Run("require user context never falls back to app-only", func(t *testing.T) {
+		client := newClient(t, true, false, true)
+		client.auth.WithRequireUserContext(true)
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/tweets/search/all", "", "", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer alice-access", header, "the route table's app-only preference is skipped")
+
+		for _, authType := range []string{"app", "oauth1,app"} {
+			_, err = client.getAuthHeader("GET", "https://api.x.com/2/users/me", authType, "", nil)
+			require.Error(t, err, authType)
+			assert.True(t, xurlErrors.IsAuthError(err))
+			assert.Contains(t, err.Error(), "--require-user-context refuses app-only auth", authType)
+		}
+
+		client = newClient(t, false, false, true)
+		client.auth.WithRequireUserContext(true)
+		_, err = client.getAuthHeader("GET", "https://api.x.com/2/users/me", "", "", nil)
+		assert.ErrorContains(t, err, "--require-user-context refuses app-only auth")
+
+		client = newClient(t, false, true, true)
+		client.auth.WithRequireUserContext(true)
+		header, err = client.getAuthHeader("GET", "https://api.x.com/2/users/me", "", "", nil)
+		require.NoError(t, err)
+		assert.True(t, strings.HasPrefix(header, "OAuth "), "OAuth1 is user context too; got %q", header)
+	})
+
+	t.Run("configured routes with an unknown type are rejected", func(t *testing.T) {
+		client := newClient(t, true, false, false,
+			store.AuthRoute{Path: "/2/tweets", Auth: "basic"},
+		)
+		_, err := client.getAuthHeader("GET", "https://api.x.com/2/tweets", "", "", nil)
+		require.Error(t, err)
+		assert.True(t, xurlErrors.IsAuthError(err))
+		assert.Contains(t, err.Error(), "invalid auth type: basic")
+	})
+}
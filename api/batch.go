@@ -0,0 +1,379 @@
+package api
+
+import (
+	"bufio"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"maps"
+	"net/http"
+	"os"
+	"slices"
+	"sort"
+	"strings"
+	"sync"
+	"time"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// BatchResult is one NDJSON line written by ExecuteBatch: the request's
+// 1-based line number in the batch file, the HTTP status, and the response
+// body. An API error's problem body is the body; a request that got no
+// response at all has status 0 and the error message instead. With an output
+// template, the body is written to File instead of being included.
+type BatchResult struct {
+	Index  int             `json:"index"`
+	Status int             `json:"status"`
+	Body   json.RawMessage `json:"body,omitempty"`
+	File   string          `json:"file,omitempty"`
+	Error  string          `json:"error,omitempty"`
+}
+
+// BatchOptions controls how ExecuteBatch runs a batch file.
+type BatchOptions struct {
+	// Strict fails the batch before any request is sent when a line is
+	// malformed, instead of skipping the line.
+	Strict bool
+	// Concurrency is the maximum number of requests in flight (default 1).
+	Concurrency int
+	// Unordered writes each result as soon as its request completes, instead
+	// of in file order.
+	Unordered bool
+	// OutputTemplate names the file each response body is written to, e.g.
+	// "out/{id}.json" (see checkOutputTemplate). Its columns are the keys of
+	// each line's "vars".
+	OutputTemplate string
+	// SummaryFile, when set, receives the BatchSummary as JSON instead of
+	// errOut.
+	SummaryFile string
+	// FailedOutput, when set, receives the lines of the failed requests as
+	// they were in the batch file, so that it can be run again. The lines
+	// of requests never sent, after a stop, follow them.
+	FailedOutput string
+	// OnFailure is what a failed request does to the rest of the batch:
+	// nothing by default, or stop sending with FailFast.
+	OnFailure FailurePolicy
+}
+
+// BatchSummary is the JSON summary of a batch run: its request stats, the
+// number of malformed lines skipped, the line numbers of the requests that
+// failed, and the number of requests not sent after the batch stopped.
+type BatchSummary struct {
+	StatsSummary
+	Malformed int   `json:"malformed"`
+	Failed    []int `json:"failed"`
+	NotSent   int   `json:"not_sent"`
+}
+
+// batchLine is one request line of a batch file.
+type batchLine struct {
+	Method  string          `json:"method"`
+	URL     string          `json:"url"`
+	Data    json.RawMessage `json:"data"`
+	Headers json.RawMessage `json:"headers"`
+	Vars    json.RawMessage `json:"vars"`
+}
+
+// batchRequest is a parsed batch line, ready to be sent.
+type batchRequest struct {
+	index   int
+	line    string
+	options RequestOptions
+	vars    map[string]string
+}
+
+// ExecuteBatch sends the requests of the JSONL file at path over client, with
+// up to batch.Concurrency in flight. Each line is {"method", "url", "data"?,
+// "headers"?, "vars"?}: data is a string sent as is or any other JSON value
+// sent as JSON, and headers is an object or a list of "Name: value" strings,
+// applied over the headers of options. The method defaults to POST when there
+// is data and GET otherwise. vars is an object of strings for
+// batch.OutputTemplate to name the line's file with.
+//
+// One BatchResult per request is written to out as NDJSON, in file order
+// unless batch.Unordered, and a summary to errOut, followed by a BatchSummary
+// as JSON unless batch.SummaryFile takes it. batch.FailedOutput collects the
+// lines of the failed requests. A malformed line is reported
+// on errOut with its line number and skipped; with batch.Strict, it fails the
+// batch before any request is sent. A 429 pauses the dispatch of every worker
+// and the request is retried, as with ExecuteCSVRequests. It returns an error
+// if any request failed, after every request has been attempted, unless
+// batch.OnFailure is FailFast or the failure is an auth error: then no more
+// requests are sent, and those in flight are waited for.
+//
+// With batch.OutputTemplate, each response body (or API error body) is written
+// to the file it names, creating directories as needed, rather than included
+// in the result. A line whose vars lack a template variable is malformed; a
+// response field that is missing, or two requests naming the same file, fail
+// the request without writing.
+func ExecuteBatch(options RequestOptions, path string, batch BatchOptions, client Client, out, errOut io.Writer) error {
+	requests, malformed, err := readBatchFile(path, options, batch.OutputTemplate)
+	if err != nil {
+		return err
+	}
+	for _, problem := range malformed {
+		fmt.Fprintln(errOut, problem)
+	}
+	if batch.Strict && len(malformed) > 0 {
+		return xurlErrors.NewIOError(fmt.Errorf("%d malformed line(s) in %s; nothing was sent", len(malformed), path))
+	}
+
+	concurrency := max(batch.Concurrency, 1)
+	jobs := make(chan int)
+	var wg sync.WaitGroup
+	var outMu sync.Mutex
+	var failed []int
+	failures := &failureTracker{policy: batch.OnFailure.or(ContinueOnError)}
+	stats := NewRequestStats()
+	limiter := &rateLimitPause{stats: stats}
+	// Results that finished ahead of an earlier request wait here, by their
+	// position in requests, until it is their turn to be written.
+	pending := map[int]BatchResult{}
+	next := 0
+	files := map[string]int{}
+
+	for w := 0; w < concurrency; w++ {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			for i := range jobs {
+				start := time.Now()
+				result, err := sendBatchRequest(requests[i], client, limiter)
+				stats.Record(result.Status, time.Since(start))
+
+				outMu.Lock()
+				if batch.OutputTemplate != "" && result.Body != nil {
+					file, writeErr := writeResultFile(batch.OutputTemplate, templateResult{
+						Index: result.Index, Status: result.Status, Row: requests[i].vars, Endpoint: requests[i].options.Endpoint, Response: result.Body,
+					}, files)
+					if writeErr != nil {
+						err = writeErr
+						result.Error = writeErr.Error()
+					}
+					result.File = file
+					result.Body = nil
+				}
+				if err != nil {
+					failed = append(failed, i)
+				}
+				failures.record(fmt.Sprintf("line %d", requests[i].index), err)
+				if batch.Unordered {
+					writeBatchResult(out, result)
+				} else {
+					pending[i] = result
+					for ; ; next++ {
+						ready, ok := pending[next]
+						if !ok {
+							break
+						}
+						writeBatchResult(out, ready)
+						delete(pending, next)
+					}
+				}
+				outMu.Unlock()
+			}
+		}()
+	}
+
+	sent := 0
+	for ; sent < len(requests) && !failures.stopped(); sent++ {
+		jobs <- sent
+	}
+	close(jobs)
+	wg.Wait()
+
+	fmt.Fprintf(errOut, "%d requests: %d succeeded, %d failed, %d malformed lines skipped\n",
+		sent, sent-len(failed), len(failed), len(malformed))
+	if sent < len(requests) {
+		fmt.Fprintf(errOut, "%d requests not sent\n", len(requests)-sent)
+	}
+
+	slices.Sort(failed)
+	summary := BatchSummary{StatsSummary: stats.Summary(), Malformed: len(malformed), Failed: []int{}, NotSent: len(requests) - sent}
+	var failedLines strings.Builder
+	for _, i := range failed {
+		summary.Failed = append(summary.Failed, requests[i].index)
+		failedLines.WriteString(requests[i].line + "\n")
+	}
+	for _, request := range requests[sent:] {
+		failedLines.WriteString(request.line + "\n")
+	}
+	if err := writeBatchSummary(summary, batch.SummaryFile, errOut); err != nil {
+		return err
+	}
+	if batch.FailedOutput != "" {
+		if err := os.WriteFile(batch.FailedOutput, []byte(failedLines.String()), 0644); err != nil {
+			return xurlErrors.NewIOError(fmt.Errorf("error writing failed requests: %v", err))
+		}
+	}
+
+	return failures.err(sent)
+}
+
+// writeBatchSummary writes summary as JSON to the file at path, or to errOut
+// when path is empty.
+func writeBatchSummary(summary BatchSummary, path string, errOut io.Writer) error {
+	line, err := json.Marshal(summary)
+	if err != nil {
+		return xurlErrors.NewJSONError(err)
+	}
+	if path == "" {
+		fmt.Fprintln(errOut, string(line))
+		return nil
+	}
+	if err := os.WriteFile(path, append(line, '\n'), 0644); err != nil {
+		return xurlErrors.NewIOError(fmt.Errorf("error writing batch summary: %v", err))
+	}
+	return nil
+}
+
+// sendBatchRequest sends one batch request and returns its result, and the
+// error when it failed.
+func sendBatchRequest(request batchRequest, client Client, limiter *rateLimitPause) (BatchResult, error) {
+	response, status, err := sendWithRateLimitRetry(request.options, client, limiter)
+	result := BatchResult{Index: request.index, Status: status, Body: batchBody(response)}
+	if err != nil {
+		if xurlErrors.IsAPIError(err) {
+			result.Body = batchBody([]byte(err.Error()))
+		} else {
+			result.Error = err.Error()
+		}
+	}
+	return result, err
+}
+
+// writeBatchResult writes result to out as one NDJSON line.
+func writeBatchResult(out io.Writer, result BatchResult) {
+	line, _ := json.Marshal(result)
+	fmt.Fprintln(out, string(line))
+}
+
+// readBatchFile parses the batch file at path into requests based on options.
+// Blank lines are ignored; each malformed line, including one whose vars
+// cannot fill outputTemplate, is returned as a message naming its line number.
+func readBatchFile(path string, options RequestOptions, outputTemplate string) ([]batchRequest, []string, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		return nil, nil, xurlErrors.NewIOError(fmt.Errorf("error opening batch file: %v", err))
+	}
+	defer file.Close()
+
+	var requests []batchRequest
+	var malformed []string
+	scanner := bufio.NewScanner(file)
+	scanner.Buffer(make([]byte, 64*1024), 16*1024*1024)
+	for lineNumber := 1; scanner.Scan(); lineNumber++ {
+		text := strings.TrimSpace(scanner.Text())
+		if text == "" {
+			continue
+		}
+		request, err := parseBatchLine(text, options)
+		if err == nil {
+			if err = checkOutputTemplate(outputTemplate, slices.Collect(maps.Keys(request.vars))); err != nil {
+				err = errors.Unwrap(err)
+			}
+		}
+		if err != nil {
+			malformed = append(malformed, fmt.Sprintf("line %d: %v", lineNumber, err))
+			continue
+		}
+		request.index = lineNumber
+		request.line = text
+		requests = append(requests, request)
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, nil, xurlErrors.NewIOError(fmt.Errorf("error reading batch file: %v", err))
+	}
+	return requests, malformed, nil
+}
+
+// parseBatchLine returns the request described by one batch line, with
+// options as its base. Its index is left for the caller to set.
+func parseBatchLine(text string, options RequestOptions) (batchRequest, error) {
+	var line batchLine
+	decoder := json.NewDecoder(strings.NewReader(text))
+	decoder.DisallowUnknownFields()
+	if err := decoder.Decode(&line); err != nil {
+		return batchRequest{}, fmt.Errorf("invalid JSON: %v", err)
+	}
+	if line.URL == "" {
+		return batchRequest{}, fmt.Errorf("missing \"url\"")
+	}
+	var vars map[string]string
+	if len(line.Vars) > 0 && json.Unmarshal(line.Vars, &vars) != nil {
+		return batchRequest{}, fmt.Errorf("\"vars\" must be an object of strings")
+	}
+
+	request := options
+	request.Endpoint = line.URL
+	request.Data = ""
+	if len(line.Data) > 0 && string(line.Data) != "null" {
+		var data string
+		if json.Unmarshal(line.Data, &data) != nil {
+			data = string(line.Data)
+		}
+		request.Data = data
+	}
+
+	request.Method = strings.ToUpper(line.Method)
+	if request.Method == "" {
+		request.Method = http.MethodGet
+		if request.Data != "" {
+			request.Method = http.MethodPost
+		}
+	}
+
+	headers, err := batchHeaders(line.Headers)
+	if err != nil {
+		return batchRequest{}, err
+	}
+	request.Headers = append([]string(nil), options.Headers...)
+	for _, header := range headers {
+		if _, ok := parseHeaderArg(header); !ok {
+			return batchRequest{}, fmt.Errorf("invalid header %q (use \"Name: value\")", header)
+		}
+		request.Headers = setHeaderArg(request.Headers, header)
+	}
+	return batchRequest{options: request, vars: vars}, nil
+}
+
+// batchHeaders returns a batch line's headers as "Name: value" strings. They
+// may be given as an object or as a list of such strings.
+func batchHeaders(raw json.RawMessage) ([]string, error) {
+	if len(raw) == 0 || string(raw) == "null" {
+		return nil, nil
+	}
+	var list []string
+	if json.Unmarshal(raw, &list) == nil {
+		return list, nil
+	}
+	var object map[string]string
+	if err := json.Unmarshal(raw, &object); err != nil {
+		return nil, fmt.Errorf("\"headers\" must be an object or a list of \"Name: value\" strings")
+	}
+	names := make([]string, 0, len(object))
+	for name := range object {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	headers := make([]string, 0, len(names))
+	for _, name := range names {
+		headers = append(headers, name+": "+object[name])
+	}
+	return headers, nil
+}
+
+// batchBody returns response as JSON for a BatchResult: a JSON body as is and
+// anything else as a JSON string.
+func batchBody(response []byte) json.RawMessage {
+	if len(response) == 0 {
+		return nil
+	}
+	if json.Valid(response) {
+		return json.RawMessage(response)
+	}
+	quoted, _ := json.Marshal(string(response))
+	return quoted
+}
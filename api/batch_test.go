@@ -0,0 +1,384 @@
+package api
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"sync"
+	"sync/atomic"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func writeTempBatch(t *testing.T, content string) string {
+	t.Helper()
+	path := filepath.Join(t.TempDir(), "batch.jsonl")
+	require.NoError(t, os.WriteFile(path, []byte(content), 0600))
+	return path
+}
+
+func batchResults(t *testing.T, out string) []BatchResult {
+	t.Helper()
+	var results []BatchResult
+	for _, line := range strings.Split(strings.TrimSpace(out), "\n") {
+		var result BatchResult
+		require.NoError(t, json.Unmarshal([]byte(line), &result))
+		results = append(results, result)
+	}
+	return results
+}
+
+func TestExecuteBatch(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "application/json")
+		if r.URL.Path == "/2/tweets/404" {
+			w.WriteHeader(http.StatusNotFound)
+			w.Write([]byte(`{"title":"Not Found Error","status":404}`))
+			return
+		}
+		body, _ := io.ReadAll(r.Body)
+		json.NewEncoder(w).Encode(map[string]string{
+			"method": r.Method,
+			"path":   r.URL.Path,
+			"body":   string(body),
+			"trace":  r.Header.Get("X-Trace"),
+		})
+	}))
+	defer server.Close()
+	client := shortcutClient(t, server)
+
+	batchPath := writeTempBatch(t, `{"url":"/2/tweets/1"}
+
+{"method":"post","url":"/2/tweets","data":{"text":"hello"},"headers":{"X-Trace":"line-3"}}
+{"url":"/2/tweets/2",
+{"method":"DELETE","url":"/2/tweets/404"}
+{"method":"GET"}
+{"url":"/2/tweets/3","headers":["X-Trace: line-7"]}
+`)
+	options := RequestOptions{Headers: []string{"X-Trace: base"}}
+
+	t.Run("malformed lines are skipped", func(t *testing.T) {
+		var out, errOut bytes.Buffer
+		err := ExecuteBatch(options, batchPath, BatchOptions{}, client, &out, &errOut)
+		require.Error(t, err)
+		assert.Equal(t, "1 of 4 requests failed", err.Error())
+
+		results := batchResults(t, out.String())
+		require.Len(t, results, 4)
+
+		assert.Equal(t, 1, results[0].Index)
+		assert.Equal(t, http.StatusOK, results[0].Status)
+		assert.JSONEq(t, `{"method":"GET","path":"/2/tweets/1","body":"","trace":"base"}`, string(results[0].Body))
+
+		assert.Equal(t, 3, results[1].Index)
+		assert.JSONEq(t, `{"method":"POST","path":"/2/tweets","body":"{\"text\":\"hello\"}","trace":"line-3"}`, string(results[1].Body))
+
+		assert.Equal(t, 5, results[2].Index)
+		assert.Equal(t, http.StatusNotFound, results[2].Status)
+		assert.JSONEq(t, `{"title":"Not Found Error","status":404}`, string(results[2].Body))
+
+		assert.Equal(t, 7, results[3].Index)
+		assert.JSONEq(t, `{"method":"GET","path":"/2/tweets/3","body":"","trace":"line-7"}`, string(results[3].Body))
+
+		assert.Contains(t, errOut.String(), "line 4: invalid JSON")
+		assert.Contains(t, errOut.String(), `line 6: missing "url"`)
+		assert.Contains(t, errOut.String(), "4 requests: 3 succeeded, 1 failed, 2 malformed lines skipped")
+	})
+
+	t.Run("strict refuses a malformed file before sending", func(t *testing.T) {
+		var out, errOut bytes.Buffer
+		err := ExecuteBatch(options, batchPath, BatchOptions{Strict: true}, client, &out, &errOut)
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), "2 malformed line(s)")
+		assert.Empty(t, out.String())
+		assert.Contains(t, errOut.String(), "line 4:")
+	})
+}
+
+func TestExecuteBatchSummary(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "application/json")
+		switch r.URL.Path {
+		case "/2/tweets/404":
+			w.WriteHeader(http.StatusNotFound)
+			w.Write([]byte(`{"title":"Not Found Error","status":404}`))
+		case "/2/tweets/500":
+			w.WriteHeader(http.StatusInternalServerError)
+			w.Write([]byte(`{"title":"Internal Error","status":500}`))
+		default:
+			w.Write([]byte(`{"data":{}}`))
+		}
+	}))
+	defer server.Close()
+	client := shortcutClient(t, server)
+
+	batchPath := writeTempBatch(t, `{"url":"/2/tweets/1"}
+{"url":"/2/tweets/404"}
+not json
+{"url":"/2/tweets/2"}
+{"url":"/2/tweets/500", "headers": {"X-Trace": "5"}}
+`)
+	dir := t.TempDir()
+
+	t.Run("summary on stderr", func(t *testing.T) {
+		var out, errOut bytes.Buffer
+		err := ExecuteBatch(RequestOptions{}, batchPath, BatchOptions{Concurrency: 2, Unordered: true}, client, &out, &errOut)
+		require.Error(t, err)
+
+		lines := strings.Split(strings.TrimSpace(errOut.String()), "\n")
+		var summary BatchSummary
+		require.NoError(t, json.Unmarshal([]byte(lines[len(lines)-1]), &summary))
+		assert.Equal(t, 4, summary.Requests)
+		assert.Equal(t, map[string]int{"2xx": 2, "4xx": 1, "5xx": 1}, summary.StatusClasses)
+		assert.Equal(t, 1, summary.Malformed)
+		assert.Equal(t, []int{2, 5}, summary.Failed)
+		assert.Zero(t, summary.RateLimitPauses)
+	})
+
+	t.Run("summary and failed lines to files", func(t *testing.T) {
+		summaryPath := filepath.Join(dir, "summary.json")
+		failedPath := filepath.Join(dir, "failed.jsonl")
+		var out, errOut bytes.Buffer
+		err := ExecuteBatch(RequestOptions{}, batchPath, BatchOptions{SummaryFile: summaryPath, FailedOutput: failedPath}, client, &out, &errOut)
+		require.Error(t, err)
+		assert.NotContains(t, errOut.String(), `"status_classes"`)
+
+		data, err := os.ReadFile(summaryPath)
+		require.NoError(t, err)
+		var summary BatchSummary
+		require.NoError(t, json.Unmarshal(data, &summary))
+		assert.Equal(t, []int{2, 5}, summary.Failed)
+
+		failed, err := os.ReadFile(failedPath)
+		require.NoError(t, err)
+		assert.Equal(t, "{\"url\":\"/2/tweets/404\"}\n{\"url\":\"/2/tweets/500\", \"headers\": {\"X-Trace\": \"5\"}}\n", string(failed))
+	})
+}
+
+// batchOfTweets writes a batch file requesting /2/tweets/1 to /2/tweets/n.
+func batchOfTweets(t *testing.T, n int) string {
+	t.Helper()
+	var lines strings.Builder
+	for i := 1; i <= n; i++ {
+		fmt.Fprintf(&lines, "{\"url\":\"/2/tweets/%d\"}\n", i)
+	}
+	return writeTempBatch(t, lines.String())
+}
+
+// fixedRateLimitBackoff makes the wait after a 429 without rate-limit headers
+// exactly d, instead of a random time of up to d.
+func fixedRateLimitBackoff(t *testing.T, d time.Duration) {
+	previousBackoff, previousJitter := rateLimitBackoff, jitter
+	rateLimitBackoff = d
+	jitter = func(n time.Duration) time.Duration { return n }
+	t.Cleanup(func() { rateLimitBackoff, jitter = previousBackoff, previousJitter })
+}
+
+func batchIndexes(t *testing.T, out string) []int {
+	t.Helper()
+	var indexes []int
+	for _, result := range batchResults(t, out) {
+		indexes = append(indexes, result.Index)
+	}
+	return indexes
+}
+
+func TestExecuteBatchConcurrency(t *testing.T) {
+	t.Run("results keep file order unless unordered", func(t *testing.T) {
+		// Earlier requests take longer, so they complete in reverse order.
+		server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			id, _ := strconv.Atoi(strings.TrimPrefix(r.URL.Path, "/2/tweets/"))
+			time.Sleep(time.Duration(4-id) * 40 * time.Millisecond)
+			w.Header().Set("Content-Type", "application/json")
+			w.Write([]byte(`{"data":{}}`))
+		}))
+		defer server.Close()
+		client := shortcutClient(t, server)
+		batchPath := batchOfTweets(t, 3)
+
+		var out bytes.Buffer
+		require.NoError(t, ExecuteBatch(RequestOptions{}, batchPath, BatchOptions{Concurrency: 3}, client, &out, io.Discard))
+		assert.Equal(t, []int{1, 2, 3}, batchIndexes(t, out.String()))
+
+		out.Reset()
+		require.NoError(t, ExecuteBatch(RequestOptions{}, batchPath, BatchOptions{Concurrency: 3, Unordered: true}, client, &out, io.Discard))
+		assert.Equal(t, []int{3, 2, 1}, batchIndexes(t, out.String()))
+	})
+
+	t.Run("no more than concurrency requests are in flight", func(t *testing.T) {
+		var inFlight, maxInFlight int32
+		server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			n := atomic.AddInt32(&inFlight, 1)
+			defer atomic.AddInt32(&inFlight, -1)
+			for {
+				seen := atomic.LoadInt32(&maxInFlight)
+				if n <= seen || atomic.CompareAndSwapInt32(&maxInFlight, seen, n) {
+					break
+				}
+			}
+			time.Sleep(20 * time.Millisecond)
+			w.Header().Set("Content-Type", "application/json")
+			w.Write([]byte(`{"data":{}}`))
+		}))
+		defer server.Close()
+		client := shortcutClient(t, server)
+
+		var out bytes.Buffer
+		require.NoError(t, ExecuteBatch(RequestOptions{}, batchOfTweets(t, 9), BatchOptions{Concurrency: 3}, client, &out, io.Discard))
+		assert.Len(t, batchResults(t, out.String()), 9)
+		assert.Equal(t, int32(3), atomic.LoadInt32(&maxInFlight))
+	})
+
+	t.Run("a 429 pauses dispatch for every worker", func(t *testing.T) {
+		fixedRateLimitBackoff(t, 150*time.Millisecond)
+
+		var mu sync.Mutex
+		var limitedAt time.Time
+		var arrivals []time.Time
+		server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			mu.Lock()
+			now := time.Now()
+			arrivals = append(arrivals, now)
+			first429 := r.URL.Path == "/2/tweets/1" && limitedAt.IsZero()
+			if first429 {
+				limitedAt = now
+			}
+			mu.Unlock()
+			w.Header().Set("Content-Type", "application/json")
+			if first429 {
+				w.WriteHeader(http.StatusTooManyRequests)
+				w.Write([]byte(`{"title":"Too Many Requests","status":429}`))
+				return
+			}
+			time.Sleep(5 * time.Millisecond)
+			w.Write([]byte(`{"data":{}}`))
+		}))
+		defer server.Close()
+		client := shortcutClient(t, server)
+
+		var out, errOut bytes.Buffer
+		require.NoError(t, ExecuteBatch(RequestOptions{Retry: RetryPolicy{MaxRetries: DefaultBatchRetries}}, batchOfTweets(t, 8), BatchOptions{Concurrency: 2}, client, &out, &errOut))
+		assert.Equal(t, []int{1, 2, 3, 4, 5, 6, 7, 8}, batchIndexes(t, out.String()))
+		assert.Contains(t, errOut.String(), `"rate_limit_pauses":1`)
+
+		// Apart from a request already on its way when the 429 came back, no
+		// worker sends anything until the pause is over.
+		mu.Lock()
+		defer mu.Unlock()
+		for _, arrival := range arrivals {
+			since := arrival.Sub(limitedAt)
+			if since > 30*time.Millisecond {
+				assert.GreaterOrEqual(t, since, rateLimitBackoff-10*time.Millisecond)
+			}
+		}
+	})
+
+	t.Run("a rate-limited POST is not resent without an idempotency key", func(t *testing.T) {
+		fixedRateLimitBackoff(t, time.Millisecond)
+
+		var posts, keyed int32
+		server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			w.Header().Set("Content-Type", "application/json")
+			if r.Header.Get(IdempotencyKeyHeader) != "" {
+				if atomic.AddInt32(&keyed, 1) > 1 {
+					w.Write([]byte(`{"data":{}}`))
+					return
+				}
+			} else {
+				atomic.AddInt32(&posts, 1)
+			}
+			w.WriteHeader(http.StatusTooManyRequests)
+			w.Write([]byte(`{"title":"Too Many Requests","status":429}`))
+		}))
+		defer server.Close()
+		client := shortcutClient(t, server)
+
+		batchPath := writeTempBatch(t, `{"method":"POST","url":"/2/tweets","data":{"text":"hi"}}
+{"method":"POST","url":"/2/tweets","data":{"text":"hi"},"headers":{"Idempotency-Key":"k1"}}
+`)
+		var out bytes.Buffer
+		err := ExecuteBatch(RequestOptions{Retry: RetryPolicy{MaxRetries: DefaultBatchRetries}}, batchPath, BatchOptions{}, client, &out, io.Discard)
+		require.Error(t, err)
+		assert.Equal(t, int32(1), atomic.LoadInt32(&posts), "sent once, never retried")
+		assert.Equal(t, int32(2), atomic.LoadInt32(&keyed), "retried with its idempotency key")
+	})
+}
+
+func TestExecuteBatchOutputTemplate(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "application/json")
+		if r.URL.Path == "/2/tweets/404" {
+			w.WriteHeader(http.StatusNotFound)
+			w.Write([]byte(`{"title":"Not Found Error","status":404}`))
+			return
+		}
+		fmt.Fprintf(w, `{"data":{"id":%q}}`, strings.TrimPrefix(r.URL.Path, "/2/tweets/"))
+	}))
+	defer server.Close()
+	client := shortcutClient(t, server)
+	dir := t.TempDir()
+
+	batchPath := writeTempBatch(t, `{"url":"/2/tweets/1","vars":{"id":"one"}}
+{"url":"/2/tweets/404","vars":{"id":"missing"}}
+{"url":"/2/tweets/2","vars":{"id":"one"}}
+{"url":"/2/tweets/3"}
+`)
+	var out, errOut bytes.Buffer
+	err := ExecuteBatch(RequestOptions{}, batchPath, BatchOptions{OutputTemplate: filepath.Join(dir, "out", "{id}-{status}.json")}, client, &out, &errOut)
+	require.Error(t, err)
+	assert.Equal(t, "2 of 3 requests failed", err.Error())
+	assert.Contains(t, errOut.String(), "line 4: unknown output template variable {id}")
+
+	results := batchResults(t, out.String())
+	require.Len(t, results, 3)
+	assert.Equal(t, filepath.Join(dir, "out", "one-200.json"), results[0].File)
+	assert.Nil(t, results[0].Body)
+	assert.Equal(t, filepath.Join(dir, "out", "missing-404.json"), results[1].File)
+	assert.Empty(t, results[2].File)
+	assert.Contains(t, results[2].Error, "for both request 1 and request 3")
+
+	written, err := os.ReadFile(results[0].File)
+	require.NoError(t, err)
+	assert.JSONEq(t, `{"data":{"id":"1"}}`, string(written))
+	written, err = os.ReadFile(results[1].File)
+	require.NoError(t, err)
+	assert.JSONEq(t, `{"title":"Not Found Error","status":404}`, string(written))
+}
+
+func TestParseBatchLine(t *testing.T) {
+	t.Run("a string body is sent as is", func(t *testing.T) {
+		got, err := parseBatchLine(`{"url":"/2/x","data":"a=1&b=2"}`, RequestOptions{})
+		require.NoError(t, err)
+		assert.Equal(t, "POST", got.options.Method)
+		assert.Equal(t, "a=1&b=2", got.options.Data)
+	})
+
+	t.Run("unknown fields and bad headers are malformed", func(t *testing.T) {
+		_, err := parseBatchLine(`{"url":"/2/x","body":"{}"}`, RequestOptions{})
+		assert.Error(t, err)
+		_, err = parseBatchLine(`{"url":"/2/x","headers":["no colon"]}`, RequestOptions{})
+		assert.Error(t, err)
+		_, err = parseBatchLine(`{"url":"/2/x","headers":"X: y"}`, RequestOptions{})
+		assert.Error(t, err)
+		_, err = parseBatchLine(`{"url":"/2/x","vars":{"id":1}}`, RequestOptions{})
+		assert.Error(t, err)
+	})
+
+	t.Run("line headers replace base headers of the same name", func(t *testing.T) {
+		base := RequestOptions{Headers: []string{"X-A: 1", "X-B: 2"}}
+		got, err := parseBatchLine(`{"url":"/2/x","headers":{"x-a":"3"}}`, base)
+		require.NoError(t, err)
+		assert.Equal(t, []string{"X-B: 2", "x-a: 3"}, got.options.Headers)
+		assert.Equal(t, []string{"X-A: 1", "X-B: 2"}, base.Headers)
+	})
+}
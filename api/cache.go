@@ -0,0 +1,158 @@
+package api
+
+import (
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"os"
+	"path/filepath"
+	"strings"
+	"time"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// CacheStatusHeader marks a response served from the ResponseCache, as
+// "hit". It is added to the response xurl reports and never sent by the API.
+const CacheStatusHeader = "X-Xurl-Cache"
+
+// DefaultCacheMaxAge is how long a cached response may be revalidated and
+// served again, unless --cache-max-age says otherwise.
+const DefaultCacheMaxAge = 24 * time.Hour
+
+// ResponseCache keeps the responses of GET requests in Dir (--cache), one
+// file per request, with their ETag and Last-Modified. A later identical
+// request is sent with If-None-Match or If-Modified-Since, and a 304 reply is
+// served as the cached response, so that unchanged data costs no body.
+//
+// Requests are identical when they have the same URL and are made with the
+// same --auth and --username, so that one account's response is never served
+// to another. Entries older than MaxAge are not used, and a successful
+// request with another method drops the entry of its URL, since it may have
+// changed the resource.
+type ResponseCache struct {
+	Dir    string
+	MaxAge time.Duration
+}
+
+// NewResponseCache returns a cache in dir whose entries are used for maxAge.
+func NewResponseCache(dir string, maxAge time.Duration) *ResponseCache {
+	return &ResponseCache{Dir: dir, MaxAge: maxAge}
+}
+
+// WithResponseCache makes the client revalidate and serve GET responses
+// from cache; nil disables it.
+func (c *ApiClient) WithResponseCache(cache *ResponseCache) *ApiClient {
+	c.cache = cache
+	return c
+}
+
+// cacheEntry is the file of one cached response.
+type cacheEntry struct {
+	ETag         string          `json:"etag,omitempty"`
+	LastModified string          `json:"last_modified,omitempty"`
+	StoredAt     time.Time       `json:"stored_at"`
+	Response     json.RawMessage `json:"response"`
+}
+
+// path returns the file of the cached GET response for req made with
+// options.
+func (rc *ResponseCache) path(req *http.Request, options RequestOptions) string {
+	sum := sha256.Sum256([]byte(strings.Join([]string{req.URL.String(), strings.ToLower(options.AuthType), options.Username}, "\n")))
+	return filepath.Join(rc.Dir, hex.EncodeToString(sum[:])+".json")
+}
+
+// revalidate returns the cached response for req, when there is a fresh one,
+// and makes req a conditional request for it. A request that is not a GET,
+// or that already is conditional (e.g. --time-cond), is left alone. A nil
+// *ResponseCache has no entries.
+func (rc *ResponseCache) revalidate(req *http.Request, options RequestOptions) *cacheEntry {
+	if rc == nil || req.Method != http.MethodGet || req.Header.Get("If-None-Match") != "" || req.Header.Get("If-Modified-Since") != "" {
+		return nil
+	}
+	data, err := os.ReadFile(rc.path(req, options))
+	if err != nil {
+		return nil
+	}
+	var entry cacheEntry
+	if json.Unmarshal(data, &entry) != nil || time.Since(entry.StoredAt) > rc.MaxAge {
+		return nil
+	}
+	if entry.ETag != "" {
+		req.Header.Set("If-None-Match", entry.ETag)
+	}
+	if entry.LastModified != "" {
+		req.Header.Set("If-Modified-Since", entry.LastModified)
+	}
+	return &entry
+}
+
+// serve turns resp, a 304 reply to a request revalidate made conditional,
+// into the cached 200 response marked with CacheStatusHeader, and returns
+// its body.
+func (entry *cacheEntry) serve(resp *http.Response) json.RawMessage {
+	resp.StatusCode = http.StatusOK
+	resp.Status = "200 OK"
+	resp.Header.Set(CacheStatusHeader, "hit")
+	return entry.Response
+}
+
+// update records the outcome of a request sent without error: a 200 reply to
+// a GET with an ETag or Last-Modified is stored, unless Cache-Control forbids
+// it, and a request with another method drops the GET entry of its URL.
+// Failing to write the cache does not fail the request.
+func (rc *ResponseCache) update(req *http.Request, options RequestOptions, resp *http.Response, response json.RawMessage) {
+	if rc == nil {
+		return
+	}
+	path := rc.path(req, options)
+	if req.Method != http.MethodGet {
+		_ = os.Remove(path)
+		return
+	}
+	entry := cacheEntry{
+		ETag:         resp.Header.Get("ETag"),
+		LastModified: resp.Header.Get("Last-Modified"),
+		StoredAt:     time.Now(),
+		Response:     response,
+	}
+	if resp.StatusCode != http.StatusOK || (entry.ETag == "" && entry.LastModified == "") || strings.Contains(strings.ToLower(resp.Header.Get("Cache-Control")), "no-store") {
+		return
+	}
+	data, err := json.Marshal(entry)
+	if err != nil {
+		return
+	}
+	if err := os.MkdirAll(rc.Dir, 0700); err != nil {
+		return
+	}
+	// Write-then-rename, so that a concurrent request never reads half an
+	// entry. Responses may hold private data: owner only.
+	tmp, err := os.CreateTemp(rc.Dir, ".entry-*.tmp")
+	if err != nil {
+		return
+	}
+	defer os.Remove(tmp.Name())
+	_, err = tmp.Write(data)
+	if closeErr := tmp.Close(); err != nil || closeErr != nil {
+		return
+	}
+	_ = os.Rename(tmp.Name(), path)
+}
+
+// Clear removes every cached response and returns how many there were. A
+// missing cache directory is empty.
+func (rc *ResponseCache) Clear() (int, error) {
+	paths, err := filepath.Glob(filepath.Join(rc.Dir, "*.json"))
+	if err != nil {
+		return 0, xurlErrors.NewIOError(err)
+	}
+	for _, path := range paths {
+		if err := os.Remove(path); err != nil {
+			return 0, xurlErrors.NewIOError(fmt.Errorf("error clearing the response cache: %v", err))
+		}
+	}
+	return len(paths), nil
+}
@@ -0,0 +1,108 @@
+package api
+
+import (
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/xdevplatform/xurl/config"
+)
+
+func TestResponseCache(t *testing.T) {
+	// The server serves the current version of /2/users/me with its ETag,
+	// answers 304 to a request for the version it has, and counts the bodies
+	// it sends.
+	version := "1"
+	var bodies int
+	var conditional string
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		conditional = r.Header.Get("If-None-Match")
+		if r.Method != http.MethodGet {
+			w.WriteHeader(http.StatusNoContent)
+			return
+		}
+		w.Header().Set("ETag", `"v`+version+`"`)
+		if conditional == `"v`+version+`"` {
+			w.WriteHeader(http.StatusNotModified)
+			return
+		}
+		bodies++
+		w.Header().Set("Content-Type", "application/json")
+		w.Write([]byte(`{"data":{"version":"` + version + `"}}`))
+	}))
+	defer server.Close()
+
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	cache := NewResponseCache(filepath.Join(t.TempDir(), "cache"), time.Hour)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock).WithResponseCache(cache)
+	get := RequestOptions{Method: "GET", Endpoint: "/2/users/me", Headers: []string{"Authorization: Bearer test-token"}}
+
+	// A 200 is stored; the same request then revalidates and is served from
+	// the cache on a 304.
+	response, resp, err := client.SendRequestWithResponse(get)
+	require.NoError(t, err)
+	assert.JSONEq(t, `{"data":{"version":"1"}}`, string(response))
+	assert.Empty(t, resp.Header.Get(CacheStatusHeader))
+
+	response, resp, err = client.SendRequestWithResponse(get)
+	require.NoError(t, err)
+	assert.Equal(t, `"v1"`, conditional)
+	assert.JSONEq(t, `{"data":{"version":"1"}}`, string(response))
+	assert.Equal(t, http.StatusOK, resp.StatusCode)
+	assert.Equal(t, "hit", resp.Header.Get(CacheStatusHeader))
+	assert.Equal(t, 1, bodies)
+
+	// A changed resource is fetched and stored again.
+	version = "2"
+	response, err = client.SendRequest(get)
+	require.NoError(t, err)
+	assert.JSONEq(t, `{"data":{"version":"2"}}`, string(response))
+	assert.Equal(t, 2, bodies)
+
+	t.Run("another account has its own entry", func(t *testing.T) {
+		_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", Headers: get.Headers, Username: "bob"})
+		require.NoError(t, err)
+		assert.Empty(t, conditional)
+	})
+
+	t.Run("an explicit condition bypasses the cache", func(t *testing.T) {
+		_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", Headers: get.Headers, IfModifiedSince: time.Now()})
+		require.NoError(t, err)
+		assert.Empty(t, conditional)
+	})
+
+	t.Run("another method invalidates the entry", func(t *testing.T) {
+		_, err := client.SendRequest(RequestOptions{Method: "DELETE", Endpoint: "/2/users/me", Headers: get.Headers})
+		require.NoError(t, err)
+		_, err = client.SendRequest(get)
+		require.NoError(t, err)
+		assert.Empty(t, conditional)
+	})
+
+	t.Run("an entry past its max age is not used", func(t *testing.T) {
+		stale := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock).WithResponseCache(NewResponseCache(cache.Dir, -time.Second))
+		_, err := stale.SendRequest(get)
+		require.NoError(t, err)
+		assert.Empty(t, conditional)
+	})
+
+	t.Run("clear", func(t *testing.T) {
+		cleared, err := cache.Clear()
+		require.NoError(t, err)
+		assert.Equal(t, 2, cleared, "one entry per account")
+		_, err = client.SendRequest(get)
+		require.NoError(t, err)
+		assert.Empty(t, conditional)
+
+		cleared, err = NewResponseCache(filepath.Join(t.TempDir(), "missing"), time.Hour).Clear()
+		require.NoError(t, err)
+		assert.Zero(t, cleared)
+	})
+}
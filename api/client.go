@@ -3,21 +3,28 @@ package api
 import (
 	"bufio"
 	"bytes"
+	"cmp"
+	"compress/gzip"
+	"crypto/rand"
 	"encoding/json"
 	"errors"
 	"fmt"
 	"io"
+	"mime"
 	"mime/multipart"
 	"net/http"
+	"net/url"
 	"os"
 	"path/filepath"
+	"slices"
 	"strings"
 	"time"
+	"unicode/utf8"
 
 	"github.com/xdevplatform/xurl/auth"
 	"github.com/xdevplatform/xurl/config"
 	xurlErrors "github.com/xdevplatform/xurl/errors"
-	"github.com/xdevplatform/xurl/version"
+	"github.com/xdevplatform/xurl/store"
 )
 
 // RequestOptions contains common options for API requests
@@ -26,20 +33,88 @@ type RequestOptions struct {
 	Endpoint string
 	Headers  []string
 	Data     string
+	// Body is a raw request body (e.g. from -d @file or -d @-). When non-nil it
+	// is sent byte-for-byte instead of Data, so binary content survives intact.
+	Body     []byte
 	AuthType string
 	Username string
 	Verbose  bool
 	Trace    bool
+	// VerboseBody (-vv) also logs the request and response bodies to stderr,
+	// with secret fields redacted.
+	VerboseBody bool
+	// Chunked sends the body with chunked transfer encoding instead of a
+	// Content-Length header.
+	Chunked bool
+	// BinaryBody sends Body as application/octet-stream (unless -H gives a
+	// Content-Type) instead of guessing its type from its content.
+	BinaryBody bool
+	// AcceptStatus lists HTTP error statuses (e.g. 404, 409) to return as a
+	// successful response instead of an API error.
+	AcceptStatus []int
+	// Retry controls how a rate-limited (HTTP 429) request is retried. POST
+	// and PATCH requests are only retried with an idempotency key or
+	// ForceRetry.
+	Retry RetryPolicy
+	// ForceRetry retries POST and PATCH requests without an idempotency key,
+	// at the risk of performing them twice.
+	ForceRetry bool
+	// RespectRateLimit waits before sending when the rate-limit state stored by
+	// an earlier request shows the endpoint's bucket is empty until its reset.
+	RespectRateLimit bool
+	// NoAutoRefresh disables refreshing the OAuth2 user token and replaying
+	// the request once when it is rejected with HTTP 401.
+	NoAutoRefresh bool
+	// Decompress inflates a gzip-compressed body (e.g. a .gz export) when it
+	// is streamed to a file.
+	Decompress bool
+	// CompressRequest gzips a request body of at least minCompressedBodySize
+	// bytes and sends it with Content-Encoding: gzip, unless -H gives a
+	// Content-Encoding. Multipart bodies are sent as they are.
+	CompressRequest bool
+	// AppendOutput appends a body streamed to a file to what the file already
+	// holds instead of replacing it.
+	AppendOutput bool
+	// ForceAuth sends credentials with an absolute URL even when its host is
+	// neither the API host nor one of trustedAuthHosts.
+	ForceAuth bool
+	// QueryParams are "key=value" pairs percent-encoded and added to the
+	// endpoint's query string, whatever the method.
+	QueryParams []string
+	// Fields maps fields and expansions query parameters ("tweet.fields",
+	// "expansions", ...; see FieldParams) to the names to request. They are
+	// validated and added to the query unless it already has that parameter.
+	Fields map[string][]string
+	// MaxResponseSize aborts reading a response body larger than this many
+	// bytes with an error. Zero means no limit.
+	MaxResponseSize int64
+	// IdempotencyKey is sent as the Idempotency-Key header of every attempt of
+	// the request; IdempotencyKeyAuto generates one per request.
+	IdempotencyKey string
+	// IfModifiedSince, when set, is sent as the If-Modified-Since header
+	// (unless Headers has one); a 304 reply then yields ErrNotModified.
+	IfModifiedSince time.Time
+	// Accept is the Accept header to send when Headers has none;
+	// DefaultAccept when empty.
+	Accept string
+	// Output controls how a successful response is printed.
+	Output OutputOptions
 }
 
+// DefaultAccept is the Accept header of a request that sets none of its own.
+const DefaultAccept = "application/json"
+
 // MultipartOptions contains options specific to multipart requests
 type MultipartOptions struct {
 	RequestOptions
-	FormFields map[string]string
-	FileField  string
-	FilePath   string
-	FileName   string
-	FileData   []byte
+	FormFields      map[string]string
+	FileField       string
+	FilePath        string
+	FileName        string
+	FileData        []byte
+	// FileContentType declares the file part's type; empty sends
+	// application/octet-stream.
+	FileContentType string
 }
 
 // Client is an interface for API clients
@@ -62,26 +137,87 @@ type ApiClient struct {
 	// clients leave it false so a missing credential surfaces as a clear auth
 	// error instead of a confusing server-side 401.
 	allowUnauthenticated bool
+	// rateLimits, when set, records the rate-limit headers of every response
+	// (see WithRateLimitStore).
+	rateLimits *store.RateLimitStore
+	// throttle, when set, caps the rate of requests (see WithThrottle).
+	throttle *Throttle
+	// cache, when set, revalidates and serves GET responses (see
+	// WithResponseCache).
+	cache *ResponseCache
+	// fixtures, when set, record or play back requests (see WithFixtures).
+	fixtures *Fixtures
 }
 
 // NewApiClient creates a new ApiClient
 func NewApiClient(config *config.Config, auth *auth.Auth) *ApiClient {
+	transport := auth.HTTPTransport()
+	if len(config.URLRewrites) > 0 {
+		transport = &urlRewriter{rewrites: config.URLRewrites, next: transport}
+	}
 	return &ApiClient{
 		url:    config.APIBaseURL,
-		client: &http.Client{Timeout: 30 * time.Second},
+		client: &http.Client{Timeout: 30 * time.Second, Transport: transport},
 		auth:   auth,
 	}
 }
 
+// urlRewriter sends each request to the host of the first matching
+// --url-rewrite rule. Credentials and OAuth1 signatures are those of the URL
+// as built, so a gateway can pass the request on to the API, and the
+// response's Request keeps that URL, so that the links of a page resolve
+// against the API host rather than the gateway.
+type urlRewriter struct {
+	rewrites []config.URLRewrite
+	// next sends the rewritten request; nil means http.DefaultTransport.
+	next http.RoundTripper
+}
+
+// RoundTrip implements http.RoundTripper.
+func (t *urlRewriter) RoundTrip(req *http.Request) (*http.Response, error) {
+	next := t.next
+	if next == nil {
+		next = http.DefaultTransport
+	}
+	target := *req.URL
+	for _, rewrite := range t.rewrites {
+		if !rewrite.Apply(&target) {
+			continue
+		}
+		sent := req.Clone(req.Context())
+		sent.URL, sent.Host = &target, target.Host
+		resp, err := next.RoundTrip(sent)
+		if resp != nil {
+			resp.Request = req
+		}
+		return resp, err
+	}
+	return next.RoundTrip(req)
+}
+
 // BuildRequest builds an HTTP request
 func (c *ApiClient) BuildRequest(requestOptions RequestOptions) (*http.Request, error) {
 	httpMethod := strings.ToUpper(requestOptions.Method)
 
-	var body io.Reader
+	var body []byte
 	contentType := ""
 
-	if requestOptions.Data != "" && (httpMethod == "POST" || httpMethod == "PUT" || httpMethod == "PATCH") {
-		body = bytes.NewBufferString(requestOptions.Data)
+	hasBody := httpMethod == "POST" || httpMethod == "PUT" || httpMethod == "PATCH"
+	if hasBody && requestOptions.Body != nil {
+		body = requestOptions.Body
+
+		switch {
+		case requestOptions.BinaryBody:
+			contentType = "application/octet-stream"
+		case json.Valid(requestOptions.Body):
+			contentType = "application/json"
+		case utf8.Valid(requestOptions.Body):
+			contentType = "application/x-www-form-urlencoded"
+		default:
+			contentType = "application/octet-stream"
+		}
+	} else if hasBody && requestOptions.Data != "" {
+		body = []byte(requestOptions.Data)
 
 		var js json.RawMessage
 		if json.Unmarshal([]byte(requestOptions.Data), &js) == nil {
@@ -91,16 +227,120 @@ func (c *ApiClient) BuildRequest(requestOptions RequestOptions) (*http.Request,
 		}
 	}
 
-	return c.buildBaseRequest(
-		requestOptions.Method,
-		requestOptions.Endpoint,
-		body,
-		contentType,
-		requestOptions.Headers,
-		requestOptions.AuthType,
-		requestOptions.Username,
-		requestOptions.Trace,
-	)
+	// Compress before signing, so that an OAuth1 body hash covers the bytes
+	// actually sent. The Content-Type stays that of the original body.
+	compressed := requestOptions.CompressRequest && len(body) >= minCompressedBodySize && !HasHeaderArg(requestOptions.Headers, "Content-Encoding")
+	if compressed {
+		var err error
+		if body, err = gzipBody(body); err != nil {
+			return nil, err
+		}
+	}
+
+	endpoint, err := requestEndpoint(requestOptions)
+	if err != nil {
+		return nil, err
+	}
+
+	req, err := c.buildBaseRequest(requestOptions, endpoint, body, contentType)
+	if err != nil {
+		return nil, err
+	}
+
+	if compressed {
+		req.Header.Set("Content-Encoding", "gzip")
+	}
+	setIfModifiedSince(req, requestOptions.IfModifiedSince)
+	applyTransferEncoding(req, requestOptions.Chunked)
+	return req, nil
+}
+
+// minCompressedBodySize is the smallest body --compress-request gzips; below
+// it the gzip header and the server's extra work outweigh the bytes saved.
+const minCompressedBodySize = 1024
+
+// gzipBody returns body gzip-compressed.
+func gzipBody(body []byte) ([]byte, error) {
+	var buf bytes.Buffer
+	gz := gzip.NewWriter(&buf)
+	if _, err := gz.Write(body); err != nil {
+		return nil, xurlErrors.NewIOError(fmt.Errorf("error compressing request body: %v", err))
+	}
+	if err := gz.Close(); err != nil {
+		return nil, xurlErrors.NewIOError(fmt.Errorf("error compressing request body: %v", err))
+	}
+	return buf.Bytes(), nil
+}
+
+// requestEndpoint returns options.Endpoint with the query parameters of
+// options.Fields and options.QueryParams added.
+func requestEndpoint(options RequestOptions) (string, error) {
+	fieldParams, err := fieldQueryParams(options.Endpoint, options.QueryParams, options.Fields)
+	if err != nil {
+		return "", err
+	}
+	return withQueryParams(options.Endpoint, append(fieldParams, options.QueryParams...))
+}
+
+// withQueryParams appends params ("key=value" pairs) to endpoint's query
+// string, percent-encoding each key and value. The endpoint may already have a
+// query of its own.
+func withQueryParams(endpoint string, params []string) (string, error) {
+	if len(params) == 0 {
+		return endpoint, nil
+	}
+
+	encoded := make([]string, 0, len(params))
+	for _, param := range params {
+		key, value, ok := strings.Cut(param, "=")
+		if !ok || key == "" {
+			return "", xurlErrors.NewHTTPError(fmt.Errorf("invalid query parameter %q: expected key=value", param))
+		}
+		encoded = append(encoded, url.QueryEscape(key)+"="+url.QueryEscape(value))
+	}
+
+	separator := "?"
+	if strings.HasSuffix(endpoint, "?") || strings.HasSuffix(endpoint, "&") {
+		separator = ""
+	} else if strings.Contains(endpoint, "?") {
+		separator = "&"
+	}
+	return endpoint + separator + strings.Join(encoded, "&"), nil
+}
+
+// applyTransferEncoding decides how the body length is sent. In-memory bodies
+// get a definite Content-Length by default (http.NewRequest sets it for byte
+// buffers and readers); chunked forces Transfer-Encoding: chunked instead, for
+// proxies or endpoints that expect a streamed upload.
+func applyTransferEncoding(req *http.Request, chunked bool) {
+	if !chunked || req.Body == nil || req.Body == http.NoBody {
+		return
+	}
+	req.ContentLength = -1
+	req.TransferEncoding = []string{"chunked"}
+}
+
+// dataStdin is where ReadDataArg reads "@-" from; tests replace it.
+var dataStdin io.Reader = os.Stdin
+
+// ReadDataArg reads the body named by a curl-style "@" data argument: "@-"
+// reads stdin and "@path" reads a file. The bytes are returned unmodified (no
+// UTF-8 conversion or newline stripping), so binary uploads survive intact.
+func ReadDataArg(data string) ([]byte, error) {
+	source := strings.TrimPrefix(data, "@")
+	if source == "-" {
+		body, err := io.ReadAll(dataStdin)
+		if err != nil {
+			return nil, xurlErrors.NewIOError(fmt.Errorf("error reading request body from stdin: %v", err))
+		}
+		return body, nil
+	}
+
+	body, err := os.ReadFile(source)
+	if err != nil {
+		return nil, xurlErrors.NewIOError(fmt.Errorf("error reading request body file: %v", err))
+	}
+	return body, nil
 }
 
 // BuildMultipartRequest builds an HTTP request with multipart form data
@@ -116,7 +356,7 @@ func (c *ApiClient) BuildMultipartRequest(options MultipartOptions) (*http.Reque
 		}
 		defer file.Close()
 
-		part, err := writer.CreateFormFile(options.FileField, filepath.Base(options.FilePath))
+		part, err := createFormFile(writer, options.FileField, filepath.Base(options.FilePath), options.FileContentType)
 		if err != nil {
 			return nil, xurlErrors.NewIOError(fmt.Errorf("error creating form file: %v", err))
 		}
@@ -125,7 +365,7 @@ func (c *ApiClient) BuildMultipartRequest(options MultipartOptions) (*http.Reque
 			return nil, xurlErrors.NewIOError(fmt.Errorf("error copying file content: %v", err))
 		}
 	} else if options.FileField != "" && len(options.FileData) > 0 { // Handle file from buffer
-		part, err := writer.CreateFormFile(options.FileField, options.FileName)
+		part, err := createFormFile(writer, options.FileField, options.FileName, options.FileContentType)
 		if err != nil {
 			return nil, xurlErrors.NewIOError(fmt.Errorf("error creating form file: %v", err))
 		}
@@ -146,92 +386,362 @@ func (c *ApiClient) BuildMultipartRequest(options MultipartOptions) (*http.Reque
 	}
 
 	// Use the common base request builder with the multipart content type
-	return c.buildBaseRequest(
-		options.Method,
-		options.Endpoint,
-		body,
-		writer.FormDataContentType(),
-		options.Headers,
-		options.AuthType,
-		options.Username,
-		options.Trace,
-	)
+	endpoint, err := requestEndpoint(options.RequestOptions)
+	if err != nil {
+		return nil, err
+	}
+
+	req, err := c.buildBaseRequest(options.RequestOptions, endpoint, body.Bytes(), writer.FormDataContentType())
+	if err != nil {
+		return nil, err
+	}
+
+	applyTransferEncoding(req, options.Chunked)
+	return req, nil
 }
 
 // SendRequest sends an HTTP request
 func (c *ApiClient) SendRequest(options RequestOptions) (json.RawMessage, error) {
-	req, err := c.BuildRequest(options)
+	response, _, err := c.SendRequestWithResponse(options)
+	return response, err
+}
+
+// SendRequestWithResponse is SendRequest that also returns the final HTTP
+// response, with its body already consumed, so that callers can inspect its
+// status and headers. The response is nil when none was received.
+func (c *ApiClient) SendRequestWithResponse(options RequestOptions) (json.RawMessage, *http.Response, error) {
+	options, err := withIdempotencyKey(options)
+	if err != nil {
+		return nil, nil, err
+	}
+	response, resp, err := c.sendWithRetries(func() (*http.Request, error) {
+		return c.BuildRequest(options)
+	}, options)
+	if err == nil && resp != nil && resp.StatusCode == http.StatusNotModified {
+		return nil, resp, ErrNotModified
+	}
+	return response, resp, err
+}
+
+// SendMultipartRequest sends an HTTP request with multipart form data
+func (c *ApiClient) SendMultipartRequest(options MultipartOptions) (json.RawMessage, error) {
+	requestOptions, err := withIdempotencyKey(options.RequestOptions)
 	if err != nil {
 		return nil, err
 	}
+	options.RequestOptions = requestOptions
+	response, _, err := c.sendWithRetries(func() (*http.Request, error) {
+		return c.BuildMultipartRequest(options)
+	}, options.RequestOptions)
+	return response, err
+}
+
+// IdempotencyKeyHeader carries RequestOptions.IdempotencyKey.
+const IdempotencyKeyHeader = "Idempotency-Key"
+
+// IdempotencyKeyAuto as RequestOptions.IdempotencyKey generates a random key.
+const IdempotencyKeyAuto = "auto"
+
+// withIdempotencyKey adds options.IdempotencyKey to options.Headers, generating
+// it first for IdempotencyKeyAuto. It is resolved once per logical request, so
+// every attempt (rate-limit retries, replays after a token refresh or clock
+// fix) sends the same key. A key already given with -H is left alone.
+func withIdempotencyKey(options RequestOptions) (RequestOptions, error) {
+	if options.IdempotencyKey == "" {
+		return options, nil
+	}
+	for _, header := range options.Headers {
+		name, _, _ := strings.Cut(header, ":")
+		if strings.EqualFold(strings.TrimSpace(name), IdempotencyKeyHeader) {
+			return options, nil
+		}
+	}
+
+	key := options.IdempotencyKey
+	if key == IdempotencyKeyAuto {
+		var err error
+		if key, err = newIdempotencyKey(); err != nil {
+			return options, err
+		}
+	}
+	options.IdempotencyKey = key
+	options.Headers = append(slices.Clip(options.Headers), IdempotencyKeyHeader+": "+key)
+	return options, nil
+}
+
+// newIdempotencyKey returns a random (version 4) UUID.
+func newIdempotencyKey() (string, error) {
+	b := make([]byte, 16)
+	if _, err := rand.Read(b); err != nil {
+		return "", xurlErrors.NewIOError(fmt.Errorf("error generating idempotency key: %v", err))
+	}
+	b[6] = b[6]&0x0f | 0x40
+	b[8] = b[8]&0x3f | 0x80
+	return fmt.Sprintf("%x-%x-%x-%x-%x", b[0:4], b[4:6], b[6:8], b[8:10], b[10:16]), nil
+}
 
+// sendWithRetries sends the request made by build, retrying a rate-limited
+// (HTTP 429) response as options.Retry allows. Each retry waits as long as
+// rateLimitWait says, and the request is rebuilt so it is freshly signed. See
+// retryAllowed for the requests that are never retried.
+func (c *ApiClient) sendWithRetries(build func() (*http.Request, error), options RequestOptions) (json.RawMessage, *http.Response, error) {
+	if options.RespectRateLimit {
+		c.waitForRateLimit(options)
+	}
+	policy := options.Retry
+	var waited time.Duration
+	for attempt := 0; ; attempt++ {
+		response, resp, err := c.sendWithAuthRefresh(build, options)
+		if err == nil || resp == nil || resp.StatusCode != http.StatusTooManyRequests || attempt >= policy.MaxRetries {
+			return response, resp, err
+		}
+		if !retryAllowed(options) {
+			fmt.Fprintf(os.Stderr, "Rate limited (HTTP 429); not retrying a %s without an idempotency key, as it might be performed twice. Pass --idempotency-key, or --force to retry anyway.\n", strings.ToUpper(options.Method))
+			return response, resp, err
+		}
+		wait := rateLimitWait(resp.Header, time.Now(), attempt)
+		if policy.MaxTime > 0 && waited+wait > policy.MaxTime {
+			fmt.Fprintf(os.Stderr, "Rate limited (HTTP 429); not retrying, as waiting %s would exceed --retry-max-time of %s.\n", wait, policy.MaxTime)
+			return response, resp, err
+		}
+		if !policy.Budget.take() {
+			fmt.Fprintln(os.Stderr, "Rate limited (HTTP 429); not retrying, as the retry budget is used up.")
+			return response, resp, err
+		}
+		waited += wait
+		var key string
+		if resp.Request != nil && resp.Request.Header.Get(IdempotencyKeyHeader) != "" {
+			key = ", " + IdempotencyKeyHeader + " " + resp.Request.Header.Get(IdempotencyKeyHeader)
+		}
+		fmt.Fprintf(os.Stderr, "Rate limited (HTTP 429); retrying in %s (retry %d of %d%s).\n", wait, attempt+1, policy.MaxRetries, key)
+		time.Sleep(wait)
+	}
+}
+
+// retryAllowed reports whether the request of options may be sent again. POST
+// and PATCH are not idempotent, so a retry could create a second post or DM
+// should the first attempt have gone through after all; they are retried only
+// with an Idempotency-Key header for the server to deduplicate on, or when
+// forced.
+func retryAllowed(options RequestOptions) bool {
+	switch strings.ToUpper(options.Method) {
+	case "POST", "PATCH":
+		return options.ForceRetry || HasHeaderArg(options.Headers, IdempotencyKeyHeader)
+	}
+	return true
+}
+
+// sendWithAuthRefresh sends the request made by build. If it was made with an
+// OAuth2 user token and is rejected with HTTP 401 (e.g. the token was revoked
+// server-side before its recorded expiry), the token is refreshed and the
+// request replayed exactly once. Bearer (app-only) and OAuth1 requests are
+// never retried.
+func (c *ApiClient) sendWithAuthRefresh(build func() (*http.Request, error), options RequestOptions) (json.RawMessage, *http.Response, error) {
+	response, resp, err := c.sendWithClockSkewRetry(build, options)
+	if err == nil || resp == nil || resp.StatusCode != http.StatusUnauthorized || options.NoAutoRefresh || !c.isOAuth2UserRequest(resp.Request, options) {
+		return response, resp, err
+	}
+
+	if _, refreshErr := c.auth.ForceRefreshOAuth2Token(options.Username); refreshErr != nil {
+		fmt.Fprintf(os.Stderr, "OAuth2 token rejected (HTTP 401) and could not be refreshed: %v\n", refreshErr)
+		return response, resp, err
+	}
+	fmt.Fprintln(os.Stderr, "OAuth2 token rejected (HTTP 401); refreshed it and retrying once.")
+	return c.sendWithClockSkewRetry(build, options)
+}
+
+// isOAuth2UserRequest reports whether req was authorized with the stored
+// OAuth2 user token for options.Username, as opposed to an app-only bearer,
+// OAuth1, or a caller-supplied Authorization header.
+func (c *ApiClient) isOAuth2UserRequest(req *http.Request, options RequestOptions) bool {
+	if c.auth == nil || req == nil || HasHeaderArg(options.Headers, "Authorization") {
+		return false
+	}
+	switch strings.ToLower(options.AuthType) {
+	case "oauth1", "app":
+		return false
+	}
+	accessToken, ok := strings.CutPrefix(req.Header.Get("Authorization"), "Bearer ")
+	return ok && c.auth.IsOAuth2AccessToken(options.Username, accessToken)
+}
+
+// sendWithClockSkewRetry sends the request made by build. If it was signed with
+// OAuth1 and the API rejects its timestamp (a drifted local clock), the server
+// clock offset is measured and stored, and a freshly signed request is sent
+// once more. The returned response has its body already consumed.
+func (c *ApiClient) sendWithClockSkewRetry(build func() (*http.Request, error), options RequestOptions) (json.RawMessage, *http.Response, error) {
+	req, err := build()
+	if err != nil {
+		return nil, nil, err
+	}
+
+	response, resp, err := c.send(req, options)
+	if err == nil || c.auth == nil || !isOAuth1Request(req) || HasHeaderArg(options.Headers, "Authorization") || !isTimestampRejection(err) {
+		return response, resp, err
+	}
+
+	previous := c.auth.TokenStore.ClockSkewSeconds
+	skew, syncErr := c.auth.SyncClockSkew(c.url)
+	if syncErr != nil || c.auth.TokenStore.ClockSkewSeconds == previous {
+		return response, resp, err
+	}
+	fmt.Fprintf(os.Stderr, "OAuth1 timestamp rejected: the local clock is %s off the server's; retrying with the corrected time.\n", skew)
+
+	req, err = build()
+	if err != nil {
+		return nil, nil, err
+	}
+	return c.send(req, options)
+}
+
+// send performs a built request and processes its response. The response is
+// returned (with its body closed) so callers can inspect status and headers.
+func (c *ApiClient) send(req *http.Request, options RequestOptions) (json.RawMessage, *http.Response, error) {
+	c.throttle.wait(throttleLog(options.Verbose))
+	cached := c.cache.revalidate(req, options)
 	c.logRequest(req, options.Verbose)
+	if options.VerboseBody {
+		logRequestBody(os.Stderr, req)
+	}
 
-	resp, err := c.client.Do(req)
+	resp, err := c.do(c.client, req)
 	if err != nil {
-		return nil, xurlErrors.NewHTTPError(err)
+		return nil, nil, xurlErrors.NewHTTPError(err)
 	}
 	defer resp.Body.Close()
+	c.recordRateLimit(resp)
 
-	return c.processResponse(resp, options.Verbose)
+	logSupportHeaders(os.Stderr, resp, options.Trace && !options.Verbose)
+	if cached != nil && resp.StatusCode == http.StatusNotModified {
+		response := cached.serve(resp)
+		c.logResponse(resp, options.Verbose)
+		return response, resp, nil
+	}
+	response, err := c.processResponse(resp, options.Verbose, options.VerboseBody, options.AcceptStatus, options.MaxResponseSize)
+	if err == nil {
+		c.cache.update(req, options, resp, response)
+	}
+	return response, resp, err
 }
 
-// SendMultipartRequest sends an HTTP request with multipart form data
-func (c *ApiClient) SendMultipartRequest(options MultipartOptions) (json.RawMessage, error) {
-	req, err := c.BuildMultipartRequest(options)
+// decodedBody undoes a gzip Content-Encoding on body (resp's body, possibly
+// wrapped). net/http already does this when it negotiated the encoding itself;
+// this covers requests whose Accept-Encoding was set explicitly with -H.
+func decodedBody(resp *http.Response, body io.Reader) (io.Reader, error) {
+	if resp.Uncompressed || !strings.EqualFold(resp.Header.Get("Content-Encoding"), "gzip") {
+		return body, nil
+	}
+	gz, err := gzip.NewReader(body)
 	if err != nil {
-		return nil, err
+		return nil, xurlErrors.NewIOError(fmt.Errorf("error decoding gzip response: %v", err))
 	}
+	return gz, nil
+}
 
-	c.logRequest(req, options.Verbose)
+// responseIsJSON reports whether a response body is JSON: its Content-Type says
+// so, or it has none (or a generic text/plain one) and the body parses.
+func responseIsJSON(contentType string, body []byte) bool {
+	if isJSONContentType(contentType) {
+		return json.Valid(body)
+	}
+	mediaType, _, _ := mime.ParseMediaType(contentType)
+	return (contentType == "" || mediaType == "text/plain") && json.Valid(body)
+}
+
+// isJSONContentType reports whether contentType is application/json or a
+// +json type such as application/problem+json.
+func isJSONContentType(contentType string) bool {
+	mediaType, _, err := mime.ParseMediaType(contentType)
+	return err == nil && (mediaType == "application/json" || strings.HasSuffix(mediaType, "+json"))
+}
+
+// maxErrorBodySnippet bounds how much of a non-JSON error body (e.g. an HTML
+// error page) goes into the error message.
+const maxErrorBodySnippet = 2048
+
+// bodySnippet returns body as text for an error message, shortened to
+// maxErrorBodySnippet bytes.
+func bodySnippet(body []byte) string {
+	text := strings.TrimSpace(strings.ToValidUTF8(string(body), "\uFFFD"))
+	if len(text) > maxErrorBodySnippet {
+		text = strings.ToValidUTF8(text[:maxErrorBodySnippet], "") + fmt.Sprintf("... (%d bytes)", len(body))
+	}
+	return text
+}
 
-	resp, err := c.client.Do(req)
+// ErrBinaryResponse is wrapped by the error for a response body that is not
+// text: not valid UTF-8, or holding NUL bytes.
+var ErrBinaryResponse = errors.New("binary response")
+
+// isBinaryBody reports whether body is binary data rather than text.
+func isBinaryBody(body []byte) bool {
+	return !utf8.Valid(body) || bytes.IndexByte(body, 0) >= 0
+}
+
+// readBodyLimited reads body in full, or fails once it passes maxSize bytes
+// when maxSize is positive.
+func readBodyLimited(body io.Reader, maxSize int64) ([]byte, error) {
+	if maxSize <= 0 {
+		data, err := io.ReadAll(body)
+		if err != nil {
+			return nil, xurlErrors.NewIOError(err)
+		}
+		return data, nil
+	}
+	data, err := io.ReadAll(io.LimitReader(body, maxSize+1))
 	if err != nil {
-		return nil, xurlErrors.NewHTTPError(err)
+		return nil, xurlErrors.NewIOError(err)
 	}
-	defer resp.Body.Close()
+	if int64(len(data)) > maxSize {
+		return nil, xurlErrors.NewIOError(fmt.Errorf("response body exceeds --max-response-size of %d bytes", maxSize))
+	}
+	return data, nil
+}
+
+// isOAuth1Request reports whether req was signed with OAuth1, in the header or
+// (--oauth1-as-query) in the query string.
+func isOAuth1Request(req *http.Request) bool {
+	return strings.HasPrefix(req.Header.Get("Authorization"), "OAuth ") || req.URL.Query().Has("oauth_signature")
+}
 
-	return c.processResponse(resp, options.Verbose)
+// isTimestampRejection reports whether err is an API error about the OAuth1
+// timestamp (e.g. "Timestamp out of bounds").
+func isTimestampRejection(err error) bool {
+	return xurlErrors.IsAPIError(err) && strings.Contains(strings.ToLower(err.Error()), "timestamp")
 }
 
 // StreamRequest sends an HTTP request and streams the response
 func (c *ApiClient) StreamRequest(options RequestOptions) error {
+	if c.fixtures != nil {
+		return xurlErrors.NewHTTPError(fmt.Errorf("streaming endpoints cannot be recorded or played back (--record, --playback)"))
+	}
 	req, err := c.BuildRequest(options)
 	if err != nil {
 		return err
 	}
 
-	if options.Verbose {
-		fmt.Printf("\033[1;34m> %s\033[0m %s\n", req.Method, req.URL)
-		for key, values := range req.Header {
-			for _, value := range values {
-				fmt.Printf("\033[1;36m> %s\033[0m: %s\n", key, value)
-			}
-		}
-		fmt.Println()
+	c.logRequest(req, options.Verbose)
+	if options.VerboseBody {
+		logRequestBody(os.Stderr, req)
 	}
 
 	client := &http.Client{
-		Timeout: 0,
+		Timeout:   0,
+		Transport: c.client.Transport,
 	}
 
-	fmt.Printf("\033[1;32mConnecting to streaming endpoint: %s\033[0m\n", options.Endpoint)
+	fmt.Fprintf(os.Stderr, "\033[1;32mConnecting to streaming endpoint: %s\033[0m\n", options.Endpoint)
 
+	c.throttle.wait(throttleLog(options.Verbose))
 	resp, err := client.Do(req)
 	if err != nil {
 		return xurlErrors.NewHTTPError(err)
 	}
 	defer resp.Body.Close()
+	c.recordRateLimit(resp)
 
-	if options.Verbose {
-		fmt.Printf("\033[1;31m< %s\033[0m\n", resp.Status)
-		for key, values := range resp.Header {
-			for _, value := range values {
-				fmt.Printf("\033[1;32m< %s\033[0m: %s\n", key, value)
-			}
-		}
-		fmt.Println()
-	}
+	logSupportHeaders(os.Stderr, resp, options.Trace && !options.Verbose)
+	c.logResponse(resp, options.Verbose)
 
 	if resp.StatusCode >= 400 {
 		body, err := io.ReadAll(resp.Body)
@@ -253,8 +763,8 @@ func (c *ApiClient) StreamRequest(options RequestOptions) error {
 	buf := make([]byte, maxScanTokenSize)
 	scanner.Buffer(buf, maxScanTokenSize)
 
-	fmt.Println("\033[1;32m--- Streaming response started ---\033[0m")
-	fmt.Println("\033[1;32m--- Press Ctrl+C to stop ---\033[0m")
+	fmt.Fprintln(os.Stderr, "\033[1;32m--- Streaming response started ---\033[0m")
+	fmt.Fprintln(os.Stderr, "\033[1;32m--- Press Ctrl+C to stop ---\033[0m")
 
 	for scanner.Scan() {
 		line := scanner.Text()
@@ -273,83 +783,166 @@ func (c *ApiClient) StreamRequest(options RequestOptions) error {
 		return xurlErrors.NewIOError(err)
 	}
 
-	fmt.Println("\033[1;32m--- End of stream ---\033[0m")
+	fmt.Fprintln(os.Stderr, "\033[1;32m--- End of stream ---\033[0m")
 	return nil
 }
 
-// buildBaseRequest creates the base HTTP request with common headers and settings
-func (c *ApiClient) buildBaseRequest(method, endpoint string, body io.Reader, contentType string, headers []string, authType, username string, trace bool) (*http.Request, error) {
-	httpMethod := strings.ToUpper(method)
+// resolveURL returns the full URL of endpoint. Absolute URLs are used as-is;
+// anything else is a path on the API base URL.
+func (c *ApiClient) resolveURL(endpoint string) string {
+	if isAbsoluteURL(endpoint) {
+		return endpoint
+	}
+	url := c.url
+	if !strings.HasSuffix(url, "/") {
+		url += "/"
+	}
+	return url + strings.TrimPrefix(endpoint, "/")
+}
 
-	// Build the full URL
-	url := endpoint
-	if !strings.HasPrefix(strings.ToLower(endpoint), "http") {
-		url = c.url
-		if !strings.HasSuffix(url, "/") {
-			url += "/"
-		}
-		if strings.HasPrefix(endpoint, "/") {
-			url += endpoint[1:]
-		} else {
-			url += endpoint
-		}
+// trustedAuthHosts are the X hosts other than the configured API host that
+// credentials are sent to for an absolute URL, such as the v1.1 media upload
+// host.
+var trustedAuthHosts = []string{"api.x.com", "upload.x.com", "api.twitter.com", "upload.twitter.com"}
+
+// isAbsoluteURL reports whether endpoint is a full http:// or https:// URL
+// rather than a path on the API host.
+func isAbsoluteURL(endpoint string) bool {
+	lower := strings.ToLower(endpoint)
+	return strings.HasPrefix(lower, "http://") || strings.HasPrefix(lower, "https://")
+}
+
+// sendsCredentialsTo reports whether requests to endpoint are authenticated:
+// API paths and absolute URLs on the API host, or https:// URLs on one of
+// trustedAuthHosts, so that credentials never cross the network in plaintext.
+func (c *ApiClient) sendsCredentialsTo(endpoint string) bool {
+	if c.isAPIURL(endpoint) {
+		return true
+	}
+	target, err := url.Parse(endpoint)
+	if err != nil {
+		return false
 	}
+	return strings.EqualFold(target.Scheme, "https") && slices.Contains(trustedAuthHosts, strings.ToLower(target.Hostname()))
+}
+
+// isAPIURL reports whether endpoint is an API path or an absolute URL on the
+// client's API host, with the same scheme as the API base URL.
+func (c *ApiClient) isAPIURL(endpoint string) bool {
+	if !isAbsoluteURL(endpoint) {
+		return true
+	}
+	target, err := url.Parse(endpoint)
+	if err != nil {
+		return false
+	}
+	base, err := url.Parse(c.url)
+	if err != nil {
+		return false
+	}
+	return strings.EqualFold(target.Scheme, base.Scheme) && strings.EqualFold(target.Host, base.Host)
+}
+
+// buildBaseRequest creates the base HTTP request with common headers and settings.
+// It is sent to endpoint, options.Endpoint with its query parameters added, and
+// takes its method, headers, and auth from options. A nil body sends no body.
+// OAuth1 signing never covers the body itself (only the URL query), apart from
+// an opt-in oauth_body_hash of it.
+func (c *ApiClient) buildBaseRequest(options RequestOptions, endpoint string, body []byte, contentType string) (*http.Request, error) {
+	httpMethod := strings.ToUpper(options.Method)
+
+	url := c.resolveURL(endpoint)
 
 	// Create the request
-	req, err := http.NewRequest(httpMethod, url, body)
+	var bodyReader io.Reader
+	if body != nil {
+		bodyReader = bytes.NewReader(body)
+	}
+	req, err := http.NewRequest(httpMethod, url, bodyReader)
 	if err != nil {
 		return nil, xurlErrors.NewHTTPError(err)
 	}
 
-	// Add headers
-	for _, header := range headers {
-		parts := strings.SplitN(header, ":", 2)
-		if len(parts) == 2 {
-			req.Header.Add(strings.TrimSpace(parts[0]), strings.TrimSpace(parts[1]))
-		}
+	// Add headers. They take precedence over the ones xurl computes below,
+	// whatever their case, and those removed with "Name:" stay out entirely.
+	removed := addHeaderArgs(req.Header, options.Headers)
+
+	// An Accept header from -H wins over options.Accept, which defaults to JSON
+	if !userSetHeader(req.Header, "Accept") && !removed["Accept"] {
+		req.Header.Set("Accept", cmp.Or(options.Accept, DefaultAccept))
 	}
 
-	// Set content type if provided
-	if contentType != "" {
+	// Set content type if provided, unless -H gave one
+	if contentType != "" && !userSetHeader(req.Header, "Content-Type") && !removed["Content-Type"] {
 		req.Header.Set("Content-Type", contentType)
 	}
 
-	// Add authorization header if not already set. A failure here is fatal: a
+	// Add authorization header if not already set. An Authorization header
+	// from -H skips auth resolution altogether: no stored credentials are
+	// looked up and no token is refreshed. A failure here is fatal: a
 	// request with no usable credentials would only produce a confusing API 401,
 	// so surface the real auth error instead. The sole exception is a client that
 	// opts into unauthenticated requests (allowUnauthenticated, set only by
 	// library/test constructors), where we proceed and let the server decide.
-	if req.Header.Get("Authorization") == "" {
-		authHeader, err := c.getAuthHeader(httpMethod, url, authType, username)
+	// Absolute URLs on other hosts get no credentials unless forced, so that a
+	// token is never handed to an arbitrary server. In playback nothing is
+	// sent, so no credentials are needed.
+	if !userSetHeader(req.Header, "Authorization") && !removed["Authorization"] && !c.fixtures.replaying() && (options.ForceAuth || c.sendsCredentialsTo(url)) {
+		var oauth1Params map[string]string
+		if c.auth != nil {
+			oauth1Params = c.auth.OAuth1BodyHashParams(contentType, body)
+		}
+		authHeader, err := c.getAuthHeader(httpMethod, url, options.AuthType, options.Username, oauth1Params)
 		if err != nil {
 			if !c.allowUnauthenticated {
 				return nil, err
 			}
+		} else if c.auth.OAuth1AsQuery() && strings.HasPrefix(authHeader, "OAuth ") {
+			params, err := auth.OAuth1QueryParams(authHeader)
+			if err != nil {
+				return nil, xurlErrors.NewAuthError("InvalidOAuth1Header", err)
+			}
+			if req.URL.RawQuery != "" {
+				req.URL.RawQuery += "&"
+			}
+			req.URL.RawQuery += params.Encode()
 		} else {
 			req.Header.Add("Authorization", authHeader)
 		}
 	}
 
 	// Add common headers
-	req.Header.Add("User-Agent", "xurl/"+version.Version)
+	setUserAgent(req.Header, removed)
 
-	if trace {
-		req.Header.Add("X-B3-Flags", "1")
+	if options.Trace && !userSetHeader(req.Header, "X-B3-Flags") {
+		req.Header.Set("X-B3-Flags", "1")
 	}
 
 	return req, nil
 }
 
-// GetAuthHeader gets the authorization header for a request
-func (c *ApiClient) getAuthHeader(method, url string, authType string, username string) (string, error) {
+// GetAuthHeader gets the authorization header for a request. oauth1Params are
+// extra parameters to sign when OAuth1 is used (e.g. oauth_body_hash).
+func (c *ApiClient) getAuthHeader(method, url string, authType string, username string, oauth1Params map[string]string) (string, error) {
 	if c.auth == nil {
 		return "", xurlErrors.NewAuthError("AuthNotSet", errors.New("auth not set"))
 	}
 
+	if strings.Contains(authType, ",") {
+		return c.getFallbackAuthHeader(method, url, authType, username, oauth1Params)
+	}
+
+	if strings.EqualFold(authType, "app") && requiresUserContext(url) {
+		return "", xurlErrors.NewAuthError("UserContextRequired", errDMUserContext)
+	}
+	if strings.EqualFold(authType, "app") && c.auth.RequireUserContext() {
+		return "", xurlErrors.NewAuthError("UserContextRequired", errAppOnlyRefused)
+	}
+
 	if authType != "" {
 		switch strings.ToLower(authType) {
 		case "oauth1":
-			return c.auth.GetOAuth1Header(method, url, nil)
+			return c.auth.GetOAuth1Header(method, url, oauth1Params)
 		case "oauth2":
 			return c.auth.GetOAuth2Header(username)
 		case "app":
@@ -359,7 +952,37 @@ func (c *ApiClient) getAuthHeader(method, url string, authType string, username
 		}
 	}
 
-	// If no auth type is specified, try to use the first OAuth2 token
+	// Without --auth, an --oauth2-token wins over every stored credential.
+	if c.auth.OAuth2AccessToken() != "" {
+		return c.auth.GetOAuth2Header(username)
+	}
+
+	// Without --auth, say how to get started when nothing is stored at all,
+	// rather than failing once every auth type has been tried.
+	if err := c.auth.MissingCredentialsError(); err != nil {
+		return "", err
+	}
+
+	// Without --auth, known routes pick their auth type from the route table.
+	// An explicit -u/--username asks for that OAuth2 user, so it skips the
+	// table, as does --require-user-context; so does a route whose preferred
+	// types have no stored credentials.
+	if username == "" && !c.auth.RequireUserContext() {
+		if preference := routeAuthPreference(method, url, c.auth.TokenStore.AuthRoutes); preference != "" {
+			routeAuthType, _, err := c.firstStoredAuthType(preference, username)
+			if err != nil {
+				return "", err
+			}
+			if routeAuthType != "" {
+				return c.getAuthHeader(method, url, routeAuthType, username, oauth1Params)
+			}
+			if requiresUserContext(url) {
+				return "", xurlErrors.NewAuthError("UserContextRequired", errDMUserContext)
+			}
+		}
+	}
+
+	// Otherwise try to use the first OAuth2 token
 	appName := c.auth.AppName()
 	token := c.auth.TokenStore.GetFirstOAuth2TokenForApp(appName)
 	if token != nil {
@@ -379,13 +1002,17 @@ func (c *ApiClient) getAuthHeader(method, url string, authType string, username
 	// If no OAuth2 token is available, try to use the first OAuth1 token
 	token = c.auth.TokenStore.GetOAuth1TokensForApp(appName)
 	if token != nil {
-		authHeader, err := c.auth.GetOAuth1Header(method, url, nil)
+		authHeader, err := c.auth.GetOAuth1Header(method, url, oauth1Params)
 		if err == nil {
 			return authHeader, nil
 		}
 	}
 
-	// If no OAuth1 token is available, try to use the bearer token
+	// If no OAuth1 token is available, try to use the bearer token, unless
+	// the caller must act as a user
+	if c.auth.RequireUserContext() {
+		return "", xurlErrors.NewAuthError("UserContextRequired", errAppOnlyRefused)
+	}
 	bearerToken, err := c.auth.GetBearerTokenHeader()
 	if err == nil {
 		return bearerToken, nil
@@ -395,49 +1022,148 @@ func (c *ApiClient) getAuthHeader(method, url string, authType string, username
 	return "", xurlErrors.NewAuthError("NoAuthMethod", errors.New("no authentication method available"))
 }
 
-// logRequest logs request details if verbose mode is enabled
+// getFallbackAuthHeader uses the first auth type in the comma-separated list
+// authTypes (e.g. "oauth2,oauth1,app") that has stored credentials. The OAuth2
+// login flow is never started for a missing token; that type is just skipped.
+func (c *ApiClient) getFallbackAuthHeader(method, url, authTypes, username string, oauth1Params map[string]string) (string, error) {
+	authType, tried, err := c.firstStoredAuthType(authTypes, username)
+	if err != nil {
+		return "", err
+	}
+	if authType == "" {
+		return "", xurlErrors.NewAuthError("NoAuthMethod", fmt.Errorf("no stored credentials for any of the requested auth types: %s", strings.Join(tried, ", ")))
+	}
+	return c.getAuthHeader(method, url, authType, username, oauth1Params)
+}
+
+// firstStoredAuthType returns the first auth type in the comma-separated list
+// authTypes that has stored credentials, or "" and the types tried when none
+// does. An oauth2 entry counts an --oauth2-token, or else only username's
+// token when one is given.
+func (c *ApiClient) firstStoredAuthType(authTypes, username string) (string, []string, error) {
+	appName := c.auth.AppName()
+	tokens := c.auth.TokenStore
+
+	var tried []string
+	for _, authType := range strings.Split(authTypes, ",") {
+		authType = strings.ToLower(strings.TrimSpace(authType))
+		var available bool
+		switch authType {
+		case "oauth2":
+			if c.auth.OAuth2AccessToken() != "" {
+				available = true
+			} else if username != "" {
+				available = tokens.GetOAuth2TokenForApp(appName, username) != nil
+			} else {
+				available = tokens.GetFirstOAuth2TokenForApp(appName) != nil
+			}
+		case "oauth1":
+			available = tokens.GetOAuth1TokensForApp(appName) != nil
+		case "app":
+			available = tokens.GetBearerTokenForApp(appName) != nil
+		default:
+			return "", nil, xurlErrors.NewAuthError("InvalidAuthType", fmt.Errorf("invalid auth type: %s", authType))
+		}
+		if available {
+			return authType, tried, nil
+		}
+		tried = append(tried, authType)
+	}
+	return "", tried, nil
+}
+
+// logRequest logs request details to stderr if verbose mode is enabled, with
+// credentials masked (see ScrubSecrets).
 func (c *ApiClient) logRequest(req *http.Request, verbose bool) {
 	if verbose {
-		fmt.Printf("\033[1;34m> %s\033[0m %s\n", req.Method, req.URL)
+		secrets := c.auth.StoredSecrets()
+		fmt.Fprintf(os.Stderr, "\033[1;34m> %s\033[0m %s\n", req.Method, ScrubSecrets(req.URL.String(), secrets))
 		for key, values := range req.Header {
 			for _, value := range values {
-				fmt.Printf("\033[1;36m> %s\033[0m: %s\n", key, value)
+				fmt.Fprintf(os.Stderr, "\033[1;36m> %s\033[0m: %s\n", key, ScrubSecrets(value, secrets))
 			}
 		}
-		fmt.Println()
+		fmt.Fprintln(os.Stderr)
 	}
 }
 
-// processResponse handles common response processing logic
-func (c *ApiClient) processResponse(resp *http.Response, verbose bool) (json.RawMessage, error) {
-	responseBody, err := io.ReadAll(resp.Body)
-	if err != nil {
-		return nil, xurlErrors.NewIOError(err)
-	}
-
+// logResponse logs the response status and headers to stderr if verbose mode
+// is enabled, with credentials masked.
+func (c *ApiClient) logResponse(resp *http.Response, verbose bool) {
 	if verbose {
-		fmt.Printf("\033[1;31m< %s\033[0m\n", resp.Status)
+		secrets := c.auth.StoredSecrets()
+		fmt.Fprintf(os.Stderr, "\033[1;31m< %s\033[0m\n", resp.Status)
 		for key, values := range resp.Header {
 			for _, value := range values {
-				fmt.Printf("\033[1;32m< %s\033[0m: %s\n", key, value)
+				fmt.Fprintf(os.Stderr, "\033[1;32m< %s\033[0m: %s\n", key, ScrubSecrets(value, secrets))
 			}
 		}
-		fmt.Println()
+		fmt.Fprintln(os.Stderr)
 	}
+}
+
+// supportHeaders are the response headers X support asks for when debugging a
+// request: the transaction ID identifies it server-side.
+var supportHeaders = []string{"x-transaction-id", "x-response-time"}
+
+// logSupportHeaders writes the support headers of resp to w when enabled. Under
+// --verbose every response header is already printed, so callers enable this
+// for --trace alone.
+func logSupportHeaders(w io.Writer, resp *http.Response, enabled bool) {
+	if !enabled {
+		return
+	}
+	for _, name := range supportHeaders {
+		if value := resp.Header.Get(name); value != "" {
+			fmt.Fprintf(w, "\033[1;32m< %s\033[0m: %s\n", name, value)
+		}
+	}
+}
+
+// processResponse handles common response processing logic. Statuses listed in
+// acceptStatus are treated as success, so their body is returned rather than an
+// API error. verboseBody logs the body to stderr. A body longer than maxSize
+// bytes (when positive) is an error, and no more than that is read.
+func (c *ApiClient) processResponse(resp *http.Response, verbose, verboseBody bool, acceptStatus []int, maxSize int64) (json.RawMessage, error) {
+	body, err := decodedBody(resp, resp.Body)
+	if err != nil {
+		return nil, err
+	}
+	responseBody, err := readBodyLimited(body, maxSize)
+	if err != nil {
+		return nil, err
+	}
+
+	c.logResponse(resp, verbose)
+	if verboseBody {
+		logBody(os.Stderr, "<", responseBody, resp.Header.Get("Content-Type"))
+	}
+
+	failed := resp.StatusCode >= 400 && !slices.Contains(acceptStatus, resp.StatusCode)
 
 	var js json.RawMessage
-	if len(responseBody) > 0 {
-		if err := json.Unmarshal(responseBody, &js); err != nil {
-			if resp.StatusCode >= 400 {
-				return nil, xurlErrors.NewHTTPError(fmt.Errorf("HTTP error: %s", resp.Status))
-			}
-			js = json.RawMessage("{}")
+	contentType := resp.Header.Get("Content-Type")
+	if len(responseBody) == 0 {
+		js = json.RawMessage("{}")
+	} else if responseIsJSON(contentType, responseBody) {
+		js = json.RawMessage(responseBody)
+	} else if isBinaryBody(responseBody) {
+		// Printing it would garble the terminal, and a JSON string cannot
+		// carry it intact.
+		binaryErr := fmt.Errorf("%w (%d bytes, %s); use --body-file-output FILE to save it, or --body-file-output - for stdout", ErrBinaryResponse, len(responseBody), cmp.Or(contentType, "no Content-Type"))
+		if failed {
+			binaryErr = fmt.Errorf("HTTP error: %s: %w", resp.Status, binaryErr)
 		}
+		return nil, xurlErrors.NewHTTPError(binaryErr)
+	} else if failed {
+		return nil, xurlErrors.NewHTTPError(fmt.Errorf("HTTP error: %s (%s): %s", resp.Status, contentType, bodySnippet(responseBody)))
 	} else {
-		js = json.RawMessage("{}")
+		// Not JSON (XML, plain text, HTML...): hand the body on as a JSON
+		// string, which printResponse prints as-is.
+		js, _ = json.Marshal(string(responseBody))
 	}
 
-	if resp.StatusCode >= 400 {
+	if failed {
 		return nil, xurlErrors.NewAPIError(js)
 	}
 
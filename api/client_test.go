@@ -1,13 +1,24 @@
 package api
 
 import (
+	"bytes"
+	"compress/gzip"
+	"crypto/hmac"
+	"crypto/sha1"
+	"encoding/base64"
 	"encoding/json"
+	"fmt"
+	"io"
 	"net/http"
 	"net/http/httptest"
+	"net/url"
 	"os"
 	"path/filepath"
+	"sort"
+	"strconv"
 	"strings"
 	"testing"
+	"time"
 
 	"github.com/xdevplatform/xurl/auth"
 	"github.com/xdevplatform/xurl/config"
@@ -177,6 +188,528 @@ func TestBuildRequest(t *testing.T) {
 	}
 }
 
+func TestBuildRequestRawBody(t *testing.T) {
+	cfg := &config.Config{APIBaseURL: "https://api.x.com"}
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(cfg, authMock)
+
+	tests := []struct {
+		name            string
+		body            []byte
+		binary          bool
+		wantContentType string
+	}{
+		{"binary bytes", []byte{0x00, 0xff, 0xd8, 0x80, 0x0a, 0xfe}, false, "application/octet-stream"},
+		{"JSON file", []byte(`{"text":"hi"}` + "\n"), false, "application/json"},
+		{"text file", []byte("status=hello"), false, "application/x-www-form-urlencoded"},
+		{"--data-binary skips detection", []byte(`{"text":"hi"}`), true, "application/octet-stream"},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			req, err := client.BuildRequest(RequestOptions{Method: "POST", Endpoint: "/2/media/upload", Body: tt.body, BinaryBody: tt.binary})
+			require.NoError(t, err)
+
+			got, err := io.ReadAll(req.Body)
+			require.NoError(t, err)
+			assert.Equal(t, tt.body, got, "raw body must be sent byte-for-byte")
+			assert.Equal(t, tt.wantContentType, req.Header.Get("Content-Type"))
+		})
+	}
+}
+
+func TestBuildRequestQueryParams(t *testing.T) {
+	cfg := &config.Config{APIBaseURL: "https://api.x.com"}
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(cfg, authMock)
+
+	tests := []struct {
+		name     string
+		endpoint string
+		method   string
+		params   []string
+		want     string
+	}{
+		{"no existing query", "/2/tweets/search/recent", "GET", []string{"query=from:xdevelopers has:media", "max_results=10"}, "https://api.x.com/2/tweets/search/recent?query=from%3Axdevelopers+has%3Amedia&max_results=10"},
+		{"merged with existing query", "/2/tweets?ids=1", "GET", []string{"tweet.fields=created_at,author_id"}, "https://api.x.com/2/tweets?ids=1&tweet.fields=created_at%2Cauthor_id"},
+		{"trailing question mark", "/2/users/me?", "GET", []string{"a=b"}, "https://api.x.com/2/users/me?a=b"},
+		{"sent in the URL for POST too", "/2/tweets", "POST", []string{"dry_run=true"}, "https://api.x.com/2/tweets?dry_run=true"},
+		{"empty value", "/2/users/me", "GET", []string{"flag="}, "https://api.x.com/2/users/me?flag="},
+		{"repeated key", "/2/tweets?ids=1", "GET", []string{"ids=2", "ids=3"}, "https://api.x.com/2/tweets?ids=1&ids=2&ids=3"},
+		{"split on the first equals sign only", "/2/tweets/search/recent", "GET", []string{"query=a=b"}, "https://api.x.com/2/tweets/search/recent?query=a%3Db"},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			req, err := client.BuildRequest(RequestOptions{Method: tt.method, Endpoint: tt.endpoint, Data: `{"text":"hi"}`, QueryParams: tt.params})
+			require.NoError(t, err)
+			assert.Equal(t, tt.want, req.URL.String())
+		})
+	}
+
+	t.Run("rejects pairs without a key", func(t *testing.T) {
+		for _, param := range []string{"novalue", "=value"} {
+			_, err := client.BuildRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", QueryParams: []string{param}})
+			require.Error(t, err)
+			assert.Contains(t, err.Error(), "expected key=value")
+		}
+	})
+}
+
+func TestBuildRequestBaseURLJoin(t *testing.T) {
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+
+	for _, base := range []string{"http://127.0.0.1:8000/mock", "http://127.0.0.1:8000/mock/"} {
+		client := NewApiClient(&config.Config{APIBaseURL: base}, authMock)
+		for _, endpoint := range []string{"/2/users/me", "2/users/me"} {
+			req, err := client.BuildRequest(RequestOptions{Method: "GET", Endpoint: endpoint, AuthType: "app"})
+			require.NoError(t, err)
+			assert.Equal(t, "http://127.0.0.1:8000/mock/2/users/me", req.URL.String(), "%s + %s", base, endpoint)
+		}
+	}
+}
+
+func TestBuildRequestAbsoluteURL(t *testing.T) {
+	cfg := &config.Config{APIBaseURL: "https://api.x.com"}
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(cfg, authMock)
+
+	tests := []struct {
+		name      string
+		endpoint  string
+		forceAuth bool
+		wantAuth  bool
+	}{
+		{"same host as the API", "https://api.x.com/2/users/me", false, true},
+		{"trusted X host", "https://upload.x.com/1.1/media/upload.json", false, true},
+		{"other host", "https://example.com/hook", false, false},
+		{"other host with --force-auth", "https://example.com/hook", true, true},
+		{"host that only shares a prefix", "https://api.x.com.example.com/2/users/me", false, false},
+		{"API host over http", "http://api.x.com/2/users/me", false, false},
+		{"trusted X host over http", "http://upload.x.com/1.1/media/upload.json", false, false},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			req, err := client.BuildRequest(RequestOptions{Method: "GET", Endpoint: tt.endpoint, AuthType: "app", ForceAuth: tt.forceAuth})
+			require.NoError(t, err)
+			assert.Equal(t, tt.endpoint, req.URL.String(), "absolute URLs are used as-is")
+			if tt.wantAuth {
+				assert.Equal(t, "Bearer test-bearer-token", req.Header.Get("Authorization"))
+			} else {
+				assert.Empty(t, req.Header.Get("Authorization"))
+			}
+		})
+	}
+
+	t.Run("an explicit Authorization header is kept", func(t *testing.T) {
+		req, err := client.BuildRequest(RequestOptions{Method: "GET", Endpoint: "https://example.com/hook", Headers: []string{"Authorization: Basic abc"}})
+		require.NoError(t, err)
+		assert.Equal(t, "Basic abc", req.Header.Get("Authorization"))
+	})
+}
+
+func TestRequestTransferEncoding(t *testing.T) {
+	var gotContentLength int64
+	var gotTransferEncoding []string
+	var gotBody []byte
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		gotContentLength = r.ContentLength
+		gotTransferEncoding = r.TransferEncoding
+		gotBody, _ = io.ReadAll(r.Body)
+		w.Header().Set("Content-Type", "application/json")
+		w.Write([]byte(`{}`))
+	}))
+	defer server.Close()
+
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+	body := `{"text":"Hello world!"}`
+
+	t.Run("fixed-size body sends Content-Length", func(t *testing.T) {
+		req, err := client.BuildRequest(RequestOptions{Method: "POST", Endpoint: "/2/tweets", Data: body})
+		require.NoError(t, err)
+		assert.Equal(t, int64(len(body)), req.ContentLength)
+
+		_, err = client.SendRequest(RequestOptions{Method: "POST", Endpoint: "/2/tweets", Data: body})
+		require.NoError(t, err)
+		assert.Equal(t, int64(len(body)), gotContentLength)
+		assert.Empty(t, gotTransferEncoding)
+		assert.Equal(t, body, string(gotBody))
+	})
+
+	t.Run("chunked forces Transfer-Encoding", func(t *testing.T) {
+		_, err := client.SendRequest(RequestOptions{Method: "POST", Endpoint: "/2/tweets", Data: body, Chunked: true})
+		require.NoError(t, err)
+		assert.Equal(t, int64(-1), gotContentLength)
+		assert.Equal(t, []string{"chunked"}, gotTransferEncoding)
+		assert.Equal(t, body, string(gotBody))
+	})
+
+	t.Run("chunked is ignored without a body", func(t *testing.T) {
+		req, err := client.BuildRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", Chunked: true})
+		require.NoError(t, err)
+		assert.Empty(t, req.TransferEncoding)
+	})
+}
+
+func TestSendRequestAcceptStatus(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Path == "/plain" {
+			w.WriteHeader(http.StatusConflict)
+			w.Write([]byte("already exists"))
+			return
+		}
+		w.Header().Set("Content-Type", "application/json")
+		w.WriteHeader(http.StatusNotFound)
+		w.Write([]byte(`{"errors":[{"title":"Not Found Error"}]}`))
+	}))
+	defer server.Close()
+
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+	_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/by/username/nobody"})
+	assert.True(t, xurlErrors.IsAPIError(err), "404 is an error unless accepted")
+
+	resp, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/by/username/nobody", AcceptStatus: []int{404, 409}})
+	require.NoError(t, err)
+	assert.JSONEq(t, `{"errors":[{"title":"Not Found Error"}]}`, string(resp))
+
+	resp, err = client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/plain", AcceptStatus: []int{409}})
+	require.NoError(t, err)
+	assert.Equal(t, `"already exists"`, string(resp), "a non-JSON body is passed on as a JSON string")
+
+	_, err = client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/by/username/nobody", AcceptStatus: []int{409}})
+	assert.True(t, xurlErrors.IsAPIError(err), "only listed statuses are accepted")
+}
+
+func TestSendRequestContentTypes(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		switch r.URL.Path {
+		case "/xml":
+			w.Header().Set("Content-Type", "application/xml")
+			w.Write([]byte(`<ok>{"not":"json"}</ok>`))
+		case "/json-as-text":
+			w.Header().Set("Content-Type", "text/plain; charset=utf-8")
+			w.Write([]byte(`{"data":{"id":"1"}}`))
+		case "/problem":
+			w.Header().Set("Content-Type", "application/problem+json")
+			w.WriteHeader(http.StatusBadRequest)
+			w.Write([]byte(`{"title":"Invalid Request","status":400}`))
+		case "/html-error":
+			w.Header().Set("Content-Type", "text/html")
+			w.WriteHeader(http.StatusBadGateway)
+			w.Write([]byte("<html><body><h1>502 Bad Gateway</h1></body></html>"))
+		}
+	}))
+	defer server.Close()
+
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+	resp, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/xml"})
+	require.NoError(t, err)
+	assert.Equal(t, `"\u003cok\u003e{\"not\":\"json\"}\u003c/ok\u003e"`, string(resp))
+
+	resp, err = client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/json-as-text"})
+	require.NoError(t, err)
+	assert.JSONEq(t, `{"data":{"id":"1"}}`, string(resp), "JSON served as text/plain is still JSON")
+
+	_, err = client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/problem"})
+	assert.True(t, xurlErrors.IsAPIError(err))
+	assert.JSONEq(t, `{"title":"Invalid Request","status":400}`, err.Error())
+
+	_, err = client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/html-error"})
+	require.Error(t, err)
+	assert.True(t, xurlErrors.IsHTTPError(err))
+	assert.Contains(t, err.Error(), "502 Bad Gateway (text/html): <html><body><h1>502 Bad Gateway</h1>", "the error page is shown, not an opaque error")
+}
+
+func TestBuildRequestAccept(t *testing.T) {
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: "https://api.x.com"}, authMock)
+
+	tests := []struct {
+		name    string
+		accept  string
+		headers []string
+		want    string
+	}{
+		{"default", "", nil, "application/json"},
+		{"--accept", "text/csv", nil, "text/csv"},
+		{"-H wins over --accept", "text/csv", []string{"accept: application/xml"}, "application/xml"},
+		{"-H wins over the default", "", []string{"Accept: */*"}, "*/*"},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			req, err := client.BuildRequest(RequestOptions{Method: "GET", Endpoint: "/2/usage/tweets", Headers: tt.headers, Accept: tt.accept})
+			require.NoError(t, err)
+			assert.Equal(t, []string{tt.want}, req.Header.Values("Accept"))
+		})
+	}
+}
+
+func TestSendRequestCSV(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.Header.Get("Accept") != "text/csv" {
+			w.Header().Set("Content-Type", "application/json")
+			w.Write([]byte(`{"data":[{"day":"2024-01-01","count":3}]}`))
+			return
+		}
+		w.Header().Set("Content-Type", "text/csv")
+		w.Write([]byte("day,count\n2024-01-01,3\n"))
+	}))
+	defer server.Close()
+
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+	resp, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/usage/tweets", Accept: "text/csv"})
+	require.NoError(t, err)
+	var text string
+	require.NoError(t, json.Unmarshal(resp, &text), "CSV is passed on as a string, not parsed as JSON")
+	assert.Equal(t, "day,count\n2024-01-01,3\n", text)
+
+	path := filepath.Join(t.TempDir(), "usage.csv")
+	_, err = client.SendRequestToFile(RequestOptions{Method: "GET", Endpoint: "/2/usage/tweets", Accept: "text/csv"}, path, nil)
+	require.NoError(t, err)
+	written, err := os.ReadFile(path)
+	require.NoError(t, err)
+	assert.Equal(t, "day,count\n2024-01-01,3\n", string(written))
+}
+
+func TestSendRequestURLRewrite(t *testing.T) {
+	var got *http.Request
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		got = r
+		w.Header().Set("Content-Type", "application/json")
+		w.Write([]byte(`{}`))
+	}))
+	defer server.Close()
+
+	rewrite, err := config.ParseURLRewrite("api.x.com=>" + server.URL)
+	require.NoError(t, err)
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: "https://api.x.com", URLRewrites: []config.URLRewrite{rewrite}}, authMock)
+
+	_, err = client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me?user.fields=id"})
+	require.NoError(t, err)
+	require.NotNil(t, got)
+	assert.Equal(t, "/2/users/me?user.fields=id", got.URL.RequestURI())
+	assert.Equal(t, strings.TrimPrefix(server.URL, "http://"), got.Host)
+	assert.NotEmpty(t, got.Header.Get("Authorization"), "credentials are those of the API host")
+}
+
+func TestSendRequestBinaryResponse(t *testing.T) {
+	png := []byte{0x89, 'P', 'N', 'G', '\r', '\n', 0x1a, '\n', 0x00, 0xff}
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "image/png")
+		if r.URL.Path == "/missing" {
+			w.WriteHeader(http.StatusNotFound)
+		}
+		w.Write(png)
+	}))
+	defer server.Close()
+
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+	_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/media.png"})
+	require.Error(t, err)
+	assert.ErrorIs(t, err, ErrBinaryResponse)
+	assert.Contains(t, err.Error(), "10 bytes, image/png")
+	assert.Contains(t, err.Error(), "--body-file-output")
+
+	_, err = client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/missing"})
+	require.Error(t, err)
+	assert.ErrorIs(t, err, ErrBinaryResponse)
+	assert.Contains(t, err.Error(), "404")
+
+	path := filepath.Join(t.TempDir(), "media.png")
+	_, err = client.SendRequestToFile(RequestOptions{Method: "GET", Endpoint: "/media.png"}, path, nil)
+	require.NoError(t, err)
+	written, err := os.ReadFile(path)
+	require.NoError(t, err)
+	assert.Equal(t, png, written, "--body-file-output keeps the raw bytes")
+}
+
+func TestIsBinaryBody(t *testing.T) {
+	assert.False(t, isBinaryBody([]byte("héllo, wörld\n")))
+	assert.False(t, isBinaryBody(nil))
+	assert.True(t, isBinaryBody([]byte{'a', 0x00, 'b'}))
+	assert.True(t, isBinaryBody([]byte{0xff, 0xfe}))
+}
+
+func TestSendRequestCompressRequest(t *testing.T) {
+	var got struct {
+		encoding    string
+		contentType string
+		body        []byte
+	}
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		got.encoding = r.Header.Get("Content-Encoding")
+		got.contentType = r.Header.Get("Content-Type")
+		var body io.Reader = r.Body
+		if got.encoding == "gzip" {
+			gz, err := gzip.NewReader(r.Body)
+			require.NoError(t, err)
+			body = gz
+		}
+		got.body, _ = io.ReadAll(body)
+		w.Header().Set("Content-Type", "application/json")
+		w.Write([]byte(`{}`))
+	}))
+	defer server.Close()
+
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+	rules := `{"add":[` + strings.Repeat(`{"value":"from:xdevelopers has:media"},`, 50) + `{"value":"x"}]}`
+	require.GreaterOrEqual(t, len(rules), minCompressedBodySize)
+
+	t.Run("large JSON body", func(t *testing.T) {
+		_, err := client.SendRequest(RequestOptions{Method: "POST", Endpoint: "/2/tweets/search/stream/rules", Data: rules, AuthType: "app", CompressRequest: true})
+		require.NoError(t, err)
+		assert.Equal(t, "gzip", got.encoding)
+		assert.Equal(t, "application/json", got.contentType)
+		assert.Equal(t, rules, string(got.body))
+	})
+
+	t.Run("large binary body", func(t *testing.T) {
+		payload := bytes.Repeat([]byte{0x00, 0xff, 0x10}, 1000)
+		_, err := client.SendRequest(RequestOptions{Method: "PUT", Endpoint: "/2/upload", Body: payload, AuthType: "app", CompressRequest: true})
+		require.NoError(t, err)
+		assert.Equal(t, "gzip", got.encoding)
+		assert.Equal(t, payload, got.body)
+	})
+
+	t.Run("small bodies are sent as is", func(t *testing.T) {
+		_, err := client.SendRequest(RequestOptions{Method: "POST", Endpoint: "/2/tweets", Data: `{"text":"hi"}`, AuthType: "app", CompressRequest: true})
+		require.NoError(t, err)
+		assert.Empty(t, got.encoding)
+		assert.Equal(t, `{"text":"hi"}`, string(got.body))
+	})
+
+	t.Run("an explicit Content-Encoding is left alone", func(t *testing.T) {
+		_, err := client.SendRequest(RequestOptions{Method: "POST", Endpoint: "/2/tweets/search/stream/rules", Data: rules, AuthType: "app", CompressRequest: true,
+			Headers: []string{"content-encoding: identity"}})
+		require.NoError(t, err)
+		assert.Equal(t, "identity", got.encoding)
+		assert.Equal(t, rules, string(got.body))
+	})
+
+	t.Run("off by default", func(t *testing.T) {
+		_, err := client.SendRequest(RequestOptions{Method: "POST", Endpoint: "/2/tweets/search/stream/rules", Data: rules, AuthType: "app"})
+		require.NoError(t, err)
+		assert.Empty(t, got.encoding)
+	})
+
+	t.Run("multipart bodies are not compressed", func(t *testing.T) {
+		_, err := client.SendMultipartRequest(MultipartOptions{
+			RequestOptions: RequestOptions{Method: "POST", Endpoint: "/2/media/upload", AuthType: "app", CompressRequest: true},
+			FileField:      "media",
+			FileName:       "media",
+			FileData:       bytes.Repeat([]byte{0x01}, 4096),
+		})
+		require.NoError(t, err)
+		assert.Empty(t, got.encoding)
+	})
+
+	t.Run("the verbose body log shows the original", func(t *testing.T) {
+		req, err := client.BuildRequest(RequestOptions{Method: "POST", Endpoint: "/2/tweets/search/stream/rules", Data: rules, AuthType: "app", CompressRequest: true})
+		require.NoError(t, err)
+		var out bytes.Buffer
+		logRequestBody(&out, req)
+		assert.Contains(t, out.String(), "from:xdevelopers has:media")
+	})
+}
+
+func TestBodySnippet(t *testing.T) {
+	assert.Equal(t, "short", bodySnippet([]byte("  short\n")))
+	long := bytes.Repeat([]byte("a"), maxErrorBodySnippet+10)
+	assert.Equal(t, strings.Repeat("a", maxErrorBodySnippet)+fmt.Sprintf("... (%d bytes)", maxErrorBodySnippet+10), bodySnippet(long))
+}
+
+func TestSendRequestMaxResponseSize(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "application/json")
+		w.Write([]byte(`{"data":{"id":"12345"}}`))
+	}))
+	defer server.Close()
+
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+	resp, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", MaxResponseSize: 23})
+	require.NoError(t, err, "a body of exactly the limit is read")
+	assert.JSONEq(t, `{"data":{"id":"12345"}}`, string(resp))
+
+	_, err = client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", MaxResponseSize: 22})
+	require.Error(t, err)
+	assert.True(t, xurlErrors.IsIOError(err))
+	assert.Contains(t, err.Error(), "exceeds --max-response-size of 22 bytes")
+}
+
+func TestLogSupportHeaders(t *testing.T) {
+	resp := &http.Response{Header: http.Header{}}
+	resp.Header.Set("X-Transaction-Id", "abc123")
+	resp.Header.Set("X-Response-Time", "42")
+	resp.Header.Set("X-Rate-Limit-Remaining", "99")
+
+	var buf bytes.Buffer
+	logSupportHeaders(&buf, resp, true)
+	assert.Contains(t, buf.String(), "x-transaction-id\033[0m: abc123")
+	assert.Contains(t, buf.String(), "x-response-time\033[0m: 42")
+	assert.NotContains(t, buf.String(), "rate-limit")
+
+	buf.Reset()
+	logSupportHeaders(&buf, resp, false)
+	assert.Empty(t, buf.String())
+}
+
+func TestReadDataArg(t *testing.T) {
+	binary := []byte{0x89, 'P', 'N', 'G', 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0xff}
+
+	t.Run("@- reads stdin bytes", func(t *testing.T) {
+		oldStdin := dataStdin
+		dataStdin = bytes.NewReader(binary)
+		defer func() { dataStdin = oldStdin }()
+
+		got, err := ReadDataArg("@-")
+		require.NoError(t, err)
+		assert.Equal(t, binary, got)
+	})
+
+	t.Run("@file reads file bytes", func(t *testing.T) {
+		path := filepath.Join(t.TempDir(), "chunk.bin")
+		require.NoError(t, os.WriteFile(path, binary, 0600))
+
+		got, err := ReadDataArg("@" + path)
+		require.NoError(t, err)
+		assert.Equal(t, binary, got)
+	})
+
+	t.Run("missing file is an IO error", func(t *testing.T) {
+		_, err := ReadDataArg("@/nonexistent/chunk.bin")
+		assert.True(t, xurlErrors.IsIOError(err))
+	})
+}
+
 func TestSendRequest(t *testing.T) {
 	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
 		if r.URL.Path == "/2/users/me" {
@@ -290,7 +823,7 @@ func TestGetAuthHeader(t *testing.T) {
 	t.Run("No auth set", func(t *testing.T) {
 		client := NewApiClient(cfg, nil)
 
-		_, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "", "")
+		_, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "", "", nil)
 
 		assert.Error(t, err, "Expected an error")
 		assert.True(t, xurlErrors.IsAuthError(err), "Expected auth error")
@@ -301,7 +834,7 @@ func TestGetAuthHeader(t *testing.T) {
 		defer os.RemoveAll(tempDir)
 		client := NewApiClient(cfg, authMock)
 
-		_, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "invalid", "")
+		_, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "invalid", "", nil)
 
 		assert.Error(t, err, "Expected an error")
 		assert.True(t, xurlErrors.IsAuthError(err), "Expected auth error")
@@ -318,7 +851,7 @@ func TestGetAuthHeader(t *testing.T) {
 		a := auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore).WithAppName("my-app")
 		client := NewApiClient(cfg, a)
 
-		header, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "", "")
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "", "", nil)
 		require.NoError(t, err)
 		assert.Equal(t, "Bearer bearer-my-app", header)
 	})
@@ -336,10 +869,164 @@ func TestGetAuthHeader(t *testing.T) {
 		a := auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore)
 		client := NewApiClient(cfg, a)
 
-		header, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "", "")
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "", "", nil)
 		require.NoError(t, err)
 		assert.Equal(t, "Bearer bearer-default", header)
 	})
+
+	t.Run("Auto-detect refreshes the first OAuth2 token inside the refresh margin", func(t *testing.T) {
+		server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			w.Header().Set("Content-Type", "application/json")
+			json.NewEncoder(w).Encode(map[string]any{
+				"access_token":  "fresh-access",
+				"token_type":    "Bearer",
+				"expires_in":    7200,
+				"refresh_token": "fresh-refresh",
+			})
+		}))
+		defer server.Close()
+
+		tokenStore, tempDir := createTempTokenStore(t)
+		defer os.RemoveAll(tempDir)
+		// Not yet expired, but due to expire within the default 300s margin.
+		expiry := uint64(time.Now().Add(2 * time.Minute).Unix())
+		require.NoError(t, tokenStore.SaveOAuth2Token("alice", "stale-access", "old-refresh", expiry))
+
+		a := auth.NewAuth(&config.Config{TokenURL: server.URL}).WithTokenStore(tokenStore)
+		client := NewApiClient(cfg, a)
+
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "", "", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer fresh-access", header)
+	})
+
+	t.Run("OAuth1 signature method override reaches the header", func(t *testing.T) {
+		tokenStore, tempDir := createTempTokenStore(t)
+		defer os.RemoveAll(tempDir)
+		tokenStore.SaveOAuth1Tokens("at", "ts", "ck", "cs")
+
+		a := auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore)
+		client := NewApiClient(cfg, a)
+
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "oauth1", "", nil)
+		require.NoError(t, err)
+		assert.Contains(t, header, `oauth_signature_method="HMAC-SHA1"`)
+
+		a.WithOAuth1SignatureMethod("hmac-sha256")
+		header, err = client.getAuthHeader("GET", "https://api.x.com/2/users/me", "oauth1", "", nil)
+		require.NoError(t, err)
+		assert.Contains(t, header, `oauth_signature_method="HMAC-SHA256"`)
+
+		a.WithOAuth1SignatureMethod("PLAINTEXT")
+		_, err = client.getAuthHeader("GET", "https://api.x.com/2/users/me", "oauth1", "", nil)
+		assert.True(t, xurlErrors.IsAuthError(err), "unsupported signature method should be an auth error")
+	})
+}
+
+func TestGetAuthHeaderFallbackOrder(t *testing.T) {
+	cfg := &config.Config{APIBaseURL: "https://api.x.com"}
+	future := uint64(time.Now().Add(time.Hour).Unix())
+
+	t.Run("uses the first type with credentials", func(t *testing.T) {
+		tokenStore, tempDir := createTempTokenStore(t)
+		defer os.RemoveAll(tempDir)
+		require.NoError(t, tokenStore.SaveOAuth1Tokens("at", "ts", "ck", "cs"))
+		require.NoError(t, tokenStore.SaveBearerToken("app-bearer"))
+
+		client := NewApiClient(cfg, auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore))
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "oauth2,oauth1,app", "", nil)
+		require.NoError(t, err)
+		assert.True(t, strings.HasPrefix(header, "OAuth "), "oauth2 has no token, so oauth1 is next")
+
+		header, err = client.getAuthHeader("GET", "https://api.x.com/2/users/me", "app, oauth1", "", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer app-bearer", header)
+	})
+
+	t.Run("oauth2 counts only the requested user", func(t *testing.T) {
+		tokenStore, tempDir := createTempTokenStore(t)
+		defer os.RemoveAll(tempDir)
+		require.NoError(t, tokenStore.SaveOAuth2Token("alice", "alice-access", "alice-refresh", future))
+		require.NoError(t, tokenStore.SaveBearerToken("app-bearer"))
+
+		client := NewApiClient(cfg, auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore))
+		header, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "oauth2,app", "alice", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer alice-access", header)
+
+		header, err = client.getAuthHeader("GET", "https://api.x.com/2/users/me", "oauth2,app", "bob", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer app-bearer", header)
+	})
+
+	t.Run("lists what was tried when nothing has credentials", func(t *testing.T) {
+		tokenStore, tempDir := createTempTokenStore(t)
+		defer os.RemoveAll(tempDir)
+
+		client := NewApiClient(cfg, auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore))
+		_, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "oauth2,oauth1,app", "", nil)
+		require.Error(t, err)
+		assert.True(t, xurlErrors.IsAuthError(err))
+		assert.Contains(t, err.Error(), "oauth2, oauth1, app")
+	})
+
+	t.Run("rejects unknown types", func(t *testing.T) {
+		tokenStore, tempDir := createTempTokenStore(t)
+		defer os.RemoveAll(tempDir)
+
+		client := NewApiClient(cfg, auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore))
+		_, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "oauth2,basic", "", nil)
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), "invalid auth type: basic")
+	})
+}
+
+func TestGetAuthHeaderOAuth2AccessToken(t *testing.T) {
+	cfg := &config.Config{APIBaseURL: "https://api.x.com"}
+	future := uint64(time.Now().Add(time.Hour).Unix())
+	urlStr := "https://api.x.com/2/users/me"
+
+	t.Run("needs no stored credentials", func(t *testing.T) {
+		tokenStore, tempDir := createTempTokenStore(t)
+		defer os.RemoveAll(tempDir)
+
+		client := NewApiClient(cfg, auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore).WithOAuth2AccessToken("given"))
+		for _, authType := range []string{"", "oauth2", "oauth1,oauth2"} {
+			header, err := client.getAuthHeader("GET", urlStr, authType, "", nil)
+			require.NoError(t, err, authType)
+			assert.Equal(t, "Bearer given", header, authType)
+		}
+	})
+
+	t.Run("wins over stored tokens", func(t *testing.T) {
+		tokenStore, tempDir := createTempTokenStore(t)
+		defer os.RemoveAll(tempDir)
+		require.NoError(t, tokenStore.SaveOAuth2Token("alice", "alice-access", "alice-refresh", future))
+		require.NoError(t, tokenStore.SaveOAuth1Tokens("at", "ts", "ck", "cs"))
+		require.NoError(t, tokenStore.SaveBearerToken("app-bearer"))
+
+		client := NewApiClient(cfg, auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore).WithOAuth2AccessToken("given"))
+		header, err := client.getAuthHeader("GET", urlStr, "", "alice", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer given", header)
+
+		// Another explicit auth type is still used.
+		header, err = client.getAuthHeader("GET", urlStr, "app", "", nil)
+		require.NoError(t, err)
+		assert.Equal(t, "Bearer app-bearer", header)
+	})
+}
+
+func TestBuildRequestNonInteractiveOAuth2(t *testing.T) {
+	tokenStore, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+
+	a := auth.NewAuth(&config.Config{ClientID: "cid"}).WithTokenStore(tokenStore).WithNonInteractive(true)
+	client := NewApiClient(&config.Config{APIBaseURL: "https://api.x.com"}, a)
+
+	_, err := client.BuildRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "oauth2"})
+	require.Error(t, err)
+	assert.True(t, xurlErrors.IsInteractionRequiredError(err), "got %v", err)
 }
 
 func TestBuildRequestPropagatesAuthError(t *testing.T) {
@@ -358,6 +1045,47 @@ func TestBuildRequestPropagatesAuthError(t *testing.T) {
 	assert.True(t, xurlErrors.IsAuthError(err), "expected an auth error")
 }
 
+func TestBuildRequestMissingCredentials(t *testing.T) {
+	// NewAuth falls back to the apps of ~/.xurl for client credentials.
+	t.Setenv("HOME", t.TempDir())
+	cfg := &config.Config{APIBaseURL: "https://api.x.com"}
+	options := RequestOptions{Method: "GET", Endpoint: "/2/users/me"}
+
+	t.Run("without client credentials", func(t *testing.T) {
+		tokenStore, tempDir := createTempTokenStore(t)
+		defer os.RemoveAll(tempDir)
+		client := NewApiClient(cfg, auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore))
+
+		_, err := client.BuildRequest(options)
+		require.Error(t, err)
+		assert.True(t, xurlErrors.IsAuthError(err))
+		assert.Contains(t, err.Error(), "no credentials configured")
+		assert.Contains(t, err.Error(), "xurl auth app --bearer-token")
+		assert.Contains(t, err.Error(), "set CLIENT_ID and CLIENT_SECRET")
+	})
+
+	t.Run("with client credentials but no token", func(t *testing.T) {
+		tokenStore, tempDir := createTempTokenStore(t)
+		defer os.RemoveAll(tempDir)
+		client := NewApiClient(cfg, auth.NewAuth(&config.Config{ClientID: "cid"}).WithTokenStore(tokenStore))
+
+		_, err := client.BuildRequest(options)
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), "no tokens stored. Run 'xurl auth oauth2'")
+	})
+
+	t.Run("any stored token is enough", func(t *testing.T) {
+		tokenStore, tempDir := createTempTokenStore(t)
+		defer os.RemoveAll(tempDir)
+		require.NoError(t, tokenStore.SaveOAuth1Tokens("token", "secret", "key", "consumer-secret"))
+		client := NewApiClient(cfg, auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore))
+
+		req, err := client.BuildRequest(options)
+		require.NoError(t, err)
+		assert.True(t, strings.HasPrefix(req.Header.Get("Authorization"), "OAuth "))
+	})
+}
+
 func TestBuildRequestAllowUnauthenticatedProceeds(t *testing.T) {
 	cfg := &config.Config{APIBaseURL: "https://api.x.com"}
 	// A client that explicitly opts into unauthenticated requests (library/test
@@ -452,7 +1180,481 @@ func TestExplicitUsernameDoesNotDowngradeToAppOnly(t *testing.T) {
 	mockAuth.WithTokenStore(ts)
 
 	client := NewApiClient(cfg, mockAuth)
-	_, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "", "alice")
+	_, err := client.getAuthHeader("GET", "https://api.x.com/2/users/me", "", "alice", nil)
 	require.Error(t, err, "explicit user with a failed token must not downgrade to app-only")
 	assert.False(t, xurlErrors.IsAPIError(err) && err.Error() == "", "should surface the refresh error")
 }
+
+// expectedOAuth1Signature recomputes an HMAC-SHA1 OAuth1 signature over the
+// oauth_* values of header plus the given query parameters, independently of
+// the auth package.
+func expectedOAuth1Signature(t *testing.T, method, baseURL, header string, query map[string]string) (string, string) {
+	t.Helper()
+	params := map[string]string{}
+	var signature string
+	for _, part := range strings.Split(strings.TrimPrefix(header, "OAuth "), ", ") {
+		kv := strings.SplitN(part, "=", 2)
+		require.Len(t, kv, 2)
+		value, err := url.QueryUnescape(strings.Trim(kv[1], `"`))
+		require.NoError(t, err)
+		if kv[0] == "oauth_signature" {
+			signature = value
+			continue
+		}
+		params[kv[0]] = value
+	}
+	for k, v := range query {
+		params[k] = v
+	}
+
+	keys := make([]string, 0, len(params))
+	for k := range params {
+		keys = append(keys, k)
+	}
+	sort.Strings(keys)
+	pairs := make([]string, 0, len(keys))
+	for _, k := range keys {
+		pairs = append(pairs, url.QueryEscape(k)+"="+url.QueryEscape(params[k]))
+	}
+	base := method + "&" + url.QueryEscape(baseURL) + "&" + url.QueryEscape(strings.Join(pairs, "&"))
+
+	mac := hmac.New(sha1.New, []byte("cs&ts"))
+	mac.Write([]byte(base))
+	return base64.StdEncoding.EncodeToString(mac.Sum(nil)), signature
+}
+
+func TestOAuth1SigningIgnoresBody(t *testing.T) {
+	tokenStore, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+	require.NoError(t, tokenStore.SaveOAuth1Tokens("at", "ts", "ck", "cs"))
+
+	a := auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore)
+	client := NewApiClient(&config.Config{APIBaseURL: "https://api.x.com"}, a)
+	query := map[string]string{"command": "APPEND", "media_id": "123"}
+
+	t.Run("multipart media upload", func(t *testing.T) {
+		req, err := client.BuildMultipartRequest(MultipartOptions{
+			RequestOptions: RequestOptions{Method: "POST", Endpoint: "/2/media/upload?command=APPEND&media_id=123", AuthType: "oauth1"},
+			FormFields:     map[string]string{"segment_index": "0"},
+			FileField:      "media",
+			FileName:       "media",
+			FileData:       []byte{0x00, 0xff, 0x10},
+		})
+		require.NoError(t, err)
+
+		header := req.Header.Get("Authorization")
+		assert.NotContains(t, header, "segment_index")
+		assert.NotContains(t, header, "oauth_body_hash")
+		expected, signature := expectedOAuth1Signature(t, "POST", "https://api.x.com/2/media/upload", header, query)
+		assert.Equal(t, expected, signature)
+	})
+
+	t.Run("raw binary body", func(t *testing.T) {
+		req, err := client.BuildRequest(RequestOptions{
+			Method:   "POST",
+			Endpoint: "/2/media/upload?command=APPEND&media_id=123",
+			Body:     []byte{0x00, 0xff, 0x10},
+			AuthType: "oauth1",
+		})
+		require.NoError(t, err)
+
+		expected, signature := expectedOAuth1Signature(t, "POST", "https://api.x.com/2/media/upload", req.Header.Get("Authorization"), query)
+		assert.Equal(t, expected, signature)
+	})
+
+	t.Run("body hash covers the multipart body", func(t *testing.T) {
+		a.WithOAuth1BodyHash(true)
+		defer a.WithOAuth1BodyHash(false)
+
+		req, err := client.BuildMultipartRequest(MultipartOptions{
+			RequestOptions: RequestOptions{Method: "POST", Endpoint: "/2/media/upload?command=APPEND&media_id=123", AuthType: "oauth1"},
+			FileField:      "media",
+			FileName:       "media",
+			FileData:       []byte{0x00, 0xff, 0x10},
+		})
+		require.NoError(t, err)
+
+		sentBody, err := io.ReadAll(req.Body)
+		require.NoError(t, err)
+		sum := sha1.Sum(sentBody)
+
+		header := req.Header.Get("Authorization")
+		assert.Contains(t, header, `oauth_body_hash="`+url.QueryEscape(base64.StdEncoding.EncodeToString(sum[:]))+`"`)
+		expected, signature := expectedOAuth1Signature(t, "POST", "https://api.x.com/2/media/upload", header, query)
+		assert.Equal(t, expected, signature)
+	})
+}
+
+func TestOAuth1AsQuery(t *testing.T) {
+	tokenStore, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+	require.NoError(t, tokenStore.SaveOAuth1Tokens("at", "ts", "ck", "cs"))
+
+	a := auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore).WithOAuth1Realm("Example").WithOAuth1AsQuery(true)
+	client := NewApiClient(&config.Config{APIBaseURL: "https://api.x.com"}, a)
+
+	req, err := client.BuildRequest(RequestOptions{Method: "GET", Endpoint: "/2/tweets/search/recent?query=golang", AuthType: "oauth1"})
+	require.NoError(t, err)
+	assert.Empty(t, req.Header.Get("Authorization"))
+
+	sent := req.URL.Query()
+	assert.Equal(t, "golang", sent.Get("query"))
+	assert.Equal(t, "ck", sent.Get("oauth_consumer_key"))
+	assert.Equal(t, "at", sent.Get("oauth_token"))
+	assert.False(t, sent.Has("realm"), "the realm is header-only")
+	assert.True(t, isOAuth1Request(req))
+
+	// The query-string parameters must carry the same signature the header
+	// form would have had.
+	var pairs []string
+	for key := range sent {
+		if strings.HasPrefix(key, "oauth_") {
+			pairs = append(pairs, fmt.Sprintf(`%s="%s"`, key, url.QueryEscape(sent.Get(key))))
+		}
+	}
+	expected, signature := expectedOAuth1Signature(t, "GET", "https://api.x.com/2/tweets/search/recent", "OAuth "+strings.Join(pairs, ", "), map[string]string{"query": "golang"})
+	assert.Equal(t, expected, signature)
+}
+
+func TestSendRequestRetriesOnOAuth1ClockSkew(t *testing.T) {
+	var signed int
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.Method == http.MethodHead {
+			return
+		}
+		signed++
+		header := r.Header.Get("Authorization")
+		i := strings.Index(header, `oauth_timestamp="`)
+		require.GreaterOrEqual(t, i, 0)
+		rest := header[i+len(`oauth_timestamp="`):]
+		timestamp, err := strconv.ParseInt(rest[:strings.Index(rest, `"`)], 10, 64)
+		require.NoError(t, err)
+
+		w.Header().Set("Content-Type", "application/json")
+		if d := time.Now().Unix() - timestamp; d > 60 || d < -60 {
+			w.WriteHeader(http.StatusUnauthorized)
+			w.Write([]byte(`{"errors":[{"code":135,"message":"Timestamp out of bounds."}]}`))
+			return
+		}
+		w.Write([]byte(`{"data":{"id":"1"}}`))
+	}))
+	defer server.Close()
+
+	tokenStore, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+	require.NoError(t, tokenStore.SaveOAuth1Tokens("at", "ts", "ck", "cs"))
+	// A stale offset makes the first signature an hour off.
+	require.NoError(t, tokenStore.SetClockSkew(-3600))
+
+	a := auth.NewAuth(&config.Config{}).WithTokenStore(tokenStore)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, a)
+
+	response, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "oauth1"})
+	require.NoError(t, err)
+	assert.JSONEq(t, `{"data":{"id":"1"}}`, string(response))
+	assert.Equal(t, 2, signed)
+	assert.InDelta(t, 0, tokenStore.ClockSkewSeconds, 2)
+}
+
+// rateLimitedServer answers the first failures requests with a 429 carrying an
+// x-rate-limit-reset one second out, then 200s. It counts every request.
+func rateLimitedServer(t *testing.T, failures int, calls *int) *httptest.Server {
+	t.Helper()
+	return httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		*calls++
+		w.Header().Set("Content-Type", "application/json")
+		if *calls <= failures {
+			w.Header().Set("x-rate-limit-reset", strconv.FormatInt(time.Now().Add(time.Second).Unix(), 10))
+			w.WriteHeader(http.StatusTooManyRequests)
+			w.Write([]byte(`{"title":"Too Many Requests","status":429}`))
+			return
+		}
+		w.Write([]byte(`{"data":{"id":"1"}}`))
+	}))
+}
+
+func TestSendRequestRetriesRateLimited(t *testing.T) {
+	previous := maxRateLimitWait
+	maxRateLimitWait = 20 * time.Millisecond
+	defer func() { maxRateLimitWait = previous }()
+
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+
+	t.Run("retries a 429 and succeeds within the budget", func(t *testing.T) {
+		var calls int
+		server := rateLimitedServer(t, 1, &calls)
+		defer server.Close()
+		client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+		response, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "app", Retry: RetryPolicy{MaxRetries: 2}})
+		require.NoError(t, err)
+		assert.JSONEq(t, `{"data":{"id":"1"}}`, string(response))
+		assert.Equal(t, 2, calls)
+	})
+
+	t.Run("returns the final 429 once retries are exhausted", func(t *testing.T) {
+		var calls int
+		server := rateLimitedServer(t, 10, &calls)
+		defer server.Close()
+		client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+		_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "app", Retry: RetryPolicy{MaxRetries: 2}})
+		require.Error(t, err)
+		assert.True(t, xurlErrors.IsAPIError(err))
+		assert.Contains(t, err.Error(), `"status":429`)
+		assert.Equal(t, 3, calls)
+	})
+
+	t.Run("does not retry without a budget", func(t *testing.T) {
+		var calls int
+		server := rateLimitedServer(t, 1, &calls)
+		defer server.Close()
+		client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+		_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "app"})
+		require.Error(t, err)
+		assert.Equal(t, 1, calls)
+	})
+
+	t.Run("does not retry a POST without an idempotency key", func(t *testing.T) {
+		var calls int
+		server := rateLimitedServer(t, 1, &calls)
+		defer server.Close()
+		client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+		_, err := client.SendRequest(RequestOptions{Method: "POST", Endpoint: "/2/tweets", Data: `{"text":"hi"}`, AuthType: "app", Retry: RetryPolicy{MaxRetries: 2}})
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), `"status":429`)
+		assert.Equal(t, 1, calls)
+
+		calls = 0
+		_, err = client.SendRequest(RequestOptions{Method: "patch", Endpoint: "/2/tweets", Data: `{"text":"hi"}`, AuthType: "app", Retry: RetryPolicy{MaxRetries: 2},
+			Headers: []string{"Idempotency-Key: abc", "Idempotency-Key:"}})
+		require.Error(t, err, "a removed key does not count")
+		assert.Equal(t, 1, calls)
+	})
+
+	t.Run("retries a POST when forced", func(t *testing.T) {
+		var calls int
+		server := rateLimitedServer(t, 1, &calls)
+		defer server.Close()
+		client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+		_, err := client.SendRequest(RequestOptions{Method: "POST", Endpoint: "/2/tweets", Data: `{"text":"hi"}`, AuthType: "app", Retry: RetryPolicy{MaxRetries: 2}, ForceRetry: true})
+		require.NoError(t, err)
+		assert.Equal(t, 2, calls)
+	})
+
+	t.Run("stops once the wait would exceed the max time", func(t *testing.T) {
+		var calls int
+		server := rateLimitedServer(t, 10, &calls)
+		defer server.Close()
+		client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+		_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "app", Retry: RetryPolicy{MaxRetries: 5, MaxTime: 30 * time.Millisecond}})
+		require.Error(t, err)
+		assert.Equal(t, 2, calls, "one 20ms wait fits in 30ms, a second does not")
+	})
+
+	t.Run("a shared budget caps retries across requests", func(t *testing.T) {
+		var calls int
+		server := rateLimitedServer(t, 10, &calls)
+		defer server.Close()
+		client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+		policy := RetryPolicy{MaxRetries: 2, Budget: NewRetryBudget(1)}
+		_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "app", Retry: policy})
+		require.Error(t, err)
+		assert.Equal(t, 2, calls, "the one retry in the budget")
+		_, err = client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "app", Retry: policy})
+		require.Error(t, err)
+		assert.Equal(t, 3, calls, "no retry is left for the second request")
+	})
+
+	t.Run("retries PUT and DELETE, which are idempotent", func(t *testing.T) {
+		for _, method := range []string{"PUT", "DELETE"} {
+			var calls int
+			server := rateLimitedServer(t, 1, &calls)
+			client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+			_, err := client.SendRequest(RequestOptions{Method: method, Endpoint: "/2/tweets/1", AuthType: "app", Retry: RetryPolicy{MaxRetries: 2}})
+			require.NoError(t, err, method)
+			assert.Equal(t, 2, calls, method)
+			server.Close()
+		}
+	})
+}
+
+func TestSendRequestIdempotencyKey(t *testing.T) {
+	previous := maxRateLimitWait
+	maxRateLimitWait = 20 * time.Millisecond
+	defer func() { maxRateLimitWait = previous }()
+
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+
+	var keys []string
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		keys = append(keys, r.Header.Values(IdempotencyKeyHeader)...)
+		w.Header().Set("Content-Type", "application/json")
+		if len(keys)%2 == 1 {
+			w.WriteHeader(http.StatusTooManyRequests)
+			w.Write([]byte(`{"title":"Too Many Requests","status":429}`))
+			return
+		}
+		w.Write([]byte(`{"data":{"id":"1"}}`))
+	}))
+	defer server.Close()
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+	post := func(key string, headers ...string) {
+		t.Helper()
+		keys = nil
+		_, err := client.SendRequest(RequestOptions{Method: "POST", Endpoint: "/2/tweets", Data: `{"text":"hi"}`, AuthType: "app",
+			Retry: RetryPolicy{MaxRetries: 1}, IdempotencyKey: key, Headers: headers})
+		require.NoError(t, err)
+		require.Len(t, keys, 2, "one key per attempt")
+		assert.Equal(t, keys[0], keys[1], "a retry reuses the key")
+	}
+
+	post("my-key")
+	assert.Equal(t, "my-key", keys[0])
+
+	post(IdempotencyKeyAuto)
+	first := keys[0]
+	assert.Regexp(t, `^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$`, first)
+	post(IdempotencyKeyAuto)
+	assert.NotEqual(t, first, keys[0], "each request gets its own generated key")
+
+	post("flag-key", "idempotency-key: header-key")
+	assert.Equal(t, "header-key", keys[0], "an explicit -H header wins")
+
+	keys = nil
+	_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "app", Retry: RetryPolicy{MaxRetries: 1}})
+	require.NoError(t, err)
+	assert.Empty(t, keys, "no header without a key")
+}
+
+func TestRateLimitWait(t *testing.T) {
+	now := time.Unix(1_700_000_000, 0)
+
+	header := http.Header{}
+	header.Set("x-rate-limit-reset", "1700000030")
+	assert.Equal(t, 31*time.Second, rateLimitWait(header, now, 0))
+
+	header = http.Header{}
+	header.Set("x-rate-limit-reset", "1699999990")
+	assert.Equal(t, time.Duration(0), rateLimitWait(header, now, 0), "a reset in the past retries immediately")
+
+	header = http.Header{}
+	header.Set("Retry-After", "7")
+	assert.Equal(t, 7*time.Second, rateLimitWait(header, now, 0))
+
+	header = http.Header{}
+	header.Set("Retry-After", now.Add(90*time.Second).UTC().Format(http.TimeFormat))
+	assert.Equal(t, 90*time.Second, rateLimitWait(header, now, 0), "Retry-After may be an HTTP date")
+
+	header = http.Header{}
+	header.Set("x-rate-limit-reset", "1700000600")
+	header.Set("Retry-After", "5")
+	assert.Equal(t, 5*time.Second, rateLimitWait(header, now, 0), "Retry-After wins over the window reset")
+
+	for i := 0; i < 100; i++ {
+		wait := rateLimitWait(http.Header{}, now, 2)
+		assert.GreaterOrEqual(t, wait, time.Duration(0))
+		assert.LessOrEqual(t, wait, 4*rateLimitBackoff, "full jitter stays within the backoff")
+	}
+	previousJitter := jitter
+	jitter = func(n time.Duration) time.Duration { return n }
+	defer func() { jitter = previousJitter }()
+	assert.Equal(t, 4*rateLimitBackoff, rateLimitWait(http.Header{}, now, 2))
+	assert.Equal(t, maxRateLimitWait, rateLimitWait(http.Header{}, now, 64), "a large attempt does not overflow")
+
+	header = http.Header{}
+	header.Set("x-rate-limit-reset", "1800000000")
+	assert.Equal(t, maxRateLimitWait, rateLimitWait(header, now, 0))
+}
+
+func TestSendRequestRefreshesOAuth2TokenOn401(t *testing.T) {
+	// setup returns a client whose API accepts only validToken, and counters
+	// for API calls and refresh-token grants.
+	setup := func(t *testing.T, validToken string) (*ApiClient, *int, *int) {
+		var apiCalls, refreshes int
+		tokenServer := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			refreshes++
+			w.Header().Set("Content-Type", "application/json")
+			json.NewEncoder(w).Encode(map[string]any{
+				"access_token":  "new-access",
+				"token_type":    "Bearer",
+				"expires_in":    7200,
+				"refresh_token": "new-refresh",
+			})
+		}))
+		t.Cleanup(tokenServer.Close)
+		apiServer := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			apiCalls++
+			w.Header().Set("Content-Type", "application/json")
+			if r.Header.Get("Authorization") != "Bearer "+validToken {
+				w.WriteHeader(http.StatusUnauthorized)
+				w.Write([]byte(`{"title":"Unauthorized","status":401}`))
+				return
+			}
+			w.Write([]byte(`{"data":{"id":"1"}}`))
+		}))
+		t.Cleanup(apiServer.Close)
+
+		tokenStore, tempDir := createTempTokenStore(t)
+		t.Cleanup(func() { os.RemoveAll(tempDir) })
+		expiry := uint64(time.Now().Add(time.Hour).Unix())
+		require.NoError(t, tokenStore.SaveOAuth2Token("alice", "old-access", "old-refresh", expiry))
+		require.NoError(t, tokenStore.SaveBearerToken("app-bearer"))
+		require.NoError(t, tokenStore.SaveOAuth1Tokens("at", "ts", "ck", "cs"))
+
+		a := auth.NewAuth(&config.Config{TokenURL: tokenServer.URL}).WithTokenStore(tokenStore)
+		return NewApiClient(&config.Config{APIBaseURL: apiServer.URL}, a), &apiCalls, &refreshes
+	}
+
+	t.Run("refreshes and replays once", func(t *testing.T) {
+		client, apiCalls, refreshes := setup(t, "new-access")
+		response, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "oauth2", Username: "alice"})
+		require.NoError(t, err)
+		assert.JSONEq(t, `{"data":{"id":"1"}}`, string(response))
+		assert.Equal(t, 2, *apiCalls)
+		assert.Equal(t, 1, *refreshes)
+	})
+
+	t.Run("auto-detected OAuth2 is refreshed too", func(t *testing.T) {
+		client, apiCalls, refreshes := setup(t, "new-access")
+		_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me"})
+		require.NoError(t, err)
+		assert.Equal(t, 2, *apiCalls)
+		assert.Equal(t, 1, *refreshes)
+	})
+
+	t.Run("never loops when the refreshed token is rejected too", func(t *testing.T) {
+		client, apiCalls, refreshes := setup(t, "never-valid")
+		_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "oauth2", Username: "alice"})
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), `"status":401`)
+		assert.Equal(t, 2, *apiCalls)
+		assert.Equal(t, 1, *refreshes)
+	})
+
+	t.Run("NoAutoRefresh surfaces the 401", func(t *testing.T) {
+		client, apiCalls, refreshes := setup(t, "new-access")
+		_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "oauth2", Username: "alice", NoAutoRefresh: true})
+		require.Error(t, err)
+		assert.Equal(t, 1, *apiCalls)
+		assert.Equal(t, 0, *refreshes)
+	})
+
+	for _, authType := range []string{"app", "oauth1"} {
+		t.Run(authType+" requests are not retried", func(t *testing.T) {
+			client, apiCalls, refreshes := setup(t, "new-access")
+			_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: authType})
+			require.Error(t, err)
+			assert.Equal(t, 1, *apiCalls)
+			assert.Equal(t, 0, *refreshes)
+		})
+	}
+}
@@ -0,0 +1,45 @@
+package api
+
+import (
+	"errors"
+	"fmt"
+	"net/http"
+	"os"
+	"strings"
+	"time"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// ErrNotModified is returned for a 304 Not Modified response, e.g. to a
+// request whose RequestOptions.IfModifiedSince the resource has not changed
+// since. Such a response has no body.
+var ErrNotModified = errors.New("not modified")
+
+// ParseTimeCondition parses a --time-cond value: an RFC 3339 timestamp, a
+// date (2006-01-02, midnight UTC), or "@path" for the modification time of
+// that file.
+func ParseTimeCondition(value string) (time.Time, error) {
+	if path, ok := strings.CutPrefix(value, "@"); ok {
+		info, err := os.Stat(path)
+		if err != nil {
+			return time.Time{}, xurlErrors.NewIOError(fmt.Errorf("cannot use the modification time of %s: %v", path, err))
+		}
+		return info.ModTime(), nil
+	}
+	for _, layout := range []string{time.RFC3339, time.DateOnly} {
+		if t, err := time.Parse(layout, value); err == nil {
+			return t, nil
+		}
+	}
+	return time.Time{}, xurlErrors.NewHTTPError(fmt.Errorf("invalid time condition %q: want an RFC 3339 time (2024-01-02T15:04:05Z), a date (2024-01-02), or @file", value))
+}
+
+// setIfModifiedSince adds an If-Modified-Since header for since to req, unless
+// since is zero or req already has one.
+func setIfModifiedSince(req *http.Request, since time.Time) {
+	if since.IsZero() || req.Header.Get("If-Modified-Since") != "" {
+		return
+	}
+	req.Header.Set("If-Modified-Since", since.UTC().Format(http.TimeFormat))
+}
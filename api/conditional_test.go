@@ -0,0 +1,106 @@
+package api
+
+import (
+	"bytes"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/xdevplatform/xurl/config"
+)
+
+func TestParseTimeCondition(t *testing.T) {
+	got, err := ParseTimeCondition("2024-03-01T10:00:00+01:00")
+	require.NoError(t, err)
+	assert.True(t, got.Equal(time.Date(2024, 3, 1, 9, 0, 0, 0, time.UTC)))
+
+	got, err = ParseTimeCondition("2024-03-01")
+	require.NoError(t, err)
+	assert.True(t, got.Equal(time.Date(2024, 3, 1, 0, 0, 0, 0, time.UTC)))
+
+	path := filepath.Join(t.TempDir(), "photo.jpg")
+	require.NoError(t, os.WriteFile(path, []byte("jpg"), 0600))
+	mtime := time.Date(2023, 12, 24, 18, 30, 0, 0, time.UTC)
+	require.NoError(t, os.Chtimes(path, mtime, mtime))
+	got, err = ParseTimeCondition("@" + path)
+	require.NoError(t, err)
+	assert.True(t, got.Equal(mtime))
+
+	_, err = ParseTimeCondition("@" + filepath.Join(t.TempDir(), "missing"))
+	assert.ErrorContains(t, err, "cannot use the modification time")
+
+	_, err = ParseTimeCondition("yesterday")
+	assert.ErrorContains(t, err, `invalid time condition "yesterday"`)
+}
+
+func TestIfModifiedSince(t *testing.T) {
+	lastModified := time.Date(2024, 3, 1, 0, 0, 0, 0, time.UTC)
+	var gotHeader string
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		gotHeader = r.Header.Get("If-Modified-Since")
+		if since, err := http.ParseTime(gotHeader); err == nil && !lastModified.After(since) {
+			w.WriteHeader(http.StatusNotModified)
+			return
+		}
+		w.Header().Set("Content-Type", "application/json")
+		w.Write([]byte(`{"data":{"id":"1"}}`))
+	}))
+	defer server.Close()
+
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock)
+
+	t.Run("unchanged", func(t *testing.T) {
+		_, resp, err := client.SendRequestWithResponse(RequestOptions{Method: "GET", Endpoint: "/2/users/me", IfModifiedSince: lastModified.Add(time.Hour)})
+		assert.ErrorIs(t, err, ErrNotModified)
+		require.NotNil(t, resp)
+		assert.Equal(t, "Fri, 01 Mar 2024 01:00:00 GMT", gotHeader)
+	})
+
+	t.Run("changed", func(t *testing.T) {
+		resp, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", IfModifiedSince: lastModified.Add(-time.Hour)})
+		require.NoError(t, err)
+		assert.JSONEq(t, `{"data":{"id":"1"}}`, string(resp))
+	})
+
+	t.Run("no condition", func(t *testing.T) {
+		_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me"})
+		require.NoError(t, err)
+		assert.Empty(t, gotHeader)
+	})
+
+	t.Run("-H wins", func(t *testing.T) {
+		_, err := client.SendRequest(RequestOptions{
+			Method:          "GET",
+			Endpoint:        "/2/users/me",
+			Headers:         []string{"If-Modified-Since: Thu, 01 Feb 2024 00:00:00 GMT"},
+			IfModifiedSince: lastModified.Add(time.Hour),
+		})
+		require.NoError(t, err)
+		assert.Equal(t, "Thu, 01 Feb 2024 00:00:00 GMT", gotHeader)
+	})
+
+	t.Run("a download leaves the file alone", func(t *testing.T) {
+		path := filepath.Join(t.TempDir(), "me.json")
+		require.NoError(t, os.WriteFile(path, []byte("cached"), 0600))
+		_, err := client.DownloadFile(RequestOptions{Endpoint: "/2/users/me", IfModifiedSince: lastModified}, path, nil)
+		assert.ErrorIs(t, err, ErrNotModified)
+		data, err := os.ReadFile(path)
+		require.NoError(t, err)
+		assert.Equal(t, "cached", string(data))
+	})
+
+	t.Run("HEAD", func(t *testing.T) {
+		var out bytes.Buffer
+		err := executeHeadRequest(&out, RequestOptions{Method: "HEAD", Endpoint: "/2/users/me", IfModifiedSince: lastModified}, client)
+		assert.ErrorIs(t, err, ErrNotModified)
+		assert.Contains(t, out.String(), "304 Not Modified")
+	})
+}
@@ -0,0 +1,287 @@
+package api
+
+import (
+	"bytes"
+	"encoding/csv"
+	"encoding/json"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"os"
+	"strings"
+	"sync"
+	"time"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// CSVResult is one NDJSON line written by ExecuteCSVRequests: the originating
+// row (by its 1-based data line number and column values) and either the
+// response body or the error. With an output template, the body is written to
+// File instead of being included.
+type CSVResult struct {
+	Line     int               `json:"line"`
+	Row      map[string]string `json:"row"`
+	Response json.RawMessage   `json:"response,omitempty"`
+	File     string            `json:"file,omitempty"`
+	Error    any               `json:"error,omitempty"`
+}
+
+// ExecuteCSVRequests sends one request per data row of the CSV file at csvPath.
+// The header row names the placeholders: every "{column}" in the endpoint and
+// body of options is replaced with that row's value. Results are written to out
+// as NDJSON, in completion order, with up to concurrency requests in flight.
+// A 429 pauses all workers and the row is retried. It returns an error if any
+// row failed, after every row has been attempted, unless policy is FailFast or
+// the failure is an auth error: then no more rows are sent.
+//
+// When options.Output.OutputTemplate is set, each response body (or API error
+// body) is written to the file it names (see checkOutputTemplate) rather than
+// to out. A template variable that cannot be resolved fails the row before
+// anything is written for it.
+func ExecuteCSVRequests(options RequestOptions, csvPath string, concurrency int, policy FailurePolicy, client Client, out io.Writer) error {
+	header, rows, err := readCSVRows(csvPath)
+	if err != nil {
+		return err
+	}
+	template := options.Output.OutputTemplate
+	if err := checkOutputTemplate(template, header); err != nil {
+		return err
+	}
+
+	if concurrency < 1 {
+		concurrency = 1
+	}
+
+	jobs := make(chan int)
+	var wg sync.WaitGroup
+	var outMu sync.Mutex
+	failures := &failureTracker{policy: policy.or(ContinueOnError)}
+	limiter := &rateLimitPause{}
+	files := map[string]int{}
+
+	for w := 0; w < concurrency; w++ {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			for i := range jobs {
+				row := csvRowMap(header, rows[i])
+				result := CSVResult{Line: i + 1, Row: row}
+
+				filled := fillCSVTemplate(options, row)
+				response, status, err := sendWithRateLimitRetry(filled, client, limiter)
+				if err != nil {
+					result.Error = errorValue(err)
+				} else {
+					result.Response = response
+				}
+
+				outMu.Lock()
+				if template != "" {
+					body := response
+					if raw, ok := result.Error.(json.RawMessage); ok {
+						body = raw
+					}
+					if body != nil {
+						file, writeErr := writeResultFile(template, templateResult{
+							Index: i + 1, Status: status, Row: row, Endpoint: filled.Endpoint, Response: body,
+						}, files)
+						if writeErr != nil {
+							err = writeErr
+							result.Error = writeErr.Error()
+						}
+						result.File = file
+						result.Response = nil
+					}
+				}
+				failures.record(fmt.Sprintf("line %d", i+1), err)
+				line, _ := json.Marshal(result)
+				fmt.Fprintln(out, string(line))
+				outMu.Unlock()
+			}
+		}()
+	}
+
+	sent := 0
+	for ; sent < len(rows) && !failures.stopped(); sent++ {
+		jobs <- sent
+	}
+	close(jobs)
+	wg.Wait()
+	return failures.err(sent)
+}
+
+// readCSVRows reads the header row and all data rows of a CSV file.
+func readCSVRows(csvPath string) ([]string, [][]string, error) {
+	file, err := os.Open(csvPath)
+	if err != nil {
+		return nil, nil, xurlErrors.NewIOError(fmt.Errorf("error opening CSV file: %v", err))
+	}
+	defer file.Close()
+
+	records, err := csv.NewReader(file).ReadAll()
+	if err != nil {
+		return nil, nil, xurlErrors.NewIOError(fmt.Errorf("error reading CSV file: %v", err))
+	}
+	if len(records) == 0 {
+		return nil, nil, xurlErrors.NewIOError(fmt.Errorf("CSV file %s has no header row", csvPath))
+	}
+
+	header := records[0]
+	for i := range header {
+		header[i] = strings.TrimSpace(header[i])
+	}
+
+	return header, records[1:], nil
+}
+
+func csvRowMap(header, record []string) map[string]string {
+	row := make(map[string]string, len(header))
+	for i, column := range header {
+		if i < len(record) {
+			row[column] = record[i]
+		} else {
+			row[column] = ""
+		}
+	}
+	return row
+}
+
+// fillCSVTemplate returns a copy of options with "{column}" placeholders filled
+// from row. Values are query-escaped in the endpoint; in the body they are
+// escaped for JSON when the body is a JSON template and query-escaped otherwise,
+// matching the content type BuildRequest will pick.
+func fillCSVTemplate(options RequestOptions, row map[string]string) RequestOptions {
+	jsonBody := strings.HasPrefix(strings.TrimSpace(options.Data), "{") || strings.HasPrefix(strings.TrimSpace(options.Data), "[")
+
+	var urlPairs, bodyPairs []string
+	for column, value := range row {
+		placeholder := "{" + column + "}"
+		urlPairs = append(urlPairs, placeholder, url.QueryEscape(value))
+		if jsonBody {
+			bodyPairs = append(bodyPairs, placeholder, jsonStringContent(value))
+		} else {
+			bodyPairs = append(bodyPairs, placeholder, url.QueryEscape(value))
+		}
+	}
+
+	filled := options
+	filled.Endpoint = strings.NewReplacer(urlPairs...).Replace(options.Endpoint)
+	filled.Data = strings.NewReplacer(bodyPairs...).Replace(options.Data)
+	return filled
+}
+
+// jsonStringContent escapes s for use inside a JSON string literal, without the
+// surrounding quotes and without HTML-escaping characters such as '&'.
+func jsonStringContent(s string) string {
+	var buf bytes.Buffer
+	enc := json.NewEncoder(&buf)
+	enc.SetEscapeHTML(false)
+	_ = enc.Encode(s)
+	encoded := strings.TrimSuffix(buf.String(), "\n")
+	return encoded[1 : len(encoded)-1]
+}
+
+// rateLimitPause is shared by the workers of a multi-request run so that one
+// 429 holds back every worker, not just the one that saw it. Each pause is
+// counted in stats, when set.
+type rateLimitPause struct {
+	mu    sync.Mutex
+	until time.Time
+	stats *RequestStats
+}
+
+func (p *rateLimitPause) wait() {
+	p.mu.Lock()
+	until := p.until
+	p.mu.Unlock()
+	if d := time.Until(until); d > 0 {
+		time.Sleep(d)
+	}
+}
+
+func (p *rateLimitPause) pauseFor(d time.Duration) {
+	p.stats.RecordRateLimitPause()
+	p.mu.Lock()
+	defer p.mu.Unlock()
+	if until := time.Now().Add(d); until.After(p.until) {
+		p.until = until
+	}
+}
+
+// sendWithRateLimitRetry sends options, retrying a 429 as options.Retry
+// allows, like sendWithRetries, but after a pause shared by every worker. As
+// with a single request, POST and PATCH are retried only when retryAllowed.
+// It also returns the final HTTP status, or 0 when none is known.
+func sendWithRateLimitRetry(options RequestOptions, client Client, limiter *rateLimitPause) (json.RawMessage, int, error) {
+	policy := options.Retry
+	// The retries are made here so that their pause holds every worker; the
+	// client sends each attempt only once.
+	options.Retry = RetryPolicy{}
+	var waited time.Duration
+	for attempt := 0; ; attempt++ {
+		limiter.wait()
+		response, status, header, err := sendWithStatus(options, client)
+		if err == nil || status != http.StatusTooManyRequests || attempt >= policy.MaxRetries || !retryAllowed(options) {
+			return response, status, err
+		}
+		wait := rateLimitWait(header, time.Now(), attempt)
+		if (policy.MaxTime > 0 && waited+wait > policy.MaxTime) || !policy.Budget.take() {
+			return response, status, err
+		}
+		waited += wait
+		limiter.pauseFor(wait)
+	}
+}
+
+// sendWithStatus sends options and reports the HTTP status and response
+// headers. Clients other than ApiClient don't expose them, so the status is
+// then taken from an API error's problem body, or assumed to be 200 on
+// success, and there are no headers.
+func sendWithStatus(options RequestOptions, client Client) (json.RawMessage, int, http.Header, error) {
+	if apiClient, ok := client.(*ApiClient); ok {
+		response, resp, err := apiClient.SendRequestWithResponse(options)
+		if resp == nil {
+			return response, 0, nil, err
+		}
+		return response, resp.StatusCode, resp.Header, err
+	}
+
+	response, err := client.SendRequest(options)
+	if err == nil {
+		return response, http.StatusOK, nil, nil
+	}
+	return response, problemStatus(err), nil, err
+}
+
+// isRateLimitError reports whether err is an API error for HTTP 429 (Too Many
+// Requests), as indicated by the problem body's "status" field.
+func isRateLimitError(err error) bool {
+	return problemStatus(err) == http.StatusTooManyRequests
+}
+
+// problemStatus returns the "status" field of an API error's problem body, or
+// 0 when err is not an API error or has none.
+func problemStatus(err error) int {
+	if !xurlErrors.IsAPIError(err) {
+		return 0
+	}
+	var problem struct {
+		Status int `json:"status"`
+	}
+	if json.Unmarshal([]byte(err.Error()), &problem) != nil {
+		return 0
+	}
+	return problem.Status
+}
+
+// errorValue renders err for a JSON result: an API error body is embedded as
+// JSON, anything else as its message.
+func errorValue(err error) any {
+	var raw json.RawMessage
+	if json.Unmarshal([]byte(err.Error()), &raw) == nil {
+		return raw
+	}
+	return err.Error()
+}
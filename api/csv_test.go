@@ -0,0 +1,243 @@
+package api
+
+import (
+	"bytes"
+	"encoding/json"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"sync"
+	"sync/atomic"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func writeTempCSV(t *testing.T, content string) string {
+	t.Helper()
+	path := filepath.Join(t.TempDir(), "input.csv")
+	require.NoError(t, os.WriteFile(path, []byte(content), 0600))
+	return path
+}
+
+func TestFillCSVTemplate(t *testing.T) {
+	row := map[string]string{"id": "123", "text": `say "hi" & bye`}
+
+	t.Run("endpoint values are query-escaped", func(t *testing.T) {
+		got := fillCSVTemplate(RequestOptions{Endpoint: "/2/tweets/{id}?q={text}"}, row)
+		assert.Equal(t, "/2/tweets/123?q=say+%22hi%22+%26+bye", got.Endpoint)
+	})
+
+	t.Run("JSON body values are JSON-escaped", func(t *testing.T) {
+		got := fillCSVTemplate(RequestOptions{Data: `{"text":"{text}","reply":{"in_reply_to_tweet_id":"{id}"}}`}, row)
+		assert.Equal(t, `{"text":"say \"hi\" & bye","reply":{"in_reply_to_tweet_id":"123"}}`, got.Data)
+		assert.True(t, json.Valid([]byte(got.Data)))
+	})
+
+	t.Run("unknown placeholders are left alone", func(t *testing.T) {
+		got := fillCSVTemplate(RequestOptions{Endpoint: "/2/users/{missing}"}, row)
+		assert.Equal(t, "/2/users/{missing}", got.Endpoint)
+	})
+}
+
+func TestExecuteCSVRequests(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "application/json")
+		if r.URL.Path == "/2/tweets/404" {
+			w.WriteHeader(http.StatusNotFound)
+			w.Write([]byte(`{"title":"Not Found Error","status":404}`))
+			return
+		}
+		body, _ := io.ReadAll(r.Body)
+		json.NewEncoder(w).Encode(map[string]string{"path": r.URL.Path, "body": string(body)})
+	}))
+	defer server.Close()
+	client := shortcutClient(t, server)
+
+	csvPath := writeTempCSV(t, "id,text\n1,hello\n2,world\n404,missing\n")
+	options := RequestOptions{Method: "POST", Endpoint: "/2/tweets/{id}", Data: `{"text":"{text}"}`}
+
+	var out bytes.Buffer
+	err := ExecuteCSVRequests(options, csvPath, 2, ContinueOnError, client, &out)
+	require.Error(t, err)
+	assert.Equal(t, "1 of 3 requests failed", err.Error())
+
+	results := map[int]CSVResult{}
+	for _, line := range strings.Split(strings.TrimSpace(out.String()), "\n") {
+		var result CSVResult
+		require.NoError(t, json.Unmarshal([]byte(line), &result))
+		results[result.Line] = result
+	}
+	require.Len(t, results, 3)
+
+	assert.Equal(t, map[string]string{"id": "1", "text": "hello"}, results[1].Row)
+	assert.JSONEq(t, `{"path":"/2/tweets/1","body":"{\"text\":\"hello\"}"}`, string(results[1].Response))
+	assert.JSONEq(t, `{"path":"/2/tweets/2","body":"{\"text\":\"world\"}"}`, string(results[2].Response))
+	assert.Nil(t, results[3].Response)
+	assert.Equal(t, map[string]any{"title": "Not Found Error", "status": float64(404)}, results[3].Error)
+}
+
+func TestExecuteCSVRequestsRetriesRateLimited(t *testing.T) {
+	fixedRateLimitBackoff(t, time.Millisecond)
+
+	var calls int32
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "application/json")
+		if atomic.AddInt32(&calls, 1) == 1 {
+			w.WriteHeader(http.StatusTooManyRequests)
+			w.Write([]byte(`{"title":"Too Many Requests","status":429}`))
+			return
+		}
+		w.Write([]byte(`{"data":{"id":"1"}}`))
+	}))
+	defer server.Close()
+	client := shortcutClient(t, server)
+
+	csvPath := writeTempCSV(t, "id\n1\n")
+
+	var out bytes.Buffer
+	err := ExecuteCSVRequests(RequestOptions{Method: "GET", Endpoint: "/2/tweets/{id}", Retry: RetryPolicy{MaxRetries: DefaultBatchRetries}}, csvPath, 1, ContinueOnError, client, &out)
+	require.NoError(t, err)
+	assert.Equal(t, int32(2), atomic.LoadInt32(&calls))
+	assert.Contains(t, out.String(), `"response":{"data":{"id":"1"}}`)
+
+	t.Run("the retry policy applies", func(t *testing.T) {
+		var calls int32
+		var mu sync.Mutex
+		var afterWait time.Duration
+		var last time.Time
+		server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			mu.Lock()
+			if atomic.AddInt32(&calls, 1) == 2 {
+				afterWait = time.Since(last)
+			}
+			last = time.Now()
+			mu.Unlock()
+			w.Header().Set("Content-Type", "application/json")
+			w.Header().Set("Retry-After", "1")
+			w.Header().Set("x-rate-limit-reset", strconv.FormatInt(time.Now().Add(time.Hour).Unix(), 10))
+			w.WriteHeader(http.StatusTooManyRequests)
+			w.Write([]byte(`{"title":"Too Many Requests","status":429}`))
+		}))
+		defer server.Close()
+		client := shortcutClient(t, server)
+
+		// Retry-After is waited, not the window reset, and --retry caps the
+		// retries.
+		err := ExecuteCSVRequests(RequestOptions{Method: "GET", Endpoint: "/2/tweets/{id}", Retry: RetryPolicy{MaxRetries: 1}}, csvPath, 1, ContinueOnError, client, io.Discard)
+		require.Error(t, err)
+		assert.Equal(t, int32(2), atomic.LoadInt32(&calls))
+		mu.Lock()
+		assert.GreaterOrEqual(t, afterWait, 900*time.Millisecond)
+		mu.Unlock()
+
+		// A wait beyond --retry-max-time is not made.
+		atomic.StoreInt32(&calls, 0)
+		err = ExecuteCSVRequests(RequestOptions{Method: "GET", Endpoint: "/2/tweets/{id}", Retry: RetryPolicy{MaxRetries: 3, MaxTime: 500 * time.Millisecond}}, csvPath, 1, ContinueOnError, client, io.Discard)
+		require.Error(t, err)
+		assert.Equal(t, int32(1), atomic.LoadInt32(&calls))
+	})
+}
+
+func TestExecuteCSVRequestsRequiresHeader(t *testing.T) {
+	csvPath := writeTempCSV(t, "")
+	err := ExecuteCSVRequests(RequestOptions{Endpoint: "/2/tweets/{id}"}, csvPath, 1, ContinueOnError, nil, io.Discard)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "no header row")
+}
+
+func TestExecuteCSVRequestsOutputTemplate(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "application/json")
+		switch r.URL.Path {
+		case "/2/users/by/username/ghost":
+			w.WriteHeader(http.StatusNotFound)
+			w.Write([]byte(`{"title":"Not Found Error","status":404}`))
+		case "/2/users/by/username/noid":
+			w.Write([]byte(`{"data":{}}`))
+		default:
+			w.Write([]byte(`{"data":{"id":"` + strings.TrimPrefix(r.URL.Path, "/2/users/by/username/") + `-id"}}`))
+		}
+	}))
+	defer server.Close()
+	client := shortcutClient(t, server)
+	dir := t.TempDir()
+	csvPath := writeTempCSV(t, "handle\nalice\nghost\nbob\n")
+
+	t.Run("index and status", func(t *testing.T) {
+		options := RequestOptions{Method: "GET", Endpoint: "/2/users/by/username/{handle}?user.fields=id",
+			Output: OutputOptions{OutputTemplate: filepath.Join(dir, "status", "resp-{index}-{status}-{query.user.fields}.json")}}
+		var out bytes.Buffer
+		err := ExecuteCSVRequests(options, csvPath, 2, ContinueOnError, client, &out)
+		require.Error(t, err, "the 404 row still fails")
+
+		data, err := os.ReadFile(filepath.Join(dir, "status", "resp-1-200-id.json"))
+		require.NoError(t, err)
+		assert.JSONEq(t, `{"data":{"id":"alice-id"}}`, string(data))
+		data, err = os.ReadFile(filepath.Join(dir, "status", "resp-2-404-id.json"))
+		require.NoError(t, err)
+		assert.JSONEq(t, `{"title":"Not Found Error","status":404}`, string(data))
+		assert.FileExists(t, filepath.Join(dir, "status", "resp-3-200-id.json"))
+		assert.NotContains(t, out.String(), `"response"`)
+		assert.Contains(t, out.String(), `"file":"`+filepath.Join(dir, "status", "resp-1-200-id.json")+`"`)
+	})
+
+	t.Run("response field and CSV column", func(t *testing.T) {
+		csvPath := writeTempCSV(t, "handle\nalice\nnoid\n")
+		options := RequestOptions{Method: "GET", Endpoint: "/2/users/by/username/{handle}",
+			Output: OutputOptions{OutputTemplate: filepath.Join(dir, "fields", "{handle}-{response.data.id}.json")}}
+		var out bytes.Buffer
+		err := ExecuteCSVRequests(options, csvPath, 1, ContinueOnError, client, &out)
+		require.Error(t, err)
+		assert.Equal(t, "1 of 2 requests failed", err.Error())
+
+		assert.FileExists(t, filepath.Join(dir, "fields", "alice-alice-id.json"))
+		entries, err := os.ReadDir(filepath.Join(dir, "fields"))
+		require.NoError(t, err)
+		assert.Len(t, entries, 1, "nothing is written for an unresolved field")
+		assert.Contains(t, out.String(), "the response has no data.id")
+	})
+
+	t.Run("unknown variables fail before any request", func(t *testing.T) {
+		options := RequestOptions{Method: "GET", Endpoint: "/2/users/by/username/{handle}",
+			Output: OutputOptions{OutputTemplate: "{nope}.json"}}
+		err := ExecuteCSVRequests(options, csvPath, 1, ContinueOnError, nil, io.Discard)
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), "unknown output template variable {nope}")
+	})
+}
+
+func TestFillOutputTemplate(t *testing.T) {
+	result := templateResult{
+		Index:    3,
+		Status:   201,
+		Row:      map[string]string{"name": "a/b"},
+		Endpoint: "/2/tweets?ids=1,2",
+		Response: json.RawMessage(`{"data":[{"id":"42","n":7,"ok":true,"obj":{}}]}`),
+	}
+
+	got, err := fillOutputTemplate("{index}-{status}-{name}-{query.ids}-{response.data.0.id}-{response.data.0.n}-{response.data.0.ok}.json", result)
+	require.NoError(t, err)
+	assert.Equal(t, "3-201-a_b-1,2-42-7-true.json", got, "path separators in values are replaced")
+
+	for template, want := range map[string]string{
+		"{response.data.1.id}":   "the response has no data.1.id",
+		"{response.data.0.obj}":  "is not a string or number",
+		"{query.missing}":        "{query.missing} is empty",
+		"{response.data.0.x.id}": "the response has no data.0.x.id",
+	} {
+		_, err := fillOutputTemplate(template, result)
+		require.Error(t, err, template)
+		assert.Contains(t, err.Error(), want, template)
+	}
+
+	assert.NoError(t, checkOutputTemplate("{index}.json", nil))
+	assert.Error(t, checkOutputTemplate("fixed.json", nil), "a constant name would overwrite itself")
+	assert.Error(t, checkOutputTemplate("{response.}.json", nil))
+}
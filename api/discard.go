@@ -0,0 +1,87 @@
+package api
+
+import (
+	"fmt"
+	"io"
+	"net/http"
+	"os"
+	"slices"
+	"time"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// DiscardResult describes a response whose body SendRequestDiscard read and
+// dropped.
+type DiscardResult struct {
+	// Proto and Status are the response's protocol and status line, e.g.
+	// "HTTP/1.1" and "200 OK".
+	Proto  string
+	Status string
+	// Bytes is the length of the body after any Content-Encoding is undone.
+	Bytes int64
+	// Headers is the time until the response headers arrived, Total the time
+	// until the body was read to its end.
+	Headers time.Duration
+	Total   time.Duration
+}
+
+// SendRequestDiscard sends the request described by options and reads the
+// whole response body, decoding it as for SendRequest, but keeps none of it.
+// It is meant for checking that an endpoint responds, and how fast, without
+// the cost of formatting the body. A failure status that options.AcceptStatus
+// does not list is an error, returned along with the result.
+func (c *ApiClient) SendRequestDiscard(options RequestOptions) (DiscardResult, error) {
+	req, err := c.BuildRequest(options)
+	if err != nil {
+		return DiscardResult{}, err
+	}
+	c.logRequest(req, options.Verbose)
+	if options.VerboseBody {
+		logRequestBody(os.Stderr, req)
+	}
+
+	// As for downloads, a large body can take longer than the regular request
+	// timeout.
+	client := &http.Client{Timeout: 0, Transport: c.client.Transport}
+	c.throttle.wait(throttleLog(options.Verbose))
+	start := time.Now()
+	resp, err := c.do(client, req)
+	if err != nil {
+		return DiscardResult{}, xurlErrors.NewHTTPError(err)
+	}
+	defer resp.Body.Close()
+	result := DiscardResult{Proto: resp.Proto, Status: resp.Status, Headers: time.Since(start)}
+	c.recordRateLimit(resp)
+	logSupportHeaders(os.Stderr, resp, options.Trace && !options.Verbose)
+
+	body, err := decodedBody(resp, resp.Body)
+	if err != nil {
+		return result, err
+	}
+	result.Bytes, err = io.Copy(io.Discard, body)
+	result.Total = time.Since(start)
+	if err != nil {
+		return result, xurlErrors.NewIOError(fmt.Errorf("error reading response: %v", err))
+	}
+	if resp.StatusCode >= 400 && !slices.Contains(options.AcceptStatus, resp.StatusCode) {
+		return result, fmt.Errorf("request failed: %s", resp.Status)
+	}
+	return result, nil
+}
+
+// ExecuteDiscardRequest sends options with SendRequestDiscard and writes one
+// line of status and timing to w in place of the body. The line is written
+// for a failure status too, before its error is returned.
+func ExecuteDiscardRequest(w io.Writer, options RequestOptions, client *ApiClient) error {
+	result, err := client.SendRequestDiscard(options)
+	if result.Status == "" {
+		return handleRequestError(err)
+	}
+	line := fmt.Sprintf("%s %s: %d bytes in %s (headers after %s)\n",
+		result.Proto, result.Status, result.Bytes, result.Total.Round(time.Millisecond), result.Headers.Round(time.Millisecond))
+	if writeErr := writeText(w, line); writeErr != nil {
+		return writeErr
+	}
+	return err
+}
@@ -0,0 +1,63 @@
+package api
+
+import (
+	"bytes"
+	"compress/gzip"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/xdevplatform/xurl/config"
+)
+
+func TestExecuteDiscardRequest(t *testing.T) {
+	body := strings.Repeat(`{"data":{"id":"1"}}`, 1000)
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "application/json")
+		switch r.URL.Path {
+		case "/2/tweets/0":
+			w.WriteHeader(http.StatusNotFound)
+			w.Write([]byte(`{"title":"Not Found Error","status":404}`))
+		case "/2/gzip":
+			w.Header().Set("Content-Encoding", "gzip")
+			gz := gzip.NewWriter(w)
+			gz.Write([]byte(body))
+			gz.Close()
+		default:
+			w.Write([]byte(body))
+		}
+	}))
+	defer server.Close()
+
+	mockAuth, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, mockAuth)
+
+	t.Run("only the status, size, and timing are printed", func(t *testing.T) {
+		var out bytes.Buffer
+		require.NoError(t, ExecuteDiscardRequest(&out, RequestOptions{Method: "GET", Endpoint: "/2/tweets/1", AuthType: "app"}, client))
+		assert.Regexp(t, `^HTTP/1\.1 200 OK: 19000 bytes in \S+ \(headers after \S+\)\n$`, out.String())
+	})
+
+	t.Run("a gzip body is counted decoded", func(t *testing.T) {
+		var out bytes.Buffer
+		require.NoError(t, ExecuteDiscardRequest(&out, RequestOptions{Method: "GET", Endpoint: "/2/gzip", AuthType: "app", Headers: []string{"Accept-Encoding: gzip"}}, client))
+		assert.Contains(t, out.String(), ": 19000 bytes in ")
+	})
+
+	t.Run("a failure status is printed and returned as an error", func(t *testing.T) {
+		var out bytes.Buffer
+		err := ExecuteDiscardRequest(&out, RequestOptions{Method: "GET", Endpoint: "/2/tweets/0", AuthType: "app"}, client)
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), "404")
+		assert.True(t, strings.HasPrefix(out.String(), "HTTP/1.1 404 Not Found: 40 bytes"), out.String())
+
+		out.Reset()
+		require.NoError(t, ExecuteDiscardRequest(&out, RequestOptions{Method: "GET", Endpoint: "/2/tweets/0", AuthType: "app", AcceptStatus: []int{404}}, client))
+	})
+}
@@ -0,0 +1,232 @@
+package api
+
+import (
+	"bufio"
+	"compress/gzip"
+	"fmt"
+	"io"
+	"net/http"
+	"os"
+	"path/filepath"
+	"time"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// DownloadFile fetches options.Endpoint (an API path or an absolute URL) and
+// streams the body to path ("-" for stdout) without holding it in memory.
+// Redirects are followed. Credentials are applied only when the URL points at
+// the API host or another X host (see sendsCredentialsTo), or with
+// options.ForceAuth; export files handed out on other hosts (e.g. pre-signed
+// storage URLs) are fetched without them. Progress is written to progress when it is
+// non-nil. It returns the number of bytes written.
+func (c *ApiClient) DownloadFile(options RequestOptions, path string, progress io.Writer) (int64, error) {
+	options.Method = "GET"
+	if options.Accept == "" {
+		// A download can be any kind of file, not just JSON.
+		options.Accept = "*/*"
+	}
+
+	var req *http.Request
+	var err error
+	if options.ForceAuth || c.sendsCredentialsTo(options.Endpoint) {
+		req, err = c.BuildRequest(options)
+	} else {
+		req, err = buildUnauthenticatedRequest(options)
+	}
+	if err != nil {
+		return 0, err
+	}
+	return c.streamToFile(req, options, path, progress)
+}
+
+// SendRequestToFile sends the request described by options (any method, with
+// its body and credentials as for SendRequest) and streams a successful
+// response body to path ("-" for stdout) instead of decoding it, so large
+// responses are never held in memory. An error response is returned as an API
+// error, as from SendRequest.
+func (c *ApiClient) SendRequestToFile(options RequestOptions, path string, progress io.Writer) (int64, error) {
+	req, err := c.BuildRequest(options)
+	if err != nil {
+		return 0, err
+	}
+	return c.streamToFile(req, options, path, progress)
+}
+
+// streamToFile performs req without a client timeout and copies a successful
+// response body to path.
+func (c *ApiClient) streamToFile(req *http.Request, options RequestOptions, path string, progress io.Writer) (int64, error) {
+	c.logRequest(req, options.Verbose)
+	if options.VerboseBody {
+		logRequestBody(os.Stderr, req)
+	}
+
+	// Large bodies can take longer than the regular request timeout.
+	client := &http.Client{Timeout: 0, Transport: c.client.Transport}
+	c.throttle.wait(throttleLog(options.Verbose))
+	resp, err := c.do(client, req)
+	if err != nil {
+		return 0, xurlErrors.NewHTTPError(err)
+	}
+	defer resp.Body.Close()
+	c.recordRateLimit(resp)
+
+	logSupportHeaders(os.Stderr, resp, options.Trace && !options.Verbose)
+	if resp.StatusCode >= 400 {
+		_, err := c.processResponse(resp, options.Verbose, options.VerboseBody, nil, options.MaxResponseSize)
+		return 0, err
+	}
+	if resp.StatusCode == http.StatusNotModified {
+		// Leave the existing file alone.
+		return 0, ErrNotModified
+	}
+
+	var body io.Reader = resp.Body
+	if progress != nil {
+		p := &downloadProgress{w: progress, total: resp.ContentLength}
+		defer p.finish()
+		body = io.TeeReader(resp.Body, p)
+	}
+	body, err = decodedBody(resp, body)
+	if err != nil {
+		return 0, err
+	}
+	if options.Decompress {
+		if body, err = gunzipIfCompressed(body); err != nil {
+			return 0, err
+		}
+	}
+
+	if path == "-" {
+		w := bufio.NewWriter(os.Stdout)
+		n, err := io.Copy(w, body)
+		if err == nil {
+			err = w.Flush()
+		}
+		if err != nil {
+			return n, xurlErrors.NewIOError(err)
+		}
+		return n, nil
+	}
+	if options.AppendOutput {
+		return appendToFile(path, body)
+	}
+	return writeFileAtomically(path, body)
+}
+
+// gunzipIfCompressed inflates r when it starts with the gzip magic bytes, as a
+// .gz export does, and returns it unchanged otherwise.
+func gunzipIfCompressed(r io.Reader) (io.Reader, error) {
+	br := bufio.NewReader(r)
+	magic, err := br.Peek(2)
+	if err != nil || magic[0] != 0x1f || magic[1] != 0x8b {
+		return br, nil
+	}
+	gz, err := gzip.NewReader(br)
+	if err != nil {
+		return nil, xurlErrors.NewIOError(fmt.Errorf("error decompressing response: %v", err))
+	}
+	return gz, nil
+}
+
+// buildUnauthenticatedRequest builds a GET for a URL outside the API, with the
+// user's headers but no Authorization.
+func buildUnauthenticatedRequest(options RequestOptions) (*http.Request, error) {
+	req, err := http.NewRequest("GET", options.Endpoint, nil)
+	if err != nil {
+		return nil, xurlErrors.NewHTTPError(err)
+	}
+	removed := addHeaderArgs(req.Header, options.Headers)
+	if !userSetHeader(req.Header, "Accept") && !removed["Accept"] && options.Accept != "" {
+		req.Header.Set("Accept", options.Accept)
+	}
+	setIfModifiedSince(req, options.IfModifiedSince)
+	setUserAgent(req.Header, removed)
+	return req, nil
+}
+
+// writeFileAtomically copies r into a temporary file next to path and renames
+// it into place, so an interrupted download never leaves a truncated file under
+// the requested name.
+func writeFileAtomically(path string, r io.Reader) (int64, error) {
+	tmp, err := os.CreateTemp(filepath.Dir(path), ".xurl-download-*")
+	if err != nil {
+		return 0, xurlErrors.NewIOError(fmt.Errorf("error creating output file: %v", err))
+	}
+	defer os.Remove(tmp.Name())
+
+	n, err := io.Copy(tmp, r)
+	if err != nil {
+		tmp.Close()
+		return n, xurlErrors.NewIOError(fmt.Errorf("error writing %s: %v", path, err))
+	}
+	if err := tmp.Close(); err != nil {
+		return n, xurlErrors.NewIOError(fmt.Errorf("error writing %s: %v", path, err))
+	}
+	if err := os.Rename(tmp.Name(), path); err != nil {
+		return n, xurlErrors.NewIOError(fmt.Errorf("error writing %s: %v", path, err))
+	}
+	return n, nil
+}
+
+// appendToFile copies r to the end of path, creating it if needed, so that
+// successive responses accumulate (e.g. as NDJSON).
+func appendToFile(path string, r io.Reader) (int64, error) {
+	f, err := os.OpenFile(path, os.O_WRONLY|os.O_CREATE|os.O_APPEND, 0644)
+	if err != nil {
+		return 0, xurlErrors.NewIOError(fmt.Errorf("error opening output file: %v", err))
+	}
+	n, err := io.Copy(f, r)
+	if closeErr := f.Close(); err == nil {
+		err = closeErr
+	}
+	if err != nil {
+		return n, xurlErrors.NewIOError(fmt.Errorf("error writing %s: %v", path, err))
+	}
+	return n, nil
+}
+
+// downloadProgress counts bytes written through it and redraws a one-line
+// progress indicator at most a few times per second.
+type downloadProgress struct {
+	w       io.Writer
+	total   int64
+	written int64
+	last    time.Time
+}
+
+func (p *downloadProgress) Write(b []byte) (int, error) {
+	p.written += int64(len(b))
+	if time.Since(p.last) >= 200*time.Millisecond {
+		p.last = time.Now()
+		p.draw()
+	}
+	return len(b), nil
+}
+
+func (p *downloadProgress) draw() {
+	if p.total > 0 {
+		fmt.Fprintf(p.w, "\rDownloaded %s of %s (%d%%)", formatBytes(p.written), formatBytes(p.total), p.written*100/p.total)
+	} else {
+		fmt.Fprintf(p.w, "\rDownloaded %s", formatBytes(p.written))
+	}
+}
+
+func (p *downloadProgress) finish() {
+	p.draw()
+	fmt.Fprintln(p.w)
+}
+
+// formatBytes renders n with a binary unit suffix, e.g. "1.5 MiB".
+func formatBytes(n int64) string {
+	const unit = 1024
+	if n < unit {
+		return fmt.Sprintf("%d B", n)
+	}
+	div, exp := int64(unit), 0
+	for m := n / unit; m >= unit; m /= unit {
+		div *= unit
+		exp++
+	}
+	return fmt.Sprintf("%.1f %ciB", float64(n)/float64(div), "KMGTPE"[exp])
+}
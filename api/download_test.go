@@ -0,0 +1,239 @@
+package api
+
+import (
+	"bytes"
+	"compress/gzip"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/xdevplatform/xurl/config"
+)
+
+func TestDownloadFile(t *testing.T) {
+	payload := strings.Repeat("export-data ", 10000)
+
+	var gotAuth []string
+	storage := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		gotAuth = append(gotAuth, r.Header.Get("Authorization"))
+		w.Write([]byte(payload))
+	}))
+	defer storage.Close()
+
+	apiServer := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		gotAuth = append(gotAuth, r.Header.Get("Authorization"))
+		switch r.URL.Path {
+		case "/2/exports/1/file":
+			w.Write([]byte(payload))
+		case "/2/exports/2/file":
+			http.Redirect(w, r, storage.URL+"/bucket/2.jsonl", http.StatusFound)
+		default:
+			w.Header().Set("Content-Type", "application/json")
+			w.WriteHeader(http.StatusNotFound)
+			w.Write([]byte(`{"title":"Not Found","status":404}`))
+		}
+	}))
+	defer apiServer.Close()
+
+	mockAuth, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: apiServer.URL}, mockAuth)
+	outDir := t.TempDir()
+
+	t.Run("API path is fetched with credentials", func(t *testing.T) {
+		gotAuth = nil
+		path := filepath.Join(outDir, "1.jsonl")
+		n, err := client.DownloadFile(RequestOptions{Endpoint: "/2/exports/1/file", AuthType: "app"}, path, nil)
+		require.NoError(t, err)
+		assert.Equal(t, int64(len(payload)), n)
+
+		data, err := os.ReadFile(path)
+		require.NoError(t, err)
+		assert.Equal(t, payload, string(data))
+		assert.Equal(t, []string{"Bearer test-bearer-token"}, gotAuth)
+	})
+
+	t.Run("redirects are followed", func(t *testing.T) {
+		path := filepath.Join(outDir, "2.jsonl")
+		_, err := client.DownloadFile(RequestOptions{Endpoint: "/2/exports/2/file", AuthType: "app"}, path, nil)
+		require.NoError(t, err)
+
+		data, err := os.ReadFile(path)
+		require.NoError(t, err)
+		assert.Equal(t, payload, string(data))
+	})
+
+	t.Run("other hosts are fetched without credentials", func(t *testing.T) {
+		gotAuth = nil
+		path := filepath.Join(outDir, "3.jsonl")
+		_, err := client.DownloadFile(RequestOptions{Endpoint: storage.URL + "/bucket/3.jsonl", AuthType: "app"}, path, nil)
+		require.NoError(t, err)
+		assert.Equal(t, []string{""}, gotAuth)
+	})
+
+	t.Run("errors leave no file behind", func(t *testing.T) {
+		path := filepath.Join(outDir, "missing.jsonl")
+		_, err := client.DownloadFile(RequestOptions{Endpoint: "/2/exports/404/file", AuthType: "app"}, path, nil)
+		require.Error(t, err)
+		assert.NoFileExists(t, path)
+
+		entries, err := os.ReadDir(outDir)
+		require.NoError(t, err)
+		for _, e := range entries {
+			assert.False(t, strings.HasPrefix(e.Name(), ".xurl-download-"), "temporary file left behind: %s", e.Name())
+		}
+	})
+}
+
+func TestSendRequestToFile(t *testing.T) {
+	payload := strings.Repeat(`{"id":"1"}`+"\n", 10000)
+
+	var gotMethod, gotBody string
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		gotMethod = r.Method
+		body, _ := io.ReadAll(r.Body)
+		gotBody = string(body)
+		if r.URL.Path != "/2/exports" {
+			w.Header().Set("Content-Type", "application/json")
+			w.WriteHeader(http.StatusBadRequest)
+			w.Write([]byte(`{"title":"Invalid Request","status":400}`))
+			return
+		}
+		w.Write([]byte(payload))
+	}))
+	defer server.Close()
+
+	mockAuth, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, mockAuth)
+
+	t.Run("streams the body of any request to the file", func(t *testing.T) {
+		path := filepath.Join(t.TempDir(), "export.jsonl")
+		n, err := client.SendRequestToFile(RequestOptions{Method: "POST", Endpoint: "/2/exports", Data: `{"q":"x"}`, AuthType: "app"}, path, nil)
+		require.NoError(t, err)
+		assert.Equal(t, int64(len(payload)), n)
+		assert.Equal(t, "POST", gotMethod)
+		assert.Equal(t, `{"q":"x"}`, gotBody)
+
+		data, err := os.ReadFile(path)
+		require.NoError(t, err)
+		assert.Equal(t, payload, string(data))
+	})
+
+	t.Run("error responses are returned, not written", func(t *testing.T) {
+		path := filepath.Join(t.TempDir(), "bad.jsonl")
+		_, err := client.SendRequestToFile(RequestOptions{Method: "GET", Endpoint: "/2/bad", AuthType: "app"}, path, nil)
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), `"status":400`)
+		assert.NoFileExists(t, path)
+	})
+
+	t.Run("append mode keeps earlier responses", func(t *testing.T) {
+		path := filepath.Join(t.TempDir(), "export.jsonl")
+		options := RequestOptions{Method: "POST", Endpoint: "/2/exports", AuthType: "app", AppendOutput: true}
+		_, err := client.SendRequestToFile(options, path, nil)
+		require.NoError(t, err)
+		_, err = client.SendRequestToFile(options, path, nil)
+		require.NoError(t, err)
+
+		data, err := os.ReadFile(path)
+		require.NoError(t, err)
+		assert.Equal(t, payload+payload, string(data))
+	})
+
+	t.Run("without append mode the file is replaced", func(t *testing.T) {
+		path := filepath.Join(t.TempDir(), "export.jsonl")
+		require.NoError(t, os.WriteFile(path, []byte("old\n"), 0644))
+		_, err := client.SendRequestToFile(RequestOptions{Method: "POST", Endpoint: "/2/exports", AuthType: "app"}, path, nil)
+		require.NoError(t, err)
+
+		data, err := os.ReadFile(path)
+		require.NoError(t, err)
+		assert.Equal(t, payload, string(data))
+	})
+}
+
+func TestDownloadFileGzip(t *testing.T) {
+	payload := strings.Repeat(`{"id":"1"}`+"\n", 1000)
+	var compressed bytes.Buffer
+	gz := gzip.NewWriter(&compressed)
+	gz.Write([]byte(payload))
+	require.NoError(t, gz.Close())
+
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		switch r.URL.Path {
+		case "/exports/1.jsonl.gz":
+			w.Header().Set("Content-Type", "application/gzip")
+		case "/exports/encoded.jsonl":
+			w.Header().Set("Content-Encoding", "gzip")
+		case "/exports/plain.jsonl":
+			w.Write([]byte(payload))
+			return
+		}
+		w.Write(compressed.Bytes())
+	}))
+	defer server.Close()
+
+	client := NewApiClient(&config.Config{APIBaseURL: "https://api.x.com"}, nil)
+
+	download := func(t *testing.T, options RequestOptions) string {
+		path := filepath.Join(t.TempDir(), "out")
+		_, err := client.DownloadFile(options, path, nil)
+		require.NoError(t, err)
+		data, err := os.ReadFile(path)
+		require.NoError(t, err)
+		return string(data)
+	}
+
+	t.Run("a .gz file is kept compressed by default", func(t *testing.T) {
+		assert.Equal(t, compressed.String(), download(t, RequestOptions{Endpoint: server.URL + "/exports/1.jsonl.gz"}))
+	})
+
+	t.Run("--decompress inflates a .gz file", func(t *testing.T) {
+		assert.Equal(t, payload, download(t, RequestOptions{Endpoint: server.URL + "/exports/1.jsonl.gz", Decompress: true}))
+	})
+
+	t.Run("--decompress leaves uncompressed files alone", func(t *testing.T) {
+		assert.Equal(t, payload, download(t, RequestOptions{Endpoint: server.URL + "/exports/plain.jsonl", Decompress: true}))
+	})
+
+	t.Run("a gzip Content-Encoding is undone even when requested explicitly", func(t *testing.T) {
+		options := RequestOptions{Endpoint: server.URL + "/exports/encoded.jsonl", Headers: []string{"Accept-Encoding: gzip"}}
+		assert.Equal(t, payload, download(t, options))
+	})
+}
+
+func TestProcessResponseDecodesGzip(t *testing.T) {
+	var compressed bytes.Buffer
+	gz := gzip.NewWriter(&compressed)
+	gz.Write([]byte(`{"data":{"id":"1"}}`))
+	require.NoError(t, gz.Close())
+
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "application/json")
+		w.Header().Set("Content-Encoding", "gzip")
+		w.Write(compressed.Bytes())
+	}))
+	defer server.Close()
+
+	mockAuth, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, mockAuth)
+
+	response, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "app", Headers: []string{"Accept-Encoding: gzip"}})
+	require.NoError(t, err)
+	assert.JSONEq(t, `{"data":{"id":"1"}}`, string(response))
+}
+
+func TestFormatBytes(t *testing.T) {
+	assert.Equal(t, "512 B", formatBytes(512))
+	assert.Equal(t, "1.5 KiB", formatBytes(1536))
+	assert.Equal(t, "2.0 MiB", formatBytes(2*1024*1024))
+}
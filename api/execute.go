@@ -2,19 +2,68 @@ package api
 
 import (
 	"encoding/json"
+	"errors"
 	"fmt"
+	"io"
+	"net/http"
+	"os"
+	"sort"
+	"strings"
+
 	"github.com/xdevplatform/xurl/utils"
 )
 
 // ExecuteRequest handles the execution of a regular API request
 func ExecuteRequest(options RequestOptions, client Client) error {
+	if apiClient, ok := client.(*ApiClient); ok && strings.EqualFold(options.Method, "HEAD") {
+		return executeHeadRequest(os.Stdout, options, apiClient)
+	}
 
-	response, clientErr := client.SendRequest(options)
+	response, contentType, clientErr := sendForOutput(options, client)
 	if clientErr != nil {
 		return handleRequestError(clientErr)
 	}
 
-	return utils.FormatAndPrintResponse(response)
+	return printResponse(response, contentType, options.Output)
+}
+
+// executeHeadRequest sends a HEAD request, which has no body to print, and
+// writes its status line and headers to w instead. A failure status is still
+// an error once they are written.
+func executeHeadRequest(w io.Writer, options RequestOptions, client *ApiClient) error {
+	_, resp, err := client.SendRequestWithResponse(options)
+	if resp == nil {
+		return handleRequestError(err)
+	}
+	if writeErr := writeStatusAndHeaders(w, resp); writeErr != nil {
+		return writeErr
+	}
+	if errors.Is(err, ErrNotModified) {
+		return err
+	}
+	if err != nil {
+		return fmt.Errorf("request failed: %s", resp.Status)
+	}
+	return nil
+}
+
+// writeStatusAndHeaders writes resp's status line and its headers, sorted by
+// name, one per line.
+func writeStatusAndHeaders(w io.Writer, resp *http.Response) error {
+	names := make([]string, 0, len(resp.Header))
+	for name := range resp.Header {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+
+	var b strings.Builder
+	fmt.Fprintf(&b, "%s %s\n", resp.Proto, resp.Status)
+	for _, name := range names {
+		for _, value := range resp.Header[name] {
+			fmt.Fprintf(&b, "%s: %s\n", name, value)
+		}
+	}
+	return writeText(w, b.String())
 }
 
 // ExecuteStreamRequest handles the execution of a streaming API request
@@ -43,13 +92,18 @@ func handleRequestError(clientErr error) error {
 
 // HandleRequest determines the type of request and executes it accordingly
 func HandleRequest(options RequestOptions, forceStream bool, mediaFile string, client Client) error {
+	// A raw body (-d @file / -d @-) on an append endpoint is a media chunk.
+	if mediaFile == "" && options.Body != nil {
+		mediaFile = "-"
+	}
+
 	if IsMediaAppendRequest(options.Endpoint, mediaFile) {
 		response, err := HandleMediaAppendRequest(options, mediaFile, client)
 		if err != nil {
 			return err
 		}
 
-		return utils.FormatAndPrintResponse(response)
+		return printResponse(response, "", options.Output)
 	}
 
 	shouldStream := forceStream || IsStreamingEndpoint(options.Endpoint)
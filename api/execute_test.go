@@ -4,6 +4,9 @@ import (
 	"bytes"
 	"fmt"
 	"io"
+	"net/http"
+	"net/http/httptest"
+	"os"
 	"strings"
 	"testing"
 
@@ -11,6 +14,7 @@ import (
 	"github.com/stretchr/testify/assert"
 	"github.com/stretchr/testify/require"
 
+	"github.com/xdevplatform/xurl/config"
 	xurlErrors "github.com/xdevplatform/xurl/errors"
 )
 
@@ -27,6 +31,49 @@ func redirectColor(w io.Writer) func() {
 	}
 }
 
+// captureFile points *f (os.Stdout or os.Stderr) at a temporary file until the
+// test ends, and returns a func that reads what was written to it.
+func captureFile(t *testing.T, f **os.File) func() string {
+	t.Helper()
+	tmp, err := os.CreateTemp(t.TempDir(), "output")
+	require.NoError(t, err)
+	old := *f
+	*f = tmp
+	t.Cleanup(func() {
+		*f = old
+		tmp.Close()
+	})
+	return func() string {
+		data, err := os.ReadFile(tmp.Name())
+		require.NoError(t, err)
+		return string(data)
+	}
+}
+
+func TestExecuteRequestStdoutHoldsOnlyTheBody(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "application/json")
+		w.Header().Set("X-Rate-Limit-Remaining", "899")
+		w.Header().Set("X-Transaction-Id", "abc123")
+		w.Write([]byte(`{"data":{"id":"1"}}`))
+	}))
+	defer server.Close()
+
+	mockAuth, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, mockAuth)
+
+	stdout := captureFile(t, &os.Stdout)
+	stderr := captureFile(t, &os.Stderr)
+	defer redirectColor(os.Stdout)()
+
+	require.NoError(t, ExecuteRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "app", Verbose: true, VerboseBody: true, Trace: true}, client))
+	assert.JSONEq(t, `{"data":{"id":"1"}}`, stdout())
+	assert.Contains(t, stderr(), "/2/users/me")
+	assert.Contains(t, stderr(), "X-Rate-Limit-Remaining")
+	assert.Contains(t, stderr(), "abc123")
+}
+
 func TestHandleRequestError(t *testing.T) {
 	t.Run("non-JSON error is returned unchanged and prints nothing", func(t *testing.T) {
 		var buf bytes.Buffer
@@ -53,3 +100,44 @@ func TestHandleRequestError(t *testing.T) {
 		assert.Contains(t, buf.String(), "bad request", "the JSON error body should be printed")
 	})
 }
+
+func TestExecuteHeadRequest(t *testing.T) {
+	var gotMethod string
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		gotMethod = r.Method
+		w.Header().Set("Content-Type", "application/json")
+		w.Header().Set("X-Rate-Limit-Remaining", "899")
+		if r.URL.Path == "/2/tweets/0" {
+			w.WriteHeader(http.StatusNotFound)
+		}
+	}))
+	defer server.Close()
+
+	mockAuth, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, mockAuth)
+
+	t.Run("status and headers are printed instead of a body", func(t *testing.T) {
+		var out bytes.Buffer
+		require.NoError(t, executeHeadRequest(&out, RequestOptions{Method: "HEAD", Endpoint: "/2/users/me", AuthType: "app"}, client))
+		assert.Equal(t, "HEAD", gotMethod)
+		assert.True(t, strings.HasPrefix(out.String(), "HTTP/1.1 200 OK\n"), out.String())
+		assert.Contains(t, out.String(), "Content-Type: application/json\n")
+		assert.Contains(t, out.String(), "X-Rate-Limit-Remaining: 899\n")
+		assert.NotContains(t, out.String(), "{}")
+	})
+
+	t.Run("a failure status is printed and returned as an error", func(t *testing.T) {
+		var out bytes.Buffer
+		err := executeHeadRequest(&out, RequestOptions{Method: "HEAD", Endpoint: "/2/tweets/0", AuthType: "app"}, client)
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), "404")
+		assert.True(t, strings.HasPrefix(out.String(), "HTTP/1.1 404 Not Found\n"), out.String())
+	})
+
+	t.Run("an accepted failure status is not an error", func(t *testing.T) {
+		var out bytes.Buffer
+		require.NoError(t, executeHeadRequest(&out, RequestOptions{Method: "HEAD", Endpoint: "/2/tweets/0", AuthType: "app", AcceptStatus: []int{404}}, client))
+		assert.Contains(t, out.String(), "404 Not Found")
+	})
+}
@@ -0,0 +1,89 @@
+package api
+
+import (
+	"fmt"
+	"net/http"
+	"sync"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// FailurePolicy says what a multi-request run (a batch, --input-csv,
+// --stdin-urls, or --interval) does when one of its requests fails.
+type FailurePolicy int
+
+const (
+	// DefaultFailurePolicy leaves the choice to the mode: polling stops at
+	// a failure, and the other modes go on.
+	DefaultFailurePolicy FailurePolicy = iota
+	// ContinueOnError records a failed request and goes on with the next.
+	ContinueOnError
+	// FailFast stops the run at the first failed request.
+	FailFast
+)
+
+// or returns p, or def for DefaultFailurePolicy.
+func (p FailurePolicy) or(def FailurePolicy) FailurePolicy {
+	if p == DefaultFailurePolicy {
+		return def
+	}
+	return p
+}
+
+// isAuthFailure reports whether err means the credentials themselves were
+// rejected, e.g. an expired token whose refresh failed: every later request
+// would fail the same way.
+func isAuthFailure(err error) bool {
+	return xurlErrors.IsAuthError(err) || xurlErrors.IsInteractionRequiredError(err) || problemStatus(err) == http.StatusUnauthorized
+}
+
+// failureTracker applies a FailurePolicy to the requests of one run. It is
+// safe for concurrent use.
+type failureTracker struct {
+	policy FailurePolicy
+	mu     sync.Mutex
+	failed int
+	// stop is the error that stopped the run, if one did.
+	stop error
+}
+
+// record notes the outcome of a request, described as what (e.g. "line 4")
+// in errors, and reports whether the run must stop. A run stops at an auth
+// failure whatever its policy.
+func (t *failureTracker) record(what string, err error) bool {
+	if err == nil {
+		return t.stopped()
+	}
+	t.mu.Lock()
+	defer t.mu.Unlock()
+	t.failed++
+	if t.stop == nil {
+		if isAuthFailure(err) {
+			t.stop = fmt.Errorf("request for %s failed with an auth error; stopping, as every later request would fail too: %w", what, err)
+		} else if t.policy == FailFast {
+			t.stop = fmt.Errorf("request for %s failed; stopping (--fail-fast)", what)
+		}
+	}
+	return t.stop != nil
+}
+
+// stopped reports whether the run must stop.
+func (t *failureTracker) stopped() bool {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+	return t.stop != nil
+}
+
+// err returns the run's error once sent requests were made: the one that
+// stopped it, or else a count of the failures, or nil if there were none.
+func (t *failureTracker) err(sent int) error {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+	if t.stop != nil {
+		return t.stop
+	}
+	if t.failed > 0 {
+		return fmt.Errorf("%d of %d requests failed", t.failed, sent)
+	}
+	return nil
+}
@@ -0,0 +1,116 @@
+package api
+
+import (
+	"bytes"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"strings"
+	"sync"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestFailurePolicy(t *testing.T) {
+	fakePollSleep(t)
+
+	// The server answers /ok, /fail (500), and /unauthorized (401); /poll
+	// answers the nth request as the nth entry of polls.
+	var mu sync.Mutex
+	var sent int
+	var polls []string
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		mu.Lock()
+		outcome := strings.TrimPrefix(r.URL.Path, "/")
+		if outcome == "poll" {
+			outcome = polls[min(sent, len(polls)-1)]
+		}
+		sent++
+		mu.Unlock()
+
+		w.Header().Set("Content-Type", "application/json")
+		switch outcome {
+		case "fail":
+			w.WriteHeader(http.StatusInternalServerError)
+			w.Write([]byte(`{"title":"Internal Error","status":500}`))
+		case "unauthorized":
+			w.WriteHeader(http.StatusUnauthorized)
+			w.Write([]byte(`{"title":"Unauthorized","status":401}`))
+		default:
+			w.Write([]byte(`{"data":{}}`))
+		}
+	}))
+	defer server.Close()
+	client := shortcutClient(t, server)
+
+	// Each mode requests the outcomes in order, one at a time.
+	modes := map[string]func(outcomes []string, policy FailurePolicy) error{
+		"batch": func(outcomes []string, policy FailurePolicy) error {
+			var lines string
+			for _, outcome := range outcomes {
+				lines += `{"url":"/` + outcome + `"}` + "\n"
+			}
+			return ExecuteBatch(RequestOptions{}, writeTempBatch(t, lines), BatchOptions{OnFailure: policy}, client, &bytes.Buffer{}, &bytes.Buffer{})
+		},
+		"input-csv": func(outcomes []string, policy FailurePolicy) error {
+			path := filepath.Join(t.TempDir(), "rows.csv")
+			require.NoError(t, os.WriteFile(path, []byte("outcome\n"+strings.Join(outcomes, "\n")+"\n"), 0600))
+			return ExecuteCSVRequests(RequestOptions{Method: "GET", Endpoint: "/{outcome}"}, path, 1, policy, client, &bytes.Buffer{})
+		},
+		"stdin-urls": func(outcomes []string, policy FailurePolicy) error {
+			in := "/" + strings.Join(outcomes, "\n/") + "\n"
+			return ExecuteURLList(RequestOptions{Method: "GET"}, strings.NewReader(in), policy, client, &bytes.Buffer{})
+		},
+		"interval": func(outcomes []string, policy FailurePolicy) error {
+			polls = outcomes
+			return Poll(RequestOptions{Method: "GET", Endpoint: "/poll"}, PollOptions{Interval: time.Second, Count: len(outcomes), OnFailure: policy}, client, &bytes.Buffer{})
+		},
+	}
+
+	for name, run := range modes {
+		t.Run(name, func(t *testing.T) {
+			sent = 0
+			err := run([]string{"ok", "fail", "ok"}, ContinueOnError)
+			require.Error(t, err, "a failure still fails the run")
+			assert.Equal(t, "1 of 3 requests failed", err.Error())
+			assert.Equal(t, 3, sent)
+
+			sent = 0
+			err = run([]string{"ok", "fail", "ok"}, FailFast)
+			require.Error(t, err)
+			assert.Contains(t, err.Error(), "--fail-fast")
+			assert.Equal(t, 2, sent)
+
+			sent = 0
+			err = run([]string{"ok", "unauthorized", "ok"}, ContinueOnError)
+			require.Error(t, err)
+			assert.Contains(t, err.Error(), "auth error")
+			assert.Equal(t, 2, sent, "an auth failure stops even a continuing run")
+
+			sent = 0
+			assert.NoError(t, run([]string{"ok", "ok"}, FailFast))
+			assert.Equal(t, 2, sent)
+		})
+	}
+
+	t.Run("defaults", func(t *testing.T) {
+		sent = 0
+		require.Error(t, modes["batch"]([]string{"fail", "ok"}, DefaultFailurePolicy))
+		assert.Equal(t, 2, sent, "a batch goes on by default")
+
+		sent = 0
+		require.Error(t, modes["interval"]([]string{"fail", "ok"}, DefaultFailurePolicy))
+		assert.Equal(t, 1, sent, "polling stops by default")
+	})
+
+	t.Run("paginate stops at a failed page", func(t *testing.T) {
+		sent = 0
+		err := Paginate(RequestOptions{Method: "GET", Endpoint: "/fail"}, client, &bytes.Buffer{})
+		require.Error(t, err)
+		assert.Equal(t, 1, sent)
+	})
+}
@@ -0,0 +1,137 @@
+package api
+
+import (
+	"fmt"
+	"net/url"
+	"slices"
+	"strings"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// FieldParam is a fields or expansions query parameter with a flag of its own
+// (e.g. --tweet-fields for tweet.fields), and the values the API documents for
+// it.
+type FieldParam struct {
+	Flag  string
+	Param string
+	Known []string
+}
+
+// FieldParams are the parameters that RequestOptions.Fields may set.
+var FieldParams = []FieldParam{
+	{Flag: "tweet-fields", Param: "tweet.fields", Known: []string{
+		"article", "attachments", "author_id", "card_uri", "community_id", "context_annotations",
+		"conversation_id", "created_at", "display_text_range", "edit_controls", "edit_history_tweet_ids",
+		"entities", "geo", "id", "in_reply_to_user_id", "lang", "media_metadata", "non_public_metrics",
+		"note_tweet", "organic_metrics", "possibly_sensitive", "promoted_metrics", "public_metrics",
+		"referenced_tweets", "reply_settings", "scopes", "source", "suggested_source_links", "text",
+		"withheld",
+	}},
+	{Flag: "user-fields", Param: "user.fields", Known: []string{
+		"affiliation", "connection_status", "created_at", "description", "entities", "id",
+		"is_identity_verified", "location", "most_recent_tweet_id", "name", "parody", "pinned_tweet_id",
+		"profile_banner_url", "profile_image_url", "protected", "public_metrics", "receives_your_dm",
+		"subscription", "subscription_type", "url", "username", "verified", "verified_followers_count",
+		"verified_type", "withheld",
+	}},
+	{Flag: "media-fields", Param: "media.fields", Known: []string{
+		"alt_text", "duration_ms", "height", "media_key", "non_public_metrics", "organic_metrics",
+		"preview_image_url", "promoted_metrics", "public_metrics", "type", "url", "variants", "width",
+	}},
+	{Flag: "poll-fields", Param: "poll.fields", Known: []string{
+		"duration_minutes", "end_datetime", "id", "options", "voting_status",
+	}},
+	{Flag: "place-fields", Param: "place.fields", Known: []string{
+		"contained_within", "country", "country_code", "full_name", "geo", "id", "name", "place_type",
+	}},
+	{Flag: "expansions", Param: "expansions", Known: []string{
+		"affiliation.user_id", "article.cover_media", "article.media_entities", "attachments.media_keys",
+		"attachments.media_source_tweet", "attachments.poll_ids", "author_id", "creator_id",
+		"edit_history_tweet_ids", "entities.mentions.username", "entities.note.mentions.username",
+		"geo.place_id", "host_ids", "in_reply_to_user_id", "invited_user_ids", "most_recent_tweet_id",
+		"owner_id", "participant_ids", "pinned_tweet_id", "referenced_tweets.id",
+		"referenced_tweets.id.attachments.media_keys", "referenced_tweets.id.author_id", "sender_id",
+		"speaker_ids", "topic_ids",
+	}},
+}
+
+// fieldQueryParams turns fields (query parameter name → values, as set by the
+// field flags) into "key=v1,v2" pairs for withQueryParams. Every value must be
+// one the API documents for its parameter; an unknown one is an error that
+// suggests the closest known name. A parameter already in the endpoint's query
+// or in queryParams wins, so an explicit -P can still send anything.
+func fieldQueryParams(endpoint string, queryParams []string, fields map[string][]string) ([]string, error) {
+	explicit := map[string]bool{}
+	if _, query, ok := strings.Cut(endpoint, "?"); ok {
+		if values, err := url.ParseQuery(query); err == nil {
+			for key := range values {
+				explicit[key] = true
+			}
+		}
+	}
+	for _, param := range queryParams {
+		key, _, _ := strings.Cut(param, "=")
+		explicit[key] = true
+	}
+
+	var params []string
+	for _, field := range FieldParams {
+		values := fields[field.Param]
+		if len(values) == 0 || explicit[field.Param] {
+			continue
+		}
+		var names []string
+		for _, value := range values {
+			name := strings.TrimSpace(value)
+			if name == "" || slices.Contains(names, name) {
+				continue
+			}
+			if !slices.Contains(field.Known, name) {
+				message := fmt.Sprintf("unknown --%s value %q", field.Flag, name)
+				if suggestion := closestName(name, field.Known); suggestion != "" {
+					message += fmt.Sprintf(" (did you mean %q?)", suggestion)
+				}
+				return nil, xurlErrors.NewHTTPError(fmt.Errorf("%s; pass it with -P %s=... to send it anyway", message, field.Param))
+			}
+			names = append(names, name)
+		}
+		if len(names) > 0 {
+			params = append(params, field.Param+"="+strings.Join(names, ","))
+		}
+	}
+	return params, nil
+}
+
+// closestName returns the candidate nearest to name by edit distance, or ""
+// when none is close enough to be a likely typo.
+func closestName(name string, candidates []string) string {
+	best, bestDistance := "", max(2, len(name)/3)+1
+	for _, candidate := range candidates {
+		if d := editDistance(name, candidate); d < bestDistance {
+			best, bestDistance = candidate, d
+		}
+	}
+	return best
+}
+
+// editDistance is the Levenshtein distance between a and b.
+func editDistance(a, b string) int {
+	previous := make([]int, len(b)+1)
+	current := make([]int, len(b)+1)
+	for j := range previous {
+		previous[j] = j
+	}
+	for i := 1; i <= len(a); i++ {
+		current[0] = i
+		for j := 1; j <= len(b); j++ {
+			cost := 1
+			if a[i-1] == b[j-1] {
+				cost = 0
+			}
+			current[j] = min(previous[j]+1, current[j-1]+1, previous[j-1]+cost)
+		}
+		previous, current = current, previous
+	}
+	return previous[len(b)]
+}
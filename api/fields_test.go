@@ -0,0 +1,103 @@
+package api
+
+import (
+	"os"
+	"slices"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/xdevplatform/xurl/config"
+)
+
+func TestFieldParamsTable(t *testing.T) {
+	seen := map[string]bool{}
+	for _, field := range FieldParams {
+		assert.False(t, seen[field.Flag], "flag %s is listed twice", field.Flag)
+		seen[field.Flag] = true
+		assert.NotEmpty(t, field.Known, field.Flag)
+		assert.True(t, slices.IsSorted(field.Known), "%s values are kept sorted", field.Flag)
+	}
+}
+
+func TestFieldQueryParams(t *testing.T) {
+	tests := []struct {
+		name        string
+		endpoint    string
+		queryParams []string
+		fields      map[string][]string
+		want        []string
+	}{
+		{"none", "/2/users/me", nil, nil, nil},
+		{"joined in table order", "/2/tweets/1", nil, map[string][]string{
+			"expansions":   {"author_id"},
+			"tweet.fields": {"created_at", " public_metrics", "created_at"},
+		}, []string{"tweet.fields=created_at,public_metrics", "expansions=author_id"}},
+		{"explicit -P wins", "/2/tweets/1", []string{"tweet.fields=anything_new"}, map[string][]string{
+			"tweet.fields": {"created_at"},
+			"user.fields":  {"username"},
+		}, []string{"user.fields=username"}},
+		{"endpoint query wins", "/2/users/me?user.fields=id", nil, map[string][]string{
+			"user.fields": {"username"},
+		}, nil},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			got, err := fieldQueryParams(tt.endpoint, tt.queryParams, tt.fields)
+			require.NoError(t, err)
+			assert.Equal(t, tt.want, got)
+		})
+	}
+
+	t.Run("unknown names suggest the closest known one", func(t *testing.T) {
+		_, err := fieldQueryParams("/2/tweets/1", nil, map[string][]string{"tweet.fields": {"created_at", "public_metric"}})
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), `unknown --tweet-fields value "public_metric" (did you mean "public_metrics"?)`)
+		assert.Contains(t, err.Error(), "-P tweet.fields=")
+
+		_, err = fieldQueryParams("/2/tweets/1", nil, map[string][]string{"expansions": {"zzzzzz"}})
+		require.Error(t, err)
+		assert.NotContains(t, err.Error(), "did you mean")
+	})
+}
+
+func TestEditDistance(t *testing.T) {
+	assert.Equal(t, 0, editDistance("id", "id"))
+	assert.Equal(t, 1, editDistance("public_metric", "public_metrics"))
+	assert.Equal(t, 2, editDistance("authro_id", "author_id"))
+	assert.Equal(t, 3, editDistance("", "geo"))
+}
+
+func TestBuildRequestFields(t *testing.T) {
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: "https://api.x.com"}, authMock)
+
+	req, err := client.BuildRequest(RequestOptions{
+		Method:      "GET",
+		Endpoint:    "/2/tweets/search/recent?query=xurl",
+		QueryParams: []string{"max_results=10", "user.fields=id"},
+		Fields: map[string][]string{
+			"tweet.fields": {"created_at", "public_metrics"},
+			"user.fields":  {"username"},
+			"expansions":   {"author_id"},
+		},
+	})
+	require.NoError(t, err)
+	assert.Equal(t, "https://api.x.com/2/tweets/search/recent?query=xurl&tweet.fields=created_at%2Cpublic_metrics&expansions=author_id&max_results=10&user.fields=id", req.URL.String())
+
+	t.Run("multipart requests", func(t *testing.T) {
+		req, err := client.BuildMultipartRequest(MultipartOptions{
+			RequestOptions: RequestOptions{
+				Method:      "POST",
+				Endpoint:    "/2/media/upload",
+				QueryParams: []string{"command=APPEND"},
+				Fields:      map[string][]string{"media.fields": {"url"}},
+			},
+			FormFields: map[string]string{"segment_index": "0"},
+		})
+		require.NoError(t, err)
+		assert.Equal(t, "https://api.x.com/2/media/upload?media.fields=url&command=APPEND", req.URL.String())
+	})
+}
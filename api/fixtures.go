@@ -0,0 +1,233 @@
+package api
+
+import (
+	"bytes"
+	"crypto/sha256"
+	"encoding/base64"
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"os"
+	"path/filepath"
+	"regexp"
+	"strings"
+	"unicode/utf8"
+)
+
+// Fixtures records the requests of an invocation and their responses in Dir
+// (--record), or answers requests from such recordings without any network
+// access (--playback), so that scripts around xurl can be tested offline.
+//
+// A request matches a recording with the same method, path, query, and body;
+// the host is not compared, and neither are OAuth1 query parameters, which
+// change with every request. A request recorded twice keeps its last
+// response. Credentials are never recorded, and in playback no credentials
+// are looked up, so none need be stored.
+type Fixtures struct {
+	Dir      string
+	Playback bool
+}
+
+// WithFixtures makes the client record its requests to, or play them back
+// from, fixtures; nil sends requests as usual.
+func (c *ApiClient) WithFixtures(fixtures *Fixtures) *ApiClient {
+	c.fixtures = fixtures
+	return c
+}
+
+// replaying reports whether requests are answered from recordings. A nil
+// *Fixtures never replays.
+func (f *Fixtures) replaying() bool {
+	return f != nil && f.Playback
+}
+
+// fixture is the file of one recorded request and its response. A body that
+// is not UTF-8 text is kept in BodyBase64 instead of Body.
+type fixture struct {
+	Request struct {
+		Method     string              `json:"method"`
+		URL        string              `json:"url"`
+		Headers    map[string][]string `json:"headers,omitempty"`
+		BodySHA256 string              `json:"body_sha256,omitempty"`
+	} `json:"request"`
+	Response struct {
+		Status     int                 `json:"status"`
+		Headers    map[string][]string `json:"headers,omitempty"`
+		Body       string              `json:"body,omitempty"`
+		BodyBase64 string              `json:"body_base64,omitempty"`
+	} `json:"response"`
+}
+
+// fixtureNameChars are the characters of a request path left out of fixture
+// file names.
+var fixtureNameChars = regexp.MustCompile(`[^A-Za-z0-9]+`)
+
+// match returns the method, path, and query that identify req among the
+// recordings, the SHA-256 of its body, and the file of its recording.
+func (f *Fixtures) match(req *http.Request) (target, bodyHash, path string, err error) {
+	target = req.URL.EscapedPath()
+	if query := withoutOAuth1Params(req.URL); query != "" {
+		target += "?" + query
+	}
+
+	if req.GetBody != nil {
+		body, err := req.GetBody()
+		if err != nil {
+			return "", "", "", err
+		}
+		defer body.Close()
+		h := sha256.New()
+		if _, err := io.Copy(h, body); err != nil {
+			return "", "", "", err
+		}
+		bodyHash = hex.EncodeToString(h.Sum(nil))
+	}
+
+	key := sha256.Sum256([]byte(req.Method + " " + target + "\n" + bodyHash))
+	name := strings.Trim(fixtureNameChars.ReplaceAllString(req.URL.Path, "_"), "_")
+	if len(name) > 80 {
+		name = name[:80]
+	}
+	path = filepath.Join(f.Dir, fmt.Sprintf("%s_%s_%s.json", req.Method, name, hex.EncodeToString(key[:6])))
+	return target, bodyHash, path, nil
+}
+
+// do performs req with client, or answers it from the recordings in
+// playback, recording the exchange with --record. Every request whose
+// response xurl reads to the end goes through here; as with client.Do, the
+// caller reports an error as an HTTP error.
+func (c *ApiClient) do(client *http.Client, req *http.Request) (*http.Response, error) {
+	if c.fixtures == nil {
+		return client.Do(req)
+	}
+	target, bodyHash, path, err := c.fixtures.match(req)
+	if err != nil {
+		return nil, err
+	}
+	if c.fixtures.Playback {
+		return replayFixture(req, target, path)
+	}
+
+	resp, err := client.Do(req)
+	if err != nil {
+		return nil, err
+	}
+	body, err := io.ReadAll(resp.Body)
+	resp.Body.Close()
+	if err != nil {
+		return nil, fmt.Errorf("error reading response: %w", err)
+	}
+	resp.Body = io.NopCloser(bytes.NewReader(body))
+	if err := c.recordFixture(req, resp, body, bodyHash, path); err != nil {
+		return nil, err
+	}
+	return resp, nil
+}
+
+// recordFixture writes the recording of req and its response, of body, to
+// path. Credentials are masked as in -v output, and the Authorization header
+// keeps only its scheme.
+func (c *ApiClient) recordFixture(req *http.Request, resp *http.Response, body []byte, bodyHash, path string) error {
+	secrets := c.auth.StoredSecrets()
+	var fx fixture
+	fx.Request.Method = req.Method
+	fx.Request.URL = ScrubSecrets(recordedURL(req.URL), secrets)
+	fx.Request.Headers = scrubbedHeaders(req.Header, secrets)
+	fx.Request.BodySHA256 = bodyHash
+	fx.Response.Status = resp.StatusCode
+	fx.Response.Headers = scrubbedHeaders(resp.Header, secrets)
+	if utf8.Valid(body) {
+		fx.Response.Body = string(body)
+	} else {
+		fx.Response.BodyBase64 = base64.StdEncoding.EncodeToString(body)
+	}
+
+	data, err := json.MarshalIndent(fx, "", "  ")
+	if err != nil {
+		return err
+	}
+	if err := os.MkdirAll(filepath.Dir(path), 0700); err != nil {
+		return fmt.Errorf("error recording %s %s: %w", req.Method, req.URL.Path, err)
+	}
+	if err := os.WriteFile(path, append(data, '\n'), 0600); err != nil {
+		return fmt.Errorf("error recording %s %s: %w", req.Method, req.URL.Path, err)
+	}
+	return nil
+}
+
+// recordedURL returns u without its OAuth1 query parameters.
+func recordedURL(u *url.URL) string {
+	recorded := *u
+	recorded.RawQuery = withoutOAuth1Params(u)
+	return recorded.String()
+}
+
+// withoutOAuth1Params returns the encoded query of u without the OAuth1
+// protocol parameters of --oauth1-as-query, which change with every request.
+func withoutOAuth1Params(u *url.URL) string {
+	query := u.Query()
+	for name := range query {
+		if strings.HasPrefix(name, "oauth_") {
+			query.Del(name)
+		}
+	}
+	return query.Encode()
+}
+
+// scrubbedHeaders returns header with credentials masked for a recording.
+func scrubbedHeaders(header http.Header, secrets []string) map[string][]string {
+	scrubbed := make(map[string][]string, len(header))
+	for name, values := range header {
+		for _, value := range values {
+			switch http.CanonicalHeaderKey(name) {
+			case "Authorization", "Proxy-Authorization", "Cookie", "Set-Cookie":
+				scheme, _, _ := strings.Cut(value, " ")
+				value = strings.TrimSpace(scheme + " " + redactedValue)
+			default:
+				value = ScrubSecrets(value, secrets)
+			}
+			scrubbed[name] = append(scrubbed[name], value)
+		}
+	}
+	return scrubbed
+}
+
+// replayFixture answers req, identified by target, from the recording at
+// path.
+func replayFixture(req *http.Request, target, path string) (*http.Response, error) {
+	data, err := os.ReadFile(path)
+	if os.IsNotExist(err) {
+		return nil, fmt.Errorf("no recorded interaction for %s %s in %s (record it with --record)", req.Method, target, filepath.Dir(path))
+	}
+	if err != nil {
+		return nil, err
+	}
+	var fx fixture
+	if err := json.Unmarshal(data, &fx); err != nil {
+		return nil, fmt.Errorf("error reading recording %s: %w", path, err)
+	}
+	body := []byte(fx.Response.Body)
+	if fx.Response.BodyBase64 != "" {
+		if body, err = base64.StdEncoding.DecodeString(fx.Response.BodyBase64); err != nil {
+			return nil, fmt.Errorf("error reading recording %s: %w", path, err)
+		}
+	}
+	header := http.Header{}
+	for name, values := range fx.Response.Headers {
+		header[name] = values
+	}
+	return &http.Response{
+		Status:        fmt.Sprintf("%d %s", fx.Response.Status, http.StatusText(fx.Response.Status)),
+		StatusCode:    fx.Response.Status,
+		Proto:         "HTTP/1.1",
+		ProtoMajor:    1,
+		ProtoMinor:    1,
+		Header:        header,
+		Body:          io.NopCloser(bytes.NewReader(body)),
+		ContentLength: int64(len(body)),
+		Request:       req,
+	}, nil
+}
@@ -0,0 +1,76 @@
+package api
+
+import (
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/xdevplatform/xurl/config"
+)
+
+func TestFixtures(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		body, _ := io.ReadAll(r.Body)
+		w.Header().Set("Content-Type", "application/json")
+		w.Header().Set("Set-Cookie", "session=secret-session")
+		w.Write([]byte(`{"data":{"method":"` + r.Method + `","body":"` + string(body) + `"}}`))
+	}))
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	dir := filepath.Join(t.TempDir(), "fixtures")
+	get := RequestOptions{Method: "GET", Endpoint: "/2/users/me", QueryParams: []string{"user.fields=created_at"}, AuthType: "app"}
+	post := RequestOptions{Method: "POST", Endpoint: "/2/tweets", Data: "hi", AuthType: "app"}
+
+	recorder := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock).WithFixtures(&Fixtures{Dir: dir})
+	recorded, err := recorder.SendRequest(get)
+	require.NoError(t, err)
+	_, err = recorder.SendRequest(post)
+	require.NoError(t, err)
+	server.Close()
+
+	// Credentials are not recorded.
+	files, err := filepath.Glob(filepath.Join(dir, "*.json"))
+	require.NoError(t, err)
+	require.Len(t, files, 2)
+	for _, file := range files {
+		data, err := os.ReadFile(file)
+		require.NoError(t, err)
+		assert.NotContains(t, string(data), "test-bearer-token")
+		assert.NotContains(t, string(data), "secret-session")
+	}
+
+	player := NewApiClient(&config.Config{APIBaseURL: "https://api.x.com"}, authMock).WithFixtures(&Fixtures{Dir: dir, Playback: true})
+
+	req, err := player.BuildRequest(get)
+	require.NoError(t, err)
+	assert.Empty(t, req.Header.Get("Authorization"), "playback looks up no credentials")
+
+	replayed, err := player.SendRequest(get)
+	require.NoError(t, err)
+	assert.JSONEq(t, string(recorded), string(replayed))
+
+	replayed, err = player.SendRequest(post)
+	require.NoError(t, err)
+	assert.JSONEq(t, `{"data":{"method":"POST","body":"hi"}}`, string(replayed))
+
+	t.Run("a request with no recording fails", func(t *testing.T) {
+		_, err := player.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me", AuthType: "app"})
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), "no recorded interaction for GET /2/users/me")
+
+		_, err = player.SendRequest(RequestOptions{Method: "POST", Endpoint: "/2/tweets", Data: "bye", AuthType: "app"})
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), "no recorded interaction")
+	})
+
+	t.Run("streaming is refused", func(t *testing.T) {
+		err := player.StreamRequest(RequestOptions{Method: "GET", Endpoint: "/2/tweets/search/stream"})
+		require.Error(t, err)
+	})
+}
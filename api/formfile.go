@@ -0,0 +1,62 @@
+package api
+
+import (
+	"fmt"
+	"io"
+	"mime"
+	"mime/multipart"
+	"net/textproto"
+	"strings"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// FormFile is a file part given with -F/--file.
+type FormFile struct {
+	// Field is the form field name, "media" unless given.
+	Field string
+	// Path is the file to read, or "-" for a body already read from stdin.
+	Path string
+	// ContentType is the part's declared type; empty leaves the default of
+	// application/octet-stream.
+	ContentType string
+}
+
+// ParseFormFile parses a -F/--file argument. Besides a bare path it accepts
+// curl's form, "field=@path", and either may end in ";type=MIME" to declare
+// the part's content type, as in "media=@clip.mp4;type=video/mp4".
+func ParseFormFile(arg string) (FormFile, error) {
+	file := FormFile{Field: "media", Path: arg}
+	if name, path, ok := strings.Cut(arg, "=@"); ok && name != "" && !strings.ContainsAny(name, `/\;`) {
+		file.Field, file.Path = name, path
+	} else if path, ok := strings.CutPrefix(arg, "@"); ok {
+		file.Path = path
+	}
+
+	if path, contentType, ok := strings.Cut(file.Path, ";type="); ok {
+		if _, _, err := mime.ParseMediaType(contentType); err != nil || !strings.Contains(contentType, "/") {
+			return FormFile{}, xurlErrors.NewIOError(fmt.Errorf("invalid content type %q in -F %s", contentType, arg))
+		}
+		file.Path, file.ContentType = path, contentType
+	}
+	if file.Path == "" {
+		return FormFile{}, xurlErrors.NewIOError(fmt.Errorf("-F %s names no file", arg))
+	}
+	return file, nil
+}
+
+// quoteEscaper escapes a form field or file name for a Content-Disposition
+// header, as multipart.Writer.CreateFormFile does.
+var quoteEscaper = strings.NewReplacer(`\`, `\\`, `"`, `\"`)
+
+// createFormFile adds a file part to writer, declared as contentType, or as
+// application/octet-stream when it is empty.
+func createFormFile(writer *multipart.Writer, field, fileName, contentType string) (io.Writer, error) {
+	if contentType == "" {
+		return writer.CreateFormFile(field, fileName)
+	}
+	header := make(textproto.MIMEHeader)
+	header.Set("Content-Disposition", fmt.Sprintf(`form-data; name="%s"; filename="%s"`, quoteEscaper.Replace(field), quoteEscaper.Replace(fileName)))
+	header.Set("Content-Type", contentType)
+	return writer.CreatePart(header)
+}
@@ -0,0 +1,86 @@
+package api
+
+import (
+	"encoding/json"
+	"mime"
+	"mime/multipart"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/xdevplatform/xurl/config"
+)
+
+func TestParseFormFile(t *testing.T) {
+	for arg, want := range map[string]FormFile{
+		"clip.mp4":                     {Field: "media", Path: "clip.mp4"},
+		"@clip.mp4":                    {Field: "media", Path: "clip.mp4"},
+		"media=@clip.mp4":              {Field: "media", Path: "clip.mp4"},
+		"file=@img.png;type=image/png": {Field: "file", Path: "img.png", ContentType: "image/png"},
+		"img.png;type=image/png":       {Field: "media", Path: "img.png", ContentType: "image/png"},
+		"-;type=video/mp4":             {Field: "media", Path: "-", ContentType: "video/mp4"},
+		"dir/a=@b.png":                 {Field: "media", Path: "dir/a=@b.png"},
+	} {
+		got, err := ParseFormFile(arg)
+		require.NoError(t, err, arg)
+		assert.Equal(t, want, got, arg)
+	}
+
+	for _, arg := range []string{"media=@", "img.png;type=", "img.png;type=png", ";type=image/png"} {
+		_, err := ParseFormFile(arg)
+		assert.Error(t, err, arg)
+	}
+}
+
+func TestBuildMultipartRequestFileContentType(t *testing.T) {
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: "https://api.x.com"}, authMock)
+
+	partType := func(options MultipartOptions) string {
+		t.Helper()
+		req, err := client.BuildMultipartRequest(options)
+		require.NoError(t, err)
+		_, params, err := mime.ParseMediaType(req.Header.Get("Content-Type"))
+		require.NoError(t, err)
+		part, err := multipart.NewReader(req.Body, params["boundary"]).NextPart()
+		require.NoError(t, err)
+		assert.Equal(t, "media", part.FormName())
+		assert.Equal(t, `clip "1".mp4`, part.FileName())
+		return part.Header.Get("Content-Type")
+	}
+
+	options := MultipartOptions{
+		RequestOptions: RequestOptions{Method: "POST", Endpoint: "/2/media/upload", AuthType: "app"},
+		FileField:      "media",
+		FileName:       `clip "1".mp4`,
+		FileData:       []byte{0x00, 0x01},
+	}
+	assert.Equal(t, "application/octet-stream", partType(options))
+	options.FileContentType = "video/mp4"
+	assert.Equal(t, "video/mp4", partType(options))
+}
+
+func TestHandleMediaAppendRequestContentType(t *testing.T) {
+	mockClient := new(MockApiClient)
+	tempFile, _ := createTempTestFile(t, 16)
+	defer os.Remove(tempFile)
+
+	requestOptions := RequestOptions{Method: "POST", Endpoint: "/2/media/upload/123456/append"}
+	mockClient.On("SendMultipartRequest", MultipartOptions{
+		RequestOptions:  requestOptions,
+		FormFields:      map[string]string{"segment_index": "0"},
+		FileField:       "media",
+		FilePath:        tempFile,
+		FileName:        filepath.Base(tempFile),
+		FileData:        []byte{},
+		FileContentType: "video/mp4",
+	}).Return(json.RawMessage(`{}`), nil).Once()
+
+	_, err := HandleMediaAppendRequest(requestOptions, "media=@"+tempFile+";type=video/mp4", mockClient)
+	assert.NoError(t, err)
+	mockClient.AssertExpectations(t)
+}
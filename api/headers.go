@@ -0,0 +1,174 @@
+package api
+
+import (
+	"bufio"
+	"bytes"
+	"encoding/base64"
+	"fmt"
+	"net/http"
+	"os"
+	"strings"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+	"github.com/xdevplatform/xurl/version"
+)
+
+// ExpandHeaderArgs expands -H arguments of the form "@path" into the
+// "Name: value" lines of that file, skipping blank lines and "#" comments, and
+// keeps the other arguments as they are. When a header name (compared without
+// regard to case) is given more than once, the last definition replaces the
+// earlier ones, as with curl.
+func ExpandHeaderArgs(args []string) ([]string, error) {
+	var headers []string
+	for _, arg := range args {
+		path, isFile := strings.CutPrefix(arg, "@")
+		if !isFile {
+			headers = setHeaderArg(headers, arg)
+			continue
+		}
+		lines, err := readHeaderFile(path)
+		if err != nil {
+			return nil, err
+		}
+		for _, line := range lines {
+			headers = setHeaderArg(headers, line)
+		}
+	}
+	return headers, nil
+}
+
+// readHeaderFile returns the header lines of a -H @file.
+func readHeaderFile(path string) ([]string, error) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, xurlErrors.NewIOError(fmt.Errorf("error reading header file: %v", err))
+	}
+
+	var lines []string
+	scanner := bufio.NewScanner(bytes.NewReader(data))
+	for n := 1; scanner.Scan(); n++ {
+		line := strings.TrimSpace(scanner.Text())
+		if line == "" || strings.HasPrefix(line, "#") {
+			continue
+		}
+		if _, ok := parseHeaderArg(line); !ok {
+			return nil, xurlErrors.NewIOError(fmt.Errorf("%s:%d: %q is not a \"Name: value\" header", path, n, line))
+		}
+		lines = append(lines, line)
+	}
+	return lines, nil
+}
+
+// setHeaderArg appends header to headers, dropping earlier headers of the same
+// name.
+func setHeaderArg(headers []string, header string) []string {
+	h, ok := parseHeaderArg(header)
+	if !ok {
+		return append(headers, header)
+	}
+	kept := headers[:0]
+	for _, existing := range headers {
+		if e, ok := parseHeaderArg(existing); !ok || !strings.EqualFold(e.name, h.name) {
+			kept = append(kept, existing)
+		}
+	}
+	return append(kept, header)
+}
+
+// headerArg is a parsed -H argument.
+type headerArg struct {
+	name  string
+	value string
+	// remove leaves the header out of the request altogether.
+	remove bool
+}
+
+// parseHeaderArg parses a -H argument with curl's conventions: "Name: value"
+// sets a header, "Name:" with nothing after the colon removes it (including
+// the ones xurl adds itself, such as User-Agent), and "Name;" sends it with
+// an empty value. ok is false for anything else.
+func parseHeaderArg(arg string) (h headerArg, ok bool) {
+	if name, value, found := strings.Cut(arg, ":"); found {
+		h = headerArg{name: strings.TrimSpace(name), value: strings.TrimSpace(value)}
+		h.remove = h.value == ""
+		return h, h.name != ""
+	}
+	if name, found := strings.CutSuffix(strings.TrimSpace(arg), ";"); found {
+		h = headerArg{name: strings.TrimSpace(name)}
+		return h, h.name != ""
+	}
+	return h, false
+}
+
+// HasHeaderArg reports whether the -H arguments args send the header name,
+// compared without regard to case: the last of them for that name sets it
+// rather than removing it.
+func HasHeaderArg(args []string, name string) bool {
+	has := false
+	for _, arg := range args {
+		if h, ok := parseHeaderArg(arg); ok && strings.EqualFold(h.name, name) {
+			has = !h.remove
+		}
+	}
+	return has
+}
+
+// DefaultBasicAuthHeader is the header --basic-auth credentials are sent in, so
+// that a gateway in front of the API can check its own credentials while X's
+// Authorization header passes through untouched.
+const DefaultBasicAuthHeader = "Proxy-Authorization"
+
+// BasicAuthHeaderArg returns the -H argument sending credentials, given as
+// "user:password", as HTTP Basic auth in the header name.
+func BasicAuthHeaderArg(name, credentials string) (string, error) {
+	if !strings.Contains(credentials, ":") {
+		return "", xurlErrors.NewAuthError("InvalidBasicAuth", fmt.Errorf("--basic-auth takes user:password"))
+	}
+	if strings.TrimSpace(name) == "" || strings.ContainsAny(name, ":; ") {
+		return "", xurlErrors.NewAuthError("InvalidBasicAuth", fmt.Errorf("invalid header name %q for --basic-auth", name))
+	}
+	return name + ": Basic " + base64.StdEncoding.EncodeToString([]byte(credentials)), nil
+}
+
+// userSetHeader reports whether header has name at all, even with an empty
+// value. Headers xurl computes itself are only added when it does not, so a
+// -H of the same name (in any case) replaces them.
+func userSetHeader(header http.Header, name string) bool {
+	_, ok := header[http.CanonicalHeaderKey(name)]
+	return ok
+}
+
+// addHeaderArgs adds -H arguments to header and returns the canonical names
+// of the headers they remove, which the caller must not add back.
+func addHeaderArgs(header http.Header, args []string) map[string]bool {
+	removed := map[string]bool{}
+	for _, arg := range args {
+		h, ok := parseHeaderArg(arg)
+		if !ok {
+			continue
+		}
+		name := http.CanonicalHeaderKey(h.name)
+		if h.remove {
+			header.Del(name)
+			removed[name] = true
+			continue
+		}
+		delete(removed, name)
+		header.Add(name, h.value)
+	}
+	return removed
+}
+
+// setUserAgent sets xurl's User-Agent unless -H set or removed it. A removed
+// one is set to empty, which net/http sends as no User-Agent at all rather
+// than its own default.
+func setUserAgent(header http.Header, removed map[string]bool) {
+	if userSetHeader(header, "User-Agent") {
+		return
+	}
+	if removed["User-Agent"] {
+		header["User-Agent"] = []string{""}
+		return
+	}
+	header.Set("User-Agent", "xurl/"+version.Version)
+}
@@ -0,0 +1,104 @@
+package api
+
+import (
+	"bytes"
+	"encoding/json"
+	"slices"
+	"strconv"
+	"strings"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// JSONDifference is a place where two JSON documents differ. Path is an RFC
+// 6901 JSON pointer; A or B is nil where only the other document has the
+// path.
+type JSONDifference struct {
+	Path string          `json:"path"`
+	A    json.RawMessage `json:"a,omitempty"`
+	B    json.RawMessage `json:"b,omitempty"`
+}
+
+// DiffJSON compares two JSON documents: objects key by key (in sorted key
+// order) and arrays index by index, reporting each differing leaf. Numbers
+// are compared by their text, so 1 and 1.0 differ.
+func DiffJSON(a, b json.RawMessage) ([]JSONDifference, error) {
+	valueA, err := decodeJSONValue(a)
+	if err != nil {
+		return nil, err
+	}
+	valueB, err := decodeJSONValue(b)
+	if err != nil {
+		return nil, err
+	}
+	var diffs []JSONDifference
+	diffJSONValues("", valueA, valueB, &diffs)
+	return diffs, nil
+}
+
+func decodeJSONValue(data json.RawMessage) (any, error) {
+	decoder := json.NewDecoder(bytes.NewReader(data))
+	decoder.UseNumber()
+	var value any
+	if err := decoder.Decode(&value); err != nil {
+		return nil, xurlErrors.NewJSONError(err)
+	}
+	return value, nil
+}
+
+func diffJSONValues(path string, a, b any, diffs *[]JSONDifference) {
+	switch a := a.(type) {
+	case map[string]any:
+		if b, ok := b.(map[string]any); ok {
+			keys := make([]string, 0, len(a)+len(b))
+			for key := range a {
+				keys = append(keys, key)
+			}
+			for key := range b {
+				if _, ok := a[key]; !ok {
+					keys = append(keys, key)
+				}
+			}
+			slices.Sort(keys)
+			for _, key := range keys {
+				childPath := path + "/" + strings.NewReplacer("~", "~0", "/", "~1").Replace(key)
+				valueA, inA := a[key]
+				valueB, inB := b[key]
+				switch {
+				case !inA:
+					*diffs = append(*diffs, JSONDifference{Path: childPath, B: encodeJSONValue(valueB)})
+				case !inB:
+					*diffs = append(*diffs, JSONDifference{Path: childPath, A: encodeJSONValue(valueA)})
+				default:
+					diffJSONValues(childPath, valueA, valueB, diffs)
+				}
+			}
+			return
+		}
+	case []any:
+		if b, ok := b.([]any); ok {
+			for i := range max(len(a), len(b)) {
+				childPath := path + "/" + strconv.Itoa(i)
+				switch {
+				case i >= len(a):
+					*diffs = append(*diffs, JSONDifference{Path: childPath, B: encodeJSONValue(b[i])})
+				case i >= len(b):
+					*diffs = append(*diffs, JSONDifference{Path: childPath, A: encodeJSONValue(a[i])})
+				default:
+					diffJSONValues(childPath, a[i], b[i], diffs)
+				}
+			}
+			return
+		}
+	}
+	// Objects and arrays only get here when b is of another type; interfaces
+	// holding different types compare unequal without panicking.
+	if a != b {
+		*diffs = append(*diffs, JSONDifference{Path: path, A: encodeJSONValue(a), B: encodeJSONValue(b)})
+	}
+}
+
+func encodeJSONValue(value any) json.RawMessage {
+	data, _ := json.Marshal(value)
+	return data
+}
@@ -0,0 +1,38 @@
+package api
+
+import (
+	"encoding/json"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestDiffJSON(t *testing.T) {
+	diffs, err := DiffJSON(
+		json.RawMessage(`{"data":{"id":"1","name":"Alice","tags":["a","b"],"a/b":1,"n":1}}`),
+		json.RawMessage(`{"data":{"id":"1","name":"Bob","tags":["a"],"a/b":2,"n":1.0,"new":null}}`),
+	)
+	require.NoError(t, err)
+
+	out, err := json.Marshal(diffs)
+	require.NoError(t, err)
+	assert.JSONEq(t, `[
+		{"path":"/data/a~1b","a":1,"b":2},
+		{"path":"/data/n","a":1,"b":1.0},
+		{"path":"/data/name","a":"Alice","b":"Bob"},
+		{"path":"/data/new","b":null},
+		{"path":"/data/tags/1","a":"b"}
+	]`, string(out))
+
+	diffs, err = DiffJSON(json.RawMessage(`{"b":[1,{"c":true}],"a":null}`), json.RawMessage(`{"a":null,"b":[1,{"c":true}]}`))
+	require.NoError(t, err)
+	assert.Empty(t, diffs, "key order does not matter")
+
+	diffs, err = DiffJSON(json.RawMessage(`{"data":[]}`), json.RawMessage(`{"data":{}}`))
+	require.NoError(t, err)
+	assert.Equal(t, []JSONDifference{{Path: "/data", A: json.RawMessage(`[]`), B: json.RawMessage(`{}`)}}, diffs)
+
+	_, err = DiffJSON(json.RawMessage(`{`), json.RawMessage(`{}`))
+	assert.Error(t, err)
+}
@@ -5,6 +5,7 @@ import (
 	"fmt"
 	"io"
 	"mime"
+	"net/url"
 	"os"
 	"path/filepath"
 	"strconv"
@@ -78,10 +79,12 @@ type MediaUploader struct {
 	filePath string
 	fileSize int64
 	verbose  bool
-	authType string
-	username string
-	headers  []string
-	trace    bool
+	// verboseBody also logs request and response bodies (-vv).
+	verboseBody bool
+	authType    string
+	username    string
+	headers     []string
+	trace       bool
 }
 
 type InitRequest struct {
@@ -128,7 +131,7 @@ func NewMediaUploaderWithoutFile(client Client, verbose, trace bool, authType st
 // Init initializes the media upload
 func (m *MediaUploader) Init(mediaType string, mediaCategory string) error {
 	if m.verbose {
-		fmt.Printf("\033[32mInitializing media upload...\033[0m\n")
+		fmt.Fprintf(os.Stderr, "\033[32mInitializing media upload...\033[0m\n")
 	}
 
 	finalUrl := MediaEndpoint +
@@ -151,8 +154,9 @@ func (m *MediaUploader) Init(mediaType string, mediaCategory string) error {
 		Data:     string(jsonData),
 		AuthType: m.authType,
 		Username: m.username,
-		Verbose:  m.verbose,
-		Trace:    m.trace,
+		Verbose:     m.verbose,
+		VerboseBody: m.verboseBody,
+		Trace:       m.trace,
 	}
 
 	response, clientErr := m.client.SendRequest(requestOptions)
@@ -188,7 +192,7 @@ func (m *MediaUploader) Append() error {
 	}
 
 	if m.verbose {
-		fmt.Printf("\033[32mUploading media in chunks...\033[0m\n")
+		fmt.Fprintf(os.Stderr, "\033[32mUploading media in chunks...\033[0m\n")
 	}
 
 	// Open the file
@@ -227,8 +231,9 @@ func (m *MediaUploader) Append() error {
 			Data:     "",
 			AuthType: m.authType,
 			Username: m.username,
-			Verbose:  m.verbose,
-			Trace:    m.trace,
+			Verbose:     m.verbose,
+			VerboseBody: m.verboseBody,
+			Trace:       m.trace,
 		}
 		multipartOptions := MultipartOptions{
 			RequestOptions: requestOptions,
@@ -249,12 +254,12 @@ func (m *MediaUploader) Append() error {
 		segmentIndex++
 
 		if m.verbose {
-			fmt.Printf("\033[33mUploaded %d of %d bytes (%.2f%%)\033[0m\n", bytesUploaded, m.fileSize, float64(bytesUploaded)/float64(m.fileSize)*100)
+			fmt.Fprintf(os.Stderr, "\033[33mUploaded %d of %d bytes (%.2f%%)\033[0m\n", bytesUploaded, m.fileSize, float64(bytesUploaded)/float64(m.fileSize)*100)
 		}
 	}
 
 	if m.verbose {
-		fmt.Printf("\033[32mUpload complete!\033[0m\n")
+		fmt.Fprintf(os.Stderr, "\033[32mUpload complete!\033[0m\n")
 	}
 
 	return nil
@@ -267,7 +272,7 @@ func (m *MediaUploader) Finalize() (json.RawMessage, error) {
 	}
 
 	if m.verbose {
-		fmt.Printf("\033[32mFinalizing media upload...\033[0m\n")
+		fmt.Fprintf(os.Stderr, "\033[32mFinalizing media upload...\033[0m\n")
 	}
 
 	finalUrl := MediaEndpoint + fmt.Sprintf("/%s/finalize", m.mediaID)
@@ -278,8 +283,9 @@ func (m *MediaUploader) Finalize() (json.RawMessage, error) {
 		Data:     "",
 		AuthType: m.authType,
 		Username: m.username,
-		Verbose:  m.verbose,
-		Trace:    m.trace,
+		Verbose:     m.verbose,
+		VerboseBody: m.verboseBody,
+		Trace:       m.trace,
 	}
 	response, clientErr := m.client.SendRequest(requestOptions)
 	if clientErr != nil {
@@ -296,7 +302,7 @@ func (m *MediaUploader) CheckStatus() (json.RawMessage, error) {
 	}
 
 	if m.verbose {
-		fmt.Println("Checking media status...")
+		fmt.Fprintln(os.Stderr, "Checking media status...")
 	}
 
 	url := MediaEndpoint + "?command=STATUS&media_id=" + m.mediaID
@@ -308,8 +314,9 @@ func (m *MediaUploader) CheckStatus() (json.RawMessage, error) {
 		Data:     "",
 		AuthType: m.authType,
 		Username: m.username,
-		Verbose:  m.verbose,
-		Trace:    m.trace,
+		Verbose:     m.verbose,
+		VerboseBody: m.verboseBody,
+		Trace:       m.trace,
 	}
 	response, clientErr := m.client.SendRequest(requestOptions)
 	if clientErr != nil {
@@ -330,7 +337,7 @@ func (m *MediaUploader) WaitForProcessing() (json.RawMessage, error) {
 	}
 
 	if m.verbose {
-		fmt.Printf("\033[32mWaiting for media processing to complete...\033[0m\n")
+		fmt.Fprintf(os.Stderr, "\033[32mWaiting for media processing to complete...\033[0m\n")
 	}
 
 	for {
@@ -356,7 +363,7 @@ func (m *MediaUploader) WaitForProcessing() (json.RawMessage, error) {
 		state := statusResponse.Data.ProcessingInfo.State
 		if state == "succeeded" {
 			if m.verbose {
-				fmt.Printf("\033[32mMedia processing complete!\033[0m\n")
+				fmt.Fprintf(os.Stderr, "\033[32mMedia processing complete!\033[0m\n")
 			}
 			return response, nil
 		} else if state == "failed" {
@@ -369,7 +376,7 @@ func (m *MediaUploader) WaitForProcessing() (json.RawMessage, error) {
 		}
 
 		if m.verbose {
-			fmt.Printf("\033[33mMedia processing in progress (%d%%), checking again in %d seconds...\033[0m\n",
+			fmt.Fprintf(os.Stderr, "\033[33mMedia processing in progress (%d%%), checking again in %d seconds...\033[0m\n",
 				statusResponse.Data.ProcessingInfo.ProgressPercent,
 				checkAfterSecs)
 		}
@@ -388,12 +395,19 @@ func (m *MediaUploader) SetMediaID(mediaID string) {
 	m.mediaID = mediaID
 }
 
+// SetVerboseBody makes the uploader log request and response bodies too, as
+// -vv does.
+func (m *MediaUploader) SetVerboseBody(verboseBody bool) {
+	m.verboseBody = verboseBody
+}
+
 // ExecuteMediaUpload handles the media upload command execution
-func ExecuteMediaUpload(filePath, mediaType, mediaCategory, authType, username string, verbose, waitForProcessing, trace bool, headers []string, client Client) error {
+func ExecuteMediaUpload(filePath, mediaType, mediaCategory, authType, username string, verbose, verboseBody, waitForProcessing, trace bool, headers []string, client Client) error {
 	uploader, err := NewMediaUploader(client, filePath, verbose, trace, authType, username, headers)
 	if err != nil {
 		return fmt.Errorf("error: %v", err)
 	}
+	uploader.SetVerboseBody(verboseBody)
 
 	// Fill in sensible defaults from the file itself when not specified. If the
 	// type can't be detected, or it is a recognized-but-unsupported type, fail
@@ -439,15 +453,16 @@ func ExecuteMediaUpload(filePath, mediaType, mediaCategory, authType, username s
 		utils.FormatAndPrintResponse(processingResponse)
 	}
 
-	fmt.Printf("\033[32mMedia uploaded successfully! Media ID: %s\033[0m\n", uploader.GetMediaID())
+	fmt.Fprintf(os.Stderr, "\033[32mMedia uploaded successfully! Media ID: %s\033[0m\n", uploader.GetMediaID())
 	return nil
 }
 
 // ExecuteMediaStatus handles the media status command execution
-func ExecuteMediaStatus(mediaID, authType, username string, verbose, wait, trace bool, headers []string, client Client) error {
+func ExecuteMediaStatus(mediaID, authType, username string, verbose, verboseBody, wait, trace bool, headers []string, client Client) error {
 	uploader := NewMediaUploaderWithoutFile(client, verbose, trace, authType, username, headers)
 
 	uploader.SetMediaID(mediaID)
+	uploader.SetVerboseBody(verboseBody)
 
 	if wait {
 		processingResponse, err := uploader.WaitForProcessing()
@@ -476,7 +491,8 @@ func ExecuteMediaStatus(mediaID, authType, username string, verbose, wait, trace
 	return nil
 }
 
-// HandleMediaAppendRequest handles a media append request with a file
+// HandleMediaAppendRequest handles a media append request with a file, given
+// as for ParseFormFile
 func HandleMediaAppendRequest(options RequestOptions, mediaFile string, client Client) (json.RawMessage, error) {
 	// TODO: This function is in a weird state since append accepts either a multipart request or a json request
 	// Right now, this function takes in segment_index from the json request and sends a multipart request
@@ -492,6 +508,11 @@ func HandleMediaAppendRequest(options RequestOptions, mediaFile string, client C
 	}
 
 	segmentIndex := ExtractSegmentIndex(options.Data)
+	if segmentIndex == "" {
+		if parsed, err := url.Parse(options.Endpoint); err == nil {
+			segmentIndex = parsed.Query().Get("segment_index")
+		}
+	}
 	if segmentIndex == "" {
 		segmentIndex = "0"
 	}
@@ -500,13 +521,25 @@ func HandleMediaAppendRequest(options RequestOptions, mediaFile string, client C
 		"segment_index": segmentIndex,
 	}
 
+	file, err := ParseFormFile(mediaFile)
+	if err != nil {
+		return nil, err
+	}
 	multipartOptions := MultipartOptions{
-		RequestOptions: options,
-		FormFields:     formFields,
-		FileField:      "media",
-		FilePath:       mediaFile,
-		FileName:       filepath.Base(mediaFile),
-		FileData:       []byte{},
+		RequestOptions:  options,
+		FormFields:      formFields,
+		FileField:       file.Field,
+		FilePath:        file.Path,
+		FileName:        filepath.Base(file.Path),
+		FileData:        []byte{},
+		FileContentType: file.ContentType,
+	}
+
+	// "-" means the chunk was already read into options.Body (-d @-).
+	if file.Path == "-" {
+		multipartOptions.FilePath = ""
+		multipartOptions.FileName = "media"
+		multipartOptions.FileData = options.Body
 	}
 
 	response, clientErr := client.SendMultipartRequest(multipartOptions)
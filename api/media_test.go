@@ -451,10 +451,10 @@ func TestExecuteMediaUpload(t *testing.T) {
 	tempFile, _ := createTempTestFile(t, 1024)
 	defer os.Remove(tempFile)
 
-	err := ExecuteMediaUpload(tempFile, "image/jpeg", "tweet_image", "oauth2", "testuser", false, false, false, []string{}, client)
+	err := ExecuteMediaUpload(tempFile, "image/jpeg", "tweet_image", "oauth2", "testuser", false, false, false, false, []string{}, client)
 	assert.NoError(t, err)
 
-	err = ExecuteMediaUpload("nonexistent.txt", "image/jpeg", "tweet_image", "oauth2", "testuser", false, false, false, []string{}, client)
+	err = ExecuteMediaUpload("nonexistent.txt", "image/jpeg", "tweet_image", "oauth2", "testuser", false, false, false, false, []string{}, client)
 	assert.Error(t, err)
 }
 
@@ -485,7 +485,7 @@ func TestExecuteMediaStatus(t *testing.T) {
 		allowUnauthenticated: true,
 	}
 
-	err := ExecuteMediaStatus("test_media_id", "oauth2", "testuser", false, false, false, []string{}, client)
+	err := ExecuteMediaStatus("test_media_id", "oauth2", "testuser", false, false, false, false, []string{}, client)
 	assert.NoError(t, err)
 }
 
@@ -528,7 +528,7 @@ func TestExecuteMediaUploadWaitsForVideoProcessing(t *testing.T) {
 	defer os.Remove(tempFile)
 
 	// Args: verbose=false, waitForProcessing=true, trace=false.
-	err := ExecuteMediaUpload(tempFile, "video/mp4", "tweet_video", "", "", false, true, false, []string{}, client)
+	err := ExecuteMediaUpload(tempFile, "video/mp4", "tweet_video", "", "", false, false, true, false, []string{}, client)
 	assert.NoError(t, err)
 	assert.GreaterOrEqual(t, atomic.LoadInt32(&statusCalls), int32(2), "expected the status endpoint to be polled while waiting")
 }
@@ -599,7 +599,7 @@ func TestExecuteMediaUploadWaitsForGIF(t *testing.T) {
 	defer os.Remove(gifFile)
 
 	// Empty media-type/category → auto-detect to image/gif + tweet_gif.
-	err := ExecuteMediaUpload(gifFile, "", "", "", "", false, true, false, []string{}, client)
+	err := ExecuteMediaUpload(gifFile, "", "", "", "", false, false, true, false, []string{}, client)
 	assert.NoError(t, err)
 	assert.GreaterOrEqual(t, atomic.LoadInt32(&statusCalls), int32(2), "GIF upload should poll processing")
 }
@@ -609,7 +609,7 @@ func TestExecuteMediaUploadUndetectableTypeErrors(t *testing.T) {
 	f := tempFileWithExt(t, ".unknownext", 16)
 	defer os.Remove(f)
 
-	err := ExecuteMediaUpload(f, "", "", "", "", false, false, false, nil, mockClient)
+	err := ExecuteMediaUpload(f, "", "", "", "", false, false, false, false, nil, mockClient)
 	assert.Error(t, err)
 	assert.Contains(t, err.Error(), "could not detect media type")
 }
@@ -622,7 +622,7 @@ func TestExecuteMediaUploadUnsupportedTypeErrors(t *testing.T) {
 	f := tempFileWithExt(t, ".bin", 16)
 	defer os.Remove(f)
 
-	err := ExecuteMediaUpload(f, "application/pdf", "", "", "", false, false, false, nil, mockClient)
+	err := ExecuteMediaUpload(f, "application/pdf", "", "", "", false, false, false, false, nil, mockClient)
 	assert.Error(t, err)
 	assert.Contains(t, err.Error(), "unsupported media type")
 }
@@ -742,3 +742,26 @@ func TestHandleMediaAppendRequest(t *testing.T) {
 
 	mockClient.AssertExpectations(t)
 }
+
+func TestHandleMediaAppendRequestFromRawBody(t *testing.T) {
+	mockClient := new(MockApiClient)
+
+	chunk := []byte{0xff, 0xd8, 0xff, 0xe0, 0x00, 0x10, 0x80, 0xfe}
+	requestOptions := RequestOptions{
+		Method:   "POST",
+		Endpoint: "/2/media/upload/123456/append?segment_index=2",
+		Body:     chunk,
+	}
+	multipartOptions := MultipartOptions{
+		RequestOptions: requestOptions,
+		FormFields:     map[string]string{"segment_index": "2"},
+		FileField:      "media",
+		FileName:       "media",
+		FileData:       chunk,
+	}
+	mockClient.On("SendMultipartRequest", multipartOptions).Return(json.RawMessage(`{}`), nil).Once()
+
+	_, err := HandleMediaAppendRequest(requestOptions, "-", mockClient)
+	assert.NoError(t, err)
+	mockClient.AssertExpectations(t)
+}
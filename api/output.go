@@ -0,0 +1,383 @@
+package api
+
+import (
+	"bytes"
+	"encoding/csv"
+	"encoding/json"
+	"fmt"
+	"io"
+	"os"
+	"sort"
+	"strconv"
+	"strings"
+	"text/template"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+	"github.com/xdevplatform/xurl/utils"
+)
+
+// DefaultArraySeparator joins the values of an array flattened into one CSV
+// cell.
+const DefaultArraySeparator = ";"
+
+// OutputOptions controls how a successful response body is printed.
+type OutputOptions struct {
+	// CSV prints the response's records as CSV instead of JSON.
+	CSV bool
+	// CSVColumns pins the CSV columns and their order; empty means every
+	// flattened key seen in any record, sorted.
+	CSVColumns []string
+	// ArraySeparator joins array values within a CSV cell (default ";").
+	ArraySeparator string
+	// Truncate, when positive, keeps only the first Truncate records of the
+	// response (see truncateRecords), or else its first Truncate bytes.
+	Truncate int
+	// OutputTemplate names the file each --input-csv response is written to,
+	// e.g. "resp-{index}-{status}.json" (see checkOutputTemplate).
+	OutputTemplate string
+	// JSONPointer, when set, prints only the value at this RFC 6901 pointer
+	// (e.g. "/data/0/id") instead of the whole response.
+	JSONPointer string
+	// Template, when set, renders the response with this template (see
+	// ParseTemplateFile) instead of printing it as JSON.
+	Template *template.Template
+}
+
+// ParseTemplateFile parses the Go text/template at path for rendering a
+// response, e.g. a Markdown report looping over {{range .data}}. The template
+// is executed on the decoded response JSON and can use the "json" function to
+// print a value as JSON.
+func ParseTemplateFile(path string) (*template.Template, error) {
+	text, err := os.ReadFile(path)
+	if err != nil {
+		return nil, xurlErrors.NewIOError(fmt.Errorf("error reading template file: %v", err))
+	}
+	tmpl, err := template.New(path).Funcs(template.FuncMap{
+		"json": func(v any) (string, error) {
+			out, err := json.Marshal(v)
+			return string(out), err
+		},
+	}).Parse(string(text))
+	if err != nil {
+		return nil, xurlErrors.NewIOError(fmt.Errorf("invalid template file: %v", err))
+	}
+	return tmpl, nil
+}
+
+// renderTemplate executes tmpl on response and writes the result to w.
+// Numbers are kept as written, so large IDs and counts print in full.
+func renderTemplate(w io.Writer, tmpl *template.Template, response json.RawMessage) error {
+	var value any
+	decoder := json.NewDecoder(bytes.NewReader(response))
+	decoder.UseNumber()
+	if err := decoder.Decode(&value); err != nil {
+		return xurlErrors.NewJSONError(err)
+	}
+	if err := tmpl.Execute(w, value); err != nil {
+		return xurlErrors.NewIOError(fmt.Errorf("error rendering template: %v", err))
+	}
+	return nil
+}
+
+// sendForOutput sends options with client, also returning the response's
+// Content-Type when client exposes it.
+func sendForOutput(options RequestOptions, client Client) (json.RawMessage, string, error) {
+	apiClient, ok := client.(*ApiClient)
+	if !ok {
+		response, err := client.SendRequest(options)
+		return response, "", err
+	}
+	response, resp, err := apiClient.SendRequestWithResponse(options)
+	if resp == nil {
+		return response, "", err
+	}
+	return response, resp.Header.Get("Content-Type"), err
+}
+
+// printResponse writes response to stdout in the format selected by output. A
+// body that was not JSON (see processResponse) is printed as-is, unless its
+// Content-Type says it is JSON.
+func printResponse(response json.RawMessage, contentType string, output OutputOptions) error {
+	if output.Template != nil {
+		if _, ok := nonJSONBody(response, contentType); ok {
+			return xurlErrors.NewJSONError(fmt.Errorf("--template-file needs a JSON response, got %s", contentType))
+		}
+		value, err := resolveJSONPointer(response, output.JSONPointer)
+		if err != nil {
+			return err
+		}
+		return renderTemplate(os.Stdout, output.Template, value)
+	}
+	if output.JSONPointer != "" {
+		if _, ok := nonJSONBody(response, contentType); ok {
+			return xurlErrors.NewJSONError(fmt.Errorf("--json-pointer needs a JSON response, got %s", contentType))
+		}
+		value, err := resolveJSONPointer(response, output.JSONPointer)
+		if err != nil {
+			return err
+		}
+		var text string
+		if !output.CSV && json.Unmarshal(value, &text) == nil {
+			return writeText(os.Stdout, text)
+		}
+		response = value
+	}
+	if text, ok := nonJSONBody(response, contentType); ok {
+		if output.Truncate > 0 && len(text) > output.Truncate {
+			defer fmt.Fprintf(os.Stderr, "\033[33m(truncated: showing %d of %d bytes)\033[0m\n", output.Truncate, len(text))
+			text = text[:output.Truncate]
+		}
+		return writeText(os.Stdout, text)
+	}
+	if output.Truncate > 0 {
+		truncated, total, ok := truncateRecords(response, output.Truncate)
+		if ok {
+			if total > output.Truncate {
+				defer fmt.Fprintf(os.Stderr, "\033[33m(truncated: showing %d of %d records)\033[0m\n", output.Truncate, total)
+			}
+			response = truncated
+		} else if !output.CSV {
+			return printTruncatedBytes(os.Stdout, os.Stderr, response, output.Truncate)
+		}
+	}
+	if output.CSV {
+		return WriteCSV(os.Stdout, response, output)
+	}
+	return utils.FormatAndPrintResponse(response)
+}
+
+// resolveJSONPointer returns the value at pointer (RFC 6901) in response.
+// Unlike a dotted path, a pointer is unambiguous for keys that contain dots:
+// "/a.b" is the key "a.b", and "~1" and "~0" stand for "/" and "~" in a key.
+func resolveJSONPointer(response json.RawMessage, pointer string) (json.RawMessage, error) {
+	if pointer != "" && !strings.HasPrefix(pointer, "/") {
+		return nil, xurlErrors.NewJSONError(fmt.Errorf("invalid JSON pointer %q: it must be empty or start with \"/\"", pointer))
+	}
+	value := response
+	if pointer == "" {
+		return value, nil
+	}
+	for _, token := range strings.Split(pointer[1:], "/") {
+		token = strings.NewReplacer("~1", "/", "~0", "~").Replace(token)
+
+		var object map[string]json.RawMessage
+		var array []json.RawMessage
+		switch {
+		case json.Unmarshal(value, &object) == nil && object != nil:
+			child, ok := object[token]
+			if !ok {
+				return nil, xurlErrors.NewJSONError(fmt.Errorf("JSON pointer %s: no key %q", pointer, token))
+			}
+			value = child
+		case json.Unmarshal(value, &array) == nil && array != nil:
+			index, err := strconv.Atoi(token)
+			if err != nil || index < 0 || index >= len(array) || (len(token) > 1 && token[0] == '0') {
+				return nil, xurlErrors.NewJSONError(fmt.Errorf("JSON pointer %s: no index %q in an array of %d", pointer, token, len(array)))
+			}
+			value = array[index]
+		default:
+			return nil, xurlErrors.NewJSONError(fmt.Errorf("JSON pointer %s: %q is not inside an object or array", pointer, token))
+		}
+	}
+	return value, nil
+}
+
+// nonJSONBody returns the text of a response that processResponse passed on
+// as a JSON string because it was not JSON, such as XML or plain text.
+func nonJSONBody(response json.RawMessage, contentType string) (string, bool) {
+	if isJSONContentType(contentType) {
+		return "", false
+	}
+	var text string
+	if json.Unmarshal(response, &text) != nil {
+		return "", false
+	}
+	return text, true
+}
+
+// writeText writes text to w, ending it with a newline.
+func writeText(w io.Writer, text string) error {
+	if !strings.HasSuffix(text, "\n") {
+		text += "\n"
+	}
+	if _, err := io.WriteString(w, text); err != nil {
+		return xurlErrors.NewIOError(err)
+	}
+	return nil
+}
+
+// truncateRecords keeps the first n records of response: the elements of its
+// "data" array, or of response itself when it is an array. It returns the
+// original record count, and false when response has no such array.
+func truncateRecords(response json.RawMessage, n int) (json.RawMessage, int, bool) {
+	var records []json.RawMessage
+	if json.Unmarshal(response, &records) == nil {
+		if len(records) <= n {
+			return response, len(records), true
+		}
+		truncated, err := json.Marshal(records[:n])
+		return truncated, len(records), err == nil
+	}
+
+	var object map[string]json.RawMessage
+	if json.Unmarshal(response, &object) != nil || json.Unmarshal(object["data"], &records) != nil {
+		return nil, 0, false
+	}
+	if len(records) <= n {
+		return response, len(records), true
+	}
+	data, err := json.Marshal(records[:n])
+	if err != nil {
+		return nil, 0, false
+	}
+	object["data"] = data
+	truncated, err := json.Marshal(object)
+	return truncated, len(records), err == nil
+}
+
+// ndjsonValue applies output's JSONPointer and Truncate to response for one
+// NDJSON line: the line holds the value at the pointer, cut to its first
+// Truncate records. A value without records is kept whole, since cutting its
+// bytes would leave the line invalid JSON.
+func ndjsonValue(response json.RawMessage, output OutputOptions) (json.RawMessage, error) {
+	value, err := resolveJSONPointer(response, output.JSONPointer)
+	if err != nil {
+		return nil, err
+	}
+	if output.Truncate > 0 {
+		if truncated, _, ok := truncateRecords(value, output.Truncate); ok {
+			value = truncated
+		}
+	}
+	return value, nil
+}
+
+// printTruncatedBytes writes the first n bytes of the indented response to w,
+// noting on notes how much was left out.
+func printTruncatedBytes(w, notes io.Writer, response json.RawMessage, n int) error {
+	var indented bytes.Buffer
+	if err := json.Indent(&indented, response, "", "  "); err != nil {
+		indented.Reset()
+		indented.Write(response)
+	}
+	out := indented.Bytes()
+	if _, err := fmt.Fprintln(w, string(out[:min(n, len(out))])); err != nil {
+		return xurlErrors.NewIOError(err)
+	}
+	if len(out) > n {
+		fmt.Fprintf(notes, "\033[33m(truncated: showing %d of %d bytes)\033[0m\n", n, len(out))
+	}
+	return nil
+}
+
+// WriteCSV renders response as CSV. The records are the elements of its "data"
+// array (or "data" itself when it is an object), falling back to the whole
+// response. Nested objects become dotted columns ("public_metrics.like_count")
+// and arrays are joined with output.ArraySeparator; for arrays of objects each
+// field gets its own column, e.g. "entities.hashtags.tag" holds every tag.
+func WriteCSV(w io.Writer, response json.RawMessage, output OutputOptions) error {
+	records, err := csvRecords(response)
+	if err != nil {
+		return err
+	}
+
+	separator := output.ArraySeparator
+	if separator == "" {
+		separator = DefaultArraySeparator
+	}
+
+	rows := make([]map[string]string, 0, len(records))
+	seen := map[string]bool{}
+	var keys []string
+	for _, record := range records {
+		flat := map[string][]string{}
+		flattenJSON("", record, flat)
+		row := make(map[string]string, len(flat))
+		for key, values := range flat {
+			row[key] = strings.Join(values, separator)
+			if !seen[key] {
+				seen[key] = true
+				keys = append(keys, key)
+			}
+		}
+		rows = append(rows, row)
+	}
+
+	columns := output.CSVColumns
+	if len(columns) == 0 {
+		sort.Strings(keys)
+		columns = keys
+	}
+
+	writer := csv.NewWriter(w)
+	if err := writer.Write(columns); err != nil {
+		return xurlErrors.NewIOError(err)
+	}
+	for _, row := range rows {
+		line := make([]string, len(columns))
+		for i, column := range columns {
+			line[i] = row[column]
+		}
+		if err := writer.Write(line); err != nil {
+			return xurlErrors.NewIOError(err)
+		}
+	}
+	writer.Flush()
+	if err := writer.Error(); err != nil {
+		return xurlErrors.NewIOError(err)
+	}
+	return nil
+}
+
+// csvRecords picks the records to render from a response body.
+func csvRecords(response json.RawMessage) ([]any, error) {
+	decoder := json.NewDecoder(bytes.NewReader(response))
+	decoder.UseNumber()
+	var body any
+	if err := decoder.Decode(&body); err != nil {
+		return nil, xurlErrors.NewJSONError(fmt.Errorf("response is not JSON: %v", err))
+	}
+
+	if object, ok := body.(map[string]any); ok {
+		if data, ok := object["data"]; ok {
+			body = data
+		}
+	}
+	switch v := body.(type) {
+	case []any:
+		return v, nil
+	case nil:
+		return nil, nil
+	default:
+		return []any{v}, nil
+	}
+}
+
+// flattenJSON appends the scalar values under value to out, keyed by their
+// dotted path. Array elements share their parent's path.
+func flattenJSON(path string, value any, out map[string][]string) {
+	switch v := value.(type) {
+	case map[string]any:
+		for key, child := range v {
+			if path != "" {
+				key = path + "." + key
+			}
+			flattenJSON(key, child, out)
+		}
+	case []any:
+		for _, child := range v {
+			flattenJSON(path, child, out)
+		}
+		if _, ok := out[path]; !ok && len(v) == 0 {
+			out[path] = nil
+		}
+	case nil:
+		if _, ok := out[path]; !ok {
+			out[path] = nil
+		}
+	case string:
+		out[path] = append(out[path], v)
+	default:
+		out[path] = append(out[path], fmt.Sprint(v))
+	}
+}
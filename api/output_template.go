@@ -0,0 +1,194 @@
+package api
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"net/url"
+	"os"
+	"path/filepath"
+	"regexp"
+	"slices"
+	"strconv"
+	"strings"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// outputTemplateVar matches a "{name}" variable of an output template.
+var outputTemplateVar = regexp.MustCompile(`\{([^{}]*)\}`)
+
+// templateResult is what an output template is filled from: one request of a
+// batch and its outcome.
+type templateResult struct {
+	// Index is the request's 1-based position in the batch.
+	Index int
+	// Status is the HTTP status, or 0 when no response was received.
+	Status int
+	// Row holds the CSV columns the request was made from, or the "vars" of
+	// its batch line.
+	Row map[string]string
+	// Endpoint is the request's endpoint, placeholders filled.
+	Endpoint string
+	// Response is the response body (an API error's body on failure).
+	Response json.RawMessage
+}
+
+// checkOutputTemplate fails for a template variable that no request could
+// resolve. Valid variables are {index}, {status}, a CSV column name such as
+// {id}, {response.PATH} for a dotted field of the response body (e.g.
+// {response.data.id}), and {query.NAME} for a query parameter of the request.
+func checkOutputTemplate(template string, columns []string) error {
+	if template == "" {
+		return nil
+	}
+	matches := outputTemplateVar.FindAllStringSubmatch(template, -1)
+	if len(matches) == 0 {
+		return xurlErrors.NewIOError(fmt.Errorf("output template %q has no variables, so every response would overwrite the same file", template))
+	}
+	for _, match := range matches {
+		name := match[1]
+		switch {
+		case name == "index", name == "status", slices.Contains(columns, name):
+		case strings.HasPrefix(name, "response.") && len(name) > len("response."):
+		case strings.HasPrefix(name, "query.") && len(name) > len("query."):
+		default:
+			return xurlErrors.NewIOError(fmt.Errorf("unknown output template variable {%s}: expected {index}, {status}, {response.FIELD}, {query.NAME}, or a CSV column or batch var", name))
+		}
+	}
+	return nil
+}
+
+// fillOutputTemplate returns template with its variables filled from result.
+// Values are made safe for use in a file name. A response field or query
+// parameter that is missing or empty is an error, so that nothing is written
+// under a misleading name.
+func fillOutputTemplate(template string, result templateResult) (string, error) {
+	var fillErr error
+	filled := outputTemplateVar.ReplaceAllStringFunc(template, func(variable string) string {
+		name := variable[1 : len(variable)-1]
+		value, err := outputTemplateValue(name, result)
+		if err == nil && value == "" {
+			err = fmt.Errorf("{%s} is empty", name)
+		}
+		if err != nil {
+			if fillErr == nil {
+				fillErr = err
+			}
+			return variable
+		}
+		return fileNameSafe(value)
+	})
+	if fillErr != nil {
+		return "", xurlErrors.NewIOError(fmt.Errorf("cannot fill output template %q: %v", template, fillErr))
+	}
+	return filled, nil
+}
+
+func outputTemplateValue(name string, result templateResult) (string, error) {
+	switch {
+	case name == "index":
+		return strconv.Itoa(result.Index), nil
+	case name == "status":
+		return strconv.Itoa(result.Status), nil
+	case strings.HasPrefix(name, "response."):
+		return responseField(result.Response, strings.TrimPrefix(name, "response."))
+	case strings.HasPrefix(name, "query."):
+		parsed, err := url.Parse(result.Endpoint)
+		if err != nil {
+			return "", err
+		}
+		return parsed.Query().Get(strings.TrimPrefix(name, "query.")), nil
+	}
+	if value, ok := result.Row[name]; ok {
+		return value, nil
+	}
+	return "", fmt.Errorf("{%s} is not a CSV column or batch var", name)
+}
+
+// responseField returns the scalar at a dotted path of a JSON body; numeric
+// segments index arrays ("data.0.id").
+func responseField(body json.RawMessage, path string) (string, error) {
+	decoder := json.NewDecoder(bytes.NewReader(body))
+	decoder.UseNumber()
+	var value any
+	if err := decoder.Decode(&value); err != nil {
+		return "", fmt.Errorf("the response is not JSON")
+	}
+
+	for _, key := range strings.Split(path, ".") {
+		switch v := value.(type) {
+		case map[string]any:
+			value = v[key]
+		case []any:
+			i, err := strconv.Atoi(key)
+			if err != nil || i < 0 || i >= len(v) {
+				return "", fmt.Errorf("the response has no %s", path)
+			}
+			value = v[i]
+		default:
+			return "", fmt.Errorf("the response has no %s", path)
+		}
+	}
+
+	switch v := value.(type) {
+	case string:
+		return v, nil
+	case json.Number, bool:
+		return fmt.Sprint(v), nil
+	case nil:
+		return "", fmt.Errorf("the response has no %s", path)
+	default:
+		return "", fmt.Errorf("response.%s is not a string or number", path)
+	}
+}
+
+// fileNameSafe replaces path separators so a value cannot leave the directory
+// the template names.
+func fileNameSafe(value string) string {
+	value = strings.NewReplacer("/", "_", `\`, "_").Replace(value)
+	if value == "." || value == ".." {
+		return "_"
+	}
+	return value
+}
+
+// writeTemplatedResponse writes body, indented when it is JSON, to path,
+// creating the parent directories.
+func writeTemplatedResponse(path string, body json.RawMessage) error {
+	var out bytes.Buffer
+	if err := json.Indent(&out, body, "", "  "); err != nil {
+		out.Reset()
+		out.Write(body)
+	}
+	out.WriteString("\n")
+
+	if dir := filepath.Dir(path); dir != "." {
+		if err := os.MkdirAll(dir, 0755); err != nil {
+			return xurlErrors.NewIOError(err)
+		}
+	}
+	if err := os.WriteFile(path, out.Bytes(), 0644); err != nil {
+		return xurlErrors.NewIOError(err)
+	}
+	return nil
+}
+
+// writeResultFile writes one request's body to the file template names for
+// it, returning the file's path. files maps each path already written to its
+// request index, so that two requests resolving to the same name are
+// reported rather than one overwriting the other.
+func writeResultFile(template string, result templateResult, files map[string]int) (string, error) {
+	path, err := fillOutputTemplate(template, result)
+	if err != nil {
+		return "", err
+	}
+	if index, ok := files[path]; ok {
+		return "", xurlErrors.NewIOError(fmt.Errorf("output template %q gives %s for both request %d and request %d", template, path, index, result.Index))
+	}
+	files[path] = result.Index
+	if err := writeTemplatedResponse(path, result.Response); err != nil {
+		return "", err
+	}
+	return path, nil
+}
@@ -0,0 +1,172 @@
+package api
+
+import (
+	"bytes"
+	"encoding/json"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+const csvTestResponse = `{
+	"data": [
+		{"id": "1850000000000000001", "text": "hello, world", "public_metrics": {"like_count": 3},
+		 "entities": {"hashtags": [{"start": 0, "end": 3, "tag": "go"}, {"start": 4, "end": 8, "tag": "rust"}]}},
+		{"id": "1850000000000000002", "text": "no tags", "lang": "en", "public_metrics": {"like_count": 0}}
+	],
+	"meta": {"result_count": 2}
+}`
+
+func TestWriteCSV(t *testing.T) {
+	t.Run("sorted union of flattened keys by default", func(t *testing.T) {
+		var out bytes.Buffer
+		require.NoError(t, WriteCSV(&out, json.RawMessage(csvTestResponse), OutputOptions{CSV: true}))
+		assert.Equal(t, "entities.hashtags.end,entities.hashtags.start,entities.hashtags.tag,id,lang,public_metrics.like_count,text\n"+
+			"3;8,0;4,go;rust,1850000000000000001,,3,\"hello, world\"\n"+
+			",,,1850000000000000002,en,0,no tags\n", out.String())
+	})
+
+	t.Run("pinned columns and a custom array separator", func(t *testing.T) {
+		var out bytes.Buffer
+		require.NoError(t, WriteCSV(&out, json.RawMessage(csvTestResponse), OutputOptions{
+			CSV:            true,
+			CSVColumns:     []string{"text", "entities.hashtags.tag", "id"},
+			ArraySeparator: "|",
+		}))
+		assert.Equal(t, "text,entities.hashtags.tag,id\n"+
+			"\"hello, world\",go|rust,1850000000000000001\n"+
+			"no tags,,1850000000000000002\n", out.String())
+	})
+
+	t.Run("a single data object is one row", func(t *testing.T) {
+		var out bytes.Buffer
+		require.NoError(t, WriteCSV(&out, json.RawMessage(`{"data":{"id":"1","username":"xdev"}}`), OutputOptions{CSV: true}))
+		assert.Equal(t, "id,username\n1,xdev\n", out.String())
+	})
+
+	t.Run("non-JSON responses are rejected", func(t *testing.T) {
+		var out bytes.Buffer
+		assert.Error(t, WriteCSV(&out, json.RawMessage(`not json`), OutputOptions{CSV: true}))
+	})
+}
+
+func TestTruncateRecords(t *testing.T) {
+	t.Run("data array", func(t *testing.T) {
+		truncated, total, ok := truncateRecords(json.RawMessage(csvTestResponse), 1)
+		require.True(t, ok)
+		assert.Equal(t, 2, total)
+		assert.JSONEq(t, `{
+			"data": [{"id": "1850000000000000001", "text": "hello, world", "public_metrics": {"like_count": 3},
+				"entities": {"hashtags": [{"start": 0, "end": 3, "tag": "go"}, {"start": 4, "end": 8, "tag": "rust"}]}}],
+			"meta": {"result_count": 2}
+		}`, string(truncated))
+	})
+
+	t.Run("top-level array", func(t *testing.T) {
+		truncated, total, ok := truncateRecords(json.RawMessage(`[1,2,3]`), 2)
+		require.True(t, ok)
+		assert.Equal(t, 3, total)
+		assert.Equal(t, `[1,2]`, string(truncated))
+	})
+
+	t.Run("fewer records than the limit", func(t *testing.T) {
+		truncated, total, ok := truncateRecords(json.RawMessage(`{"data":[1]}`), 5)
+		require.True(t, ok)
+		assert.Equal(t, 1, total)
+		assert.Equal(t, `{"data":[1]}`, string(truncated))
+	})
+
+	t.Run("no records", func(t *testing.T) {
+		_, _, ok := truncateRecords(json.RawMessage(`{"data":{"id":"1"}}`), 1)
+		assert.False(t, ok)
+	})
+}
+
+func TestPrintTruncatedBytes(t *testing.T) {
+	var out, notes bytes.Buffer
+	require.NoError(t, printTruncatedBytes(&out, &notes, json.RawMessage(`{"data":{"id":"1"}}`), 10))
+	assert.Equal(t, "{\n  \"data\"\n", out.String())
+	assert.Contains(t, notes.String(), "showing 10 of 33 bytes")
+
+	out.Reset()
+	notes.Reset()
+	require.NoError(t, printTruncatedBytes(&out, &notes, json.RawMessage(`{}`), 10))
+	assert.Equal(t, "{}\n", out.String())
+	assert.Empty(t, notes.String())
+}
+
+func TestNonJSONBody(t *testing.T) {
+	text, ok := nonJSONBody(json.RawMessage(`"<ok/>"`), "application/xml")
+	assert.True(t, ok)
+	assert.Equal(t, "<ok/>", text)
+
+	_, ok = nonJSONBody(json.RawMessage(`{"data":{}}`), "application/xml")
+	assert.False(t, ok, "only bodies passed on as strings are raw text")
+	_, ok = nonJSONBody(json.RawMessage(`"a JSON string"`), "application/json")
+	assert.False(t, ok, "a JSON string response stays JSON")
+
+	var out bytes.Buffer
+	require.NoError(t, writeText(&out, "line"))
+	require.NoError(t, writeText(&out, "done\n"))
+	assert.Equal(t, "line\ndone\n", out.String())
+}
+
+func TestResolveJSONPointer(t *testing.T) {
+	response := json.RawMessage(`{"data":[{"id":"1","public_metrics":{"like_count":3}}],"a.b":{"c":true},"x/y":1,"m~n":2,"":"empty key"}`)
+
+	tests := []struct {
+		pointer string
+		want    string
+	}{
+		{"", string(response)},
+		{"/data/0/id", `"1"`},
+		{"/data/0/public_metrics/like_count", `3`},
+		{"/a.b/c", `true`},
+		{"/x~1y", `1`},
+		{"/m~0n", `2`},
+		{"/", `"empty key"`},
+	}
+	for _, tt := range tests {
+		t.Run(tt.pointer, func(t *testing.T) {
+			value, err := resolveJSONPointer(response, tt.pointer)
+			require.NoError(t, err)
+			assert.JSONEq(t, tt.want, string(value))
+		})
+	}
+
+	for _, pointer := range []string{"data", "/missing", "/data/1", "/data/01", "/data/-", "/data/0/id/x"} {
+		t.Run("error "+pointer, func(t *testing.T) {
+			_, err := resolveJSONPointer(response, pointer)
+			require.Error(t, err)
+			assert.True(t, xurlErrors.IsJSONError(err))
+		})
+	}
+}
+
+func TestRenderTemplate(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "report.md.tmpl")
+	require.NoError(t, os.WriteFile(path, []byte(
+		"# {{.meta.result_count}} posts\n{{range .data}}- {{.id}}: {{.text}} ({{.public_metrics.like_count}} likes)\n{{end}}"+
+			"{{json (index .data 0).entities.hashtags}}\n"), 0600))
+	tmpl, err := ParseTemplateFile(path)
+	require.NoError(t, err)
+
+	var out bytes.Buffer
+	require.NoError(t, renderTemplate(&out, tmpl, json.RawMessage(csvTestResponse)))
+	assert.Equal(t, "# 2 posts\n"+
+		"- 1850000000000000001: hello, world (3 likes)\n"+
+		"- 1850000000000000002: no tags (0 likes)\n"+
+		`[{"end":3,"start":0,"tag":"go"},{"end":8,"start":4,"tag":"rust"}]`+"\n", out.String())
+
+	t.Run("a template that does not parse is reported before sending", func(t *testing.T) {
+		bad := filepath.Join(t.TempDir(), "bad.tmpl")
+		require.NoError(t, os.WriteFile(bad, []byte("{{range .data}}"), 0600))
+		_, err := ParseTemplateFile(bad)
+		assert.ErrorContains(t, err, "invalid template file")
+	})
+}
@@ -0,0 +1,165 @@
+package api
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"regexp"
+	"strings"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// Paginate sends options and then requests each following page until there
+// is none, writing every page to out as one NDJSON line, shaped by the
+// JSONPointer and Truncate of options.Output (see ndjsonValue). The next page is the
+// URL of the response's Link header with rel="next" (RFC 8288), as gateways
+// and some endpoints send; failing that, it is the same request with the
+// body's meta.next_token as its next_token parameter (search and counts
+// endpoints) or pagination_token parameter (the rest of the X API); see
+// paginationParam.
+//
+// A Link to another host is refused rather than sent credentials. Pagination
+// also stops with an error if a page points back to one already fetched.
+func Paginate(options RequestOptions, client *ApiClient, out io.Writer) error {
+	seen := map[string]bool{}
+	for {
+		response, resp, err := client.SendRequestWithResponse(options)
+		if err != nil {
+			return handleRequestError(err)
+		}
+		value, err := ndjsonValue(response, options.Output)
+		if err != nil {
+			return err
+		}
+		if err := writeNDJSONLine(out, value); err != nil {
+			return err
+		}
+
+		next, err := nextPage(options, resp, response)
+		if err != nil || next == nil {
+			return err
+		}
+		key := next.Endpoint + "\n" + strings.Join(next.QueryParams, "&")
+		if seen[key] {
+			return xurlErrors.NewHTTPError(fmt.Errorf("pagination loops back to %s", next.Endpoint))
+		}
+		seen[key] = true
+		options = *next
+	}
+}
+
+// nextPage returns the options for the page after response (received as
+// resp for options), or nil on the last page.
+func nextPage(options RequestOptions, resp *http.Response, response json.RawMessage) (*RequestOptions, error) {
+	if resp == nil || resp.Request == nil {
+		return nil, nil
+	}
+	current := resp.Request.URL
+
+	if link := nextLink(resp.Header); link != "" {
+		target, err := current.Parse(link)
+		if err != nil {
+			return nil, xurlErrors.NewHTTPError(fmt.Errorf("invalid Link header URL %q: %v", link, err))
+		}
+		if !strings.EqualFold(target.Host, current.Host) {
+			return nil, xurlErrors.NewHTTPError(fmt.Errorf("not following the next-page Link to another host, %s", target.Host))
+		}
+		// The link is the complete next URL, query included.
+		options.Endpoint = target.String()
+		options.QueryParams = nil
+		options.Fields = nil
+		return &options, nil
+	}
+
+	var page struct {
+		Meta struct {
+			NextToken string `json:"next_token"`
+		} `json:"meta"`
+	}
+	if json.Unmarshal(response, &page) != nil || page.Meta.NextToken == "" {
+		return nil, nil
+	}
+	param := paginationParam(current)
+	endpoint, err := url.Parse(options.Endpoint)
+	if err != nil {
+		return nil, xurlErrors.NewHTTPError(err)
+	}
+	query := endpoint.Query()
+	query.Set(param, page.Meta.NextToken)
+	endpoint.RawQuery = query.Encode()
+	options.Endpoint = endpoint.String()
+
+	var params []string
+	for _, p := range options.QueryParams {
+		if key, _, _ := strings.Cut(p, "="); key != param {
+			params = append(params, p)
+		}
+	}
+	options.QueryParams = params
+	return &options, nil
+}
+
+// nextTokenPaths are the path segments of the endpoints that take the next
+// page's token as next_token rather than pagination_token.
+var nextTokenPaths = []string{"/search/", "/tweets/counts/"}
+
+// paginationParam returns the query parameter that carries the next page's
+// token for a request to u: the one u already has, or else the one its
+// endpoint takes.
+func paginationParam(u *url.URL) string {
+	query := u.Query()
+	switch {
+	case query.Has("next_token"):
+		return "next_token"
+	case query.Has("pagination_token"):
+		return "pagination_token"
+	}
+	for _, path := range nextTokenPaths {
+		if strings.Contains(u.Path, path) {
+			return "next_token"
+		}
+	}
+	return "pagination_token"
+}
+
+// linkSeparator matches the comma between two links of a Link header.
+var linkSeparator = regexp.MustCompile(`,\s*<`)
+
+// nextLink returns the target of the rel="next" link in header's Link
+// values, or "" when there is none. A link is "<URL>" followed by
+// ";"-separated parameters, and links are separated by commas.
+func nextLink(header http.Header) string {
+	for _, value := range header.Values("Link") {
+		for value != "" {
+			start := strings.IndexByte(value, '<')
+			end := strings.IndexByte(value, '>')
+			if start < 0 || end < start {
+				break
+			}
+			target := value[start+1 : end]
+			value = value[end+1:]
+
+			params := value
+			if loc := linkSeparator.FindStringIndex(value); loc != nil {
+				params = value[:loc[0]]
+			}
+			value = value[len(params):]
+
+			for _, param := range strings.Split(params, ";") {
+				name, rel, ok := strings.Cut(strings.TrimSpace(param), "=")
+				if !ok || !strings.EqualFold(strings.TrimSpace(name), "rel") {
+					continue
+				}
+				for _, r := range strings.Fields(strings.Trim(strings.TrimSpace(rel), `"`)) {
+					if strings.EqualFold(r, "next") {
+						return target
+					}
+				}
+			}
+		}
+	}
+	return ""
+}
@@ -0,0 +1,146 @@
+package api
+
+import (
+	"bytes"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/xdevplatform/xurl/config"
+)
+
+func TestPaginate(t *testing.T) {
+	var queries []string
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		queries = append(queries, r.URL.Path+"?"+r.URL.RawQuery)
+		w.Header().Set("Content-Type", "application/json")
+		switch r.URL.Path {
+		case "/gateway/items":
+			switch r.URL.Query().Get("page") {
+			case "":
+				w.Header().Add("Link", `</gateway/items?page=2>; rel="next", </gateway/items?page=9>; rel="last"`)
+			case "2":
+				w.Header().Add("Link", `<`+"http://"+r.Host+`/gateway/items?page=3>; rel="prev next"`)
+			}
+			w.Write([]byte(`{"page":"` + r.URL.Query().Get("page") + `"}`))
+		case "/2/tweets/search/recent", "/2/tweets/counts/recent", "/2/users/1/followers", "/2/custom/items":
+			if token := r.URL.Query().Get("next_token") + r.URL.Query().Get("pagination_token"); token == "" || token == "t1" {
+				w.Write([]byte(`{"data":[1,2],"meta":{"next_token":"t2"}}`))
+				return
+			}
+			w.Write([]byte(`{"data":[3],"meta":{}}`))
+		case "/loop":
+			w.Header().Set("Link", `</loop>; rel=next`)
+			w.Write([]byte(`{}`))
+		case "/elsewhere":
+			w.Header().Set("Link", `<https://example.com/next>; rel="next"`)
+			w.Write([]byte(`{}`))
+		}
+	}))
+	defer server.Close()
+	client := shortcutClient(t, server)
+
+	t.Run("Link rel=next is followed", func(t *testing.T) {
+		queries = nil
+		var out bytes.Buffer
+		require.NoError(t, Paginate(RequestOptions{Method: "GET", Endpoint: "/gateway/items", QueryParams: []string{"size=10"}}, client, &out))
+		assert.Equal(t, []string{"/gateway/items?size=10", "/gateway/items?page=2", "/gateway/items?page=3"}, queries)
+		assert.Equal(t, "{\"page\":\"\"}\n{\"page\":\"2\"}\n{\"page\":\"3\"}\n", out.String())
+	})
+
+	t.Run("meta.next_token is sent back", func(t *testing.T) {
+		queries = nil
+		var out bytes.Buffer
+		require.NoError(t, Paginate(RequestOptions{Method: "GET", Endpoint: "/2/tweets/search/recent?query=xurl"}, client, &out))
+		require.NoError(t, Paginate(RequestOptions{Method: "GET", Endpoint: "/2/users/1/followers", QueryParams: []string{"pagination_token=t1"}}, client, &out))
+		assert.Equal(t, []string{
+			"/2/tweets/search/recent?query=xurl",
+			"/2/tweets/search/recent?next_token=t2&query=xurl",
+			"/2/users/1/followers?pagination_token=t1",
+			"/2/users/1/followers?pagination_token=t2",
+		}, queries)
+		assert.Equal(t, 4, strings.Count(out.String(), "\n"))
+	})
+
+	t.Run("counts endpoints take next_token", func(t *testing.T) {
+		queries = nil
+		require.NoError(t, Paginate(RequestOptions{Method: "GET", Endpoint: "/2/tweets/counts/recent?query=xurl"}, client, &bytes.Buffer{}))
+		assert.Equal(t, []string{
+			"/2/tweets/counts/recent?query=xurl",
+			"/2/tweets/counts/recent?next_token=t2&query=xurl",
+		}, queries)
+	})
+
+	t.Run("each page honors --json-pointer and --truncate", func(t *testing.T) {
+		var out bytes.Buffer
+		options := RequestOptions{Method: "GET", Endpoint: "/2/custom/items", Output: OutputOptions{JSONPointer: "/data", Truncate: 1}}
+		require.NoError(t, Paginate(options, client, &out))
+		assert.Equal(t, "[1]\n[3]\n", out.String(), "the next page still comes from the whole response")
+	})
+
+	t.Run("the parameter the request already has is reused", func(t *testing.T) {
+		queries = nil
+		require.NoError(t, Paginate(RequestOptions{Method: "GET", Endpoint: "/2/custom/items?next_token=t1"}, client, &bytes.Buffer{}))
+		assert.Equal(t, []string{"/2/custom/items?next_token=t1", "/2/custom/items?next_token=t2"}, queries)
+	})
+
+	t.Run("a loop or another host stops pagination", func(t *testing.T) {
+		err := Paginate(RequestOptions{Method: "GET", Endpoint: "/loop"}, client, &bytes.Buffer{})
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), "loops back")
+
+		err = Paginate(RequestOptions{Method: "GET", Endpoint: "/elsewhere"}, client, &bytes.Buffer{})
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), "example.com")
+	})
+}
+
+func TestPaginateURLRewrite(t *testing.T) {
+	var queries, authorizations []string
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		queries = append(queries, r.URL.RequestURI())
+		authorizations = append(authorizations, r.Header.Get("Authorization"))
+		w.Header().Set("Content-Type", "application/json")
+		switch r.URL.Query().Get("page") {
+		case "":
+			w.Header().Set("Link", `</2/users/me?page=2>; rel="next"`)
+		case "2":
+			w.Header().Set("Link", `<https://api.x.com/2/users/me?page=3>; rel="next"`)
+		}
+		w.Write([]byte(`{}`))
+	}))
+	defer server.Close()
+
+	rewrite, err := config.ParseURLRewrite("api.x.com=>" + server.URL)
+	require.NoError(t, err)
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	client := NewApiClient(&config.Config{APIBaseURL: "https://api.x.com", URLRewrites: []config.URLRewrite{rewrite}}, authMock)
+
+	// Links resolve against the API host, not the gateway, and so keep
+	// their credentials and pass the same-host check.
+	require.NoError(t, Paginate(RequestOptions{Method: "GET", Endpoint: "/2/users/me"}, client, &bytes.Buffer{}))
+	assert.Equal(t, []string{"/2/users/me", "/2/users/me?page=2", "/2/users/me?page=3"}, queries)
+	for i, authorization := range authorizations {
+		assert.NotEmpty(t, authorization, "page %d", i+1)
+	}
+}
+
+func TestNextLink(t *testing.T) {
+	for _, tt := range [][2]string{
+		{`<https://a/?p=2>; rel="next"`, "https://a/?p=2"},
+		{`<https://a/?p=1>; rel="prev",  <https://a/?p=3>; rel="next"`, "https://a/?p=3"},
+		{`<https://a/?x=1,2>; title="a, b"; REL=Next`, "https://a/?x=1,2"},
+		{`<https://a/?p=9>; rel="last"`, ""},
+		{`garbage`, ""},
+	} {
+		header := http.Header{}
+		header.Set("Link", tt[0])
+		assert.Equal(t, tt[1], nextLink(header), tt[0])
+	}
+}
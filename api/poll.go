@@ -0,0 +1,128 @@
+package api
+
+import (
+	"bytes"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"os"
+	"time"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// ErrConditionNotMet is returned by Poll when its Count of requests ran out
+// before the Until predicate held.
+var ErrConditionNotMet = errors.New("--until condition not met")
+
+// PollOptions controls how Poll repeats a request.
+type PollOptions struct {
+	// Interval is the time from the start of one request to the next.
+	Interval time.Duration
+	// Count stops after this many requests; 0 repeats until Until holds, or
+	// indefinitely without it.
+	Count int
+	// Until, when set, stops as soon as a response satisfies it.
+	Until *Predicate
+	// OnFailure is what an error response does: stop polling by default, or
+	// with ContinueOnError, poll again at the next interval.
+	OnFailure FailurePolicy
+}
+
+// pollSleep waits between polls; tests replace it.
+var pollSleep = time.Sleep
+
+// Poll sends options every poll.Interval and writes each response to out as
+// one NDJSON line (a body that is not JSON as a JSON string), shaped by the
+// JSONPointer and Truncate of options.Output (see ndjsonValue). Each request is
+// built afresh, so OAuth2 tokens are refreshed as they expire. A 429 is not
+// counted as a poll: Poll waits until the rate-limit window resets (see
+// rateLimitWait) and tries again, while options.Retry.Budget lasts.
+//
+// An error response is written as {"error": ...} and counted as a poll. It
+// stops polling unless poll.OnFailure is ContinueOnError, and an auth error
+// always does. Poll then returns an error if any request failed, even when
+// poll.Until held in the end. When poll.Count requests were made and
+// poll.Until never held, Poll returns ErrConditionNotMet.
+func Poll(options RequestOptions, poll PollOptions, client Client, out io.Writer) error {
+	failures := &failureTracker{policy: poll.OnFailure.or(FailFast)}
+	for n := 0; poll.Count == 0 || n < poll.Count; {
+		start := time.Now()
+		response, header, err := sendForPoll(options, client)
+		if err != nil && isRateLimitError(err) && options.Retry.Budget.take() {
+			wait := rateLimitWait(header, time.Now(), 0)
+			fmt.Fprintf(os.Stderr, "Rate limited (HTTP 429); polling again in %s.\n", wait.Round(time.Second))
+			pollSleep(wait)
+			continue
+		}
+		n++
+
+		if err != nil {
+			line, _ := json.Marshal(map[string]any{"error": errorValue(err)})
+			if writeErr := writeNDJSONLine(out, line); writeErr != nil {
+				return writeErr
+			}
+			if failures.record(fmt.Sprintf("poll %d", n), err) {
+				return failures.err(n)
+			}
+		} else {
+			value, err := ndjsonValue(response, options.Output)
+			if err != nil {
+				return err
+			}
+			if err := writeNDJSONLine(out, value); err != nil {
+				return err
+			}
+			if poll.Until != nil {
+				done, err := poll.Until.Match(response)
+				if err != nil {
+					return err
+				}
+				if done {
+					return failures.err(n)
+				}
+			}
+		}
+
+		if poll.Count == 0 || n < poll.Count {
+			pollSleep(poll.Interval - time.Since(start))
+		}
+	}
+	if err := failures.err(poll.Count); err != nil {
+		return err
+	}
+	if poll.Until != nil {
+		return ErrConditionNotMet
+	}
+	return nil
+}
+
+// sendForPoll sends options, also returning the response headers when client
+// exposes them.
+func sendForPoll(options RequestOptions, client Client) (json.RawMessage, http.Header, error) {
+	if apiClient, ok := client.(*ApiClient); ok {
+		response, resp, err := apiClient.SendRequestWithResponse(options)
+		if resp == nil {
+			return response, nil, err
+		}
+		return response, resp.Header, err
+	}
+	response, err := client.SendRequest(options)
+	return response, nil, err
+}
+
+// writeNDJSONLine writes response to out as one compact JSON line.
+func writeNDJSONLine(out io.Writer, response json.RawMessage) error {
+	var line bytes.Buffer
+	if json.Compact(&line, response) != nil {
+		line.Reset()
+		line.Write(batchBody(response))
+	}
+	line.WriteByte('\n')
+	if _, err := out.Write(line.Bytes()); err != nil {
+		return xurlErrors.NewIOError(err)
+	}
+	return nil
+}
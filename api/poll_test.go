@@ -0,0 +1,149 @@
+package api
+
+import (
+	"bytes"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"sync/atomic"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// sequenceServer answers the nth request with bodies[n], repeating the last
+// body once the sequence runs out. A body of "429" is sent as a rate limit.
+func sequenceServer(t *testing.T, bodies ...string) (*httptest.Server, *int32) {
+	t.Helper()
+	var requests int32
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		n := int(atomic.AddInt32(&requests, 1)) - 1
+		body := bodies[min(n, len(bodies)-1)]
+		w.Header().Set("Content-Type", "application/json")
+		if body == "429" {
+			w.Header().Set("Retry-After", "7")
+			w.WriteHeader(http.StatusTooManyRequests)
+			w.Write([]byte(`{"title":"Too Many Requests","status":429}`))
+			return
+		}
+		w.Write([]byte(body))
+	}))
+	t.Cleanup(server.Close)
+	return server, &requests
+}
+
+func fakePollSleep(t *testing.T) *[]time.Duration {
+	t.Helper()
+	var sleeps []time.Duration
+	old := pollSleep
+	pollSleep = func(d time.Duration) { sleeps = append(sleeps, d) }
+	t.Cleanup(func() { pollSleep = old })
+	return &sleeps
+}
+
+func TestPoll(t *testing.T) {
+	options := RequestOptions{Method: "GET", Endpoint: "/2/tweets/1"}
+
+	t.Run("count stops after that many responses", func(t *testing.T) {
+		sleeps := fakePollSleep(t)
+		server, requests := sequenceServer(t, `{"n": 1}`, `{"n": 2}`, `{"n": 3}`)
+		var out bytes.Buffer
+		require.NoError(t, Poll(options, PollOptions{Interval: time.Minute, Count: 3}, shortcutClient(t, server), &out))
+		assert.Equal(t, "{\"n\":1}\n{\"n\":2}\n{\"n\":3}\n", out.String())
+		assert.Equal(t, int32(3), atomic.LoadInt32(requests))
+		require.Len(t, *sleeps, 2, "no sleep after the last request")
+		assert.Greater(t, (*sleeps)[0], 59*time.Second)
+	})
+
+	t.Run("each line honors --json-pointer and --truncate", func(t *testing.T) {
+		fakePollSleep(t)
+		server, _ := sequenceServer(t, `{"data": {"items": [1, 2, 3]}}`)
+		shaped := options
+		shaped.Output = OutputOptions{JSONPointer: "/data/items", Truncate: 2}
+		var out bytes.Buffer
+		require.NoError(t, Poll(shaped, PollOptions{Interval: time.Minute, Count: 2}, shortcutClient(t, server), &out))
+		assert.Equal(t, "[1,2]\n[1,2]\n", out.String())
+	})
+
+	t.Run("until stops at the first matching response", func(t *testing.T) {
+		fakePollSleep(t)
+		server, requests := sequenceServer(t, `{"data":{"state":"pending"}}`, `{"data":{"state":"pending"}}`, `{"data":{"state":"done"}}`)
+		until, err := ParsePredicate(`.data.state == "done"`)
+		require.NoError(t, err)
+		var out bytes.Buffer
+		require.NoError(t, Poll(options, PollOptions{Interval: time.Second, Count: 10, Until: until}, shortcutClient(t, server), &out))
+		assert.Equal(t, int32(3), atomic.LoadInt32(requests))
+		assert.Equal(t, 3, strings.Count(out.String(), "\n"))
+	})
+
+	t.Run("count running out before until holds", func(t *testing.T) {
+		fakePollSleep(t)
+		server, _ := sequenceServer(t, `{"data":{"state":"pending"}}`)
+		until, err := ParsePredicate(`.data.state == "done"`)
+		require.NoError(t, err)
+		err = Poll(options, PollOptions{Interval: time.Second, Count: 2, Until: until}, shortcutClient(t, server), &bytes.Buffer{})
+		assert.ErrorIs(t, err, ErrConditionNotMet)
+	})
+
+	t.Run("a 429 waits and is not counted", func(t *testing.T) {
+		sleeps := fakePollSleep(t)
+		server, requests := sequenceServer(t, `{"n": 1}`, "429", `{"n": 2}`)
+		var out bytes.Buffer
+		require.NoError(t, Poll(options, PollOptions{Interval: time.Second, Count: 2}, shortcutClient(t, server), &out))
+		assert.Equal(t, "{\"n\":1}\n{\"n\":2}\n", out.String())
+		assert.Equal(t, int32(3), atomic.LoadInt32(requests))
+		assert.Contains(t, *sleeps, 7*time.Second)
+	})
+
+	t.Run("an error response stops polling", func(t *testing.T) {
+		fakePollSleep(t)
+		server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			w.Header().Set("Content-Type", "application/json")
+			w.WriteHeader(http.StatusNotFound)
+			w.Write([]byte(`{"title":"Not Found Error","status":404}`))
+		}))
+		defer server.Close()
+		err := Poll(options, PollOptions{Interval: time.Second}, shortcutClient(t, server), &bytes.Buffer{})
+		assert.Error(t, err)
+	})
+}
+
+func TestPredicate(t *testing.T) {
+	response := []byte(`{"data":[{"id":"1850000000000000001","public_metrics":{"like_count":120}}],"meta":{"result_count":1,"next-token":null},"done":false}`)
+	tests := []struct {
+		expr string
+		want bool
+	}{
+		{".", true},
+		{".meta.result_count", true},
+		{".meta.missing", false},
+		{`.meta."next-token"`, false},
+		{".done", false},
+		{".data[0].public_metrics.like_count >= 100", true},
+		{".data[0].public_metrics.like_count < 100", false},
+		{".data[0].public_metrics.like_count == 120.0", true},
+		{`.data[0].id == "1850000000000000001"`, true},
+		{`.data[0].id != "1850000000000000002"`, true},
+		{".data[1].id == null", true},
+		{`.data[0].public_metrics.like_count > "100"`, false},
+	}
+	for _, tt := range tests {
+		p, err := ParsePredicate(tt.expr)
+		require.NoError(t, err, tt.expr)
+		got, err := p.Match(response)
+		require.NoError(t, err, tt.expr)
+		assert.Equal(t, tt.want, got, tt.expr)
+	}
+
+	for _, expr := range []string{"", "data.id", ".data ==", ".data == done", ".data[x]", ".data | length"} {
+		_, err := ParsePredicate(expr)
+		assert.Error(t, err, expr)
+	}
+
+	p, err := ParsePredicate(".data")
+	require.NoError(t, err)
+	_, err = p.Match([]byte("not json"))
+	assert.Error(t, err)
+}
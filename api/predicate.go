@@ -0,0 +1,199 @@
+package api
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"reflect"
+	"regexp"
+	"strconv"
+	"strings"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// predicatePattern splits a predicate into its path, and optionally an
+// operator and a JSON value: ".data.public_metrics.like_count >= 100".
+var predicatePattern = regexp.MustCompile(`^\s*(\.[^\s=!<>]*)\s*(?:(==|!=|<=|>=|<|>)\s*(.+?))?\s*$`)
+
+// pathStep matches one step of a predicate path: ".key", ".\"quoted key\"",
+// or "[index]".
+var pathStep = regexp.MustCompile(`^(?:\.([A-Za-z_][A-Za-z0-9_]*)|\."((?:[^"\\]|\\.)*)"|\[(\d+)\])`)
+
+// Predicate is a jq-style condition on a JSON response, such as
+// ".data.public_metrics.like_count >= 100" or ".meta.next_token". A path alone
+// is true when its value is neither null nor false, as in jq; a missing field
+// is null.
+type Predicate struct {
+	expr     string
+	path     []any // string keys and int indexes
+	operator string
+	value    any
+}
+
+// ParsePredicate parses a predicate: a path of ".key" and "[index]" steps
+// ("." alone is the whole response), optionally followed by one of ==, !=, <,
+// <=, >, >= and a JSON value to compare the path's value with.
+func ParsePredicate(expr string) (*Predicate, error) {
+	match := predicatePattern.FindStringSubmatch(expr)
+	if match == nil {
+		return nil, predicateError(expr, "expected a path such as .data.id, optionally followed by an operator and a JSON value")
+	}
+	p := &Predicate{expr: expr, operator: match[2]}
+
+	rest := match[1]
+	if rest == "." {
+		rest = ""
+	}
+	for rest != "" {
+		step := pathStep.FindStringSubmatch(rest)
+		if step == nil {
+			return nil, predicateError(expr, fmt.Sprintf("cannot parse path at %q", rest))
+		}
+		switch {
+		case step[1] != "":
+			p.path = append(p.path, step[1])
+		case step[3] != "":
+			index, _ := strconv.Atoi(step[3])
+			p.path = append(p.path, index)
+		default:
+			key, err := strconv.Unquote(`"` + step[2] + `"`)
+			if err != nil {
+				return nil, predicateError(expr, fmt.Sprintf("invalid quoted key %q", step[2]))
+			}
+			p.path = append(p.path, key)
+		}
+		rest = rest[len(step[0]):]
+	}
+
+	if p.operator != "" {
+		value, err := decodeJSONNumbers([]byte(match[3]))
+		if err != nil {
+			return nil, predicateError(expr, fmt.Sprintf("%q is not a JSON value (quote strings)", match[3]))
+		}
+		p.value = value
+	}
+	return p, nil
+}
+
+// Match reports whether the predicate holds for response.
+func (p *Predicate) Match(response json.RawMessage) (bool, error) {
+	value, err := decodeJSONNumbers(response)
+	if err != nil {
+		return false, xurlErrors.NewJSONError(fmt.Errorf("--until needs a JSON response: %v", err))
+	}
+	for _, step := range p.path {
+		switch key := step.(type) {
+		case string:
+			object, _ := value.(map[string]any)
+			value = object[key]
+		case int:
+			array, _ := value.([]any)
+			if key < len(array) {
+				value = array[key]
+			} else {
+				value = nil
+			}
+		}
+	}
+
+	switch p.operator {
+	case "":
+		return value != nil && value != false, nil
+	case "==":
+		return jsonEqual(value, p.value), nil
+	case "!=":
+		return !jsonEqual(value, p.value), nil
+	}
+	order, ok := jsonCompare(value, p.value)
+	if !ok {
+		return false, nil
+	}
+	switch p.operator {
+	case "<":
+		return order < 0, nil
+	case "<=":
+		return order <= 0, nil
+	case ">":
+		return order > 0, nil
+	default:
+		return order >= 0, nil
+	}
+}
+
+// String returns the predicate as it was written.
+func (p *Predicate) String() string {
+	return p.expr
+}
+
+func predicateError(expr, reason string) error {
+	return xurlErrors.NewJSONError(fmt.Errorf("invalid --until predicate %q: %s", expr, reason))
+}
+
+// decodeJSONNumbers decodes data keeping numbers as json.Number, so that large
+// IDs and counts compare exactly.
+func decodeJSONNumbers(data []byte) (any, error) {
+	var value any
+	decoder := json.NewDecoder(bytes.NewReader(data))
+	decoder.UseNumber()
+	if err := decoder.Decode(&value); err != nil {
+		return nil, err
+	}
+	if decoder.More() {
+		return nil, fmt.Errorf("unexpected data after the value")
+	}
+	return value, nil
+}
+
+// jsonEqual compares two decoded JSON values, numbers by value (so 1 == 1.0).
+func jsonEqual(a, b any) bool {
+	if order, ok := compareNumbers(a, b); ok {
+		return order == 0
+	}
+	return reflect.DeepEqual(a, b)
+}
+
+// jsonCompare orders two numbers or two strings; ok is false for anything else.
+func jsonCompare(a, b any) (int, bool) {
+	if order, ok := compareNumbers(a, b); ok {
+		return order, true
+	}
+	as, aok := a.(string)
+	bs, bok := b.(string)
+	if aok && bok {
+		return strings.Compare(as, bs), true
+	}
+	return 0, false
+}
+
+func compareNumbers(a, b any) (int, bool) {
+	an, aok := a.(json.Number)
+	bn, bok := b.(json.Number)
+	if !aok || !bok {
+		return 0, false
+	}
+	// Integers are compared exactly; IDs overflow a float64.
+	if ai, err := strconv.ParseInt(an.String(), 10, 64); err == nil {
+		if bi, err := strconv.ParseInt(bn.String(), 10, 64); err == nil {
+			switch {
+			case ai < bi:
+				return -1, true
+			case ai > bi:
+				return 1, true
+			}
+			return 0, true
+		}
+	}
+	af, aerr := an.Float64()
+	bf, berr := bn.Float64()
+	if aerr != nil || berr != nil {
+		return 0, false
+	}
+	switch {
+	case af < bf:
+		return -1, true
+	case af > bf:
+		return 1, true
+	}
+	return 0, true
+}
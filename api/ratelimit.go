@@ -0,0 +1,92 @@
+package api
+
+import (
+	"fmt"
+	"net/http"
+	"net/url"
+	"os"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/xdevplatform/xurl/store"
+)
+
+// WithRateLimitStore makes the client record the rate-limit headers of every
+// response in rateLimits, so that later invocations know the state of each
+// endpoint's bucket (see RequestOptions.RespectRateLimit).
+func (c *ApiClient) WithRateLimitStore(rateLimits *store.RateLimitStore) *ApiClient {
+	c.rateLimits = rateLimits
+	return c
+}
+
+// rateLimitBucket names the rate-limit bucket of a request: its host, method,
+// and endpoint family, the path with IDs and usernames replaced so that
+// /2/tweets/1 and /2/tweets/2 share "/2/tweets/:id" as they share a limit.
+func rateLimitBucket(method string, u *url.URL) string {
+	if method == "" {
+		method = http.MethodGet
+	}
+	segments := strings.Split(u.Path, "/")
+	for i, segment := range segments {
+		switch {
+		case segment != "" && strings.Trim(segment, "0123456789") == "":
+			segments[i] = ":id"
+		case i >= 2 && segments[i-2] == "by" && segments[i-1] == "username":
+			segments[i] = ":username"
+		}
+	}
+	return u.Host + " " + strings.ToUpper(method) + " " + strings.Join(segments, "/")
+}
+
+// rateLimitFromHeader reads the x-rate-limit-* headers of a response. ok is
+// false when it has no remaining count and reset time.
+func rateLimitFromHeader(header http.Header) (limit store.RateLimit, ok bool) {
+	remaining, err := strconv.Atoi(header.Get("x-rate-limit-remaining"))
+	if err != nil {
+		return limit, false
+	}
+	reset, err := strconv.ParseInt(header.Get("x-rate-limit-reset"), 10, 64)
+	if err != nil {
+		return limit, false
+	}
+	limit.Limit, _ = strconv.Atoi(header.Get("x-rate-limit-limit"))
+	limit.Remaining = remaining
+	limit.Reset = reset
+	return limit, true
+}
+
+// recordRateLimit stores the rate-limit state of resp when the client has a
+// rate-limit store. The state is only a hint, so failing to save it is not an
+// error.
+func (c *ApiClient) recordRateLimit(resp *http.Response) {
+	if c.rateLimits == nil || resp.Request == nil {
+		return
+	}
+	if limit, ok := rateLimitFromHeader(resp.Header); ok {
+		_ = c.rateLimits.Record(rateLimitBucket(resp.Request.Method, resp.Request.URL), limit)
+	}
+}
+
+// waitForRateLimit sleeps until the bucket of options resets when the stored
+// state says it is empty, so that a script calling xurl in a loop waits
+// instead of being rejected with a 429. The wait is capped at maxRateLimitWait.
+func (c *ApiClient) waitForRateLimit(options RequestOptions) {
+	if c.rateLimits == nil {
+		return
+	}
+	u, err := url.Parse(c.resolveURL(options.Endpoint))
+	if err != nil {
+		return
+	}
+	bucket := rateLimitBucket(options.Method, u)
+	limit := c.rateLimits.Get(bucket)
+	now := time.Now()
+	if !limit.Exhausted(now) {
+		return
+	}
+	// Add a second so the request lands after the window has reopened.
+	wait := min(time.Unix(limit.Reset, 0).Sub(now)+time.Second, maxRateLimitWait)
+	fmt.Fprintf(os.Stderr, "Rate limit of %s used up (0 of %d left); waiting %s for it to reset.\n", bucket, limit.Limit, wait.Round(time.Second))
+	time.Sleep(wait)
+}
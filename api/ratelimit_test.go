@@ -0,0 +1,84 @@
+package api
+
+import (
+	"net/http"
+	"net/http/httptest"
+	"net/url"
+	"os"
+	"path/filepath"
+	"strconv"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/xdevplatform/xurl/config"
+	"github.com/xdevplatform/xurl/store"
+)
+
+func TestRateLimitBucket(t *testing.T) {
+	tests := []struct {
+		method, rawURL, want string
+	}{
+		{"GET", "https://api.x.com/2/tweets/1234567890", "api.x.com GET /2/tweets/:id"},
+		{"", "https://api.x.com/2/tweets/search/recent?query=xurl", "api.x.com GET /2/tweets/search/recent"},
+		{"delete", "https://api.x.com/2/users/42/likes/99", "api.x.com DELETE /2/users/:id/likes/:id"},
+		{"GET", "https://api.x.com/2/users/by/username/xdevelopers", "api.x.com GET /2/users/by/username/:username"},
+	}
+	for _, tt := range tests {
+		u, err := url.Parse(tt.rawURL)
+		require.NoError(t, err)
+		assert.Equal(t, tt.want, rateLimitBucket(tt.method, u))
+	}
+}
+
+func TestRespectRateLimit(t *testing.T) {
+	previous := maxRateLimitWait
+	maxRateLimitWait = 50 * time.Millisecond
+	defer func() { maxRateLimitWait = previous }()
+
+	reset := time.Now().Add(10 * time.Minute).Unix()
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "application/json")
+		w.Header().Set("x-rate-limit-limit", "300")
+		w.Header().Set("x-rate-limit-remaining", "0")
+		w.Header().Set("x-rate-limit-reset", strconv.FormatInt(reset, 10))
+		w.Write([]byte(`{"data":{}}`))
+	}))
+	defer server.Close()
+
+	authMock, tempDir := createMockAuth(t)
+	defer os.RemoveAll(tempDir)
+	statePath := filepath.Join(t.TempDir(), "ratelimits.yml")
+	client := NewApiClient(&config.Config{APIBaseURL: server.URL}, authMock).
+		WithRateLimitStore(store.NewRateLimitStoreWithPath(statePath))
+
+	_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/tweets/1", AuthType: "app"})
+	require.NoError(t, err)
+
+	// A new invocation sees the state saved by the previous one.
+	host, _ := url.Parse(server.URL)
+	saved := store.NewRateLimitStoreWithPath(statePath).Get(host.Host + " GET /2/tweets/:id")
+	require.NotNil(t, saved)
+	assert.Equal(t, store.RateLimit{Limit: 300, Remaining: 0, Reset: reset}, *saved)
+
+	t.Run("waits for an empty bucket of the same family", func(t *testing.T) {
+		start := time.Now()
+		_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/tweets/2", AuthType: "app", RespectRateLimit: true})
+		require.NoError(t, err)
+		assert.GreaterOrEqual(t, time.Since(start), maxRateLimitWait)
+	})
+
+	t.Run("other endpoints and requests without the option do not wait", func(t *testing.T) {
+		for _, options := range []RequestOptions{
+			{Method: "GET", Endpoint: "/2/tweets/2", AuthType: "app"},
+			{Method: "GET", Endpoint: "/2/users/me", AuthType: "app", RespectRateLimit: true},
+		} {
+			start := time.Now()
+			_, err := client.SendRequest(options)
+			require.NoError(t, err)
+			assert.Less(t, time.Since(start), maxRateLimitWait)
+		}
+	})
+}
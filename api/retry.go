@@ -0,0 +1,108 @@
+package api
+
+import (
+	"math/rand/v2"
+	"net/http"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+)
+
+// RetryPolicy controls how a rate-limited (HTTP 429) request is retried.
+type RetryPolicy struct {
+	// MaxRetries is how many times one request is retried; 0 never retries.
+	MaxRetries int
+	// MaxTime caps the total time one request waits between its retries. A
+	// retry whose wait would exceed it is not made. 0 means no cap.
+	MaxTime time.Duration
+	// Budget, when set, caps the retries of all the requests that share it,
+	// e.g. those of a batch or of --paginate, so that a flapping server does
+	// not multiply waits across hundreds of requests.
+	Budget *RetryBudget
+}
+
+// RetryBudget is a number of retries shared by many requests. It is safe for
+// concurrent use; a nil *RetryBudget is unlimited.
+type RetryBudget struct {
+	mu        sync.Mutex
+	remaining int
+}
+
+// NewRetryBudget returns a budget of n retries.
+func NewRetryBudget(n int) *RetryBudget {
+	return &RetryBudget{remaining: n}
+}
+
+// take uses up one retry, reporting false when none is left.
+func (b *RetryBudget) take() bool {
+	if b == nil {
+		return true
+	}
+	b.mu.Lock()
+	defer b.mu.Unlock()
+	if b.remaining <= 0 {
+		return false
+	}
+	b.remaining--
+	return true
+}
+
+// DefaultBatchRetries is how many times a rate-limited request of xurl batch,
+// --input-csv, or --stdin-urls is retried unless --retry says otherwise.
+const DefaultBatchRetries = 3
+
+// maxRateLimitWait caps the wait before retrying a 429, so a bogus reset time
+// cannot stall xurl indefinitely. X rate-limit windows are 15 minutes.
+var maxRateLimitWait = 15*time.Minute + 5*time.Second
+
+// rateLimitBackoff is the ceiling of the first wait after a 429 that says
+// nothing of when to retry; it doubles per retry.
+var rateLimitBackoff = time.Second
+
+// jitter returns a random duration in [0, n]; tests replace it.
+var jitter = func(n time.Duration) time.Duration {
+	return time.Duration(rand.Int64N(int64(n) + 1))
+}
+
+// rateLimitWait returns how long to wait before retrying a 429: as long as
+// Retry-After says (in seconds or as an HTTP date), which is the server's
+// answer for this very request, else until the x-rate-limit-reset epoch second
+// of the whole window, else a random wait of up to an exponential backoff
+// ("full jitter"), so that clients rate limited together do not retry
+// together. The result is capped at maxRateLimitWait.
+func rateLimitWait(header http.Header, now time.Time, attempt int) time.Duration {
+	var wait time.Duration
+	if after, ok := retryAfter(header, now); ok {
+		wait = after
+	} else if reset, err := strconv.ParseInt(header.Get("x-rate-limit-reset"), 10, 64); err == nil {
+		// Add a second so the retry lands after the window has reopened.
+		wait = time.Unix(reset, 0).Sub(now) + time.Second
+	} else {
+		backoff := maxRateLimitWait
+		if attempt < 30 {
+			backoff = min(rateLimitBackoff<<attempt, maxRateLimitWait)
+		}
+		wait = jitter(backoff)
+	}
+	if wait < 0 {
+		wait = 0
+	}
+	return min(wait, maxRateLimitWait)
+}
+
+// retryAfter parses a Retry-After header, given either as delay seconds or as
+// an HTTP date, into the wait from now.
+func retryAfter(header http.Header, now time.Time) (time.Duration, bool) {
+	value := strings.TrimSpace(header.Get("Retry-After"))
+	if value == "" {
+		return 0, false
+	}
+	if seconds, err := strconv.Atoi(value); err == nil {
+		return time.Duration(seconds) * time.Second, true
+	}
+	if date, err := http.ParseTime(value); err == nil {
+		return date.Sub(now), true
+	}
+	return 0, false
+}
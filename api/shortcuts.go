@@ -5,6 +5,7 @@ import (
 	"fmt"
 	"net/url"
 	"strings"
+	"time"
 )
 
 // ------------------------------------------------
@@ -103,6 +104,38 @@ func CreatePost(client Client, text string, mediaIDs []string, opts RequestOptio
 	return client.SendRequest(opts)
 }
 
+// NewPostBody builds the body for a new post. replyTo and quote are optional
+// post IDs or URLs; when set they become reply.in_reply_to_tweet_id and
+// quote_tweet_id, so one post can both reply to and quote others.
+func NewPostBody(text, replyTo, quote string, mediaIDs []string) PostBody {
+	body := PostBody{Text: text}
+	if replyTo != "" {
+		body.Reply = &PostReply{InReplyToPostID: ResolvePostID(replyTo)}
+	}
+	if quote != "" {
+		quoteID := ResolvePostID(quote)
+		body.Quote = &quoteID
+	}
+	if len(mediaIDs) > 0 {
+		body.Media = &PostMedia{MediaIDs: mediaIDs}
+	}
+	return body
+}
+
+// SendPost creates a post from a prepared body.
+func SendPost(client Client, body PostBody, opts RequestOptions) (json.RawMessage, error) {
+	data, err := json.Marshal(body)
+	if err != nil {
+		return nil, fmt.Errorf("failed to marshal post body: %w", err)
+	}
+
+	opts.Method = "POST"
+	opts.Endpoint = "/2/tweets"
+	opts.Data = string(data)
+
+	return client.SendRequest(opts)
+}
+
 // ReplyToPost sends a reply to an existing post.
 func ReplyToPost(client Client, postID, text string, mediaIDs []string, opts RequestOptions) (json.RawMessage, error) {
 	postID = ResolvePostID(postID)
@@ -172,6 +205,13 @@ func ReadPost(client Client, postID string, opts RequestOptions) (json.RawMessag
 
 // SearchPosts searches recent posts.
 func SearchPosts(client Client, query string, maxResults int, opts RequestOptions) (json.RawMessage, error) {
+	return SearchPostsBetween(client, query, maxResults, time.Time{}, time.Time{}, opts)
+}
+
+// SearchPostsBetween is SearchPosts limited to posts created from since up to
+// until, sent as RFC 3339 start_time/end_time. A zero time leaves that bound
+// open.
+func SearchPostsBetween(client Client, query string, maxResults int, since, until time.Time, opts RequestOptions) (json.RawMessage, error) {
 	q := url.QueryEscape(query)
 
 	// X API enforces min 10 / max 100 for search
@@ -179,6 +219,12 @@ func SearchPosts(client Client, query string, maxResults int, opts RequestOption
 
 	opts.Method = "GET"
 	opts.Endpoint = fmt.Sprintf("/2/tweets/search/recent?query=%s&max_results=%d&tweet.fields=created_at,public_metrics,conversation_id,entities&expansions=author_id&user.fields=username,name,verified", q, maxResults)
+	if !since.IsZero() {
+		opts.Endpoint += "&start_time=" + url.QueryEscape(since.UTC().Format(time.RFC3339))
+	}
+	if !until.IsZero() {
+		opts.Endpoint += "&end_time=" + url.QueryEscape(until.UTC().Format(time.RFC3339))
+	}
 	opts.Data = ""
 
 	return client.SendRequest(opts)
@@ -373,6 +419,23 @@ func SendDM(client Client, participantID, text string, opts RequestOptions) (jso
 	return client.SendRequest(opts)
 }
 
+// SendDMToConversation sends a direct message to an existing conversation
+// (one-to-one or group) by its conversation ID.
+func SendDMToConversation(client Client, conversationID, text string, opts RequestOptions) (json.RawMessage, error) {
+	data, err := json.Marshal(struct {
+		Text string `json:"text"`
+	}{Text: text})
+	if err != nil {
+		return nil, fmt.Errorf("failed to marshal DM body: %w", err)
+	}
+
+	opts.Method = "POST"
+	opts.Endpoint = fmt.Sprintf("/2/dm_conversations/%s/messages", url.PathEscape(conversationID))
+	opts.Data = string(data)
+
+	return client.SendRequest(opts)
+}
+
 // GetDMEvents fetches recent DM events.
 func GetDMEvents(client Client, maxResults int, opts RequestOptions) (json.RawMessage, error) {
 	maxResults = clampResults(maxResults, 1, 100)
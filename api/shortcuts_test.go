@@ -10,8 +10,10 @@ import (
 	"strconv"
 	"strings"
 	"testing"
+	"time"
 
 	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/mock"
 	"github.com/stretchr/testify/require"
 
 	"github.com/xdevplatform/xurl/config"
@@ -111,6 +113,9 @@ func setupShortcutServer() *httptest.Server {
 func shortcutClient(t *testing.T, server *httptest.Server) *ApiClient {
 	authMock, tempDir := createMockAuth(t)
 	t.Cleanup(func() { os.RemoveAll(tempDir) })
+	// DM endpoints refuse the app-only bearer, so also store user-context
+	// (OAuth1) credentials.
+	require.NoError(t, authMock.TokenStore.SaveOAuth1Tokens("test-access-token", "test-token-secret", "test-consumer-key", "test-consumer-secret"))
 
 	cfg := &config.Config{APIBaseURL: server.URL}
 	return NewApiClient(cfg, authMock)
@@ -151,6 +156,40 @@ func TestCreatePostWithMedia(t *testing.T) {
 	assert.NotNil(t, resp)
 }
 
+func TestNewPostBody(t *testing.T) {
+	tests := []struct {
+		name     string
+		text     string
+		replyTo  string
+		quote    string
+		mediaIDs []string
+		want     string
+	}{
+		{"text only", "hello world", "", "", nil, `{"text":"hello world"}`},
+		{"reply", "hi", "https://x.com/u/status/123", "", nil, `{"text":"hi","reply":{"in_reply_to_tweet_id":"123"}}`},
+		{"quote", "look", "", "456", nil, `{"text":"look","quote_tweet_id":"456"}`},
+		{"reply and quote with media", "both", "123", "https://x.com/u/status/456", []string{"m1"},
+			`{"text":"both","reply":{"in_reply_to_tweet_id":"123"},"quote_tweet_id":"456","media":{"media_ids":["m1"]}}`},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			data, err := json.Marshal(NewPostBody(tt.text, tt.replyTo, tt.quote, tt.mediaIDs))
+			require.NoError(t, err)
+			assert.JSONEq(t, tt.want, string(data))
+		})
+	}
+}
+
+func TestSendPost(t *testing.T) {
+	server := setupShortcutServer()
+	defer server.Close()
+	client := shortcutClient(t, server)
+
+	resp, err := SendPost(client, NewPostBody("Hello!", "123", "", nil), baseTestOpts())
+	require.NoError(t, err)
+	assert.Contains(t, string(resp), "99999")
+}
+
 // ---- ReplyToPost ----
 
 func TestReplyToPost(t *testing.T) {
@@ -243,6 +282,20 @@ func TestSearchPosts(t *testing.T) {
 	assert.Equal(t, 1, result.Meta.ResultCount)
 }
 
+func TestSearchPostsBetween(t *testing.T) {
+	mockClient := new(MockApiClient)
+	mockClient.On("SendRequest", mock.MatchedBy(func(opts RequestOptions) bool {
+		return strings.Contains(opts.Endpoint, "&start_time=2024-05-08T15%3A30%3A00Z") &&
+			strings.HasSuffix(opts.Endpoint, "&end_time=2024-05-09T00%3A00%3A00Z")
+	})).Return(json.RawMessage(`{}`), nil)
+
+	since := time.Date(2024, 5, 8, 15, 30, 0, 0, time.UTC)
+	until := time.Date(2024, 5, 9, 2, 0, 0, 0, time.FixedZone("CEST", 2*60*60))
+	_, err := SearchPostsBetween(mockClient, "golang", 10, since, until, RequestOptions{})
+	require.NoError(t, err)
+	mockClient.AssertExpectations(t)
+}
+
 // ---- GetMe ----
 
 func TestGetMe(t *testing.T) {
@@ -310,6 +363,25 @@ func TestSendDMEscapesText(t *testing.T) {
 	assert.Equal(t, tricky, parsed.Text)
 }
 
+func TestSendDMToConversation(t *testing.T) {
+	var gotMethod, gotPath string
+	var gotBody []byte
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		gotMethod, gotPath = r.Method, r.URL.Path
+		gotBody, _ = io.ReadAll(r.Body)
+		w.Header().Set("Content-Type", "application/json")
+		w.Write([]byte(`{"data":{"dm_conversation_id":"1-42","dm_event_id":"7"}}`))
+	}))
+	defer server.Close()
+	client := shortcutClient(t, server)
+
+	_, err := SendDMToConversation(client, "1-42", "hello again", baseTestOpts())
+	require.NoError(t, err)
+	assert.Equal(t, "POST", gotMethod)
+	assert.Equal(t, "/2/dm_conversations/1-42/messages", gotPath)
+	assert.JSONEq(t, `{"text":"hello again"}`, string(gotBody))
+}
+
 // ---- max-results clamping ----
 
 func TestMaxResultsClamping(t *testing.T) {
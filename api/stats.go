@@ -0,0 +1,92 @@
+package api
+
+import (
+	"fmt"
+	"maps"
+	"slices"
+	"sync"
+	"time"
+)
+
+// RequestStats accumulates the outcomes of the requests of a multi-request
+// run: their count by status class, their latencies, and the rate-limit
+// pauses they caused. It is safe for concurrent use.
+type RequestStats struct {
+	mu              sync.Mutex
+	start           time.Time
+	classes         map[string]int
+	latencies       []time.Duration
+	rateLimitPauses int
+}
+
+// StatsSummary is a snapshot of RequestStats, as written in JSON summaries.
+// Durations are in milliseconds.
+type StatsSummary struct {
+	Requests int `json:"requests"`
+	// StatusClasses counts requests by "2xx", "4xx", and so on; "none" counts
+	// those that got no response.
+	StatusClasses   map[string]int `json:"status_classes"`
+	DurationMS      int64          `json:"duration_ms"`
+	LatencyP50MS    int64          `json:"latency_p50_ms"`
+	LatencyP95MS    int64          `json:"latency_p95_ms"`
+	RateLimitPauses int            `json:"rate_limit_pauses"`
+}
+
+// NewRequestStats returns empty stats, timing the run from now.
+func NewRequestStats() *RequestStats {
+	return &RequestStats{start: time.Now(), classes: map[string]int{}}
+}
+
+// Record adds a request that ended with status (0 for no response) after
+// latency.
+func (s *RequestStats) Record(status int, latency time.Duration) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	s.classes[statusClass(status)]++
+	s.latencies = append(s.latencies, latency)
+}
+
+// RecordRateLimitPause adds a pause made for a 429. A nil *RequestStats
+// ignores it.
+func (s *RequestStats) RecordRateLimitPause() {
+	if s == nil {
+		return
+	}
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	s.rateLimitPauses++
+}
+
+// Summary returns the stats so far.
+func (s *RequestStats) Summary() StatsSummary {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	latencies := slices.Clone(s.latencies)
+	slices.Sort(latencies)
+	return StatsSummary{
+		Requests:        len(latencies),
+		StatusClasses:   maps.Clone(s.classes),
+		DurationMS:      time.Since(s.start).Milliseconds(),
+		LatencyP50MS:    percentile(latencies, 50).Milliseconds(),
+		LatencyP95MS:    percentile(latencies, 95).Milliseconds(),
+		RateLimitPauses: s.rateLimitPauses,
+	}
+}
+
+// statusClass returns "2xx" for 200 to 299 and so on, or "none" for 0.
+func statusClass(status int) string {
+	if status <= 0 {
+		return "none"
+	}
+	return fmt.Sprintf("%dxx", status/100)
+}
+
+// percentile returns the nearest-rank pth percentile of sorted, or 0 when it
+// is empty.
+func percentile(sorted []time.Duration, p int) time.Duration {
+	if len(sorted) == 0 {
+		return 0
+	}
+	rank := (p*len(sorted) + 99) / 100
+	return sorted[max(rank, 1)-1]
+}
@@ -0,0 +1,28 @@
+package api
+
+import (
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestRequestStats(t *testing.T) {
+	stats := NewRequestStats()
+	for i := 1; i <= 20; i++ {
+		stats.Record(200, time.Duration(i)*time.Millisecond)
+	}
+	stats.Record(429, 100*time.Millisecond)
+	stats.Record(0, 0)
+	stats.RecordRateLimitPause()
+	(*RequestStats)(nil).RecordRateLimitPause()
+
+	summary := stats.Summary()
+	assert.Equal(t, 22, summary.Requests)
+	assert.Equal(t, map[string]int{"2xx": 20, "4xx": 1, "none": 1}, summary.StatusClasses)
+	assert.Equal(t, int64(10), summary.LatencyP50MS)
+	assert.Equal(t, int64(20), summary.LatencyP95MS)
+	assert.Equal(t, 1, summary.RateLimitPauses)
+
+	assert.Zero(t, NewRequestStats().Summary().LatencyP95MS)
+}
@@ -0,0 +1,106 @@
+package api
+
+import (
+	"fmt"
+	"io"
+	"os"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+)
+
+// Throttle caps the rate of outgoing requests (--throttle) with a token
+// bucket: it holds up to Requests tokens, refills at Requests per Window, and
+// each request takes one, waiting for it when the bucket is empty. It is safe
+// for concurrent use; a nil *Throttle never waits.
+//
+// Because the bucket refills while a 429 wait sleeps, a retry after one waits
+// only for whichever of the two is longer, never for both in turn.
+type Throttle struct {
+	Requests int
+	Window   time.Duration
+
+	mu sync.Mutex
+	// tokens is the bucket's content at last; it goes below zero while
+	// requests are queued for tokens not yet refilled.
+	tokens float64
+	last   time.Time
+}
+
+// ParseThrottle parses a --throttle value, "N/WINDOW": N requests per window,
+// where the window is a duration such as "15m" or "500ms", or a bare unit
+// such as "s" for one of it. "10/15m" allows 10 requests every 15 minutes.
+func ParseThrottle(spec string) (*Throttle, error) {
+	count, window, ok := strings.Cut(strings.TrimSpace(spec), "/")
+	n, err := strconv.Atoi(count)
+	if !ok || err != nil || n < 1 {
+		return nil, fmt.Errorf("invalid --throttle %q: expected N/WINDOW with N > 0, e.g. 10/15m", spec)
+	}
+	if window != "" && !strings.ContainsAny(window[:1], "0123456789.") {
+		window = "1" + window
+	}
+	d, err := time.ParseDuration(window)
+	if err != nil || d <= 0 {
+		return nil, fmt.Errorf("invalid --throttle %q: the window must be a positive duration, e.g. 15m or s", spec)
+	}
+	return NewThrottle(n, d), nil
+}
+
+// NewThrottle returns a throttle allowing n requests per window, starting
+// with a full bucket.
+func NewThrottle(n int, window time.Duration) *Throttle {
+	return &Throttle{Requests: n, Window: window, tokens: float64(n)}
+}
+
+// reserve takes a token at now and returns how long to wait until it is
+// there.
+func (t *Throttle) reserve(now time.Time) time.Duration {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+	rate := float64(t.Requests) / float64(t.Window) // tokens per nanosecond
+	if !t.last.IsZero() {
+		t.tokens = min(float64(t.Requests), t.tokens+float64(now.Sub(t.last))*rate)
+	}
+	t.last = now
+	t.tokens--
+	if t.tokens >= 0 {
+		return 0
+	}
+	return time.Duration(-t.tokens / rate)
+}
+
+// throttleSleep waits for a throttled request; tests replace it.
+var throttleSleep = time.Sleep
+
+// wait blocks until a request may be sent, reporting the wait on log when
+// there is one and log is set.
+func (t *Throttle) wait(log io.Writer) {
+	if t == nil {
+		return
+	}
+	d := t.reserve(time.Now())
+	if d <= 0 {
+		return
+	}
+	if log != nil {
+		fmt.Fprintf(log, "Throttled (--throttle %d/%s): waiting %s\n", t.Requests, t.Window, d.Round(time.Millisecond))
+	}
+	throttleSleep(d)
+}
+
+// WithThrottle makes the client wait for throttle before every request;
+// clients sharing a throttle share its cap. nil removes the cap.
+func (c *ApiClient) WithThrottle(throttle *Throttle) *ApiClient {
+	c.throttle = throttle
+	return c
+}
+
+// throttleLog is where a throttled request's wait is reported: stderr in
+// verbose mode, else nowhere.
+func throttleLog(verbose bool) io.Writer {
+	if verbose {
+		return os.Stderr
+	}
+	return nil
+}
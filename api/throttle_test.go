@@ -0,0 +1,77 @@
+package api
+
+import (
+	"net/http"
+	"net/http/httptest"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseThrottle(t *testing.T) {
+	for spec, want := range map[string][2]any{
+		"10/15m":    {10, 15 * time.Minute},
+		"5/s":       {5, time.Second},
+		"1/500ms":   {1, 500 * time.Millisecond},
+		" 100/1h ":  {100, time.Hour},
+		"3/.5s":     {3, 500 * time.Millisecond},
+		"2/h":       {2, time.Hour},
+		"450/15m0s": {450, 15 * time.Minute},
+	} {
+		throttle, err := ParseThrottle(spec)
+		require.NoError(t, err, spec)
+		assert.Equal(t, want[0], throttle.Requests, spec)
+		assert.Equal(t, want[1], throttle.Window, spec)
+	}
+
+	for _, spec := range []string{"", "10", "0/1s", "-1/1s", "x/1s", "10/", "10/0s", "10/-1m", "10/fortnight"} {
+		_, err := ParseThrottle(spec)
+		assert.Error(t, err, spec)
+	}
+}
+
+func TestThrottleReserve(t *testing.T) {
+	throttle := NewThrottle(2, 10*time.Second)
+	start := time.Unix(1_700_000_000, 0)
+
+	assert.Zero(t, throttle.reserve(start), "the bucket starts full")
+	assert.Zero(t, throttle.reserve(start))
+	assert.Equal(t, 5*time.Second, throttle.reserve(start), "a token refills every 5s")
+	assert.Equal(t, 10*time.Second, throttle.reserve(start), "queued requests wait in turn")
+
+	// 10s later the queued requests have had their tokens, and the bucket
+	// is empty again.
+	assert.Equal(t, 5*time.Second, throttle.reserve(start.Add(10*time.Second)))
+
+	// After a long idle time the bucket holds no more than its size.
+	later := start.Add(time.Hour)
+	assert.Zero(t, throttle.reserve(later))
+	assert.Zero(t, throttle.reserve(later))
+	assert.Equal(t, 5*time.Second, throttle.reserve(later))
+
+	// A 429 wait that outlasts the throttle's leaves nothing more to wait.
+	assert.Zero(t, throttle.reserve(later.Add(30*time.Second)))
+
+	var nilThrottle *Throttle
+	nilThrottle.wait(nil)
+}
+
+func TestThrottleSendRequest(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "application/json")
+		w.Write([]byte(`{}`))
+	}))
+	defer server.Close()
+	client := shortcutClient(t, server).WithThrottle(NewThrottle(3, 300*time.Millisecond))
+
+	// The first 3 requests use the full bucket; the 4th waits for a token,
+	// 100ms.
+	start := time.Now()
+	for i := 0; i < 4; i++ {
+		_, err := client.SendRequest(RequestOptions{Method: "GET", Endpoint: "/2/users/me"})
+		require.NoError(t, err)
+	}
+	assert.GreaterOrEqual(t, time.Since(start), 90*time.Millisecond)
+}
@@ -0,0 +1,70 @@
+package api
+
+import (
+	"bufio"
+	"encoding/json"
+	"fmt"
+	"io"
+	"strings"
+
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+)
+
+// URLResult is one NDJSON line written by ExecuteURLList: the URL's 1-based
+// line number in the input, the URL, the HTTP status, and either the response
+// body or the error (an API error's problem body, or else its message).
+type URLResult struct {
+	Line     int             `json:"line"`
+	URL      string          `json:"url"`
+	Status   int             `json:"status"`
+	Response json.RawMessage `json:"response,omitempty"`
+	Error    any             `json:"error,omitempty"`
+}
+
+// ExecuteURLList sends options once for each URL read from in, one per line,
+// and writes a URLResult per request to out as NDJSON, its response shaped by
+// the JSONPointer and Truncate of options.Output (see ndjsonValue); a pointer
+// that matches nothing fails the request. Blank lines and lines
+// starting with "#" are skipped. A 429 is retried after a pause, as with
+// ExecuteCSVRequests. A failed request is recorded and the next URL is sent,
+// unless policy is FailFast or the failure is an auth error. It returns an
+// error if any request failed.
+func ExecuteURLList(options RequestOptions, in io.Reader, policy FailurePolicy, client Client, out io.Writer) error {
+	scanner := bufio.NewScanner(in)
+	scanner.Buffer(make([]byte, 64*1024), 1024*1024)
+	limiter := &rateLimitPause{}
+	failures := &failureTracker{policy: policy.or(ContinueOnError)}
+	var sent int
+	for lineNumber := 1; scanner.Scan(); lineNumber++ {
+		url := strings.TrimSpace(scanner.Text())
+		if url == "" || strings.HasPrefix(url, "#") {
+			continue
+		}
+
+		request := options
+		request.Endpoint = url
+		response, status, err := sendWithRateLimitRetry(request, client, limiter)
+		if err == nil {
+			response, err = ndjsonValue(response, options.Output)
+		}
+		result := URLResult{Line: lineNumber, URL: url, Status: status}
+		if err != nil {
+			result.Error = errorValue(err)
+		} else {
+			result.Response = batchBody(response)
+		}
+		line, _ := json.Marshal(result)
+		if _, writeErr := fmt.Fprintln(out, string(line)); writeErr != nil {
+			return xurlErrors.NewIOError(writeErr)
+		}
+
+		sent++
+		if failures.record(fmt.Sprintf("line %d", lineNumber), err) {
+			return failures.err(sent)
+		}
+	}
+	if err := scanner.Err(); err != nil {
+		return xurlErrors.NewIOError(fmt.Errorf("error reading URLs: %v", err))
+	}
+	return failures.err(sent)
+}
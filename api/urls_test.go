@@ -0,0 +1,93 @@
+package api
+
+import (
+	"bytes"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func urlResults(t *testing.T, out string) []URLResult {
+	t.Helper()
+	var results []URLResult
+	for _, line := range strings.Split(strings.TrimSpace(out), "\n") {
+		var result URLResult
+		require.NoError(t, json.Unmarshal([]byte(line), &result))
+		results = append(results, result)
+	}
+	return results
+}
+
+func TestExecuteURLList(t *testing.T) {
+	var methods []string
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		methods = append(methods, r.Method)
+		w.Header().Set("Content-Type", "application/json")
+		if r.URL.Path == "/2/tweets/404" {
+			w.WriteHeader(http.StatusNotFound)
+			w.Write([]byte(`{"title":"Not Found Error","status":404}`))
+			return
+		}
+		w.Write([]byte(`{"data":{"path":"` + r.URL.Path + `","trace":"` + r.Header.Get("X-Trace") + `"}}`))
+	}))
+	defer server.Close()
+	client := shortcutClient(t, server)
+
+	input := `# tweets to look up
+/2/tweets/1
+
+/2/tweets/404
+  /2/tweets/2
+`
+	options := RequestOptions{Method: "DELETE", Headers: []string{"X-Trace: shared"}}
+
+	t.Run("a failure is recorded and the run goes on", func(t *testing.T) {
+		methods = nil
+		var out bytes.Buffer
+		err := ExecuteURLList(options, strings.NewReader(input), ContinueOnError, client, &out)
+		require.Error(t, err)
+		assert.Equal(t, "1 of 3 requests failed", err.Error())
+		assert.Equal(t, []string{"DELETE", "DELETE", "DELETE"}, methods)
+
+		results := urlResults(t, out.String())
+		require.Len(t, results, 3)
+		assert.Equal(t, 2, results[0].Line)
+		assert.Equal(t, "/2/tweets/1", results[0].URL)
+		assert.Equal(t, http.StatusOK, results[0].Status)
+		assert.JSONEq(t, `{"data":{"path":"/2/tweets/1","trace":"shared"}}`, string(results[0].Response))
+
+		assert.Equal(t, 4, results[1].Line)
+		assert.Equal(t, http.StatusNotFound, results[1].Status)
+		assert.Nil(t, results[1].Response)
+		assert.Equal(t, map[string]any{"title": "Not Found Error", "status": float64(404)}, results[1].Error)
+
+		assert.Equal(t, 5, results[2].Line)
+		assert.Equal(t, "/2/tweets/2", results[2].URL)
+	})
+
+	t.Run("fail-fast stops at the first failure", func(t *testing.T) {
+		var out bytes.Buffer
+		err := ExecuteURLList(options, strings.NewReader(input), FailFast, client, &out)
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), "line 4")
+		assert.Len(t, urlResults(t, out.String()), 2)
+	})
+
+	t.Run("responses honor --json-pointer", func(t *testing.T) {
+		var out bytes.Buffer
+		shaped := options
+		shaped.Output = OutputOptions{JSONPointer: "/data/path"}
+		err := ExecuteURLList(shaped, strings.NewReader("/2/tweets/1\n/2/tweets/404\n"), ContinueOnError, client, &out)
+		require.Error(t, err)
+
+		results := urlResults(t, out.String())
+		require.Len(t, results, 2)
+		assert.JSONEq(t, `"/2/tweets/1"`, string(results[0].Response))
+		assert.Equal(t, float64(404), results[1].Error.(map[string]any)["status"], "an error keeps its whole body")
+	})
+}
@@ -0,0 +1,175 @@
+package api
+
+import (
+	"bytes"
+	"compress/gzip"
+	"encoding/json"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"regexp"
+	"sort"
+	"strings"
+	"unicode/utf8"
+)
+
+// redactedValue replaces secret values in logged bodies.
+const redactedValue = "[REDACTED]"
+
+// secretFields are the JSON keys and form fields whose values --verbose-body
+// never prints.
+var secretFields = map[string]bool{
+	"access_token":       true,
+	"refresh_token":      true,
+	"client_secret":      true,
+	"code_verifier":      true,
+	"consumer_secret":    true,
+	"token_secret":       true,
+	"oauth_token_secret": true,
+	"password":           true,
+}
+
+// minScrubbedSecretLength keeps ScrubSecrets from masking short stored values
+// that would also match ordinary text.
+const minScrubbedSecretLength = 8
+
+var (
+	// credentialPattern matches the credentials of a Bearer or Basic
+	// Authorization value; the length floor spares prose like "Bearer token".
+	credentialPattern = regexp.MustCompile(`(?i)\b(Bearer|Basic)(\s+)[A-Za-z0-9._~+/=%-]{16,}`)
+	// secretParamPattern matches a secretFields query or form parameter.
+	secretParamPattern = regexp.MustCompile(`(?i)\b(` + secretFieldAlternation() + `)=[^&\s"']+`)
+	// secretJSONPattern matches a secretFields JSON string member.
+	secretJSONPattern = regexp.MustCompile(`(?i)"(` + secretFieldAlternation() + `)"(\s*:\s*)"(?:[^"\\]|\\.)*"`)
+)
+
+func secretFieldAlternation() string {
+	names := make([]string, 0, len(secretFields))
+	for name := range secretFields {
+		names = append(names, regexp.QuoteMeta(name))
+	}
+	sort.Strings(names)
+	return strings.Join(names, "|")
+}
+
+// ScrubSecrets masks what looks like a credential in text before it is shown:
+// Bearer and Basic credentials, secretFields as query or form parameters
+// (e.g. "?access_token=...") or JSON members, and any of secrets, the values
+// known to be secret such as those of the token store.
+func ScrubSecrets(text string, secrets []string) string {
+	for _, secret := range secrets {
+		if len(secret) >= minScrubbedSecretLength {
+			text = strings.ReplaceAll(text, secret, redactedValue)
+		}
+	}
+	text = credentialPattern.ReplaceAllString(text, "${1}${2}"+redactedValue)
+	text = secretParamPattern.ReplaceAllString(text, "${1}="+redactedValue)
+	return secretJSONPattern.ReplaceAllString(text, `"${1}"${2}"`+redactedValue+`"`)
+}
+
+// logRequestBody writes req's body for --verbose-body without consuming it,
+// decompressing a body gzipped by --compress-request.
+func logRequestBody(w io.Writer, req *http.Request) {
+	if req.GetBody == nil {
+		return
+	}
+	body, err := req.GetBody()
+	if err != nil {
+		return
+	}
+	defer body.Close()
+	var reader io.Reader = body
+	if strings.EqualFold(req.Header.Get("Content-Encoding"), "gzip") {
+		gz, err := gzip.NewReader(body)
+		if err != nil {
+			return
+		}
+		reader = gz
+	}
+	data, err := io.ReadAll(reader)
+	if err != nil {
+		return
+	}
+	logBody(w, ">", data, req.Header.Get("Content-Type"))
+}
+
+// logBody writes a request (marker ">") or response ("<") body to w:
+// pretty-printed when it is JSON, one field per line when it is form-encoded,
+// with secretFields redacted either way. Binary bodies are only summarized.
+func logBody(w io.Writer, marker string, body []byte, contentType string) {
+	if len(body) == 0 {
+		return
+	}
+
+	var text string
+	switch {
+	case json.Valid(body):
+		text = redactedJSON(body)
+	case strings.HasPrefix(strings.ToLower(contentType), "application/x-www-form-urlencoded"):
+		text = redactedForm(string(body))
+	case utf8.Valid(body):
+		text = string(body)
+	default:
+		text = fmt.Sprintf("[%d bytes of binary data]", len(body))
+	}
+
+	for _, line := range strings.Split(strings.TrimRight(text, "\n"), "\n") {
+		fmt.Fprintf(w, "\033[1;35m%s\033[0m %s\n", marker, line)
+	}
+	fmt.Fprintln(w)
+}
+
+// redactedJSON pretty-prints body with the values of secretFields replaced,
+// at any depth.
+func redactedJSON(body []byte) string {
+	var value any
+	decoder := json.NewDecoder(bytes.NewReader(body))
+	decoder.UseNumber()
+	if err := decoder.Decode(&value); err != nil {
+		return string(body)
+	}
+	redacted, err := json.MarshalIndent(redactJSONValue(value), "", "  ")
+	if err != nil {
+		return string(body)
+	}
+	return string(redacted)
+}
+
+func redactJSONValue(value any) any {
+	switch v := value.(type) {
+	case map[string]any:
+		for key, field := range v {
+			if secretFields[strings.ToLower(key)] {
+				v[key] = redactedValue
+			} else {
+				v[key] = redactJSONValue(field)
+			}
+		}
+	case []any:
+		for i, item := range v {
+			v[i] = redactJSONValue(item)
+		}
+	}
+	return value
+}
+
+// redactedForm lists the fields of a form-encoded body one per line, with the
+// values of secretFields replaced.
+func redactedForm(body string) string {
+	var lines []string
+	for _, pair := range strings.Split(body, "&") {
+		key, value, _ := strings.Cut(pair, "=")
+		if name, err := url.QueryUnescape(key); err == nil {
+			key = name
+		}
+		if decoded, err := url.QueryUnescape(value); err == nil {
+			value = decoded
+		}
+		if secretFields[strings.ToLower(key)] {
+			value = redactedValue
+		}
+		lines = append(lines, key+"="+value)
+	}
+	return strings.Join(lines, "\n")
+}
@@ -6,19 +6,24 @@ import (
 	"crypto/rand"
 	"crypto/sha1"
 	"crypto/sha256"
+	"crypto/tls"
 	"encoding/base64"
+	"encoding/hex"
 	"encoding/json"
 	"errors"
 	"fmt"
+	"hash"
 	"io"
-	"math/big"
 	"net"
 	"net/http"
 	"net/url"
 	"os"
 	"os/exec"
+	"slices"
 	"sort"
+	"strconv"
 	"strings"
+	"sync"
 	"time"
 
 	"github.com/xdevplatform/xurl/config"
@@ -40,15 +45,73 @@ type Auth struct {
 	redirectURI        string
 	redirectURIFromEnv bool
 	appName            string // explicit app override (empty = use default)
+	// oauth1SignatureMethod overrides the OAuth1 signature method stored with
+	// the token (empty = use the stored method, or HMAC-SHA1).
+	oauth1SignatureMethod string
+	// oauth1Realm overrides the OAuth1 realm stored with the token (empty = use
+	// the stored realm, if any).
+	oauth1Realm string
+	// oauth1BodyHash enables the OAuth body hash extension for requests whose
+	// body is not form-encoded.
+	oauth1BodyHash bool
+	// oauth1AsQuery sends OAuth1 protocol parameters in the query string
+	// instead of the Authorization header.
+	oauth1AsQuery bool
+	// debugSignature, when set, receives the inputs and result of every OAuth1
+	// signature (see WithDebugSignature).
+	debugSignature io.Writer
+	// callbackTimeout bounds the wait for the OAuth2 browser callback (zero =
+	// defaultOAuth2CallbackTimeout).
+	callbackTimeout time.Duration
+	// refreshMargin refreshes OAuth2 tokens this long before they expire.
+	refreshMargin time.Duration
+	// expiryWarning is the remaining token lifetime below which
+	// OAuth2ExpiryWarning warns.
+	expiryWarning time.Duration
+	// nonInteractive makes the browser login flow fail instead of starting.
+	nonInteractive bool
+	// requireUserContext refuses app-only auth for this invocation.
+	requireUserContext bool
+	// oauth2AccessToken, when set, is the OAuth2 user token of every request
+	// (--oauth2-token); stored OAuth2 tokens are neither used nor refreshed.
+	oauth2AccessToken string
+	// tokenAuthMethod overrides how client credentials are sent to the token
+	// endpoint (empty = decided by oauth2AuthStyle).
+	tokenAuthMethod string
+	// clientCert is the TLS client certificate presented to the API and the
+	// token endpoint (mutual TLS), if any.
+	clientCert *tls.Certificate
+	// scopes are the OAuth2 scopes to request at login (nil = every scope
+	// from getOAuth2Scopes).
+	scopes []string
+	// oauth2Mu serialises getting and refreshing OAuth2 tokens, so concurrent
+	// requests (e.g. xurl batch --concurrency) refresh a token once instead of
+	// racing on the token store and on a single-use refresh token.
+	oauth2Mu sync.Mutex
 }
 
+// OAuth1 signature methods. HMAC-SHA1 is what the X API expects; HMAC-SHA256 is
+// for gateways in front of it that only accept SHA-256 signatures.
+const (
+	OAuth1SignatureHMACSHA1   = "HMAC-SHA1"
+	OAuth1SignatureHMACSHA256 = "HMAC-SHA256"
+)
+
+// Ways of authenticating the client at the OAuth2 token endpoint: an HTTP
+// Basic Authorization header, or client_id/client_secret in the form body.
+const (
+	TokenAuthMethodBasic = "basic"
+	TokenAuthMethodBody  = "body"
+)
+
 var openBrowserFunc = openBrowser
 
 var startListenerFunc = StartListener
 
-// oauth2ExpirySkewSeconds refreshes a token slightly before its real expiry so a
-// token handed to a caller does not expire mid-request.
-const oauth2ExpirySkewSeconds = 30
+// defaultOAuth2CallbackTimeout is how long the interactive flow waits for the
+// browser to hit the local callback before giving up, e.g. when the user closed
+// the tab without authorizing.
+const defaultOAuth2CallbackTimeout = 2 * time.Minute
 
 // NewAuth creates a new Auth object.
 // Credentials are resolved in order: env-var config → active app in .xurl store.
@@ -70,6 +133,15 @@ func NewAuth(cfg *config.Config) *Auth {
 		clientSecret = app.ClientSecret
 	}
 
+	refreshMargin := cfg.TokenRefreshMargin
+	if refreshMargin <= 0 {
+		refreshMargin = config.DefaultTokenRefreshMargin
+	}
+	expiryWarning := cfg.TokenExpiryWarning
+	if expiryWarning <= 0 {
+		expiryWarning = config.DefaultTokenExpiryWarning
+	}
+
 	return &Auth{
 		TokenStore:         ts,
 		infoURL:            cfg.InfoURL,
@@ -80,6 +152,9 @@ func NewAuth(cfg *config.Config) *Auth {
 		redirectURI:        cfg.RedirectURI,
 		redirectURIFromEnv: cfg.RedirectURIFromEnv,
 		appName:            appName,
+		refreshMargin:      refreshMargin,
+		expiryWarning:      expiryWarning,
+		nonInteractive:     cfg.NonInteractive,
 	}
 }
 
@@ -112,6 +187,290 @@ func (a *Auth) WithAppName(appName string) *Auth {
 	return a
 }
 
+// WithNonInteractive turns non-interactive mode on or off: in it, anything
+// that would open a browser or wait for the user fails with an
+// interaction-required error instead.
+func (a *Auth) WithNonInteractive(enabled bool) *Auth {
+	a.nonInteractive = enabled
+	return a
+}
+
+// NonInteractive reports whether non-interactive mode is on.
+func (a *Auth) NonInteractive() bool {
+	return a.nonInteractive
+}
+
+// WithRequireUserContext makes requests of this invocation fail before they
+// are sent unless they are authorized as a user (OAuth2 or OAuth1), rather
+// than fall back to the app-only bearer token.
+func (a *Auth) WithRequireUserContext(enabled bool) *Auth {
+	a.requireUserContext = enabled
+	return a
+}
+
+// RequireUserContext reports whether app-only auth is refused.
+func (a *Auth) RequireUserContext() bool {
+	return a.requireUserContext
+}
+
+// WithOAuth2AccessToken makes token, an OAuth2 user access token obtained
+// elsewhere, the one sent whenever OAuth2 is used, whatever the token store
+// holds. It is never stored or refreshed.
+func (a *Auth) WithOAuth2AccessToken(token string) *Auth {
+	a.oauth2AccessToken = token
+	return a
+}
+
+// OAuth2AccessToken returns the token set with WithOAuth2AccessToken, if any.
+func (a *Auth) OAuth2AccessToken() string {
+	return a.oauth2AccessToken
+}
+
+// WithTokenAuthMethod overrides how client credentials are sent to the token
+// endpoint, for both the code exchange and refreshes: TokenAuthMethodBasic or
+// TokenAuthMethodBody. Empty restores the default.
+func (a *Auth) WithTokenAuthMethod(method string) (*Auth, error) {
+	switch method {
+	case "", TokenAuthMethodBasic, TokenAuthMethodBody:
+		a.tokenAuthMethod = method
+		return a, nil
+	}
+	return a, xurlErrors.NewAuthError("InvalidTokenAuthMethod",
+		fmt.Errorf("unknown token auth method %q (want %s or %s)", method, TokenAuthMethodBasic, TokenAuthMethodBody))
+}
+
+// OAuth2 scope presets for WithScopePreset. ScopePresetAll, the default,
+// requests every scope xurl can use; ScopePresetDM only those the DM endpoints
+// need, for apps not approved for the rest.
+const (
+	ScopePresetAll = "all"
+	ScopePresetDM  = "dm"
+)
+
+// WithScopePreset chooses the OAuth2 scopes requested at login. Empty means
+// ScopePresetAll.
+func (a *Auth) WithScopePreset(preset string) (*Auth, error) {
+	switch preset {
+	case "", ScopePresetAll:
+		a.scopes = nil
+	case ScopePresetDM:
+		a.scopes = []string{"tweet.read", "users.read", "dm.read", "dm.write", "offline.access"}
+	default:
+		return a, xurlErrors.NewAuthError("InvalidScopePreset",
+			fmt.Errorf("unknown scope preset %q (want %s or %s)", preset, ScopePresetAll, ScopePresetDM))
+	}
+	return a, nil
+}
+
+// WithClientCertificate loads a PEM client certificate and private key that
+// every request (API and token endpoint alike) then presents for mutual TLS.
+// keyFile may be empty when certFile holds both.
+func (a *Auth) WithClientCertificate(certFile, keyFile string) (*Auth, error) {
+	if keyFile == "" {
+		keyFile = certFile
+	}
+	cert, err := tls.LoadX509KeyPair(certFile, keyFile)
+	if err != nil {
+		return a, xurlErrors.NewAuthError("ClientCertificateError",
+			fmt.Errorf("cannot load the client certificate %s with key %s: %v", certFile, keyFile, err))
+	}
+	a.clientCert = &cert
+	return a, nil
+}
+
+// WithNoSaveToken keeps tokens obtained or refreshed by this process in
+// memory only, leaving the token store file untouched.
+func (a *Auth) WithNoSaveToken(noSave bool) *Auth {
+	a.TokenStore.InMemory = noSave
+	return a
+}
+
+// WithInfoURL sets the URL used to look up the authenticated user, e.g. after
+// --base-url changes the API base URL it is derived from.
+func (a *Auth) WithInfoURL(infoURL string) *Auth {
+	a.infoURL = infoURL
+	return a
+}
+
+// StoredSecrets returns the secret values of the token store (see
+// store.TokenStore.Secrets), for masking in output. It is nil-safe.
+func (a *Auth) StoredSecrets() []string {
+	if a == nil || a.TokenStore == nil {
+		return nil
+	}
+	return a.TokenStore.Secrets()
+}
+
+// MissingCredentialsError returns an error saying how to set up credentials
+// when the active app has no stored token of any kind (OAuth2, OAuth1, or
+// app-only), and nil otherwise. Without client credentials there is not even
+// a way to log in, so the message then starts from scratch.
+func (a *Auth) MissingCredentialsError() error {
+	if a.TokenStore == nil {
+		return nil
+	}
+	if a.TokenStore.GetFirstOAuth2TokenForApp(a.appName) != nil ||
+		a.TokenStore.GetOAuth1TokensForApp(a.appName) != nil ||
+		a.TokenStore.GetBearerTokenForApp(a.appName) != nil {
+		return nil
+	}
+	if a.clientID == "" {
+		return xurlErrors.NewAuthError("NoCredentials", errors.New("no credentials configured. Run 'xurl auth app --bearer-token TOKEN' for app-only auth, or set CLIENT_ID and CLIENT_SECRET (or 'xurl auth apps add') and run 'xurl auth oauth2'"))
+	}
+	return xurlErrors.NewAuthError("NoCredentials", errors.New("no tokens stored. Run 'xurl auth oauth2' to log in, or 'xurl auth app --bearer-token TOKEN' for app-only auth"))
+}
+
+// HTTPTransport returns the transport for requests made with these
+// credentials: nil (the default transport) unless a client certificate is
+// set, in which case it is a clone of the default that presents it.
+func (a *Auth) HTTPTransport() http.RoundTripper {
+	if a == nil || a.clientCert == nil {
+		return nil
+	}
+	transport := http.DefaultTransport.(*http.Transport).Clone()
+	transport.TLSClientConfig = &tls.Config{Certificates: []tls.Certificate{*a.clientCert}}
+	return transport
+}
+
+// oauth2Context is the context for token endpoint calls, carrying an HTTP
+// client with HTTPTransport when it is not the default.
+func (a *Auth) oauth2Context() context.Context {
+	ctx := context.Background()
+	if transport := a.HTTPTransport(); transport != nil {
+		ctx = context.WithValue(ctx, oauth2.HTTPClient, &http.Client{Transport: transport})
+	}
+	return ctx
+}
+
+// WithOAuth1SignatureMethod sets the OAuth1 signature method for this
+// invocation, overriding the one stored with the OAuth1 token.
+func (a *Auth) WithOAuth1SignatureMethod(method string) *Auth {
+	a.oauth1SignatureMethod = method
+	return a
+}
+
+// WithOAuth1Realm sets the realm sent in the OAuth1 Authorization header for
+// this invocation, overriding the one stored with the OAuth1 token.
+func (a *Auth) WithOAuth1Realm(realm string) *Auth {
+	a.oauth1Realm = realm
+	return a
+}
+
+// WithOAuth1BodyHash enables the OAuth body hash extension for this
+// invocation: see OAuth1BodyHashParams.
+func (a *Auth) WithOAuth1BodyHash(enabled bool) *Auth {
+	a.oauth1BodyHash = enabled
+	return a
+}
+
+// WithOAuth1AsQuery makes OAuth1-signed requests of this invocation carry
+// their oauth_* parameters in the query string (RFC 5849 §3.5.3) rather than
+// in the Authorization header.
+func (a *Auth) WithOAuth1AsQuery(enabled bool) *Auth {
+	a.oauth1AsQuery = enabled
+	return a
+}
+
+// WithDebugSignature makes every OAuth1 signature of this invocation write its
+// base string, signing key (secrets masked to their length), and result to w,
+// for comparison with another implementation such as X's OAuth tool.
+func (a *Auth) WithDebugSignature(w io.Writer) *Auth {
+	a.debugSignature = w
+	return a
+}
+
+// OAuth1AsQuery reports whether OAuth1 parameters go in the query string.
+func (a *Auth) OAuth1AsQuery() bool {
+	return a.oauth1AsQuery
+}
+
+// OAuth1QueryParams converts an Authorization header made by GetOAuth1Header
+// into the equivalent query parameters. The signature is unchanged, as the
+// oauth_* parameters are signed the same wherever they are sent; the realm is
+// dropped because it only exists in the header form.
+func OAuth1QueryParams(header string) (url.Values, error) {
+	rest, ok := strings.CutPrefix(header, "OAuth ")
+	if !ok {
+		return nil, fmt.Errorf("not an OAuth1 Authorization header")
+	}
+
+	params := url.Values{}
+	for rest != "" {
+		key, value, ok := strings.Cut(rest, "=\"")
+		if !ok {
+			return nil, fmt.Errorf("malformed OAuth1 Authorization header")
+		}
+		// Values are quoted; only the realm may contain backslash escapes.
+		end := 0
+		for end < len(value) && value[end] != '"' {
+			if value[end] == '\\' {
+				end++
+			}
+			end++
+		}
+		if end >= len(value) {
+			return nil, fmt.Errorf("malformed OAuth1 Authorization header")
+		}
+		if key != "realm" {
+			decoded, err := url.QueryUnescape(value[:end])
+			if err != nil {
+				return nil, err
+			}
+			params.Set(key, decoded)
+		}
+		rest = strings.TrimPrefix(value[end+1:], ", ")
+	}
+	return params, nil
+}
+
+// OAuth1BodyHashParams returns the oauth_body_hash parameter to pass to
+// GetOAuth1Header for a request with the given body, or nil when the extension
+// is off. The hash uses the digest of the signature method in effect (SHA-1 or
+// SHA-256). Form-encoded bodies never get one: their parameters are signed
+// directly, and the extension forbids the hash for them.
+func (a *Auth) OAuth1BodyHashParams(contentType string, body []byte) map[string]string {
+	if !a.oauth1BodyHash || strings.HasPrefix(strings.ToLower(contentType), "application/x-www-form-urlencoded") {
+		return nil
+	}
+	token := a.TokenStore.GetOAuth1TokensForApp(a.appName)
+	if token == nil || token.OAuth1 == nil {
+		return nil
+	}
+	newHash, err := signatureHash(a.oauth1SignatureMethodFor(token.OAuth1))
+	if err != nil {
+		// GetOAuth1Header reports the invalid method.
+		return nil
+	}
+	h := newHash()
+	h.Write(body)
+	return map[string]string{"oauth_body_hash": base64.StdEncoding.EncodeToString(h.Sum(nil))}
+}
+
+// oauth1SignatureMethodFor resolves the signature method for token: the
+// per-invocation override, then the method stored with the token, then
+// HMAC-SHA1. The short forms SHA1 and SHA256 name the HMAC methods.
+func (a *Auth) oauth1SignatureMethodFor(token *store.OAuth1Token) string {
+	signatureMethod := a.oauth1SignatureMethod
+	if signatureMethod == "" {
+		signatureMethod = token.SignatureMethod
+	}
+	if signatureMethod == "" {
+		signatureMethod = OAuth1SignatureHMACSHA1
+	}
+	signatureMethod = strings.ToUpper(signatureMethod)
+	if signatureMethod == "SHA1" || signatureMethod == "SHA256" {
+		signatureMethod = "HMAC-" + signatureMethod
+	}
+	return signatureMethod
+}
+
+// WithCallbackTimeout sets how long OAuth2Flow waits for the browser callback.
+// Zero or a negative duration restores the default.
+func (a *Auth) WithCallbackTimeout(timeout time.Duration) *Auth {
+	a.callbackTimeout = timeout
+	return a
+}
+
 func (a *Auth) resolveRedirectURIForApp(appName string) string {
 	app := a.TokenStore.ResolveApp(appName)
 	if app != nil && app.RedirectURI != "" {
@@ -120,7 +479,9 @@ func (a *Auth) resolveRedirectURIForApp(appName string) string {
 	return config.DefaultRedirectURI
 }
 
-// GetOAuth1Header gets the OAuth1 header for a request
+// GetOAuth1Header gets the OAuth1 header for a request. additionalParams are
+// signed along with the URL query; an oauth_body_hash among them (see
+// OAuth1BodyHashParams) is also sent in the header.
 func (a *Auth) GetOAuth1Header(method, urlStr string, additionalParams map[string]string) (string, error) {
 	token := a.TokenStore.GetOAuth1TokensForApp(a.appName)
 	if token == nil || token.OAuth1 == nil {
@@ -134,43 +495,69 @@ func (a *Auth) GetOAuth1Header(method, urlStr string, additionalParams map[strin
 		return "", xurlErrors.NewAuthError("InvalidURL", err)
 	}
 
-	params := make(map[string]string)
-
-	query := parsedURL.Query()
-	for key := range query {
-		params[key] = query.Get(key)
+	// Every value of a repeated query key is signed (RFC 5849 §3.4.1.3.1).
+	params := parsedURL.Query()
+	for key, value := range additionalParams {
+		params.Set(key, value)
 	}
 
-	for key, value := range additionalParams {
-		params[key] = value
+	signatureMethod := a.oauth1SignatureMethodFor(oauth1Token)
+	newHash, err := signatureHash(signatureMethod)
+	if err != nil {
+		return "", xurlErrors.NewAuthError("InvalidSignatureMethod", err)
 	}
 
-	params["oauth_consumer_key"] = oauth1Token.ConsumerKey
-	params["oauth_nonce"] = generateNonce()
-	params["oauth_signature_method"] = "HMAC-SHA1"
-	params["oauth_timestamp"] = generateTimestamp()
-	params["oauth_token"] = oauth1Token.AccessToken
-	params["oauth_version"] = "1.0"
+	params.Set("oauth_consumer_key", oauth1Token.ConsumerKey)
+	params.Set("oauth_nonce", generateNonce())
+	params.Set("oauth_signature_method", signatureMethod)
+	params.Set("oauth_timestamp", generateTimestampWithSkew(a.TokenStore.ClockSkewSeconds))
+	params.Set("oauth_token", oauth1Token.AccessToken)
+	params.Set("oauth_version", "1.0")
 
-	signature, err := generateSignature(method, urlStr, params, oauth1Token.ConsumerSecret, oauth1Token.TokenSecret)
+	baseString, err := signatureBaseString(method, urlStr, params)
 	if err != nil {
 		return "", xurlErrors.NewAuthError("SignatureGenerationError", err)
 	}
+	signature := signBaseString(baseString, signingKey(oauth1Token.ConsumerSecret, oauth1Token.TokenSecret), newHash)
+	if a.debugSignature != nil {
+		fmt.Fprintf(a.debugSignature, "OAuth1 signature base string: %s\n", baseString)
+		fmt.Fprintf(a.debugSignature, "OAuth1 signing key: %s&%s\n", maskedSecret("consumer secret", oauth1Token.ConsumerSecret), maskedSecret("token secret", oauth1Token.TokenSecret))
+		fmt.Fprintf(a.debugSignature, "OAuth1 signature (%s): %s\n", signatureMethod, signature)
+	}
+
+	// The realm goes first and is not part of the signature base string
+	// (RFC 5849 §3.4.1.3.1), so it is added only to the header.
+	realm := a.oauth1Realm
+	if realm == "" {
+		realm = oauth1Token.Realm
+	}
 
 	var oauthParams []string
+	if realm != "" {
+		oauthParams = append(oauthParams, fmt.Sprintf("realm=\"%s\"", quoteRealm(realm)))
+	}
+	if bodyHash, ok := additionalParams["oauth_body_hash"]; ok {
+		oauthParams = append(oauthParams, fmt.Sprintf("oauth_body_hash=\"%s\"", encode(bodyHash)))
+	}
 	oauthParams = append(oauthParams, fmt.Sprintf("oauth_consumer_key=\"%s\"", encode(oauth1Token.ConsumerKey)))
-	oauthParams = append(oauthParams, fmt.Sprintf("oauth_nonce=\"%s\"", encode(params["oauth_nonce"])))
+	oauthParams = append(oauthParams, fmt.Sprintf("oauth_nonce=\"%s\"", encode(params.Get("oauth_nonce"))))
 	oauthParams = append(oauthParams, fmt.Sprintf("oauth_signature=\"%s\"", encode(signature)))
-	oauthParams = append(oauthParams, fmt.Sprintf("oauth_signature_method=\"%s\"", encode("HMAC-SHA1")))
-	oauthParams = append(oauthParams, fmt.Sprintf("oauth_timestamp=\"%s\"", encode(params["oauth_timestamp"])))
+	oauthParams = append(oauthParams, fmt.Sprintf("oauth_signature_method=\"%s\"", encode(signatureMethod)))
+	oauthParams = append(oauthParams, fmt.Sprintf("oauth_timestamp=\"%s\"", encode(params.Get("oauth_timestamp"))))
 	oauthParams = append(oauthParams, fmt.Sprintf("oauth_token=\"%s\"", encode(oauth1Token.AccessToken)))
 	oauthParams = append(oauthParams, fmt.Sprintf("oauth_version=\"%s\"", encode("1.0")))
 
 	return "OAuth " + strings.Join(oauthParams, ", "), nil
 }
 
-// GetOAuth2Token gets or refreshes an OAuth2 token
+// GetOAuth2Token gets or refreshes an OAuth2 token. A token given with
+// WithOAuth2AccessToken is returned as is.
 func (a *Auth) GetOAuth2Header(username string) (string, error) {
+	if a.oauth2AccessToken != "" {
+		return "Bearer " + a.oauth2AccessToken, nil
+	}
+	a.oauth2Mu.Lock()
+	defer a.oauth2Mu.Unlock()
 	var token *store.Token
 
 	if username != "" {
@@ -207,8 +594,15 @@ func (a *Auth) GetOAuth2Header(username string) (string, error) {
 // with an HTTP Basic Authorization header; public clients (PKCE, no secret) send
 // the client_id in the request body. Letting x/oauth2 auto-detect proved
 // unreliable against X (it could fail with "unauthorized_client: Missing valid
-// authorization header"), so the style is selected explicitly.
+// authorization header"), so the style is selected explicitly. A method set with
+// WithTokenAuthMethod takes precedence.
 func (a *Auth) oauth2AuthStyle() oauth2.AuthStyle {
+	switch a.tokenAuthMethod {
+	case TokenAuthMethodBasic:
+		return oauth2.AuthStyleInHeader
+	case TokenAuthMethodBody:
+		return oauth2.AuthStyleInParams
+	}
 	if a.clientSecret != "" {
 		return oauth2.AuthStyleInHeader
 	}
@@ -226,7 +620,7 @@ func (a *Auth) newOAuth2Config() *oauth2.Config {
 			AuthStyle: a.oauth2AuthStyle(),
 		},
 		RedirectURL: a.redirectURI,
-		Scopes:      getOAuth2Scopes(),
+		Scopes:      a.oauth2Scopes(),
 	}
 }
 
@@ -250,27 +644,40 @@ func (a *Auth) prepareOAuth2Flow() (*oauth2Attempt, error) {
 	}
 	state := base64.StdEncoding.EncodeToString(b)
 
-	verifier, challenge, err := generateCodeVerifierAndChallenge()
+	verifier, _, err := generateCodeVerifierAndChallenge()
 	if err != nil {
 		return nil, xurlErrors.NewAuthError("IOError", err)
 	}
 
-	authURL := config.AuthCodeURL(state,
-		oauth2.SetAuthURLParam("code_challenge", challenge),
-		oauth2.SetAuthURLParam("code_challenge_method", "S256"))
-
+	authURL := buildAuthorizeURL(config, state, verifier)
 	return &oauth2Attempt{config: config, state: state, verifier: verifier, authURL: authURL}, nil
 }
 
+// buildAuthorizeURL returns the authorization URL for state and the PKCE
+// verifier: response_type=code, client_id, redirect_uri, scope, state, and the
+// S256 code_challenge derived from verifier. It has no side effects, so the URL
+// handed to the browser can be checked directly.
+func buildAuthorizeURL(config *oauth2.Config, state, verifier string) string {
+	return config.AuthCodeURL(state,
+		oauth2.SetAuthURLParam("code_challenge", codeChallengeS256(verifier)),
+		oauth2.SetAuthURLParam("code_challenge_method", "S256"))
+}
+
 // exchangeAndSave swaps an authorization code for a token (using the PKCE
 // verifier) and persists it. Diagnostics go to stderr so callers that reserve
 // stdout for machine output (e.g. the mcp bridge) are never corrupted.
 func (a *Auth) exchangeAndSave(attempt *oauth2Attempt, username, code string) (string, error) {
-	token, err := attempt.config.Exchange(context.Background(), code,
+	token, err := attempt.config.Exchange(a.oauth2Context(), code,
 		oauth2.SetAuthURLParam("code_verifier", attempt.verifier))
 	if err != nil {
 		return "", xurlErrors.NewAuthError("TokenExchangeError", err)
 	}
+	// Without a refresh token the login would silently stop working when the
+	// access token expires, so fail now rather than store a dead-end token.
+	if token.RefreshToken == "" && slices.Contains(attempt.config.Scopes, "offline.access") {
+		return "", xurlErrors.NewAuthError("TokenExchangeError",
+			errors.New("the token response has no refresh_token although offline.access was requested"))
+	}
 
 	usernameStr, resolvedFromLookup := a.resolveStorageUsername(username, token.AccessToken)
 	if err := a.saveOAuth2Token(usernameStr, token); err != nil {
@@ -287,7 +694,13 @@ func (a *Auth) exchangeAndSave(attempt *oauth2Attempt, username, code string) (s
 // OAuth2Flow runs the interactive authorization-code flow: it starts a local
 // callback listener, opens the browser, and waits for the redirect. On machines
 // without a reachable browser/callback, use the headless flow (StartHeadlessLogin) instead.
+// In non-interactive mode it fails before binding the listener.
 func (a *Auth) OAuth2Flow(username string) (string, error) {
+	if a.nonInteractive {
+		return "", xurlErrors.NewInteractionRequiredError("oauth2_browser_login",
+			"run 'xurl auth oauth2' interactively, or 'xurl auth oauth2 --open-url-only' followed by '--code', to store a token first")
+	}
+
 	attempt, err := a.prepareOAuth2Flow()
 	if err != nil {
 		return "", err
@@ -297,9 +710,12 @@ func (a *Auth) OAuth2Flow(username string) (string, error) {
 	if err != nil {
 		return "", xurlErrors.NewAuthError("InvalidRedirectURI", err)
 	}
+	for _, warning := range a.redirectURIWarnings(listenerConfig) {
+		fmt.Fprintf(os.Stderr, "Warning: %s\n", warning)
+	}
 
 	codeChan := make(chan string, 1)
-	listenerReady := make(chan struct{})
+	listenerReady := make(chan int, 1)
 	listenerErrChan := make(chan error, 1)
 
 	callback := func(code, receivedState string) error {
@@ -315,21 +731,35 @@ func (a *Auth) OAuth2Flow(username string) (string, error) {
 		return nil
 	}
 
+	timeout := a.callbackTimeout
+	if timeout <= 0 {
+		timeout = defaultOAuth2CallbackTimeout
+	}
+
 	go func() {
-		if err := startListenerFunc(listenerConfig.Addresses, listenerConfig.CallbackPath, callback, listenerReady); err != nil {
+		if err := startListenerFunc(listenerConfig.Addresses, listenerConfig.CallbackPath, callback, listenerReady, timeout); err != nil {
 			listenerErrChan <- err
 		}
 	}()
 
 	select {
-	case <-listenerReady:
+	case port := <-listenerReady:
+		if listenerConfig.EphemeralPort {
+			// The authorize URL must name the port actually bound, and the
+			// code exchange must repeat the same redirect_uri.
+			attempt.config.RedirectURL = redirectURIWithPort(a.redirectURI, port)
+			attempt.authURL = buildAuthorizeURL(attempt.config, attempt.state, attempt.verifier)
+			fmt.Fprintf(os.Stderr, "Listening for the OAuth2 callback on %s\n", attempt.config.RedirectURL)
+		}
 	case err := <-listenerErrChan:
 		return "", xurlErrors.NewAuthError("ListenerError", err)
 	}
 
 	if err := openBrowserFunc(attempt.authURL); err != nil {
-		fmt.Fprintln(os.Stderr, "Failed to open browser automatically. Please visit this URL manually:")
+		fmt.Fprintf(os.Stderr, "Could not open a browser automatically (%v).\n", err)
+		fmt.Fprintln(os.Stderr, "Open this URL in a browser on this machine to authorize xurl:")
 		fmt.Fprintln(os.Stderr, attempt.authURL)
+		fmt.Fprintf(os.Stderr, "Waiting up to %s for the callback on %s.\n", timeout, attempt.config.RedirectURL)
 		fmt.Fprintln(os.Stderr, "(On a remote/headless machine, re-run with --headless to paste the code instead.)")
 	}
 
@@ -340,9 +770,12 @@ func (a *Auth) OAuth2Flow(username string) (string, error) {
 			return "", xurlErrors.NewAuthError("ListenerError", errors.New("oauth2 listener failed"))
 		}
 	case err := <-listenerErrChan:
+		// The listener's own timeout already carries an actionable message.
+		var xe *xurlErrors.Error
+		if errors.As(err, &xe) && xe.Message == "Timeout" {
+			return "", err
+		}
 		return "", xurlErrors.NewAuthError("ListenerError", err)
-	case <-time.After(5 * time.Minute):
-		return "", xurlErrors.NewAuthError("Timeout", errors.New("authentication timed out"))
 	}
 
 	return a.exchangeAndSave(attempt, username, code)
@@ -358,6 +791,9 @@ type HeadlessLogin struct {
 	auth     *Auth
 	attempt  *oauth2Attempt
 	username string
+	// resumed is set when the login was restored by ResumeHeadlessLogin; its
+	// stored pending state is cleared once Complete succeeds.
+	resumed bool
 }
 
 // StartHeadlessLogin begins a headless login: it generates the PKCE/state
@@ -371,6 +807,32 @@ func (a *Auth) StartHeadlessLogin(username string) (*HeadlessLogin, error) {
 	return &HeadlessLogin{auth: a, attempt: attempt, username: username}, nil
 }
 
+// ResumeHeadlessLogin restores the login that SavePending stored for the active
+// app, so that a separate invocation can Complete it.
+func (a *Auth) ResumeHeadlessLogin() (*HeadlessLogin, error) {
+	pending := a.TokenStore.GetPendingOAuth2LoginForApp(a.appName)
+	if pending == nil {
+		return nil, xurlErrors.NewAuthError("NoPendingLogin", errors.New("no pending OAuth2 login; start one with `xurl auth oauth2 --open-url-only`"))
+	}
+
+	config := a.newOAuth2Config()
+	config.RedirectURL = pending.RedirectURI
+	attempt := &oauth2Attempt{config: config, state: pending.State, verifier: pending.CodeVerifier}
+	return &HeadlessLogin{auth: a, attempt: attempt, username: pending.Username, resumed: true}, nil
+}
+
+// SavePending stores the login's state, PKCE verifier and redirect URI in the
+// token store so that ResumeHeadlessLogin can finish it from another process.
+// Handing out the URL and exchanging the code then need not share a process.
+func (h *HeadlessLogin) SavePending() error {
+	return h.auth.TokenStore.SetPendingOAuth2LoginForApp(h.auth.appName, &store.PendingOAuth2Login{
+		State:        h.attempt.state,
+		CodeVerifier: h.attempt.verifier,
+		RedirectURI:  h.attempt.config.RedirectURL,
+		Username:     h.username,
+	})
+}
+
 // AuthURL is the URL the user opens in a browser (on any device) to authorize.
 func (h *HeadlessLogin) AuthURL() string { return h.attempt.authURL }
 
@@ -386,7 +848,16 @@ func (h *HeadlessLogin) Complete(pasted string) (string, error) {
 	if err != nil {
 		return "", xurlErrors.NewAuthError("InvalidCode", err)
 	}
-	return h.auth.exchangeAndSave(h.attempt, h.username, code)
+	accessToken, err := h.auth.exchangeAndSave(h.attempt, h.username, code)
+	if err != nil {
+		return "", err
+	}
+	if h.resumed {
+		if err := h.auth.TokenStore.SetPendingOAuth2LoginForApp(h.auth.appName, nil); err != nil {
+			return "", xurlErrors.NewAuthError("TokenStorageError", err)
+		}
+	}
+	return accessToken, nil
 }
 
 // parseHeadlessAuthCode extracts the authorization code from a pasted value,
@@ -430,6 +901,8 @@ func (a *Auth) RefreshOAuth2Token(username string) (string, error) {
 // locally cached expiry. Use it when the server rejects a token the local clock
 // still considers valid (e.g. an HTTP 401 after a revocation or scope change).
 func (a *Auth) ForceRefreshOAuth2Token(username string) (string, error) {
+	a.oauth2Mu.Lock()
+	defer a.oauth2Mu.Unlock()
 	return a.refreshOAuth2Token(username, true)
 }
 
@@ -439,13 +912,8 @@ func (a *Auth) refreshOAuth2Token(username string, force bool) (string, error) {
 		return "", xurlErrors.NewAuthError("TokenNotFound", errors.New("oauth2 token not found"))
 	}
 
-	if !force {
-		currentTime := time.Now().Unix()
-		// Refresh slightly before the real expiry so a token handed to a caller
-		// does not expire in-flight (mirrors x/oauth2's expiryDelta).
-		if uint64(currentTime)+oauth2ExpirySkewSeconds < token.OAuth2.ExpirationTime {
-			return token.OAuth2.AccessToken, nil
-		}
+	if !force && !a.oauth2TokenNeedsRefresh(token.OAuth2) {
+		return token.OAuth2.AccessToken, nil
 	}
 
 	config := &oauth2.Config{
@@ -457,7 +925,7 @@ func (a *Auth) refreshOAuth2Token(username string, force bool) (string, error) {
 		},
 	}
 
-	tokenSource := config.TokenSource(context.Background(), &oauth2.Token{
+	tokenSource := config.TokenSource(a.oauth2Context(), &oauth2.Token{
 		RefreshToken: token.OAuth2.RefreshToken,
 	})
 
@@ -465,6 +933,11 @@ func (a *Auth) refreshOAuth2Token(username string, force bool) (string, error) {
 	if err != nil {
 		return "", xurlErrors.NewAuthError("RefreshTokenError", err)
 	}
+	// A refresh response may omit an unchanged refresh token; keep the stored
+	// one rather than saving a token that can never be refreshed again.
+	if newToken.RefreshToken == "" {
+		newToken.RefreshToken = token.OAuth2.RefreshToken
+	}
 
 	usernameStr := storedUsername
 	if usernameStr == "" {
@@ -483,6 +956,60 @@ func (a *Auth) refreshOAuth2Token(username string, force bool) (string, error) {
 	return newToken.AccessToken, nil
 }
 
+// oauth2TokenNeedsRefresh reports whether token expires within the refresh
+// margin. Refreshing early keeps a token from expiring in-flight during a long
+// request or chunked upload.
+func (a *Auth) oauth2TokenNeedsRefresh(token *store.OAuth2Token) bool {
+	deadline := time.Now().Add(a.refreshMargin).Unix()
+	return uint64(deadline) >= token.ExpirationTime
+}
+
+// WithTokenRefreshMargin sets how long before expiry OAuth2 tokens are
+// refreshed.
+func (a *Auth) WithTokenRefreshMargin(margin time.Duration) *Auth {
+	a.refreshMargin = margin
+	return a
+}
+
+// OAuth2ExpiryWarning describes the OAuth2 token that would be used for
+// username when it expires within the expiry-warning threshold, for display
+// before a long-running operation; otherwise it returns "". Tokens inside the
+// refresh margin are not reported, since they are refreshed on first use, nor
+// is a token from WithOAuth2AccessToken, whose expiry is unknown.
+func (a *Auth) OAuth2ExpiryWarning(username string) string {
+	if a.oauth2AccessToken != "" {
+		return ""
+	}
+	storedUsername, token := a.getOAuth2TokenRecord(username)
+	if token == nil || token.OAuth2 == nil {
+		return ""
+	}
+	now := time.Now()
+	if token.OAuth2.ExpiresWithin(a.refreshMargin, now) || !token.OAuth2.ExpiresWithin(a.expiryWarning, now) {
+		return ""
+	}
+	who := "the default user"
+	if storedUsername != "" {
+		who = "@" + storedUsername
+	}
+	return fmt.Sprintf("your OAuth2 token for %s expires in %s; a long-running operation may outlive it", who, FormatRemaining(token.OAuth2.ExpiresIn(now)))
+}
+
+// FormatRemaining renders a token lifetime coarsely, e.g. "3 minutes".
+func FormatRemaining(d time.Duration) string {
+	unit, n := "second", int64(d.Round(time.Second)/time.Second)
+	switch {
+	case d >= time.Hour:
+		unit, n = "hour", int64(d.Round(time.Hour)/time.Hour)
+	case d >= time.Minute:
+		unit, n = "minute", int64(d.Round(time.Minute)/time.Minute)
+	}
+	if n == 1 {
+		return "1 " + unit
+	}
+	return fmt.Sprintf("%d %ss", n, unit)
+}
+
 // GetValidOAuth2Token returns a valid OAuth2 access token for the active app and
 // the given username, refreshing and persisting it if it has expired. Pass an
 // empty username to use the app's default (or first) user.
@@ -499,6 +1026,12 @@ func (a *Auth) GetValidOAuth2Token(username string) (string, error) {
 type oauth2ListenerConfig struct {
 	Addresses    []string
 	CallbackPath string
+	// Scheme and Host are the redirect URI's, for redirectURIWarnings.
+	Scheme string
+	Host   string
+	// EphemeralPort is set for a redirect URI with port 0: the listener binds
+	// any free port and the redirect URI is rewritten to name it.
+	EphemeralPort bool
 }
 
 func listenerConfigFromRedirectURI(redirectURI string) (oauth2ListenerConfig, error) {
@@ -517,17 +1050,59 @@ func listenerConfigFromRedirectURI(redirectURI string) (oauth2ListenerConfig, er
 		port = "8080"
 	}
 
+	// The callback is served on the redirect URI's own path, which must be
+	// given: the browser is sent to exactly that URI, so guessing one (such as
+	// /callback) would leave the listener waiting on a path never requested.
 	callbackPath := parsedURL.Path
 	if callbackPath == "" {
-		callbackPath = "/callback"
+		return oauth2ListenerConfig{}, fmt.Errorf("redirect URI %q has no path for the callback; include the one registered for the app, e.g. http://localhost:8080/callback", redirectURI)
 	}
 
 	return oauth2ListenerConfig{
-		Addresses:    listenerAddressesForHost(host, port),
-		CallbackPath: callbackPath,
+		Addresses:     listenerAddressesForHost(host, port),
+		CallbackPath:  callbackPath,
+		Scheme:        parsedURL.Scheme,
+		Host:          host,
+		EphemeralPort: port == "0",
 	}, nil
 }
 
+// redirectURIWarnings lists the ways the redirect URI cannot work with the
+// callback listener, or may not match the one registered for the app, so they
+// can be reported before the browser is opened rather than after a timeout.
+func (a *Auth) redirectURIWarnings(listener oauth2ListenerConfig) []string {
+	var warnings []string
+	if strings.EqualFold(listener.Scheme, "https") {
+		warnings = append(warnings, fmt.Sprintf("the redirect URI %s uses https, but xurl serves the callback over plain http; the browser will not reach it (use --headless instead)", a.redirectURI))
+	}
+	if ip := net.ParseIP(listener.Host); !strings.EqualFold(listener.Host, "localhost") && (ip == nil || !ip.IsLoopback()) {
+		warnings = append(warnings, fmt.Sprintf("the redirect URI host %q is not a loopback address; the callback is served on it, but the browser may not reach it there", listener.Host))
+	}
+	if listener.EphemeralPort {
+		warnings = append(warnings, "the redirect URI has port 0, so xurl picks a free port and sends that in redirect_uri; this only works if the app accepts any loopback port")
+	}
+	if a.redirectURIFromEnv {
+		if stored := a.TokenStore.ResolveApp(a.appName); stored != nil && stored.RedirectURI != "" && stored.RedirectURI != a.redirectURI {
+			warnings = append(warnings, fmt.Sprintf("REDIRECT_URI (%s) differs from the app's stored redirect URI (%s); X rejects a redirect URI that is not registered for the app", a.redirectURI, stored.RedirectURI))
+		}
+	}
+	return warnings
+}
+
+// redirectURIWithPort returns redirectURI with its port replaced by port.
+func redirectURIWithPort(redirectURI string, port int) string {
+	parsedURL, err := url.Parse(redirectURI)
+	if err != nil {
+		return redirectURI
+	}
+	host := parsedURL.Hostname()
+	if host == "" {
+		host = "localhost"
+	}
+	parsedURL.Host = net.JoinHostPort(host, strconv.Itoa(port))
+	return parsedURL.String()
+}
+
 func listenerAddressesForHost(host, port string) []string {
 	if strings.EqualFold(host, "localhost") {
 		return []string{
@@ -552,6 +1127,34 @@ func (a *Auth) resolveStorageUsername(explicitUsername, accessToken string) (str
 	return username, true
 }
 
+// IsOAuth2AccessToken reports whether accessToken is the stored OAuth2 access
+// token for username (the default user when empty), i.e. whether a request
+// carrying it was made with a refreshable user token.
+func (a *Auth) IsOAuth2AccessToken(username, accessToken string) bool {
+	_, token := a.getOAuth2TokenRecord(username)
+	return token != nil && token.OAuth2 != nil && accessToken != "" && token.OAuth2.AccessToken == accessToken
+}
+
+// OAuth2AccountChoices lists the accounts a request without a username could
+// mean: the app's named OAuth2 users, sorted, when there are several and no
+// default user picks one. It returns nil when the choice is unambiguous.
+func (a *Auth) OAuth2AccountChoices() []string {
+	if defaultUser := a.TokenStore.GetDefaultUser(a.appName); defaultUser != "" &&
+		a.TokenStore.GetOAuth2TokenForApp(a.appName, defaultUser) != nil {
+		return nil
+	}
+	var usernames []string
+	for _, username := range a.TokenStore.GetOAuth2UsernamesForApp(a.appName) {
+		if username != "" {
+			usernames = append(usernames, username)
+		}
+	}
+	if len(usernames) < 2 {
+		return nil
+	}
+	return usernames
+}
+
 func (a *Auth) getOAuth2TokenRecord(username string) (string, *store.Token) {
 	if username != "" {
 		return username, a.TokenStore.GetOAuth2TokenForApp(a.appName, username)
@@ -580,6 +1183,32 @@ func (a *Auth) GetBearerTokenHeader() (string, error) {
 	return "Bearer " + token.Bearer, nil
 }
 
+// VerifyBearerToken checks that the API accepts an app-only Bearer Token by
+// calling the info endpoint with it. An app-only token cannot act as a user, so
+// a valid token is answered with 403 there; only a 401 means the token itself
+// was rejected.
+func (a *Auth) VerifyBearerToken(token string) error {
+	req, err := http.NewRequest("GET", a.infoURL, nil)
+	if err != nil {
+		return xurlErrors.NewAuthError("RequestCreationError", err)
+	}
+
+	req.Header.Add("Authorization", "Bearer "+token)
+
+	client := &http.Client{Timeout: 10 * time.Second, Transport: a.HTTPTransport()}
+	resp, err := client.Do(req)
+	if err != nil {
+		return xurlErrors.NewAuthError("NetworkError", err)
+	}
+	defer resp.Body.Close()
+
+	if resp.StatusCode == http.StatusUnauthorized {
+		return xurlErrors.NewAuthError("InvalidToken", fmt.Errorf("bearer token rejected by the API (%s)", resp.Status))
+	}
+
+	return nil
+}
+
 func (a *Auth) fetchUsername(accessToken string) (string, error) {
 	req, err := http.NewRequest("GET", a.infoURL, nil)
 	if err != nil {
@@ -588,7 +1217,7 @@ func (a *Auth) fetchUsername(accessToken string) (string, error) {
 
 	req.Header.Add("Authorization", "Bearer "+accessToken)
 
-	client := &http.Client{Timeout: 10 * time.Second}
+	client := &http.Client{Timeout: 10 * time.Second, Transport: a.HTTPTransport()}
 	resp, err := client.Do(req)
 	if err != nil {
 		return "", xurlErrors.NewAuthError("NetworkError", err)
@@ -616,47 +1245,164 @@ func (a *Auth) fetchUsername(accessToken string) (string, error) {
 	return "", xurlErrors.NewAuthError("UsernameNotFound", errors.New("username not found when fetching username"))
 }
 
-func generateSignature(method, urlStr string, params map[string]string, consumerSecret, tokenSecret string) (string, error) {
+// signatureHash returns the hash function behind an OAuth1 signature method.
+func signatureHash(signatureMethod string) (func() hash.Hash, error) {
+	switch signatureMethod {
+	case OAuth1SignatureHMACSHA1:
+		return sha1.New, nil
+	case OAuth1SignatureHMACSHA256:
+		return sha256.New, nil
+	default:
+		return nil, fmt.Errorf("unsupported OAuth1 signature method %q (use %s or %s)", signatureMethod, OAuth1SignatureHMACSHA1, OAuth1SignatureHMACSHA256)
+	}
+}
+
+func generateSignature(method, urlStr string, params url.Values, consumerSecret, tokenSecret string, newHash func() hash.Hash) (string, error) {
+	baseString, err := signatureBaseString(method, urlStr, params)
+	if err != nil {
+		return "", err
+	}
+	return signBaseString(baseString, signingKey(consumerSecret, tokenSecret), newHash), nil
+}
+
+// signatureBaseString builds the signature base string of RFC 5849 §3.4.1:
+// the method, the base string URI, and the encoded parameters, every value of
+// a repeated key included, sorted by encoded key and then encoded value
+// (§3.4.1.3.2).
+func signatureBaseString(method, urlStr string, params url.Values) (string, error) {
 	parsedURL, err := url.Parse(urlStr)
 	if err != nil {
 		return "", xurlErrors.NewAuthError("InvalidURL", err)
 	}
 
-	baseURL := fmt.Sprintf("%s://%s%s", parsedURL.Scheme, parsedURL.Host, parsedURL.Path)
+	baseURL := baseStringURI(parsedURL)
 
-	var keys []string
-	for key := range params {
-		keys = append(keys, key)
+	type pair struct{ key, value string }
+	var pairs []pair
+	for key, values := range params {
+		for _, value := range values {
+			pairs = append(pairs, pair{encode(key), encode(value)})
+		}
 	}
-	sort.Strings(keys)
+	sort.Slice(pairs, func(i, j int) bool {
+		if pairs[i].key != pairs[j].key {
+			return pairs[i].key < pairs[j].key
+		}
+		return pairs[i].value < pairs[j].value
+	})
 
 	var paramPairs []string
-	for _, key := range keys {
-		paramPairs = append(paramPairs, fmt.Sprintf("%s=%s", encode(key), encode(params[key])))
+	for _, p := range pairs {
+		paramPairs = append(paramPairs, p.key+"="+p.value)
 	}
 	paramString := strings.Join(paramPairs, "&")
 
-	signatureBaseString := fmt.Sprintf("%s&%s&%s",
+	return fmt.Sprintf("%s&%s&%s",
 		strings.ToUpper(method),
 		encode(baseURL),
-		encode(paramString))
+		encode(paramString)), nil
+}
+
+// signingKey is the HMAC key of RFC 5849 §3.4.2: both secrets, encoded.
+func signingKey(consumerSecret, tokenSecret string) string {
+	return fmt.Sprintf("%s&%s", encode(consumerSecret), encode(tokenSecret))
+}
+
+// signBaseString returns the base64 HMAC of baseString under key.
+func signBaseString(baseString, key string, newHash func() hash.Hash) string {
+	h := hmac.New(newHash, []byte(key))
+	h.Write([]byte(baseString))
+	return base64.StdEncoding.EncodeToString(h.Sum(nil))
+}
 
-	signingKey := fmt.Sprintf("%s&%s", encode(consumerSecret), encode(tokenSecret))
+// maskedSecret stands in for a secret in --debug-signature output, giving
+// only the length of its encoded form, which is what the signing key holds.
+func maskedSecret(name, secret string) string {
+	return fmt.Sprintf("<%s, %d chars>", name, len(encode(secret)))
+}
 
-	h := hmac.New(sha1.New, []byte(signingKey))
-	h.Write([]byte(signatureBaseString))
-	signature := base64.StdEncoding.EncodeToString(h.Sum(nil))
+// quoteRealm escapes a realm for the quoted-string it is sent in (RFC 2617
+// §1.2). Unlike the oauth_* parameters it is not percent-encoded, so a backslash
+// or double quote would otherwise end the value early.
+func quoteRealm(realm string) string {
+	return strings.NewReplacer(`\`, `\\`, `"`, `\"`).Replace(realm)
+}
+
+// baseStringURI builds the base string URI of RFC 5849 §3.4.1.2: scheme and
+// host lowercased, the port kept only when it is not the scheme's default, and
+// the query and fragment dropped (query parameters are signed separately).
+func baseStringURI(u *url.URL) string {
+	scheme := strings.ToLower(u.Scheme)
+	host := strings.ToLower(u.Hostname())
+	if strings.Contains(host, ":") {
+		host = "[" + host + "]"
+	}
 
-	return signature, nil
+	port := u.Port()
+	if (scheme == "http" && port == "80") || (scheme == "https" && port == "443") {
+		port = ""
+	}
+	if port != "" {
+		host += ":" + port
+	}
+
+	path := u.EscapedPath()
+	if path == "" {
+		path = "/"
+	}
+
+	return scheme + "://" + host + path
 }
 
+// generateNonce returns 128 random bits in hex, so that requests signed in the
+// same second, even concurrently, never share a nonce.
 func generateNonce() string {
-	n, _ := rand.Int(rand.Reader, big.NewInt(1000000000))
-	return n.String()
+	b := make([]byte, 16)
+	_, _ = rand.Read(b)
+	return hex.EncodeToString(b)
 }
 
+// timeNow is the clock OAuth1 timestamps are taken from; tests replace it to
+// simulate a skewed local clock.
+var timeNow = time.Now
+
 func generateTimestamp() string {
-	return fmt.Sprintf("%d", time.Now().Unix())
+	return generateTimestampWithSkew(0)
+}
+
+// generateTimestampWithSkew returns the current Unix time shifted by the
+// measured offset of the server clock, so a drifted local clock still produces
+// timestamps the server accepts.
+func generateTimestampWithSkew(skewSeconds int64) string {
+	return fmt.Sprintf("%d", timeNow().Unix()+skewSeconds)
+}
+
+// SyncClockSkew measures how far the server's clock at serverURL is from the
+// local clock, using the Date header of an unauthenticated request, and stores
+// the offset for later OAuth1 timestamps. It returns the offset.
+func (a *Auth) SyncClockSkew(serverURL string) (time.Duration, error) {
+	req, err := http.NewRequest("HEAD", serverURL, nil)
+	if err != nil {
+		return 0, xurlErrors.NewAuthError("RequestCreationError", err)
+	}
+
+	client := &http.Client{Timeout: 10 * time.Second, Transport: a.HTTPTransport()}
+	resp, err := client.Do(req)
+	if err != nil {
+		return 0, xurlErrors.NewAuthError("NetworkError", err)
+	}
+	resp.Body.Close()
+
+	serverTime, err := http.ParseTime(resp.Header.Get("Date"))
+	if err != nil {
+		return 0, xurlErrors.NewAuthError("ClockSkewError", fmt.Errorf("server sent no usable Date header: %v", err))
+	}
+
+	skew := serverTime.Sub(timeNow()).Round(time.Second)
+	if err := a.TokenStore.SetClockSkew(int64(skew / time.Second)); err != nil {
+		return 0, xurlErrors.NewAuthError("TokenStorageError", err)
+	}
+	return skew, nil
 }
 
 func encode(s string) string {
@@ -669,10 +1415,22 @@ func generateCodeVerifierAndChallenge() (string, string, error) {
 		return "", "", err
 	}
 	verifier := base64.RawURLEncoding.EncodeToString(b)
-	h := sha256.New()
-	h.Write([]byte(verifier))
-	challenge := base64.RawURLEncoding.EncodeToString(h.Sum(nil))
-	return verifier, challenge, nil
+	return verifier, codeChallengeS256(verifier), nil
+}
+
+// codeChallengeS256 derives the PKCE S256 code challenge from verifier (RFC 7636
+// §4.2).
+func codeChallengeS256(verifier string) string {
+	h := sha256.Sum256([]byte(verifier))
+	return base64.RawURLEncoding.EncodeToString(h[:])
+}
+
+// oauth2Scopes returns the scopes chosen by WithScopePreset.
+func (a *Auth) oauth2Scopes() []string {
+	if a.scopes != nil {
+		return a.scopes
+	}
+	return getOAuth2Scopes()
 }
 
 func getOAuth2Scopes() []string {
@@ -717,9 +1475,79 @@ func getOAuth2Scopes() []string {
 	return scopes
 }
 
+// browserLaunchWait is how long a launcher gets to report failure. Launchers
+// such as xdg-open exit non-zero when no browser is available; one still running
+// after this is assumed to have opened the browser.
+var browserLaunchWait = 3 * time.Second
+
 func openBrowser(url string) error {
-	cmd, args := browserLaunchCommand(runtime.GOOS, url)
-	return exec.Command(cmd, args...).Start()
+	return launchBrowser(browserLaunchCommands(runtime.GOOS, url, runtime.GOOS == "linux" && isWSL()))
+}
+
+// launchBrowser runs each candidate launcher in turn until one succeeds. It
+// returns an error naming every attempt when none does.
+func launchBrowser(candidates [][]string) error {
+	var errs []error
+	for _, candidate := range candidates {
+		path, err := exec.LookPath(candidate[0])
+		if err != nil {
+			errs = append(errs, fmt.Errorf("%s: not found", candidate[0]))
+			continue
+		}
+		cmd := exec.Command(path, candidate[1:]...)
+		if err := cmd.Start(); err != nil {
+			errs = append(errs, fmt.Errorf("%s: %w", candidate[0], err))
+			continue
+		}
+		done := make(chan error, 1)
+		go func() { done <- cmd.Wait() }()
+		select {
+		case err := <-done:
+			if err == nil {
+				return nil
+			}
+			errs = append(errs, fmt.Errorf("%s: %w", candidate[0], err))
+		case <-time.After(browserLaunchWait):
+			return nil
+		}
+	}
+	return errors.Join(errs...)
+}
+
+// browserLaunchCommands lists the launchers to try for url, in order. Under WSL
+// the Linux launchers usually have no browser to hand off to, so the Windows
+// host's browser is tried first via wslview and then cmd.exe.
+func browserLaunchCommands(goos, url string, wsl bool) [][]string {
+	var candidates [][]string
+	if wsl {
+		candidates = append(candidates,
+			[]string{"wslview", url},
+			[]string{"cmd.exe", "/c", "start", `""`, escapeCmdArg(url)})
+	}
+	cmd, args := browserLaunchCommand(goos, url)
+	return append(candidates, append([]string{cmd}, args...))
+}
+
+// escapeCmdArg caret-escapes cmd.exe metacharacters so a URL's query string
+// (with its '&' separators) reaches "start" intact.
+func escapeCmdArg(s string) string {
+	var b strings.Builder
+	for _, r := range s {
+		if strings.ContainsRune("^&|<>()", r) {
+			b.WriteRune('^')
+		}
+		b.WriteRune(r)
+	}
+	return b.String()
+}
+
+// isWSL reports whether xurl is running under Windows Subsystem for Linux.
+func isWSL() bool {
+	if os.Getenv("WSL_DISTRO_NAME") != "" || os.Getenv("WSL_INTEROP") != "" {
+		return true
+	}
+	release, err := os.ReadFile("/proc/sys/kernel/osrelease")
+	return err == nil && strings.Contains(strings.ToLower(string(release)), "microsoft")
 }
 
 func browserLaunchCommand(goos, url string) (string, []string) {
@@ -1,11 +1,30 @@
 package auth
 
 import (
+	"bytes"
+	"context"
+	"crypto/ecdsa"
+	"crypto/elliptic"
+	"crypto/rand"
+	"crypto/sha1"
+	"crypto/sha256"
+	"crypto/tls"
+	"crypto/x509"
+	"crypto/x509/pkix"
+	"encoding/base64"
 	"encoding/json"
+	"encoding/pem"
+	"hash"
+	"math/big"
 	"net/http"
 	"net/http/httptest"
+	"net/url"
 	"os"
 	"path/filepath"
+	"runtime"
+	"strconv"
+	"strings"
+	"sync"
 	"testing"
 	"time"
 
@@ -14,6 +33,7 @@ import (
 	"golang.org/x/oauth2"
 
 	"github.com/xdevplatform/xurl/config"
+	xurlErrors "github.com/xdevplatform/xurl/errors"
 	"github.com/xdevplatform/xurl/store"
 )
 
@@ -106,6 +126,24 @@ func TestGenerateNonce(t *testing.T) {
 
 	assert.NotEmpty(t, nonce1, "Expected non-empty nonce")
 	assert.NotEqual(t, nonce1, nonce2, "Expected different nonces")
+
+	// Concurrent signers must not collide either.
+	nonces := make(chan string, 1000)
+	var wg sync.WaitGroup
+	for i := 0; i < cap(nonces); i++ {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			nonces <- generateNonce()
+		}()
+	}
+	wg.Wait()
+	close(nonces)
+	seen := map[string]bool{}
+	for nonce := range nonces {
+		assert.False(t, seen[nonce], "duplicate nonce %s", nonce)
+		seen[nonce] = true
+	}
 }
 
 func TestGenerateTimestamp(t *testing.T) {
@@ -118,6 +156,44 @@ func TestGenerateTimestamp(t *testing.T) {
 	}
 }
 
+func TestGenerateTimestampWithSkew(t *testing.T) {
+	defer func() { timeNow = time.Now }()
+	timeNow = func() time.Time { return time.Unix(1700000000, 0) }
+
+	assert.Equal(t, "1700000000", generateTimestamp())
+	assert.Equal(t, "1700000300", generateTimestampWithSkew(300))
+	assert.Equal(t, "1699999990", generateTimestampWithSkew(-10))
+}
+
+func TestSyncClockSkew(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		assert.Empty(t, r.Header.Get("Authorization"), "the skew probe must be unauthenticated")
+	}))
+	defer server.Close()
+
+	tokenStore, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+	require.NoError(t, tokenStore.SaveOAuth1Tokens("at", "ts", "ck", "cs"))
+	a := NewAuth(&config.Config{}).WithTokenStore(tokenStore)
+
+	// A local clock running five minutes behind the server.
+	defer func() { timeNow = time.Now }()
+	timeNow = func() time.Time { return time.Now().Add(-5 * time.Minute) }
+
+	skew, err := a.SyncClockSkew(server.URL)
+	require.NoError(t, err)
+	assert.InDelta(t, 300, skew.Seconds(), 2)
+	assert.InDelta(t, 300, tokenStore.ClockSkewSeconds, 2)
+
+	// Signed timestamps are corrected back to (roughly) the server's time.
+	header, err := a.GetOAuth1Header("GET", "https://api.x.com/2/users/me", nil)
+	require.NoError(t, err)
+	_, values := parseOAuthHeader(t, header)
+	timestamp, err := strconv.ParseInt(values["oauth_timestamp"], 10, 64)
+	require.NoError(t, err)
+	assert.InDelta(t, time.Now().Unix(), timestamp, 2)
+}
+
 func TestEncode(t *testing.T) {
 	testCases := []struct {
 		input    string
@@ -148,6 +224,58 @@ func TestGenerateCodeVerifierAndChallenge(t *testing.T) {
 	assert.NotEqual(t, verifier, challenge, "Expected verifier and challenge to be different")
 }
 
+func TestBuildAuthorizeURL(t *testing.T) {
+	a := NewAuth(&config.Config{
+		ClientID:    "test-client-id",
+		AuthURL:     "https://x.com/i/oauth2/authorize",
+		TokenURL:    "https://api.x.com/2/oauth2/token",
+		RedirectURI: "http://localhost:8080/callback",
+	})
+	tokenStore, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+	a.WithTokenStore(tokenStore)
+
+	// RFC 7636 Appendix B test vector.
+	verifier := "dBjftJeZ4CVP-mJ0kDEIEEjUSDDvCyQyabmFXIAIgaI"
+	authURL := buildAuthorizeURL(a.newOAuth2Config(), "state-123", verifier)
+
+	u, err := url.Parse(authURL)
+	require.NoError(t, err)
+	assert.Equal(t, "https", u.Scheme)
+	assert.Equal(t, "x.com", u.Host)
+	assert.Equal(t, "/i/oauth2/authorize", u.Path)
+
+	q := u.Query()
+	assert.Equal(t, "code", q.Get("response_type"))
+	assert.Equal(t, "test-client-id", q.Get("client_id"))
+	assert.Equal(t, "http://localhost:8080/callback", q.Get("redirect_uri"))
+	assert.Equal(t, strings.Join(getOAuth2Scopes(), " "), q.Get("scope"))
+	assert.Equal(t, "state-123", q.Get("state"))
+	assert.Equal(t, "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM", q.Get("code_challenge"))
+	assert.Equal(t, "S256", q.Get("code_challenge_method"))
+}
+
+func TestPrepareOAuth2FlowURLMatchesAttempt(t *testing.T) {
+	a := NewAuth(&config.Config{
+		ClientID:    "test-client-id",
+		AuthURL:     "https://x.com/i/oauth2/authorize",
+		RedirectURI: "http://127.0.0.1:9999/cb",
+	})
+	tokenStore, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+	a.WithTokenStore(tokenStore)
+
+	attempt, err := a.prepareOAuth2Flow()
+	require.NoError(t, err)
+
+	u, err := url.Parse(attempt.authURL)
+	require.NoError(t, err)
+	q := u.Query()
+	assert.Equal(t, attempt.state, q.Get("state"))
+	assert.Equal(t, codeChallengeS256(attempt.verifier), q.Get("code_challenge"))
+	assert.Equal(t, "http://127.0.0.1:9999/cb", q.Get("redirect_uri"))
+}
+
 func TestGetOAuth2Scopes(t *testing.T) {
 	scopes := getOAuth2Scopes()
 
@@ -160,6 +288,23 @@ func TestGetOAuth2Scopes(t *testing.T) {
 	assert.Contains(t, scopes, "broadcast.write", "Expected 'broadcast.write' scope")
 }
 
+func TestWithScopePreset(t *testing.T) {
+	a := NewAuth(&config.Config{ClientID: "test-client-id", AuthURL: "https://x.com/i/oauth2/authorize"})
+
+	_, err := a.WithScopePreset(ScopePresetDM)
+	require.NoError(t, err)
+	assert.Equal(t, []string{"tweet.read", "users.read", "dm.read", "dm.write", "offline.access"}, a.newOAuth2Config().Scopes)
+
+	_, err = a.WithScopePreset("")
+	require.NoError(t, err)
+	assert.Equal(t, getOAuth2Scopes(), a.newOAuth2Config().Scopes)
+
+	_, err = a.WithScopePreset("dms")
+	require.Error(t, err)
+	assert.True(t, xurlErrors.IsAuthError(err))
+	assert.Contains(t, err.Error(), `unknown scope preset "dms"`)
+}
+
 func TestCredentialResolutionPriority(t *testing.T) {
 	tokenStore, tempDir := createTempTokenStore(t)
 	defer os.RemoveAll(tempDir)
@@ -464,6 +609,40 @@ func TestBrowserLaunchCommand(t *testing.T) {
 	})
 }
 
+func TestBrowserLaunchCommands(t *testing.T) {
+	url := "https://x.com/i/oauth2/authorize?client_id=abc&state=123"
+
+	t.Run("native launcher only outside WSL", func(t *testing.T) {
+		assert.Equal(t, [][]string{{"xdg-open", url}}, browserLaunchCommands("linux", url, false))
+	})
+
+	t.Run("WSL tries the Windows host browser first", func(t *testing.T) {
+		assert.Equal(t, [][]string{
+			{"wslview", url},
+			{"cmd.exe", "/c", "start", `""`, "https://x.com/i/oauth2/authorize?client_id=abc^&state=123"},
+			{"xdg-open", url},
+		}, browserLaunchCommands("linux", url, true))
+	})
+}
+
+func TestLaunchBrowserFallsBack(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("relies on the true/false utilities")
+	}
+
+	t.Run("skips missing and failing launchers", func(t *testing.T) {
+		err := launchBrowser([][]string{{"xurl-no-such-browser"}, {"false"}, {"true"}})
+		assert.NoError(t, err)
+	})
+
+	t.Run("reports every failed attempt", func(t *testing.T) {
+		err := launchBrowser([][]string{{"xurl-no-such-browser"}, {"false"}})
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), "xurl-no-such-browser: not found")
+		assert.Contains(t, err.Error(), "false: exit status 1")
+	})
+}
+
 func TestListenerConfigFromRedirectURI(t *testing.T) {
 	testCases := []struct {
 		name          string
@@ -489,6 +668,18 @@ func TestListenerConfigFromRedirectURI(t *testing.T) {
 			wantAddresses: []string{"127.0.0.1:8080", "[::1]:8080"},
 			wantCallback:  "/callback",
 		},
+		{
+			name:          "any registered path is served",
+			redirectURI:   "http://localhost:3000/auth/x/return",
+			wantAddresses: []string{"127.0.0.1:3000", "[::1]:3000"},
+			wantCallback:  "/auth/x/return",
+		},
+		{
+			name:          "port 0 asks for an ephemeral port",
+			redirectURI:   "http://127.0.0.1:0/oauth/x/callback",
+			wantAddresses: []string{"127.0.0.1:0"},
+			wantCallback:  "/oauth/x/callback",
+		},
 	}
 
 	for _, tc := range testCases {
@@ -499,6 +690,14 @@ func TestListenerConfigFromRedirectURI(t *testing.T) {
 			assert.Equal(t, tc.wantCallback, config.CallbackPath)
 		})
 	}
+
+	t.Run("a redirect URI without a path is rejected", func(t *testing.T) {
+		for _, redirectURI := range []string{"http://localhost:8080", "http://127.0.0.1:9090?x=1"} {
+			_, err := listenerConfigFromRedirectURI(redirectURI)
+			require.Error(t, err, redirectURI)
+			assert.Contains(t, err.Error(), "has no path")
+		}
+	})
 }
 
 func TestRefreshOAuth2TokenPreservesUnnamedTokenWhenUsernameLookupFails(t *testing.T) {
@@ -637,7 +836,7 @@ func TestGetValidOAuth2Token(t *testing.T) {
 		ts, dir := createTempTokenStore(t)
 		defer os.RemoveAll(dir)
 
-		// Expires in 10s — still "valid" by the raw clock, but inside the 30s skew.
+		// Expires in 10s — still "valid" by the raw clock, but inside the refresh margin.
 		soon := uint64(time.Now().Add(10 * time.Second).Unix())
 		require.NoError(t, ts.SaveOAuth2TokenForApp("default", "alice", "old-access", "old-refresh", soon))
 
@@ -648,6 +847,87 @@ func TestGetValidOAuth2Token(t *testing.T) {
 	})
 }
 
+func TestOAuth2TokenRefreshMargin(t *testing.T) {
+	cases := []struct {
+		name      string
+		expiresIn time.Duration
+		want      string
+	}{
+		{"just inside the margin is refreshed", 290 * time.Second, "margin-access"},
+		{"just outside the margin is reused", 320 * time.Second, "old-access"},
+	}
+	for _, tc := range cases {
+		t.Run(tc.name, func(t *testing.T) {
+			server := mockTokenServer(t, "margin-access", "margin-refresh")
+			defer server.Close()
+
+			ts, dir := createTempTokenStore(t)
+			defer os.RemoveAll(dir)
+
+			expiry := uint64(time.Now().Add(tc.expiresIn).Unix())
+			require.NoError(t, ts.SaveOAuth2TokenForApp("default", "alice", "old-access", "old-refresh", expiry))
+
+			a := NewAuth(&config.Config{TokenURL: serverURL(server, "/token"), TokenRefreshMargin: 300 * time.Second}).WithTokenStore(ts)
+			tok, err := a.GetValidOAuth2Token("alice")
+			require.NoError(t, err)
+			assert.Equal(t, tc.want, tok)
+		})
+	}
+
+	t.Run("unset margin uses the default", func(t *testing.T) {
+		a := NewAuth(&config.Config{})
+		assert.Equal(t, config.DefaultTokenRefreshMargin, a.refreshMargin)
+	})
+
+	t.Run("WithTokenRefreshMargin overrides the margin", func(t *testing.T) {
+		server := mockTokenServer(t, "margin-access", "margin-refresh")
+		defer server.Close()
+
+		ts, dir := createTempTokenStore(t)
+		defer os.RemoveAll(dir)
+
+		expiry := uint64(time.Now().Add(20 * time.Minute).Unix())
+		require.NoError(t, ts.SaveOAuth2TokenForApp("default", "alice", "old-access", "old-refresh", expiry))
+
+		a := NewAuth(&config.Config{TokenURL: serverURL(server, "/token")}).WithTokenStore(ts).WithTokenRefreshMargin(30 * time.Minute)
+		tok, err := a.GetValidOAuth2Token("alice")
+		require.NoError(t, err)
+		assert.Equal(t, "margin-access", tok)
+	})
+}
+
+func TestOAuth2ExpiryWarning(t *testing.T) {
+	ts, dir := createTempTokenStore(t)
+	defer os.RemoveAll(dir)
+
+	save := func(expiresIn time.Duration) {
+		require.NoError(t, ts.SaveOAuth2TokenForApp("default", "alice", "access", "refresh", uint64(time.Now().Add(expiresIn).Unix())))
+	}
+	a := NewAuth(&config.Config{
+		TokenRefreshMargin: time.Minute,
+		TokenExpiryWarning: 10 * time.Minute,
+	}).WithTokenStore(ts)
+
+	save(3*time.Minute + 10*time.Second)
+	assert.Contains(t, a.OAuth2ExpiryWarning("alice"), "your OAuth2 token for @alice expires in 3 minutes")
+	assert.Contains(t, a.OAuth2ExpiryWarning(""), "@alice", "the default user's token is checked")
+
+	save(30 * time.Second)
+	assert.Empty(t, a.OAuth2ExpiryWarning("alice"), "tokens inside the refresh margin are refreshed on first use")
+
+	save(time.Hour)
+	assert.Empty(t, a.OAuth2ExpiryWarning("alice"))
+
+	assert.Empty(t, a.OAuth2ExpiryWarning("bob"), "no token, no warning")
+}
+
+func TestFormatRemaining(t *testing.T) {
+	assert.Equal(t, "45 seconds", FormatRemaining(45*time.Second))
+	assert.Equal(t, "1 minute", FormatRemaining(70*time.Second))
+	assert.Equal(t, "3 minutes", FormatRemaining(3*time.Minute+10*time.Second))
+	assert.Equal(t, "2 hours", FormatRemaining(2*time.Hour))
+}
+
 func TestForceRefreshOAuth2Token(t *testing.T) {
 	server := mockTokenServer(t, "forced-access", "forced-refresh")
 	defer server.Close()
@@ -720,6 +1000,81 @@ func TestOAuth2AuthStyle(t *testing.T) {
 	// Public client (no secret) -> client_id in the request body.
 	noSecret := &Auth{clientSecret: ""}
 	assert.Equal(t, oauth2.AuthStyleInParams, noSecret.oauth2AuthStyle())
+
+	// An explicit method overrides either default.
+	_, err := withSecret.WithTokenAuthMethod(TokenAuthMethodBody)
+	require.NoError(t, err)
+	assert.Equal(t, oauth2.AuthStyleInParams, withSecret.oauth2AuthStyle())
+	_, err = noSecret.WithTokenAuthMethod(TokenAuthMethodBasic)
+	require.NoError(t, err)
+	assert.Equal(t, oauth2.AuthStyleInHeader, noSecret.oauth2AuthStyle())
+
+	_, err = noSecret.WithTokenAuthMethod("jwt")
+	assert.True(t, xurlErrors.IsAuthError(err))
+	assert.Equal(t, oauth2.AuthStyleInHeader, noSecret.oauth2AuthStyle(), "an invalid method leaves the setting alone")
+}
+
+func TestTokenEndpointClientAuthentication(t *testing.T) {
+	type tokenRequest struct {
+		authorization string
+		form          url.Values
+	}
+	var requests []tokenRequest
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		r.ParseForm()
+		requests = append(requests, tokenRequest{r.Header.Get("Authorization"), r.PostForm})
+		w.Header().Set("Content-Type", "application/json")
+		json.NewEncoder(w).Encode(map[string]any{
+			"access_token":  "access",
+			"token_type":    "Bearer",
+			"expires_in":    3600,
+			"refresh_token": "refresh",
+		})
+	}))
+	defer server.Close()
+
+	basic := "Basic " + base64.StdEncoding.EncodeToString([]byte("client-id:client-secret"))
+	cases := []struct {
+		name       string
+		secret     string
+		method     string
+		wantHeader string
+		wantSecret string
+	}{
+		{"confidential client defaults to basic", "client-secret", "", basic, ""},
+		{"public client defaults to body", "", "", "", ""},
+		{"basic forced", "client-secret", TokenAuthMethodBasic, basic, ""},
+		{"body forced", "client-secret", TokenAuthMethodBody, "", "client-secret"},
+	}
+	for _, tc := range cases {
+		t.Run(tc.name, func(t *testing.T) {
+			ts, dir := createTempTokenStore(t)
+			defer os.RemoveAll(dir)
+			require.NoError(t, ts.SaveOAuth2TokenForApp("default", "alice", "old-access", "old-refresh", 1))
+
+			a := NewAuth(&config.Config{ClientID: "client-id", TokenURL: server.URL}).WithTokenStore(ts)
+			a.clientSecret = tc.secret
+			_, err := a.WithTokenAuthMethod(tc.method)
+			require.NoError(t, err)
+
+			requests = nil
+			_, err = a.newOAuth2Config().Exchange(context.Background(), "auth-code")
+			require.NoError(t, err)
+			_, err = a.ForceRefreshOAuth2Token("alice")
+			require.NoError(t, err)
+
+			require.Len(t, requests, 2)
+			for i, grant := range []string{"authorization_code", "refresh_token"} {
+				req := requests[i]
+				assert.Equal(t, grant, req.form.Get("grant_type"))
+				assert.Equal(t, tc.wantHeader, req.authorization, grant)
+				assert.Equal(t, tc.wantSecret, req.form.Get("client_secret"), grant)
+				if tc.wantHeader == "" {
+					assert.Equal(t, "client-id", req.form.Get("client_id"), grant)
+				}
+			}
+		})
+	}
 }
 
 func TestParseHeadlessAuthCode(t *testing.T) {
@@ -785,6 +1140,67 @@ func TestHeadlessLoginExchangesPastedCode(t *testing.T) {
 	assert.Equal(t, "headless-refresh", stored.OAuth2.RefreshToken)
 }
 
+// mockTokenServerNoRefresh answers every grant with an access token but no
+// refresh_token, and records the grant types it was asked for.
+func mockTokenServerNoRefresh(t *testing.T, accessToken string, grants *[]string) *httptest.Server {
+	t.Helper()
+	return httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		r.ParseForm()
+		*grants = append(*grants, r.PostForm.Get("grant_type"))
+		w.Header().Set("Content-Type", "application/json")
+		json.NewEncoder(w).Encode(map[string]any{
+			"access_token": accessToken,
+			"token_type":   "Bearer",
+			"expires_in":   3600,
+		})
+	}))
+}
+
+func TestRefreshKeepsRefreshTokenWhenResponseOmitsIt(t *testing.T) {
+	var grants []string
+	server := mockTokenServerNoRefresh(t, "refreshed-access", &grants)
+	defer server.Close()
+
+	ts, dir := createTempTokenStore(t)
+	defer os.RemoveAll(dir)
+	require.NoError(t, ts.SaveOAuth2TokenForApp("default", "alice", "old-access", "old-refresh", 1))
+
+	a := NewAuth(&config.Config{TokenURL: server.URL}).WithTokenStore(ts)
+	tok, err := a.GetValidOAuth2Token("alice")
+	require.NoError(t, err)
+	assert.Equal(t, "refreshed-access", tok)
+	assert.Equal(t, []string{"refresh_token"}, grants)
+
+	stored := ts.GetOAuth2TokenForApp("default", "alice")
+	require.NotNil(t, stored)
+	assert.Equal(t, "refreshed-access", stored.OAuth2.AccessToken)
+	assert.Equal(t, "old-refresh", stored.OAuth2.RefreshToken, "the previous refresh token must be kept")
+}
+
+func TestInitialLoginRequiresRefreshToken(t *testing.T) {
+	var grants []string
+	server := mockTokenServerNoRefresh(t, "login-access", &grants)
+	defer server.Close()
+
+	tokenStore, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+
+	a := NewAuth(&config.Config{
+		AuthURL:     "https://x.com/i/oauth2/authorize",
+		TokenURL:    server.URL,
+		RedirectURI: "http://localhost:8080/callback",
+	}).WithTokenStore(tokenStore)
+
+	hl, err := a.StartHeadlessLogin("alice")
+	require.NoError(t, err)
+
+	_, err = hl.Complete("test-auth-code")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "no refresh_token")
+	assert.Equal(t, []string{"authorization_code"}, grants)
+	assert.Nil(t, tokenStore.GetOAuth2TokenForApp("default", "alice"), "a token that cannot be refreshed is not stored")
+}
+
 // TestHeadlessLoginRejectsStateMismatch verifies a pasted redirect URL whose
 // state does not match the login attempt is rejected before any token exchange.
 func TestHeadlessLoginRejectsStateMismatch(t *testing.T) {
@@ -809,3 +1225,635 @@ func TestHeadlessLoginRejectsStateMismatch(t *testing.T) {
 	require.Error(t, err)
 	assert.Contains(t, err.Error(), "state mismatch")
 }
+
+// TestHeadlessLoginResumesPendingState verifies that a login whose URL was
+// handed out by one Auth (--open-url-only) can be completed by another (--code),
+// and that the pending state is cleared afterwards.
+func TestHeadlessLoginResumesPendingState(t *testing.T) {
+	server := mockTokenServer(t, "resumed-access", "resumed-refresh")
+	defer server.Close()
+
+	tokenStore, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+	tokenStore.AddApp("my-app", "client-id", "client-secret")
+
+	cfg := &config.Config{
+		AuthURL:     "https://x.com/i/oauth2/authorize",
+		TokenURL:    server.URL + "/token",
+		RedirectURI: "http://localhost:8080/callback",
+	}
+
+	_, err := NewAuth(cfg).WithTokenStore(tokenStore).WithAppName("my-app").ResumeHeadlessLogin()
+	require.Error(t, err, "nothing pending yet")
+
+	started, err := NewAuth(cfg).WithTokenStore(tokenStore).WithAppName("my-app").StartHeadlessLogin("alice")
+	require.NoError(t, err)
+	require.NoError(t, started.SavePending())
+
+	authURL, err := url.Parse(started.AuthURL())
+	require.NoError(t, err)
+	state := authURL.Query().Get("state")
+
+	pending := tokenStore.GetPendingOAuth2LoginForApp("my-app")
+	require.NotNil(t, pending)
+	assert.Equal(t, state, pending.State)
+	assert.Equal(t, "http://localhost:8080/callback", pending.RedirectURI)
+
+	resumed, err := NewAuth(cfg).WithTokenStore(tokenStore).WithAppName("my-app").ResumeHeadlessLogin()
+	require.NoError(t, err)
+
+	_, err = resumed.Complete("http://localhost:8080/callback?state=bogus&code=abc")
+	require.Error(t, err)
+	assert.NotNil(t, tokenStore.GetPendingOAuth2LoginForApp("my-app"), "a failed completion keeps the pending login")
+
+	tok, err := resumed.Complete("http://localhost:8080/callback?state=" + url.QueryEscape(state) + "&code=abc")
+	require.NoError(t, err)
+	assert.Equal(t, "resumed-access", tok)
+
+	stored := tokenStore.GetOAuth2TokenForApp("my-app", "alice")
+	require.NotNil(t, stored)
+	assert.Equal(t, "resumed-access", stored.OAuth2.AccessToken)
+	assert.Nil(t, tokenStore.GetPendingOAuth2LoginForApp("my-app"))
+}
+
+func TestVerifyBearerToken(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		switch r.Header.Get("Authorization") {
+		case "Bearer good-token":
+			// App-only tokens cannot call /2/users/me, but the API still
+			// authenticated them.
+			w.WriteHeader(http.StatusForbidden)
+		default:
+			w.WriteHeader(http.StatusUnauthorized)
+		}
+	}))
+	defer server.Close()
+
+	a := NewAuth(&config.Config{InfoURL: server.URL})
+
+	assert.NoError(t, a.VerifyBearerToken("good-token"))
+
+	err := a.VerifyBearerToken("typo-token")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "InvalidToken")
+}
+
+func TestBaseStringURI(t *testing.T) {
+	testCases := []struct {
+		name     string
+		input    string
+		expected string
+	}{
+		{"default port omitted", "https://api.x.com/2/users/me", "https://api.x.com/2/users/me"},
+		{"explicit default https port dropped", "https://api.x.com:443/2/users/me", "https://api.x.com/2/users/me"},
+		{"explicit default http port dropped", "http://example.com:80/path", "http://example.com/path"},
+		{"non-default port kept", "http://127.0.0.1:54321/2/tweets", "http://127.0.0.1:54321/2/tweets"},
+		{"https on port 80 kept", "https://example.com:80/path", "https://example.com:80/path"},
+		{"mixed-case scheme and host lowercased", "HTTPS://API.X.com/2/Users/Me", "https://api.x.com/2/Users/Me"},
+		{"query and fragment stripped", "https://api.x.com/2/tweets?ids=1#frag", "https://api.x.com/2/tweets"},
+		{"empty path becomes slash", "https://api.x.com", "https://api.x.com/"},
+	}
+
+	for _, tc := range testCases {
+		t.Run(tc.name, func(t *testing.T) {
+			u, err := url.Parse(tc.input)
+			require.NoError(t, err)
+			assert.Equal(t, tc.expected, baseStringURI(u))
+		})
+	}
+}
+
+func TestGenerateSignatureIgnoresDefaultPort(t *testing.T) {
+	params := url.Values{"oauth_nonce": {"1"}, "oauth_timestamp": {"1"}}
+
+	plain, err := generateSignature("GET", "https://api.x.com/2/users/me", params, "cs", "ts", sha1.New)
+	require.NoError(t, err)
+	withPort, err := generateSignature("GET", "https://API.x.com:443/2/users/me", params, "cs", "ts", sha1.New)
+	require.NoError(t, err)
+	assert.Equal(t, plain, withPort)
+
+	otherPort, err := generateSignature("GET", "https://api.x.com:8443/2/users/me", params, "cs", "ts", sha1.New)
+	require.NoError(t, err)
+	assert.NotEqual(t, plain, otherPort)
+}
+
+func TestGenerateSignatureVectors(t *testing.T) {
+	testCases := []struct {
+		method   string
+		newHash  func() hash.Hash
+		expected string
+	}{
+		{OAuth1SignatureHMACSHA1, sha1.New, "MLfxAY79Pu7IqzDnjlHs37oUPN4="},
+		{OAuth1SignatureHMACSHA256, sha256.New, "mP7ueUAUNuDhbT6t8UBkaQymqGvYWUhpbVwGhmuT+zQ="},
+	}
+
+	for _, tc := range testCases {
+		t.Run(tc.method, func(t *testing.T) {
+			params := url.Values{
+				"ids":                    {"1,2"},
+				"oauth_consumer_key":     {"ck"},
+				"oauth_nonce":            {"nonce"},
+				"oauth_signature_method": {tc.method},
+				"oauth_timestamp":        {"1700000000"},
+				"oauth_token":            {"at"},
+				"oauth_version":          {"1.0"},
+			}
+			signature, err := generateSignature("GET", "https://api.x.com/2/tweets?ids=1,2", params, "cs", "ts", tc.newHash)
+			require.NoError(t, err)
+			assert.Equal(t, tc.expected, signature)
+		})
+	}
+}
+
+func TestGenerateSignatureRepeatedKey(t *testing.T) {
+	// Every value of a repeated key is signed, sorted by encoded value:
+	// ids=1, ids=1%2C3, ids=2.
+	params := url.Values{
+		"ids":                    {"2", "1", "1,3"},
+		"oauth_consumer_key":     {"ck"},
+		"oauth_nonce":            {"nonce"},
+		"oauth_signature_method": {OAuth1SignatureHMACSHA1},
+		"oauth_timestamp":        {"1700000000"},
+		"oauth_token":            {"at"},
+		"oauth_version":          {"1.0"},
+	}
+	baseString, err := signatureBaseString("GET", "https://api.x.com/2/tweets", params)
+	require.NoError(t, err)
+	assert.Equal(t, "GET&https%3A%2F%2Fapi.x.com%2F2%2Ftweets&ids%3D1%26ids%3D1%252C3%26ids%3D2%26oauth_consumer_key%3Dck%26oauth_nonce%3Dnonce%26oauth_signature_method%3DHMAC-SHA1%26oauth_timestamp%3D1700000000%26oauth_token%3Dat%26oauth_version%3D1.0", baseString)
+
+	signature, err := generateSignature("GET", "https://api.x.com/2/tweets", params, "cs", "ts", sha1.New)
+	require.NoError(t, err)
+	assert.Equal(t, "3Ag6kfCKT9rFiJpjOaFEYNx7Hic=", signature)
+
+	t.Run("GetOAuth1Header signs every value from the URL", func(t *testing.T) {
+		tokenStore, tempDir := createTempTokenStore(t)
+		defer os.RemoveAll(tempDir)
+		require.NoError(t, tokenStore.SaveOAuth1Tokens("at", "ts", "ck", "cs"))
+		a := NewAuth(&config.Config{}).WithTokenStore(tokenStore)
+
+		header, err := a.GetOAuth1Header("GET", "https://api.x.com/2/tweets?ids=2&ids=1&ids=1%2C3", nil)
+		require.NoError(t, err)
+		_, values := parseOAuthHeader(t, header)
+		nonce, _ := url.QueryUnescape(values["oauth_nonce"])
+		timestamp, _ := url.QueryUnescape(values["oauth_timestamp"])
+		got, _ := url.QueryUnescape(values["oauth_signature"])
+
+		params.Set("oauth_nonce", nonce)
+		params.Set("oauth_timestamp", timestamp)
+		expected, err := generateSignature("GET", "https://api.x.com/2/tweets", params, "cs", "ts", sha1.New)
+		require.NoError(t, err)
+		assert.Equal(t, expected, got)
+	})
+}
+
+func TestSignatureMethodFromTokenStore(t *testing.T) {
+	tokenStore, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+	require.NoError(t, tokenStore.SaveOAuth1Tokens("at", "ts", "ck", "cs"))
+	require.NoError(t, tokenStore.SetOAuth1SignatureMethodForApp("", OAuth1SignatureHMACSHA256))
+
+	a := NewAuth(&config.Config{}).WithTokenStore(tokenStore)
+
+	header, err := a.GetOAuth1Header("GET", "https://api.x.com/2/users/me", nil)
+	require.NoError(t, err)
+	assert.Contains(t, header, `oauth_signature_method="HMAC-SHA256"`)
+
+	// The per-invocation override wins over the stored method.
+	header, err = a.WithOAuth1SignatureMethod(OAuth1SignatureHMACSHA1).GetOAuth1Header("GET", "https://api.x.com/2/users/me", nil)
+	require.NoError(t, err)
+	assert.Contains(t, header, `oauth_signature_method="HMAC-SHA1"`)
+
+	// The short forms name the HMAC methods.
+	header, err = a.WithOAuth1SignatureMethod("sha256").GetOAuth1Header("GET", "https://api.x.com/2/users/me", nil)
+	require.NoError(t, err)
+	assert.Contains(t, header, `oauth_signature_method="HMAC-SHA256"`)
+	header, err = a.WithOAuth1SignatureMethod("SHA1").GetOAuth1Header("GET", "https://api.x.com/2/users/me", nil)
+	require.NoError(t, err)
+	assert.Contains(t, header, `oauth_signature_method="HMAC-SHA1"`)
+}
+
+// parseOAuthHeader splits an "OAuth k="v", ..." header into its ordered keys and
+// (still percent-encoded) values.
+func parseOAuthHeader(t *testing.T, header string) ([]string, map[string]string) {
+	t.Helper()
+	require.True(t, strings.HasPrefix(header, "OAuth "), "not an OAuth header: %s", header)
+	var keys []string
+	values := map[string]string{}
+	for _, part := range strings.Split(strings.TrimPrefix(header, "OAuth "), ", ") {
+		kv := strings.SplitN(part, "=", 2)
+		require.Len(t, kv, 2)
+		keys = append(keys, kv[0])
+		values[kv[0]] = strings.Trim(kv[1], `"`)
+	}
+	return keys, values
+}
+
+func TestOAuth1Realm(t *testing.T) {
+	tokenStore, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+	require.NoError(t, tokenStore.SaveOAuth1Tokens("at", "ts", "ck", "cs"))
+
+	a := NewAuth(&config.Config{}).WithTokenStore(tokenStore)
+	urlStr := "https://api.x.com/2/users/me"
+
+	t.Run("absent when unset", func(t *testing.T) {
+		header, err := a.GetOAuth1Header("GET", urlStr, nil)
+		require.NoError(t, err)
+		keys, _ := parseOAuthHeader(t, header)
+		assert.NotContains(t, keys, "realm")
+	})
+
+	t.Run("stored realm is emitted first", func(t *testing.T) {
+		require.NoError(t, tokenStore.SetOAuth1RealmForApp("", "https://api.x.com/"))
+		defer tokenStore.SetOAuth1RealmForApp("", "")
+
+		header, err := a.GetOAuth1Header("GET", urlStr, nil)
+		require.NoError(t, err)
+		assert.True(t, strings.HasPrefix(header, `OAuth realm="https://api.x.com/", oauth_consumer_key=`), header)
+	})
+
+	t.Run("quotes in the realm are escaped", func(t *testing.T) {
+		header, err := a.WithOAuth1Realm(`say "hi"`).GetOAuth1Header("GET", urlStr, nil)
+		require.NoError(t, err)
+		defer a.WithOAuth1Realm("")
+
+		assert.True(t, strings.HasPrefix(header, `OAuth realm="say \"hi\"", oauth_consumer_key=`), header)
+	})
+
+	t.Run("override realm is excluded from the signature", func(t *testing.T) {
+		header, err := a.WithOAuth1Realm("https://gateway.example/").GetOAuth1Header("GET", urlStr, nil)
+		require.NoError(t, err)
+		defer a.WithOAuth1Realm("")
+
+		keys, values := parseOAuthHeader(t, header)
+		require.Equal(t, "realm", keys[0])
+		assert.Equal(t, "https://gateway.example/", values["realm"])
+
+		nonce, _ := url.QueryUnescape(values["oauth_nonce"])
+		timestamp, _ := url.QueryUnescape(values["oauth_timestamp"])
+		signature, _ := url.QueryUnescape(values["oauth_signature"])
+
+		params := url.Values{
+			"oauth_consumer_key":     {"ck"},
+			"oauth_nonce":            {nonce},
+			"oauth_signature_method": {OAuth1SignatureHMACSHA1},
+			"oauth_timestamp":        {timestamp},
+			"oauth_token":            {"at"},
+			"oauth_version":          {"1.0"},
+		}
+		expected, err := generateSignature("GET", urlStr, params, "cs", "ts", sha1.New)
+		require.NoError(t, err)
+		assert.Equal(t, expected, signature, "the realm must not be part of the signed parameters")
+	})
+}
+
+func TestOAuth1QueryParams(t *testing.T) {
+	tokenStore, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+	require.NoError(t, tokenStore.SaveOAuth1Tokens("at", "ts", "ck", "cs"))
+
+	a := NewAuth(&config.Config{}).WithTokenStore(tokenStore).WithOAuth1Realm(`say "hi", oauth_token="x"`)
+	header, err := a.GetOAuth1Header("GET", "https://api.x.com/2/users/me", nil)
+	require.NoError(t, err)
+	_, values := parseOAuthHeader(t, header)
+
+	params, err := OAuth1QueryParams(header)
+	require.NoError(t, err)
+	assert.False(t, params.Has("realm"))
+	assert.Equal(t, "at", params.Get("oauth_token"), "an oauth_* lookalike inside the realm is ignored")
+	signature, _ := url.QueryUnescape(values["oauth_signature"])
+	assert.Equal(t, signature, params.Get("oauth_signature"))
+	assert.Len(t, params, 7)
+
+	_, err = OAuth1QueryParams("Bearer abc")
+	assert.Error(t, err)
+	_, err = OAuth1QueryParams(`OAuth oauth_token="unterminated`)
+	assert.Error(t, err)
+}
+
+func TestDebugSignature(t *testing.T) {
+	tokenStore, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+	require.NoError(t, tokenStore.SaveOAuth1Tokens("at", "token-secret", "ck", "consumer/secret"))
+	require.NoError(t, tokenStore.SaveBearerToken("bearer"))
+
+	var out bytes.Buffer
+	a := NewAuth(&config.Config{}).WithTokenStore(tokenStore).WithDebugSignature(&out)
+	header, err := a.GetOAuth1Header("POST", "https://api.x.com/2/tweets?a=1", nil)
+	require.NoError(t, err)
+	_, values := parseOAuthHeader(t, header)
+	signature, _ := url.QueryUnescape(values["oauth_signature"])
+	nonce, _ := url.QueryUnescape(values["oauth_nonce"])
+	timestamp, _ := url.QueryUnescape(values["oauth_timestamp"])
+
+	lines := strings.Split(strings.TrimSuffix(out.String(), "\n"), "\n")
+	require.Len(t, lines, 3)
+	baseString, ok := strings.CutPrefix(lines[0], "OAuth1 signature base string: ")
+	require.True(t, ok, lines[0])
+	assert.Equal(t, "POST&https%3A%2F%2Fapi.x.com%2F2%2Ftweets&a%3D1%26oauth_consumer_key%3Dck%26oauth_nonce%3D"+nonce+
+		"%26oauth_signature_method%3DHMAC-SHA1%26oauth_timestamp%3D"+timestamp+"%26oauth_token%3Dat%26oauth_version%3D1.0", baseString)
+	assert.Equal(t, "OAuth1 signing key: <consumer secret, 17 chars>&<token secret, 12 chars>", lines[1])
+	assert.Equal(t, "OAuth1 signature (HMAC-SHA1): "+signature, lines[2])
+	assert.Equal(t, signature, signBaseString(baseString, signingKey("consumer/secret", "token-secret"), sha1.New),
+		"the printed base string is the one that was signed")
+	assert.NotContains(t, out.String(), "consumer/secret")
+	assert.NotContains(t, out.String(), "token-secret")
+
+	out.Reset()
+	_, err = a.GetBearerTokenHeader()
+	require.NoError(t, err)
+	assert.Empty(t, out.String(), "only OAuth1 requests are described")
+}
+
+func TestOAuth1BodyHash(t *testing.T) {
+	tokenStore, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+	require.NoError(t, tokenStore.SaveOAuth1Tokens("at", "ts", "ck", "cs"))
+
+	a := NewAuth(&config.Config{}).WithTokenStore(tokenStore)
+	body := []byte("Hello World!")
+
+	t.Run("off by default", func(t *testing.T) {
+		assert.Nil(t, a.OAuth1BodyHashParams("application/json", body))
+	})
+
+	a.WithOAuth1BodyHash(true)
+	defer a.WithOAuth1BodyHash(false)
+
+	t.Run("vectors", func(t *testing.T) {
+		tests := []struct {
+			method   string
+			body     []byte
+			expected string
+		}{
+			{OAuth1SignatureHMACSHA1, body, "Lve95gjOVATpfV8EL5X4nxwjKHE="},
+			{OAuth1SignatureHMACSHA1, nil, "2jmj7l5rSw0yVb/vlWAYkK/YBwk="},
+			{OAuth1SignatureHMACSHA256, body, "f4OxZX/x/FO5LcGBSKHWXfwtSx+j1ncoSt3SABJtkGk="},
+			{OAuth1SignatureHMACSHA256, nil, "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU="},
+		}
+		for _, tc := range tests {
+			a.WithOAuth1SignatureMethod(tc.method)
+			params := a.OAuth1BodyHashParams("application/octet-stream", tc.body)
+			assert.Equal(t, tc.expected, params["oauth_body_hash"], "%s of %q", tc.method, tc.body)
+		}
+		a.WithOAuth1SignatureMethod("")
+	})
+
+	t.Run("not used for form-encoded bodies", func(t *testing.T) {
+		assert.Nil(t, a.OAuth1BodyHashParams("application/x-www-form-urlencoded", []byte("a=1")))
+	})
+
+	t.Run("sent in the header and signed", func(t *testing.T) {
+		urlStr := "https://api.x.com/2/tweets"
+		header, err := a.GetOAuth1Header("POST", urlStr, a.OAuth1BodyHashParams("application/json", body))
+		require.NoError(t, err)
+
+		_, values := parseOAuthHeader(t, header)
+		bodyHash, _ := url.QueryUnescape(values["oauth_body_hash"])
+		assert.Equal(t, "Lve95gjOVATpfV8EL5X4nxwjKHE=", bodyHash)
+
+		nonce, _ := url.QueryUnescape(values["oauth_nonce"])
+		timestamp, _ := url.QueryUnescape(values["oauth_timestamp"])
+		signature, _ := url.QueryUnescape(values["oauth_signature"])
+
+		params := url.Values{
+			"oauth_body_hash":        {bodyHash},
+			"oauth_consumer_key":     {"ck"},
+			"oauth_nonce":            {nonce},
+			"oauth_signature_method": {OAuth1SignatureHMACSHA1},
+			"oauth_timestamp":        {timestamp},
+			"oauth_token":            {"at"},
+			"oauth_version":          {"1.0"},
+		}
+		expected, err := generateSignature("POST", urlStr, params, "cs", "ts", sha1.New)
+		require.NoError(t, err)
+		assert.Equal(t, expected, signature)
+	})
+}
+
+func TestOAuth2FlowNonInteractive(t *testing.T) {
+	var listened, opened bool
+	origListener, origBrowser := startListenerFunc, openBrowserFunc
+	startListenerFunc = func([]string, string, func(code, state string) error, chan<- int, time.Duration) error {
+		listened = true
+		return nil
+	}
+	openBrowserFunc = func(string) error {
+		opened = true
+		return nil
+	}
+	defer func() { startListenerFunc, openBrowserFunc = origListener, origBrowser }()
+
+	ts, dir := createTempTokenStore(t)
+	defer os.RemoveAll(dir)
+	a := NewAuth(&config.Config{ClientID: "cid", NonInteractive: true}).WithTokenStore(ts)
+	require.True(t, a.NonInteractive())
+
+	// With no stored token, GetOAuth2Header would fall back to the browser flow.
+	_, err := a.GetOAuth2Header("")
+	require.Error(t, err)
+	assert.True(t, xurlErrors.IsInteractionRequiredError(err))
+	assert.False(t, listened, "no callback listener may be bound")
+	assert.False(t, opened, "no browser may be opened")
+
+	var body map[string]string
+	require.NoError(t, json.Unmarshal([]byte(err.Error()), &body), "the error must be machine-readable")
+	assert.Equal(t, "interaction_required", body["error"])
+	assert.Equal(t, "oauth2_browser_login", body["action"])
+	assert.NotEmpty(t, body["hint"])
+}
+
+func TestOAuth2FlowEphemeralPort(t *testing.T) {
+	var exchangedRedirect string
+	tokenServer := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		r.ParseForm()
+		exchangedRedirect = r.PostForm.Get("redirect_uri")
+		w.Header().Set("Content-Type", "application/json")
+		w.Write([]byte(`{"access_token":"ephemeral-access","token_type":"bearer","expires_in":3600,"refresh_token":"r"}`))
+	}))
+	defer tokenServer.Close()
+
+	var authorizeRedirect string
+	origBrowser := openBrowserFunc
+	openBrowserFunc = func(authURL string) error {
+		parsed, err := url.Parse(authURL)
+		require.NoError(t, err)
+		authorizeRedirect = parsed.Query().Get("redirect_uri")
+		go func() {
+			resp, err := http.Get(authorizeRedirect + "?code=the-code&state=" + url.QueryEscape(parsed.Query().Get("state")))
+			if err == nil {
+				resp.Body.Close()
+			}
+		}()
+		return nil
+	}
+	defer func() { openBrowserFunc = origBrowser }()
+
+	ts, dir := createTempTokenStore(t)
+	defer os.RemoveAll(dir)
+	a := NewAuth(&config.Config{
+		ClientID:    "cid",
+		AuthURL:     "https://x.com/i/oauth2/authorize",
+		TokenURL:    tokenServer.URL,
+		RedirectURI: "http://127.0.0.1:0/oauth/x/callback",
+	}).WithTokenStore(ts)
+
+	token, err := a.OAuth2Flow("alice")
+	require.NoError(t, err)
+	assert.Equal(t, "ephemeral-access", token)
+
+	assert.Regexp(t, `^http://127\.0\.0\.1:[1-9][0-9]*/oauth/x/callback$`, authorizeRedirect, "the bound port replaces port 0")
+	assert.Equal(t, authorizeRedirect, exchangedRedirect, "the exchange must repeat the authorize redirect_uri")
+}
+
+func TestOAuth2FlowNoSaveToken(t *testing.T) {
+	tokenServer := mockTokenServer(t, "memory-access", "memory-refresh")
+	defer tokenServer.Close()
+
+	origBrowser := openBrowserFunc
+	openBrowserFunc = func(authURL string) error {
+		parsed, err := url.Parse(authURL)
+		require.NoError(t, err)
+		go func() {
+			resp, err := http.Get(parsed.Query().Get("redirect_uri") + "?code=the-code&state=" + url.QueryEscape(parsed.Query().Get("state")))
+			if err == nil {
+				resp.Body.Close()
+			}
+		}()
+		return nil
+	}
+	defer func() { openBrowserFunc = origBrowser }()
+
+	ts, dir := createTempTokenStore(t)
+	defer os.RemoveAll(dir)
+	a := NewAuth(&config.Config{
+		ClientID:    "cid",
+		AuthURL:     "https://x.com/i/oauth2/authorize",
+		TokenURL:    tokenServer.URL,
+		RedirectURI: "http://127.0.0.1:0/callback",
+	}).WithTokenStore(ts).WithNoSaveToken(true)
+
+	token, err := a.OAuth2Flow("alice")
+	require.NoError(t, err)
+	assert.Equal(t, "memory-access", token)
+
+	assert.NoFileExists(t, ts.FilePath, "nothing is written to the token store file")
+	stored := ts.GetFirstOAuth2Token()
+	require.NotNil(t, stored, "the token is still usable for the rest of the process")
+	assert.Equal(t, "memory-access", stored.OAuth2.AccessToken)
+}
+
+func TestRedirectURIWarnings(t *testing.T) {
+	ts, dir := createTempTokenStore(t)
+	defer os.RemoveAll(dir)
+	ts.Apps["default"].RedirectURI = "http://127.0.0.1:8080/oauth/x/callback"
+
+	warnings := func(redirectURI string, fromEnv bool) []string {
+		a := &Auth{TokenStore: ts, redirectURI: redirectURI, redirectURIFromEnv: fromEnv}
+		listener, err := listenerConfigFromRedirectURI(redirectURI)
+		require.NoError(t, err)
+		return a.redirectURIWarnings(listener)
+	}
+
+	assert.Empty(t, warnings("http://localhost:8080/callback", false))
+	assert.Empty(t, warnings("http://127.0.0.1:8080/oauth/x/callback", true), "the env value matches the stored one")
+
+	got := warnings("https://localhost:8080/callback", false)
+	require.Len(t, got, 1)
+	assert.Contains(t, got[0], "plain http")
+
+	got = warnings("http://example.com:8080/callback", false)
+	require.Len(t, got, 1)
+	assert.Contains(t, got[0], "not a loopback address")
+
+	got = warnings("http://[::1]:0/callback", false)
+	require.Len(t, got, 1)
+	assert.Contains(t, got[0], "any loopback port")
+
+	got = warnings("http://localhost:8080/callback", true)
+	require.Len(t, got, 1)
+	assert.Contains(t, got[0], "differs from the app's stored redirect URI (http://127.0.0.1:8080/oauth/x/callback)")
+}
+
+// writeClientCertificate writes a self-signed client certificate and its key
+// to dir as cert.pem and key.pem, and both to combined.pem.
+func writeClientCertificate(t *testing.T, dir string) {
+	t.Helper()
+	key, err := ecdsa.GenerateKey(elliptic.P256(), rand.Reader)
+	require.NoError(t, err)
+	template := &x509.Certificate{
+		SerialNumber: big.NewInt(1),
+		Subject:      pkix.Name{CommonName: "xurl-test-client"},
+		NotBefore:    time.Now().Add(-time.Hour),
+		NotAfter:     time.Now().Add(time.Hour),
+		ExtKeyUsage:  []x509.ExtKeyUsage{x509.ExtKeyUsageClientAuth},
+	}
+	der, err := x509.CreateCertificate(rand.Reader, template, template, &key.PublicKey, key)
+	require.NoError(t, err)
+	keyDER, err := x509.MarshalECPrivateKey(key)
+	require.NoError(t, err)
+
+	certPEM := pem.EncodeToMemory(&pem.Block{Type: "CERTIFICATE", Bytes: der})
+	keyPEM := pem.EncodeToMemory(&pem.Block{Type: "EC PRIVATE KEY", Bytes: keyDER})
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "cert.pem"), certPEM, 0600))
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "key.pem"), keyPEM, 0600))
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "combined.pem"), append(certPEM, keyPEM...), 0600))
+}
+
+func TestWithClientCertificate(t *testing.T) {
+	dir := t.TempDir()
+	writeClientCertificate(t, dir)
+
+	t.Run("No certificate uses the default transport", func(t *testing.T) {
+		a := NewAuth(&config.Config{})
+		assert.Nil(t, a.HTTPTransport())
+	})
+
+	t.Run("Separate key file", func(t *testing.T) {
+		a := NewAuth(&config.Config{})
+		_, err := a.WithClientCertificate(filepath.Join(dir, "cert.pem"), filepath.Join(dir, "key.pem"))
+		require.NoError(t, err)
+		assert.NotNil(t, a.HTTPTransport())
+	})
+
+	t.Run("Key in the certificate file", func(t *testing.T) {
+		a := NewAuth(&config.Config{})
+		_, err := a.WithClientCertificate(filepath.Join(dir, "combined.pem"), "")
+		require.NoError(t, err)
+		assert.NotNil(t, a.HTTPTransport())
+	})
+
+	t.Run("Missing file", func(t *testing.T) {
+		a := NewAuth(&config.Config{})
+		_, err := a.WithClientCertificate(filepath.Join(dir, "missing.pem"), "")
+		require.Error(t, err)
+		assert.True(t, xurlErrors.IsAuthError(err))
+		assert.Contains(t, err.Error(), "cannot load the client certificate")
+		assert.Nil(t, a.HTTPTransport())
+	})
+
+	t.Run("Certificate is presented to the server", func(t *testing.T) {
+		var commonName string
+		server := httptest.NewUnstartedServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			if len(r.TLS.PeerCertificates) > 0 {
+				commonName = r.TLS.PeerCertificates[0].Subject.CommonName
+			}
+		}))
+		server.TLS = &tls.Config{ClientAuth: tls.RequireAnyClientCert}
+		server.StartTLS()
+		defer server.Close()
+
+		a := NewAuth(&config.Config{})
+		_, err := a.WithClientCertificate(filepath.Join(dir, "combined.pem"), "")
+		require.NoError(t, err)
+		transport := a.HTTPTransport().(*http.Transport)
+		roots := x509.NewCertPool()
+		roots.AddCert(server.Certificate())
+		transport.TLSClientConfig.RootCAs = roots
+
+		resp, err := (&http.Client{Transport: transport}).Get(server.URL)
+		require.NoError(t, err)
+		resp.Body.Close()
+		assert.Equal(t, "xurl-test-client", commonName)
+	})
+}
@@ -4,15 +4,47 @@ import (
 	"context"
 	"errors"
 	"fmt"
+	"io"
 	"net"
 	"net/http"
+	"os"
+	"strconv"
+	"strings"
 	"sync"
 	"time"
 
 	xurlErrors "github.com/xdevplatform/xurl/errors"
 )
 
-func StartListener(addresses []string, callbackPath string, callback func(code, state string) error, ready chan<- struct{}) error {
+// listenerWarnings receives partial-bind warnings; tests replace it.
+var listenerWarnings io.Writer = os.Stderr
+
+// warnPartialBind reports the callback addresses that could not be bound. When
+// IPv4 loopback is among them the browser, which is usually sent to 127.0.0.1
+// for "localhost", may never reach the listener, so that case says how to
+// recover instead of leaving the user waiting.
+func warnPartialBind(w io.Writer, bound []string, bindErrs []error) {
+	for _, err := range bindErrs {
+		fmt.Fprintf(w, "Warning: could not listen for the OAuth2 callback: %v\n", err)
+	}
+	fmt.Fprintf(w, "Listening on %s only.\n", strings.Join(bound, ", "))
+
+	for _, address := range bound {
+		if host, _, err := net.SplitHostPort(address); err == nil && net.ParseIP(host).To4() != nil {
+			return
+		}
+	}
+	fmt.Fprintln(w, "The browser may be redirected to 127.0.0.1 and never reach xurl. Free the port on 127.0.0.1,")
+	fmt.Fprintln(w, "or re-run with --headless and paste the redirect URL instead.")
+}
+
+// StartListener serves callbackPath on every address that can be bound and
+// returns once callback has handled a request, or after timeout with a Timeout
+// auth error, shutting the servers down either way. Once listening, it sends
+// the bound port on ready (which should be buffered). An address with port 0
+// gets an ephemeral port, which the other port-0 addresses then share so that
+// "localhost" is served on one port for both loopback families.
+func StartListener(addresses []string, callbackPath string, callback func(code, state string) error, ready chan<- int, timeout time.Duration) error {
 	mux := http.NewServeMux()
 	done := make(chan error, 1)
 	servers := make([]*http.Server, 0, len(addresses))
@@ -48,23 +80,41 @@ func StartListener(addresses []string, callbackPath string, callback func(code,
 		finish(nil)
 	})
 
+	// Bind every address that can be bound: a machine without IPv6 (or with
+	// the port taken on one loopback family) can still receive the callback on
+	// the other, so a partial bind is a warning rather than a failure.
+	var bindErrs []error
+	var bound []string
+	boundPort := 0
 	for _, address := range addresses {
+		if host, port, err := net.SplitHostPort(address); err == nil && port == "0" && boundPort != 0 {
+			address = net.JoinHostPort(host, strconv.Itoa(boundPort))
+		}
 		listener, err := net.Listen("tcp", address)
 		if err != nil {
-			for _, existing := range listeners {
-				_ = existing.Close()
-			}
-			return xurlErrors.NewAuthError("ServerError", err)
+			bindErrs = append(bindErrs, err)
+			continue
 		}
+		address = listener.Addr().String()
+		if tcpAddr, ok := listener.Addr().(*net.TCPAddr); ok && boundPort == 0 {
+			boundPort = tcpAddr.Port
+		}
+		bound = append(bound, address)
 		listeners = append(listeners, listener)
 		servers = append(servers, &http.Server{
 			Addr:    address,
 			Handler: mux,
 		})
 	}
+	if len(listeners) == 0 {
+		return xurlErrors.NewAuthError("ServerError", errors.Join(bindErrs...))
+	}
+	if len(bindErrs) > 0 {
+		warnPartialBind(listenerWarnings, bound, bindErrs)
+	}
 
 	if ready != nil {
-		close(ready)
+		ready <- boundPort
 	}
 
 	for i, listener := range listeners {
@@ -79,10 +129,12 @@ func StartListener(addresses []string, callbackPath string, callback func(code,
 	select {
 	case err := <-done:
 		return err
-	case <-time.After(5 * time.Minute):
+	case <-time.After(timeout):
 		for _, server := range servers {
 			_ = server.Shutdown(context.Background())
 		}
-		return xurlErrors.NewAuthError("Timeout", errors.New("timeout waiting for callback"))
+		return xurlErrors.NewAuthError("Timeout", fmt.Errorf(
+			"authorization timed out: no OAuth2 callback received on %s within %s; re-run (with a longer --auth-timeout if needed), or use --headless if the browser showed a connection error",
+			strings.Join(bound, ", "), timeout))
 	}
 }
@@ -0,0 +1,137 @@
+package auth
+
+import (
+	"bytes"
+	"fmt"
+	"net"
+	"net/http"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// freeAddress returns a loopback address with a port nothing is listening on.
+func freeAddress(t *testing.T) string {
+	listener, err := net.Listen("tcp", "127.0.0.1:0")
+	require.NoError(t, err)
+	address := listener.Addr().String()
+	require.NoError(t, listener.Close())
+	return address
+}
+
+func TestStartListenerPartialBind(t *testing.T) {
+	busy, err := net.Listen("tcp", "127.0.0.1:0")
+	require.NoError(t, err)
+	defer busy.Close()
+	free := freeAddress(t)
+
+	var warnings bytes.Buffer
+	previous := listenerWarnings
+	listenerWarnings = &warnings
+	defer func() { listenerWarnings = previous }()
+
+	ready := make(chan int, 1)
+	done := make(chan error, 1)
+	var gotCode string
+	go func() {
+		done <- StartListener([]string{busy.Addr().String(), free}, "/callback", func(code, state string) error {
+			gotCode = code
+			return nil
+		}, ready, time.Minute)
+	}()
+
+	select {
+	case <-ready:
+	case err := <-done:
+		t.Fatalf("listener failed to start: %v", err)
+	}
+
+	resp, err := http.Get("http://" + free + "/callback?code=abc&state=xyz")
+	require.NoError(t, err)
+	resp.Body.Close()
+	assert.Equal(t, http.StatusOK, resp.StatusCode)
+
+	require.NoError(t, <-done)
+	assert.Equal(t, "abc", gotCode)
+	assert.Contains(t, warnings.String(), busy.Addr().String())
+	assert.Contains(t, warnings.String(), "Listening on "+free+" only.")
+	assert.NotContains(t, warnings.String(), "--headless")
+}
+
+func TestStartListenerNoBind(t *testing.T) {
+	busy, err := net.Listen("tcp", "127.0.0.1:0")
+	require.NoError(t, err)
+	defer busy.Close()
+
+	err = StartListener([]string{busy.Addr().String()}, "/callback", func(code, state string) error {
+		return nil
+	}, nil, time.Minute)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), busy.Addr().String())
+}
+
+func TestStartListenerTimeout(t *testing.T) {
+	address := freeAddress(t)
+
+	start := time.Now()
+	err := StartListener([]string{address}, "/callback", func(code, state string) error {
+		return nil
+	}, nil, 50*time.Millisecond)
+	require.Error(t, err)
+	assert.Less(t, time.Since(start), 5*time.Second)
+	assert.Contains(t, err.Error(), "authorization timed out")
+	assert.Contains(t, err.Error(), address)
+
+	// The server was shut down, so the address can be bound again.
+	listener, err := net.Listen("tcp", address)
+	require.NoError(t, err)
+	listener.Close()
+}
+
+func TestWarnPartialBindIPv6Only(t *testing.T) {
+	var warnings bytes.Buffer
+	warnPartialBind(&warnings, []string{"[::1]:8080"}, []error{assert.AnError})
+
+	assert.Contains(t, warnings.String(), "Listening on [::1]:8080 only.")
+	assert.Contains(t, warnings.String(), "--headless")
+}
+
+func TestStartListenerEphemeralPortAndCustomPath(t *testing.T) {
+	ready := make(chan int, 1)
+	done := make(chan error, 1)
+	var gotCode string
+	go func() {
+		done <- StartListener([]string{"127.0.0.1:0"}, "/oauth/x/callback", func(code, state string) error {
+			gotCode = code
+			return nil
+		}, ready, time.Minute)
+	}()
+
+	var port int
+	select {
+	case port = <-ready:
+	case err := <-done:
+		t.Fatalf("listener failed to start: %v", err)
+	}
+	require.NotZero(t, port, "the bound port must be reported")
+
+	resp, err := http.Get(fmt.Sprintf("http://127.0.0.1:%d/callback?code=abc&state=xyz", port))
+	require.NoError(t, err)
+	resp.Body.Close()
+	assert.Equal(t, http.StatusNotFound, resp.StatusCode, "only the exact callback path is served")
+
+	// Only the redirect URI's host is bound, not every interface.
+	if conn, err := net.Dial("tcp", fmt.Sprintf("[::1]:%d", port)); err == nil {
+		conn.Close()
+		t.Errorf("::1 must not be bound for a 127.0.0.1 redirect URI")
+	}
+
+	resp, err = http.Get(fmt.Sprintf("http://127.0.0.1:%d/oauth/x/callback?code=abc&state=xyz", port))
+	require.NoError(t, err)
+	resp.Body.Close()
+	assert.Equal(t, http.StatusOK, resp.StatusCode)
+	require.NoError(t, <-done)
+	assert.Equal(t, "abc", gotCode)
+}
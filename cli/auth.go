@@ -2,16 +2,20 @@ package cli
 
 import (
 	"bufio"
+	"encoding/json"
 	"fmt"
 	"io"
 	"os"
 	"strings"
+	"time"
 
 	"github.com/charmbracelet/lipgloss"
 	"github.com/spf13/cobra"
 
+	"github.com/xdevplatform/xurl/api"
 	"github.com/xdevplatform/xurl/auth"
 	"github.com/xdevplatform/xurl/config"
+	xurlErrors "github.com/xdevplatform/xurl/errors"
 	"github.com/xdevplatform/xurl/store"
 )
 
@@ -24,7 +28,7 @@ var (
 )
 
 // CreateAuthCommand creates the auth command and its subcommands
-func CreateAuthCommand(a *auth.Auth) *cobra.Command {
+func CreateAuthCommand(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	var authCmd = &cobra.Command{
 		Use:   "auth",
 		Short: "Authentication management",
@@ -32,11 +36,13 @@ func CreateAuthCommand(a *auth.Auth) *cobra.Command {
 
 	authCmd.AddCommand(createAuthAppOnlyCmd(a))
 	authCmd.AddCommand(createAuthOAuth2Cmd(a))
-	authCmd.AddCommand(createAuthOAuth1Cmd(a))
+	authCmd.AddCommand(createAuthOAuth1Cmd(cfg, a))
 	authCmd.AddCommand(createAuthStatusCmd())
+	authCmd.AddCommand(createAuthRefreshCmd(a))
 	authCmd.AddCommand(createAuthClearCmd(a))
 	authCmd.AddCommand(createAppCmd(a))
 	authCmd.AddCommand(createDefaultCmd(a))
+	authCmd.AddCommand(createAuthLayoutCmd(a))
 
 	return authCmd
 }
@@ -45,6 +51,7 @@ func CreateAuthCommand(a *auth.Auth) *cobra.Command {
 
 func createAuthAppOnlyCmd(a *auth.Auth) *cobra.Command {
 	var bearerToken string
+	var noVerify bool
 
 	cmd := &cobra.Command{
 		Use:     "app-only [TOKEN]",
@@ -57,9 +64,15 @@ It is distinct from OAuth2 user-context tokens (obtained via 'xurl auth oauth2')
 both are sent as "Authorization: Bearer", which is why this command is named for
 the auth mode (app-only) rather than the token scheme (bearer).
 
+After saving, the token is checked with one request to the API so a typo is
+caught now rather than on the first real request. If the API rejects it, the
+previously stored token is restored. Use --no-verify to skip the check (e.g.
+when offline).
+
 Examples:
   xurl auth app-only AAAA...                # token as an argument
   xurl auth app-only --app prod AAAA...     # for a specific registered app
+  xurl auth app-only --no-verify AAAA...    # save without checking the token
   cat token.txt | xurl auth app-only -      # read the token from stdin (keeps it out of shell history)`,
 		Args: cobra.MaximumNArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
@@ -79,40 +92,117 @@ Examples:
 				fmt.Fprintln(os.Stderr, "Error: provide the Bearer Token as an argument, via --bearer-token, or '-' to read from stdin.")
 				os.Exit(1)
 			}
-			if err := a.TokenStore.SaveBearerTokenForApp(a.AppName(), token); err != nil {
+			verifyErr, err := saveBearerToken(a, token, !noVerify)
+			if err != nil {
 				fmt.Fprintln(os.Stderr, "Error saving bearer token:", err)
 				os.Exit(1)
 			}
+			if verifyErr != nil {
+				fprintError(os.Stderr, "Bearer token verification failed: %v", verifyErr)
+				fmt.Fprintln(os.Stderr, "The new token was discarded; re-run 'xurl auth app-only' with the correct token, or pass --no-verify to skip this check.")
+				os.Exit(1)
+			}
 			fmt.Printf("\033[32mApp-only authentication configured!\033[0m\n")
+			if !noVerify {
+				fmt.Printf("\033[32mBearer token verified.\033[0m\n")
+			}
 		},
 	}
 
 	// Retained (no longer required) so existing `auth app --bearer-token ...`
 	// invocations keep working; passing the token as an argument is preferred.
 	cmd.Flags().StringVar(&bearerToken, "bearer-token", "", "Bearer token (alternative to passing it as an argument)")
+	cmd.Flags().BoolVar(&noVerify, "no-verify", false, "Save the token without checking it against the API")
 
 	return cmd
 }
 
+// saveBearerToken stores token as the active app's bearer token and, when
+// verify is set, checks it against the API. A rejected token is replaced by
+// the app's previous one. err reports a failure to save; verifyErr a failed
+// verification.
+func saveBearerToken(a *auth.Auth, token string, verify bool) (verifyErr, err error) {
+	appName := a.AppName()
+	previous := a.TokenStore.GetBearerTokenForApp(appName)
+
+	if err := a.TokenStore.SaveBearerTokenForApp(appName, token); err != nil {
+		return nil, err
+	}
+	if !verify {
+		return nil, nil
+	}
+	if verifyErr = a.VerifyBearerToken(token); verifyErr != nil {
+		if err := a.TokenStore.RestoreBearerTokenForApp(appName, previous); err != nil {
+			return verifyErr, err
+		}
+	}
+	return verifyErr, nil
+}
+
 // ─── auth oauth2 ────────────────────────────────────────────────────
 
 func createAuthOAuth2Cmd(a *auth.Auth) *cobra.Command {
-	var headless bool
+	var headless, openURLOnly bool
+	var code, scopePreset string
+	var authTimeout time.Duration
 	cmd := &cobra.Command{
 		Use:   "oauth2 [USERNAME]",
 		Short: "Configure OAuth2 authentication",
 		Long: `Configure OAuth2 (user-context) authentication.
 
+Every scope xurl can use is requested unless --scope-preset says otherwise;
+--scope-preset dm asks only for what the DM endpoints need.
+
 By default this opens a browser and listens on the app's redirect URI
 (localhost) for the callback. On a remote/headless machine where that callback
 is unreachable, use --headless: xurl prints the authorization URL, you open it
-on any device, and paste the resulting redirect URL (or code) back in.`,
+on any device, and paste the resulting redirect URL (or code) back in.
+
+If no callback arrives within --auth-timeout (default 2m), e.g. because the
+browser tab was closed without authorizing, the listener is shut down and the
+command fails instead of waiting forever.
+
+For scripts that drive the browser themselves, --open-url-only prints the
+authorization URL to stdout and exits without starting a listener; the PKCE
+state is kept in the token store until a later --code call exchanges the
+redirected URL (or bare code) for a token:
+
+  url=$(xurl auth oauth2 --app my-app --open-url-only alice)
+  # ... open $url and capture the redirect ...
+  xurl auth oauth2 --app my-app --code "$redirected_url"`,
 		Args: cobra.MaximumNArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
 			username := ""
 			if len(args) > 0 {
 				username = args[0]
 			}
+			if _, err := a.WithScopePreset(scopePreset); err != nil {
+				exitWithError(err)
+			}
+
+			if openURLOnly {
+				if err := printOAuth2URLOnly(a, username); err != nil {
+					fmt.Fprintln(os.Stderr, "OAuth2 authentication failed:", err)
+					os.Exit(1)
+				}
+				return
+			}
+			if code != "" {
+				if username != "" {
+					fmt.Fprintln(os.Stderr, "Error: --code completes the login started by --open-url-only; give the username there instead.")
+					os.Exit(1)
+				}
+				hl, err := a.ResumeHeadlessLogin()
+				if err == nil {
+					_, err = hl.Complete(code)
+				}
+				if err != nil {
+					fmt.Fprintln(os.Stderr, "OAuth2 authentication failed:", err)
+					os.Exit(1)
+				}
+				fmt.Printf("\033[32mOAuth2 authentication successful!\033[0m\n")
+				return
+			}
 
 			// Warn when --app is not specified and the active/default app has
 			// no client credentials but another registered app does. Tokens
@@ -133,9 +223,12 @@ on any device, and paste the resulting redirect URL (or code) back in.`,
 			if headless {
 				err = runHeadlessLogin(a, username)
 			} else {
-				_, err = a.OAuth2Flow(username)
+				_, err = a.WithCallbackTimeout(authTimeout).OAuth2Flow(username)
 			}
 			if err != nil {
+				if xurlErrors.IsInteractionRequiredError(err) {
+					exitWithError(err)
+				}
 				fmt.Fprintln(os.Stderr, "OAuth2 authentication failed:", err)
 				os.Exit(1)
 			}
@@ -143,15 +236,46 @@ on any device, and paste the resulting redirect URL (or code) back in.`,
 		},
 	}
 
+	cmd.Flags().StringVar(&scopePreset, "scope-preset", auth.ScopePresetAll, "OAuth2 scopes to request: all, or dm for just those the DM endpoints need (tweet.read, users.read, dm.read, dm.write, offline.access)")
 	cmd.Flags().BoolVar(&headless, "headless", false, "Authenticate without a local browser/callback: print the URL and paste the code back (for remote/headless machines)")
+	cmd.Flags().DurationVar(&authTimeout, "auth-timeout", 2*time.Minute, "How long to wait for the browser callback before giving up")
+	cmd.Flags().BoolVar(&openURLOnly, "open-url-only", false, "Print the authorization URL and exit without starting a listener; finish later with --code")
+	cmd.Flags().StringVar(&code, "code", "", "Finish a login started with --open-url-only using the redirected URL (or just the code)")
+	cmd.MarkFlagsMutuallyExclusive("headless", "open-url-only", "code")
 
 	return cmd
 }
 
+// printOAuth2URLOnly starts a login, saves its PKCE state for a later --code
+// call, and prints the authorization URL alone on stdout so scripts can capture
+// it; the follow-up instructions go to stderr.
+func printOAuth2URLOnly(a *auth.Auth, username string) error {
+	hl, err := a.StartHeadlessLogin(username)
+	if err != nil {
+		return err
+	}
+	if err := hl.SavePending(); err != nil {
+		return err
+	}
+
+	fmt.Println(hl.AuthURL())
+	appFlag := ""
+	if a.AppName() != "" {
+		appFlag = " --app " + a.AppName()
+	}
+	fmt.Fprintf(os.Stderr, "After authorizing, finish with: xurl auth oauth2%s --code '<redirected URL>'\n", appFlag)
+	return nil
+}
+
 // runHeadlessLogin drives the headless OAuth2 flow: print the authorize URL,
 // read the pasted redirect URL/code from stdin, and complete the exchange. The
 // auth package owns the protocol; this function owns the (styled) presentation.
 func runHeadlessLogin(a *auth.Auth, username string) error {
+	if a.NonInteractive() {
+		return xurlErrors.NewInteractionRequiredError("oauth2_headless_prompt",
+			"use 'xurl auth oauth2 --open-url-only' and then '--code' instead of --headless")
+	}
+
 	hl, err := a.StartHeadlessLogin(username)
 	if err != nil {
 		return err
@@ -203,26 +327,62 @@ func renderHeadlessInstructions(out io.Writer, authURL, redirectURI string, styl
 
 // ─── auth oauth1 ────────────────────────────────────────────────────
 
-func createAuthOAuth1Cmd(a *auth.Auth) *cobra.Command {
-	var consumerKey, consumerSecret, accessToken, tokenSecret string
+func createAuthOAuth1Cmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
+	var creds store.OAuth1Token
+	var noVerify, force bool
 
 	cmd := &cobra.Command{
 		Use:   "oauth1",
 		Short: "Configure OAuth1 authentication",
+		Long: `Store OAuth1 (user-context) credentials for the active app.
+
+After saving, the credentials are checked by signing one request to
+/2/users/me and the authenticated username is reported. If the API rejects
+them, the previously stored OAuth1 credentials are restored; pass --force to
+keep the new ones anyway, or --no-verify to skip the check (e.g. when offline).`,
 		Run: func(cmd *cobra.Command, args []string) {
-			err := a.TokenStore.SaveOAuth1TokensForApp(a.AppName(), accessToken, tokenSecret, consumerKey, consumerSecret)
+			creds.SignatureMethod = strings.ToUpper(creds.SignatureMethod)
+			if creds.SignatureMethod == "SHA1" || creds.SignatureMethod == "SHA256" {
+				creds.SignatureMethod = "HMAC-" + creds.SignatureMethod
+			}
+			if creds.SignatureMethod != "" && creds.SignatureMethod != auth.OAuth1SignatureHMACSHA1 && creds.SignatureMethod != auth.OAuth1SignatureHMACSHA256 {
+				fmt.Fprintf(os.Stderr, "Error: unsupported signature method %q (use %s or %s)\n", creds.SignatureMethod, auth.OAuth1SignatureHMACSHA1, auth.OAuth1SignatureHMACSHA256)
+				os.Exit(1)
+			}
+
+			var client api.Client
+			if !noVerify {
+				client = readClientFlags(cmd).apply(api.NewApiClient(cfg, a))
+			}
+			username, verifyErr, err := saveOAuth1Credentials(a, creds, client, force)
 			if err != nil {
 				fmt.Fprintln(os.Stderr, "Error saving OAuth1 tokens:", err)
 				os.Exit(1)
 			}
+			if verifyErr != nil {
+				fprintError(os.Stderr, "OAuth1 verification failed: %v", verifyErr)
+				if force {
+					fmt.Fprintln(os.Stderr, "The credentials were saved anyway (--force).")
+					return
+				}
+				fmt.Fprintln(os.Stderr, "The new credentials were discarded; check them, or pass --force to keep them or --no-verify to skip this check.")
+				os.Exit(1)
+			}
 			fmt.Printf("\033[32mOAuth1 credentials saved successfully!\033[0m\n")
+			if username != "" {
+				fmt.Printf("\033[32mVerified as @%s.\033[0m\n", username)
+			}
 		},
 	}
 
-	cmd.Flags().StringVar(&consumerKey, "consumer-key", "", "Consumer key for OAuth1")
-	cmd.Flags().StringVar(&consumerSecret, "consumer-secret", "", "Consumer secret for OAuth1")
-	cmd.Flags().StringVar(&accessToken, "access-token", "", "Access token for OAuth1")
-	cmd.Flags().StringVar(&tokenSecret, "token-secret", "", "Token secret for OAuth1")
+	cmd.Flags().StringVar(&creds.ConsumerKey, "consumer-key", "", "Consumer key for OAuth1")
+	cmd.Flags().StringVar(&creds.ConsumerSecret, "consumer-secret", "", "Consumer secret for OAuth1")
+	cmd.Flags().StringVar(&creds.AccessToken, "access-token", "", "Access token for OAuth1")
+	cmd.Flags().StringVar(&creds.TokenSecret, "token-secret", "", "Token secret for OAuth1")
+	cmd.Flags().StringVar(&creds.Realm, "realm", "", "Realm to store with the tokens and send in the Authorization header (for gateways that require one)")
+	cmd.Flags().StringVar(&creds.SignatureMethod, "signature-method", "", "Signature method to store with the tokens: HMAC-SHA1 (default) or HMAC-SHA256, or SHA1/SHA256 for short")
+	cmd.Flags().BoolVar(&noVerify, "no-verify", false, "Save the credentials without checking them against the API")
+	cmd.Flags().BoolVar(&force, "force", false, "Keep the credentials even if the API rejects them")
 
 	cmd.MarkFlagRequired("consumer-key")
 	cmd.MarkFlagRequired("consumer-secret")
@@ -232,14 +392,84 @@ func createAuthOAuth1Cmd(a *auth.Auth) *cobra.Command {
 	return cmd
 }
 
+// saveOAuth1Credentials stores creds for the active app and, when client is
+// non-nil, verifies them with a signed request. A rejected verification
+// restores the app's previous OAuth1 tokens unless force is set. err reports a
+// failure to save; verifyErr a failed verification.
+func saveOAuth1Credentials(a *auth.Auth, creds store.OAuth1Token, client api.Client, force bool) (username string, verifyErr, err error) {
+	appName := a.AppName()
+	previous := a.TokenStore.GetOAuth1TokensForApp(appName)
+
+	if err := a.TokenStore.SaveOAuth1TokensForApp(appName, creds.AccessToken, creds.TokenSecret, creds.ConsumerKey, creds.ConsumerSecret); err != nil {
+		return "", nil, err
+	}
+	if creds.SignatureMethod != "" {
+		if err := a.TokenStore.SetOAuth1SignatureMethodForApp(appName, creds.SignatureMethod); err != nil {
+			return "", nil, err
+		}
+	}
+	if creds.Realm != "" {
+		if err := a.TokenStore.SetOAuth1RealmForApp(appName, creds.Realm); err != nil {
+			return "", nil, err
+		}
+	}
+
+	if client == nil {
+		return "", nil, nil
+	}
+	username, verifyErr = verifyOAuth1Credentials(client)
+	if verifyErr != nil && !force {
+		if err := a.TokenStore.RestoreOAuth1TokensForApp(appName, previous); err != nil {
+			return "", verifyErr, err
+		}
+	}
+	return username, verifyErr, nil
+}
+
+// verifyOAuth1Credentials signs a request to /2/users/me with the stored OAuth1
+// credentials and returns the authenticated username.
+func verifyOAuth1Credentials(client api.Client) (string, error) {
+	response, err := client.SendRequest(api.RequestOptions{
+		Method:   "GET",
+		Endpoint: "/2/users/me",
+		AuthType: "oauth1",
+	})
+	if err != nil {
+		return "", err
+	}
+
+	var me struct {
+		Data struct {
+			Username string `json:"username"`
+		} `json:"data"`
+	}
+	if err := json.Unmarshal(response, &me); err != nil {
+		return "", fmt.Errorf("unexpected response from /2/users/me: %v", err)
+	}
+	return me.Data.Username, nil
+}
+
 // ─── auth status ────────────────────────────────────────────────────
 
 func createAuthStatusCmd() *cobra.Command {
+	var asJSON bool
+
 	cmd := &cobra.Command{
 		Use:   "status",
 		Short: "Show authentication status",
 		Run: func(cmd *cobra.Command, args []string) {
 			ts := store.NewTokenStore()
+			expiryWarning := config.NewConfig().TokenExpiryWarning
+			now := time.Now()
+
+			if asJSON {
+				out, err := json.MarshalIndent(buildAuthStatus(ts, expiryWarning, now), "", "  ")
+				if err != nil {
+					exitWithError(err)
+				}
+				fmt.Println(string(out))
+				return
+			}
 
 			apps := ts.ListApps()
 			defaultApp := ts.GetDefaultApp()
@@ -277,10 +507,14 @@ func createAuthStatusCmd() *cobra.Command {
 				usernames := ts.GetOAuth2UsernamesForApp(name)
 				if len(usernames) > 0 {
 					for _, u := range usernames {
+						var note string
+						if token := ts.GetOAuth2TokenForApp(name, u); token != nil && token.OAuth2 != nil {
+							note = oauth2ExpiryNote(token.OAuth2, expiryWarning, now)
+						}
 						if u == app.DefaultUser {
-							fmt.Printf("    ▸ oauth2: %s\n", displayOAuth2Username(u))
+							fmt.Printf("    ▸ oauth2: %s%s\n", displayOAuth2Username(u), note)
 						} else {
-							fmt.Printf("      oauth2: %s\n", displayOAuth2Username(u))
+							fmt.Printf("      oauth2: %s%s\n", displayOAuth2Username(u), note)
 						}
 					}
 				} else {
@@ -305,12 +539,166 @@ func createAuthStatusCmd() *cobra.Command {
 					fmt.Println()
 				}
 			}
+
+			if ts.ClockSkewSeconds != 0 {
+				fmt.Printf("\nclock skew: %+ds (server clock vs local; applied to OAuth1 timestamps)\n", ts.ClockSkewSeconds)
+			}
+		},
+	}
+
+	cmd.Flags().BoolVar(&asJSON, "json", false, "Print the status as a JSON object for scripts")
+	return cmd
+}
+
+// authStatus is the `auth status --json` document.
+type authStatus struct {
+	DefaultApp       string      `json:"default_app"`
+	Apps             []appStatus `json:"apps"`
+	ClockSkewSeconds int64       `json:"clock_skew_seconds"`
+}
+
+// appStatus is one registered app in authStatus. Only whether credentials are
+// stored is reported, never their values.
+type appStatus struct {
+	Name              string                `json:"name"`
+	Default           bool                  `json:"default"`
+	ClientID          string                `json:"client_id"`
+	RedirectURI       string                `json:"redirect_uri"`
+	RedirectURISource string                `json:"redirect_uri_source"`
+	OAuth2            []oauth2AccountStatus `json:"oauth2"`
+	OAuth1            bool                  `json:"oauth1"`
+	Bearer            bool                  `json:"bearer"`
+}
+
+// oauth2AccountStatus is one stored OAuth2 account in appStatus. Expired and
+// ExpiringSoon match the red and yellow markers of the text output.
+type oauth2AccountStatus struct {
+	Username     string    `json:"username"`
+	Default      bool      `json:"default"`
+	ExpiresAt    time.Time `json:"expires_at"`
+	Expired      bool      `json:"expired"`
+	ExpiringSoon bool      `json:"expiring_soon"`
+}
+
+// buildAuthStatus collects what `auth status` shows for every app of ts.
+// Accounts expiring within expiryWarning of now are flagged as expiring soon.
+func buildAuthStatus(ts *store.TokenStore, expiryWarning time.Duration, now time.Time) authStatus {
+	status := authStatus{
+		DefaultApp:       ts.GetDefaultApp(),
+		Apps:             []appStatus{},
+		ClockSkewSeconds: ts.ClockSkewSeconds,
+	}
+	for _, name := range ts.ListApps() {
+		app := ts.GetApp(name)
+		redirectURI, _, source := config.ResolveRedirectURI(name)
+		entry := appStatus{
+			Name:              name,
+			Default:           name == status.DefaultApp,
+			ClientID:          app.ClientID,
+			RedirectURI:       redirectURI,
+			RedirectURISource: source,
+			OAuth2:            []oauth2AccountStatus{},
+			OAuth1:            app.OAuth1Token != nil,
+			Bearer:            app.BearerToken != nil,
+		}
+		for _, username := range ts.GetOAuth2UsernamesForApp(name) {
+			account := oauth2AccountStatus{Username: username, Default: username == app.DefaultUser}
+			if token := ts.GetOAuth2TokenForApp(name, username); token != nil && token.OAuth2 != nil {
+				remaining := token.OAuth2.ExpiresIn(now)
+				account.ExpiresAt = time.Unix(int64(token.OAuth2.ExpirationTime), 0).UTC()
+				account.Expired = remaining <= 0
+				account.ExpiringSoon = remaining > 0 && remaining <= expiryWarning
+			}
+			entry.OAuth2 = append(entry.OAuth2, account)
+		}
+		status.Apps = append(status.Apps, entry)
+	}
+	return status
+}
+
+// oauth2ExpiryNote marks an OAuth2 token in `auth status`: red once its access
+// token has expired, yellow when it expires within threshold.
+func oauth2ExpiryNote(token *store.OAuth2Token, threshold time.Duration, now time.Time) string {
+	remaining := token.ExpiresIn(now)
+	switch {
+	case remaining <= 0:
+		return "  \033[31m(access token expired; refreshed on next use)\033[0m"
+	case remaining <= threshold:
+		return fmt.Sprintf("  \033[33m(expires in %s)\033[0m", auth.FormatRemaining(remaining))
+	default:
+		return ""
+	}
+}
+
+// ─── auth refresh ───────────────────────────────────────────────────
+
+func createAuthRefreshCmd(a *auth.Auth) *cobra.Command {
+	var all bool
+
+	cmd := &cobra.Command{
+		Use:   "refresh [USERNAME]",
+		Short: "Refresh stored OAuth2 tokens",
+		Long: `Exchange stored OAuth2 refresh tokens for new access tokens now, without
+making an API request. Give a USERNAME to refresh one account, or --all to
+refresh every OAuth2 account of the active app (or --app).
+
+Nothing here opens a browser or prompts, so it is safe to run from cron. A
+failure for one account does not stop the others; the command exits non-zero
+if any account failed.
+
+Examples:
+  xurl auth refresh alice
+  xurl auth refresh --all
+  xurl --app bots --non-interactive auth refresh --all`,
+		Args: cobra.MaximumNArgs(1),
+		Run: func(cmd *cobra.Command, args []string) {
+			var usernames []string
+			switch {
+			case all && len(args) > 0:
+				fmt.Fprintln(os.Stderr, "\033[31mError: give a USERNAME or --all, not both\033[0m")
+				os.Exit(1)
+			case all:
+				usernames = a.TokenStore.GetOAuth2UsernamesForApp(a.AppName())
+				if len(usernames) == 0 {
+					fmt.Println("No OAuth2 accounts stored for this app.")
+					return
+				}
+			case len(args) == 1:
+				usernames = args
+			default:
+				fmt.Fprintln(os.Stderr, "\033[31mError: give a USERNAME to refresh, or --all\033[0m")
+				os.Exit(1)
+			}
+
+			if !refreshOAuth2Accounts(os.Stdout, a, usernames, time.Now) {
+				os.Exit(1)
+			}
 		},
 	}
 
+	cmd.Flags().BoolVar(&all, "all", false, "Refresh every OAuth2 account of the app")
 	return cmd
 }
 
+// refreshOAuth2Accounts refreshes each account's OAuth2 token, writing one
+// line per account to w, and reports whether all of them succeeded.
+func refreshOAuth2Accounts(w io.Writer, a *auth.Auth, usernames []string, now func() time.Time) bool {
+	ok := true
+	for _, username := range usernames {
+		if _, err := a.ForceRefreshOAuth2Token(username); err != nil {
+			fmt.Fprintf(w, "\033[31m✗ %s: %s\033[0m\n", displayOAuth2Username(username), scrubbedError(err))
+			ok = false
+			continue
+		}
+		note := ""
+		if token := a.TokenStore.GetOAuth2TokenForApp(a.AppName(), username); token != nil && token.OAuth2 != nil && token.OAuth2.ExpirationTime != 0 {
+			note = fmt.Sprintf(" (expires in %s)", auth.FormatRemaining(token.OAuth2.ExpiresIn(now())))
+		}
+		fmt.Fprintf(w, "\033[32m✓ %s\033[0m%s\n", displayOAuth2Username(username), note)
+	}
+	return ok
+}
+
 // ─── auth clear ─────────────────────────────────────────────────────
 
 func createAuthClearCmd(a *auth.Auth) *cobra.Command {
@@ -399,12 +787,12 @@ Examples:
 			name := args[0]
 			err := a.TokenStore.AddApp(name, clientID, clientSecret)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			if redirectURI != "" {
 				if err := a.TokenStore.SetAppRedirectURI(name, redirectURI); err != nil {
-					fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+					printError(err)
 					os.Exit(1)
 				}
 			}
@@ -444,12 +832,12 @@ Examples:
 				os.Exit(1)
 			}
 			if err := a.TokenStore.UpdateApp(name, clientID, clientSecret); err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			if redirectURI != "" {
 				if err := a.TokenStore.SetAppRedirectURI(name, redirectURI); err != nil {
-					fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+					printError(err)
 					os.Exit(1)
 				}
 			}
@@ -473,7 +861,7 @@ func createAppRemoveCmd(a *auth.Auth) *cobra.Command {
 			name := args[0]
 			err := a.TokenStore.RemoveApp(name)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			fmt.Printf("\033[32mApp %q removed.\033[0m\n", name)
@@ -573,7 +961,7 @@ func createAppRedirectURISetCmd(a *auth.Auth) *cobra.Command {
 			name := args[0]
 			redirectURI := args[1]
 			if err := a.TokenStore.SetAppRedirectURI(name, redirectURI); err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			fmt.Printf("\033[32mRedirect URI set for app %q.\033[0m\n", name)
@@ -583,6 +971,42 @@ func createAppRedirectURISetCmd(a *auth.Auth) *cobra.Command {
 	return cmd
 }
 
+// ─── auth layout ────────────────────────────────────────────────────
+
+func createAuthLayoutCmd(a *auth.Auth) *cobra.Command {
+	cmd := &cobra.Command{
+		Use:   "layout [file|per-account]",
+		Short: "Show or change how OAuth2 tokens are stored on disk",
+		Long: `Show or change how OAuth2 tokens are stored on disk.
+
+file (the default) keeps every token in ~/.xurl/auth.yml. per-account gives
+each OAuth2 account a file of its own under ~/.xurl/tokens/<app>/, so that
+saving or refreshing one account's token rewrites only that file and a corrupt
+file only loses that account. Apps, OAuth1 and bearer tokens, and settings stay
+in auth.yml either way. Switching moves the existing tokens.
+
+Examples:
+  xurl auth layout                # show the current layout
+  xurl auth layout per-account    # one file per OAuth2 account
+  xurl auth layout file           # back to a single auth.yml`,
+		Args: cobra.MaximumNArgs(1),
+		Run: func(cmd *cobra.Command, args []string) {
+			ts := a.TokenStore
+			if len(args) == 0 {
+				fmt.Println(ts.GetTokenLayout())
+				return
+			}
+			if err := ts.SetTokenLayout(args[0]); err != nil {
+				printError(err)
+				os.Exit(1)
+			}
+			fmt.Printf("\033[32mToken layout set to %s.\033[0m\n", ts.GetTokenLayout())
+		},
+	}
+
+	return cmd
+}
+
 // ─── auth default ───────────────────────────────────────────────────
 
 func createDefaultCmd(a *auth.Auth) *cobra.Command {
@@ -607,7 +1031,7 @@ Examples:
 				// Non-interactive: set default app by name
 				appName := args[0]
 				if err := ts.SetDefaultApp(appName); err != nil {
-					fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+					printError(err)
 					os.Exit(1)
 				}
 				fmt.Printf("\033[32mDefault app set to %q\033[0m\n", appName)
@@ -615,7 +1039,7 @@ Examples:
 				if len(args) == 2 {
 					userName := args[1]
 					if err := ts.SetDefaultUser(appName, userName); err != nil {
-						fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+						printError(err)
 						os.Exit(1)
 					}
 					fmt.Printf("\033[32mDefault user set to %q\033[0m\n", userName)
@@ -623,6 +1047,11 @@ Examples:
 				return
 			}
 
+			if a.NonInteractive() {
+				exitWithError(xurlErrors.NewInteractionRequiredError("default_picker",
+					"pass APP_NAME [USERNAME] as arguments"))
+			}
+
 			// Interactive: pick app
 			apps := ts.ListApps()
 			if len(apps) == 0 {
@@ -632,7 +1061,7 @@ Examples:
 
 			appChoice, err := RunPicker("Select default app", apps)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			if appChoice == "" {
@@ -640,7 +1069,7 @@ Examples:
 			}
 
 			if err := ts.SetDefaultApp(appChoice); err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			fmt.Printf("\033[32mDefault app set to %q\033[0m\n", appChoice)
@@ -650,12 +1079,12 @@ Examples:
 			if len(users) > 0 {
 				userChoice, err := RunPicker("Select default OAuth2 user", users)
 				if err != nil {
-					fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+					printError(err)
 					os.Exit(1)
 				}
 				if userChoice != "" {
 					if err := ts.SetDefaultUser(appChoice, userChoice); err != nil {
-						fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+						printError(err)
 						os.Exit(1)
 					}
 					fmt.Printf("\033[32mDefault user set to %q\033[0m\n", userChoice)
@@ -1,11 +1,22 @@
 package cli
 
 import (
+	"bytes"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
 	"testing"
+	"time"
 
+	"github.com/spf13/cobra"
 	"github.com/stretchr/testify/assert"
 	"github.com/stretchr/testify/require"
 
+	"github.com/xdevplatform/xurl/api"
+	"github.com/xdevplatform/xurl/auth"
+	"github.com/xdevplatform/xurl/config"
 	"github.com/xdevplatform/xurl/store"
 )
 
@@ -84,3 +95,291 @@ func TestOAuth2NoAppCredentialWarning(t *testing.T) {
 		assert.Equal(t, "default", targetName)
 	})
 }
+
+// oauth1TestAuth returns an *auth.Auth backed by a temp store whose default app
+// already holds OAuth1 tokens with access token "old-token".
+func oauth1TestAuth(t *testing.T) *auth.Auth {
+	t.Helper()
+	ts := &store.TokenStore{
+		Apps:       map[string]*store.App{"default": {OAuth2Tokens: map[string]store.Token{}}},
+		DefaultApp: "default",
+		FilePath:   filepath.Join(t.TempDir(), ".xurl"),
+	}
+	require.NoError(t, ts.SaveOAuth1TokensForApp("default", "old-token", "old-secret", "old-ck", "old-cs"))
+	return auth.NewAuth(&config.Config{}).WithTokenStore(ts)
+}
+
+func TestSaveOAuth1Credentials(t *testing.T) {
+	newCreds := store.OAuth1Token{
+		AccessToken:    "new-token",
+		TokenSecret:    "new-secret",
+		ConsumerKey:    "new-ck",
+		ConsumerSecret: "new-cs",
+	}
+
+	t.Run("verified credentials are kept", func(t *testing.T) {
+		var gotAuth string
+		server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			assert.Equal(t, "/2/users/me", r.URL.Path)
+			gotAuth = r.Header.Get("Authorization")
+			w.Header().Set("Content-Type", "application/json")
+			w.Write([]byte(`{"data":{"id":"1","username":"alice"}}`))
+		}))
+		defer server.Close()
+
+		a := oauth1TestAuth(t)
+		client := api.NewApiClient(&config.Config{APIBaseURL: server.URL}, a)
+
+		username, verifyErr, err := saveOAuth1Credentials(a, newCreds, client, false)
+		require.NoError(t, err)
+		require.NoError(t, verifyErr)
+		assert.Equal(t, "alice", username)
+		assert.Contains(t, gotAuth, `oauth_token="new-token"`)
+		assert.Equal(t, "new-token", a.TokenStore.GetOAuth1TokensForApp("default").OAuth1.AccessToken)
+	})
+
+	t.Run("rejected credentials are rolled back", func(t *testing.T) {
+		server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			w.Header().Set("Content-Type", "application/json")
+			w.WriteHeader(http.StatusUnauthorized)
+			w.Write([]byte(`{"title":"Unauthorized","status":401}`))
+		}))
+		defer server.Close()
+
+		a := oauth1TestAuth(t)
+		client := api.NewApiClient(&config.Config{APIBaseURL: server.URL}, a)
+
+		_, verifyErr, err := saveOAuth1Credentials(a, newCreds, client, false)
+		require.NoError(t, err)
+		require.Error(t, verifyErr)
+		assert.Equal(t, "old-token", a.TokenStore.GetOAuth1TokensForApp("default").OAuth1.AccessToken)
+
+		data, err := os.ReadFile(a.TokenStore.FilePath)
+		require.NoError(t, err)
+		assert.NotContains(t, string(data), "new-token")
+	})
+
+	t.Run("force keeps rejected credentials", func(t *testing.T) {
+		server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			w.WriteHeader(http.StatusUnauthorized)
+			w.Write([]byte(`{"title":"Unauthorized","status":401}`))
+		}))
+		defer server.Close()
+
+		a := oauth1TestAuth(t)
+		client := api.NewApiClient(&config.Config{APIBaseURL: server.URL}, a)
+
+		_, verifyErr, err := saveOAuth1Credentials(a, newCreds, client, true)
+		require.NoError(t, err)
+		require.Error(t, verifyErr)
+		assert.Equal(t, "new-token", a.TokenStore.GetOAuth1TokensForApp("default").OAuth1.AccessToken)
+	})
+
+	t.Run("no-verify saves without a request", func(t *testing.T) {
+		a := oauth1TestAuth(t)
+
+		username, verifyErr, err := saveOAuth1Credentials(a, newCreds, nil, false)
+		require.NoError(t, err)
+		require.NoError(t, verifyErr)
+		assert.Empty(t, username)
+		assert.Equal(t, "new-token", a.TokenStore.GetOAuth1TokensForApp("default").OAuth1.AccessToken)
+	})
+}
+
+func TestSaveBearerToken(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.Header.Get("Authorization") != "Bearer good-token" {
+			w.WriteHeader(http.StatusUnauthorized)
+			return
+		}
+		// A valid app-only token cannot act as a user.
+		w.WriteHeader(http.StatusForbidden)
+	}))
+	defer server.Close()
+
+	bearerAuth := func(t *testing.T) *auth.Auth {
+		a := oauth1TestAuth(t).WithInfoURL(server.URL)
+		require.NoError(t, a.TokenStore.SaveBearerTokenForApp("default", "old-token"))
+		return a
+	}
+	bearer := func(a *auth.Auth) string {
+		return a.TokenStore.GetBearerTokenForApp("default").Bearer
+	}
+
+	t.Run("a verified token is kept", func(t *testing.T) {
+		a := bearerAuth(t)
+		verifyErr, err := saveBearerToken(a, "good-token", true)
+		require.NoError(t, err)
+		require.NoError(t, verifyErr)
+		assert.Equal(t, "good-token", bearer(a))
+	})
+
+	t.Run("a rejected token is rolled back", func(t *testing.T) {
+		a := bearerAuth(t)
+		verifyErr, err := saveBearerToken(a, "bad-token", true)
+		require.NoError(t, err)
+		require.Error(t, verifyErr)
+		assert.Equal(t, "old-token", bearer(a))
+
+		data, err := os.ReadFile(a.TokenStore.FilePath)
+		require.NoError(t, err)
+		assert.NotContains(t, string(data), "bad-token")
+	})
+
+	t.Run("no-verify saves without a request", func(t *testing.T) {
+		a := bearerAuth(t)
+		verifyErr, err := saveBearerToken(a, "bad-token", false)
+		require.NoError(t, err)
+		require.NoError(t, verifyErr)
+		assert.Equal(t, "bad-token", bearer(a))
+	})
+}
+
+func TestOAuth2ExpiryNote(t *testing.T) {
+	now := time.Unix(1_700_000_000, 0)
+	threshold := 10 * time.Minute
+
+	expired := oauth2ExpiryNote(&store.OAuth2Token{ExpirationTime: 1_699_999_000}, threshold, now)
+	assert.Contains(t, expired, "\033[31m")
+	assert.Contains(t, expired, "expired")
+
+	soon := oauth2ExpiryNote(&store.OAuth2Token{ExpirationTime: 1_700_000_180}, threshold, now)
+	assert.Contains(t, soon, "\033[33m")
+	assert.Contains(t, soon, "expires in 3 minutes")
+
+	assert.Empty(t, oauth2ExpiryNote(&store.OAuth2Token{ExpirationTime: 1_700_003_600}, threshold, now))
+}
+
+func TestBuildAuthStatus(t *testing.T) {
+	t.Setenv("HOME", t.TempDir())
+	t.Setenv("REDIRECT_URI", "")
+	os.Unsetenv("REDIRECT_URI")
+
+	now := time.Unix(1_700_000_000, 0)
+	ts := &store.TokenStore{
+		Apps: map[string]*store.App{
+			"default": {ClientID: "cid", ClientSecret: "csecret", DefaultUser: "bob", OAuth2Tokens: map[string]store.Token{}},
+			"other":   {OAuth2Tokens: map[string]store.Token{}},
+		},
+		DefaultApp:       "default",
+		ClockSkewSeconds: -3,
+		FilePath:         filepath.Join(t.TempDir(), ".xurl"),
+	}
+	require.NoError(t, ts.SaveOAuth2TokenForApp("default", "alice", "a1", "r1", 1_699_999_000))
+	require.NoError(t, ts.SaveOAuth2TokenForApp("default", "bob", "b1", "r2", 1_700_000_180))
+	require.NoError(t, ts.SaveOAuth2TokenForApp("default", "carol", "c1", "r3", 1_700_003_600))
+	require.NoError(t, ts.SaveBearerTokenForApp("default", "bearer"))
+
+	status := buildAuthStatus(ts, 10*time.Minute, now)
+	assert.Equal(t, "default", status.DefaultApp)
+	assert.Equal(t, int64(-3), status.ClockSkewSeconds)
+	require.Len(t, status.Apps, 2)
+
+	app := status.Apps[0]
+	assert.Equal(t, "default", app.Name)
+	assert.True(t, app.Default)
+	assert.Equal(t, "cid", app.ClientID)
+	assert.Equal(t, config.DefaultRedirectURI, app.RedirectURI)
+	assert.True(t, app.Bearer)
+	assert.False(t, app.OAuth1)
+	assert.Equal(t, []oauth2AccountStatus{
+		{Username: "alice", ExpiresAt: time.Unix(1_699_999_000, 0).UTC(), Expired: true},
+		{Username: "bob", Default: true, ExpiresAt: time.Unix(1_700_000_180, 0).UTC(), ExpiringSoon: true},
+		{Username: "carol", ExpiresAt: time.Unix(1_700_003_600, 0).UTC()},
+	}, app.OAuth2)
+
+	assert.Equal(t, "other", status.Apps[1].Name)
+	assert.False(t, status.Apps[1].Default)
+	assert.Empty(t, status.Apps[1].OAuth2)
+
+	out, err := json.Marshal(status)
+	require.NoError(t, err)
+	assert.Contains(t, string(out), `"expires_at":"2023-11-14T21:56:40Z"`)
+	assert.Contains(t, string(out), `"oauth2":[]`, "an app without accounts lists none rather than null")
+	assert.NotContains(t, string(out), "csecret")
+	assert.NotContains(t, string(out), `"a1"`)
+}
+
+func TestRefreshOAuth2Accounts(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		require.NoError(t, r.ParseForm())
+		if r.PostForm.Get("refresh_token") == "revoked" {
+			w.Header().Set("Content-Type", "application/json")
+			w.WriteHeader(http.StatusBadRequest)
+			w.Write([]byte(`{"error":"invalid_grant"}`))
+			return
+		}
+		w.Header().Set("Content-Type", "application/json")
+		w.Write([]byte(`{"access_token":"fresh","token_type":"bearer","expires_in":7200,"refresh_token":"next"}`))
+	}))
+	defer server.Close()
+
+	ts := &store.TokenStore{
+		Apps:       map[string]*store.App{"default": {ClientID: "cid", OAuth2Tokens: map[string]store.Token{}}},
+		DefaultApp: "default",
+		FilePath:   filepath.Join(t.TempDir(), ".xurl"),
+	}
+	future := uint64(time.Now().Add(time.Hour).Unix())
+	require.NoError(t, ts.SaveOAuth2TokenForApp("default", "alice", "a1", "good", future))
+	require.NoError(t, ts.SaveOAuth2TokenForApp("default", "bob", "b1", "revoked", future))
+	require.NoError(t, ts.SaveOAuth2TokenForApp("default", "carol", "c1", "good", future))
+	a := auth.NewAuth(&config.Config{TokenURL: server.URL, NonInteractive: true}).WithTokenStore(ts)
+
+	var out bytes.Buffer
+	now := time.Now()
+	ok := refreshOAuth2Accounts(&out, a, ts.GetOAuth2UsernamesForApp(""), func() time.Time { return now })
+	assert.False(t, ok, "bob's failure must be reported")
+
+	assert.Contains(t, out.String(), "✓ alice")
+	assert.Contains(t, out.String(), "✗ bob")
+	assert.Contains(t, out.String(), "✓ carol", "a failure must not stop the remaining accounts")
+	assert.Equal(t, "fresh", ts.GetOAuth2TokenForApp("default", "carol").OAuth2.AccessToken)
+	assert.Equal(t, "b1", ts.GetOAuth2TokenForApp("default", "bob").OAuth2.AccessToken)
+}
+
+func TestSelectOAuth2Account(t *testing.T) {
+	ts := &store.TokenStore{
+		Apps:       map[string]*store.App{"default": {ClientID: "cid", OAuth2Tokens: map[string]store.Token{}}},
+		DefaultApp: "default",
+		FilePath:   filepath.Join(t.TempDir(), ".xurl"),
+	}
+	future := uint64(time.Now().Add(time.Hour).Unix())
+	require.NoError(t, ts.SaveOAuth2TokenForApp("default", "bob", "b1", "r", future))
+	require.NoError(t, ts.SaveOAuth2TokenForApp("default", "alice", "a1", "r", future))
+	a := auth.NewAuth(&config.Config{}).WithTokenStore(ts)
+
+	var offered []string
+	pickAccountFunc = func(title string, items []string) (string, error) {
+		offered = items
+		return "bob", nil
+	}
+	defer func() { pickAccountFunc = RunPicker }()
+
+	run := func(args ...string) string {
+		cmd := &cobra.Command{Use: "xurl"}
+		cmd.Flags().String("auth", "", "")
+		cmd.Flags().StringP("username", "u", "", "")
+		cmd.Flags().Bool("interactive-auth-select", false, "")
+		cmd.Flags().StringArrayP("header", "H", nil, "")
+		require.NoError(t, cmd.ParseFlags(args))
+		offered = nil
+		require.NoError(t, selectOAuth2Account(cmd, a))
+		username, _ := cmd.Flags().GetString("username")
+		return username
+	}
+
+	assert.Equal(t, "bob", run("--auth", "oauth2", "--interactive-auth-select"))
+	assert.Equal(t, []string{"alice", "bob"}, offered)
+	assert.Equal(t, "bob", run("--auth", "oauth2,app", "--interactive-auth-select"))
+
+	assert.Equal(t, "", run("--auth", "oauth2", "--interactive-auth-select=false"), "prompting can be turned off")
+	assert.Equal(t, "alice", run("--auth", "oauth2", "-u", "alice", "--interactive-auth-select"))
+	assert.Equal(t, "", run("--auth", "app", "--interactive-auth-select"))
+	assert.Nil(t, offered)
+	assert.Equal(t, "", run("--auth", "oauth2", "--interactive-auth-select", "-H", "authorization: Bearer mine"), "a passed-through Authorization uses no account")
+	assert.Nil(t, offered)
+
+	require.NoError(t, ts.SetDefaultUser("default", "alice"))
+	assert.Equal(t, "", run("--auth", "oauth2", "--interactive-auth-select"), "a default user makes the choice unambiguous")
+	assert.Nil(t, offered)
+}
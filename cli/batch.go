@@ -0,0 +1,130 @@
+package cli
+
+import (
+	"fmt"
+	"os"
+
+	"github.com/spf13/cobra"
+
+	"github.com/xdevplatform/xurl/api"
+	"github.com/xdevplatform/xurl/auth"
+	"github.com/xdevplatform/xurl/config"
+	"github.com/xdevplatform/xurl/store"
+)
+
+// CreateBatchCommand creates the batch command
+func CreateBatchCommand(cfg *config.Config, a *auth.Auth) *cobra.Command {
+	cmd := &cobra.Command{
+		Use:   "batch [flags] FILE.jsonl",
+		Short: "Send the requests listed in a JSONL file",
+		Long: `Send the requests of a JSONL file, one request per line:
+
+  {"method": "POST", "url": "/2/tweets", "data": {"text": "hello"}, "headers": {"X-Name": "value"}}
+
+Only "url" is required. "data" is sent as JSON, or as is when it is a string;
+the method defaults to POST when there is data and GET otherwise. "headers" may
+also be a list of "Name: value" strings, and replace -H headers of the same name.
+
+One line per request is printed to stdout, {"index", "status", "body"}, where
+index is the request's line number; a summary goes to stderr. A malformed line
+is reported and skipped, or with --strict fails the batch before anything is
+sent. xurl exits with status 1 if any request failed.
+
+The summary ends with a JSON line of counts by status class, the duration, p50
+and p95 latency, rate-limit pauses, and the line numbers of failed requests;
+--summary-file writes it to a file instead. --failed-output writes the lines
+of the failed requests to a file, ready to be run again as a batch.
+
+A failed request does not stop the batch unless --fail-fast is given; then no
+more requests are sent, and --failed-output also gets the lines never sent.
+An auth failure, such as an expired token that could not be refreshed, always
+stops the batch, since every later request would fail too.
+
+Requests are sent one at a time unless --concurrency allows more in flight.
+Results are still printed in file order, unless --unordered prints each as
+soon as it completes. A 429 pauses every worker before the request is retried.
+
+--output-template writes each response body to its own file instead, named
+from {index}, {status}, {response.FIELD}, {query.NAME}, and the keys of a
+line's "vars" object, such as {"url": "/2/users/123", "vars": {"id": "123"}}.
+Directories are created as needed; two requests naming the same file fail.
+
+Examples:
+  xurl batch requests.jsonl
+  xurl batch --strict --auth oauth2 -u alice requests.jsonl > results.jsonl
+  xurl batch --concurrency 8 --unordered lookups.jsonl
+  xurl batch --output-template 'out/{id}.json' lookups.jsonl
+  xurl batch --summary-file summary.json --failed-output retry.jsonl requests.jsonl`,
+		Args: cobra.ExactArgs(1),
+		Run: func(cmd *cobra.Command, args []string) {
+			authType, _ := cmd.Flags().GetString("auth")
+			username, _ := cmd.Flags().GetString("username")
+			verbose, verboseBody := verbosity(cmd)
+			trace, _ := cmd.Flags().GetBool("trace")
+			strict, _ := cmd.Flags().GetBool("strict")
+			concurrency, _ := cmd.Flags().GetInt("concurrency")
+			unordered, _ := cmd.Flags().GetBool("unordered")
+			outputTemplate, _ := cmd.Flags().GetString("output-template")
+			summaryFile, _ := cmd.Flags().GetString("summary-file")
+			failedOutput, _ := cmd.Flags().GetString("failed-output")
+			retries, _ := cmd.Flags().GetInt("retry")
+			retryMaxTime, _ := cmd.Flags().GetDuration("retry-max-time")
+			retryBudgetSize, _ := cmd.Flags().GetInt("retry-budget")
+			forceRetry, _ := cmd.Flags().GetBool("force")
+			onFailure := failurePolicy(cmd)
+			respectRateLimit, _ := cmd.Flags().GetBool("respect-rate-limit")
+			headers := headerArgs(cmd)
+			client := readClientFlags(cmd).apply(api.NewApiClient(cfg, a).WithRateLimitStore(store.NewRateLimitStore()))
+			warnOAuth2Expiry(a, authType, username, headers)
+			var retryBudget *api.RetryBudget
+			if retryBudgetSize > 0 {
+				retryBudget = api.NewRetryBudget(retryBudgetSize)
+			}
+
+			err := api.ExecuteBatch(api.RequestOptions{
+				Headers:          headers,
+				AuthType:         authType,
+				Username:         username,
+				Verbose:          verbose,
+				VerboseBody:      verboseBody,
+				Trace:            trace,
+				RespectRateLimit: respectRateLimit,
+				Retry:            api.RetryPolicy{MaxRetries: retries, MaxTime: retryMaxTime, Budget: retryBudget},
+				ForceRetry:       forceRetry,
+			}, args[0], api.BatchOptions{
+				Strict:         strict,
+				Concurrency:    concurrency,
+				Unordered:      unordered,
+				OutputTemplate: outputTemplate,
+				SummaryFile:    summaryFile,
+				FailedOutput:   failedOutput,
+				OnFailure:      onFailure,
+			}, client, os.Stdout, os.Stderr)
+			if err != nil {
+				fmt.Fprintf(os.Stderr, "\033[31m%s\033[0m\n", scrubbedError(err))
+				os.Exit(1)
+			}
+		},
+	}
+
+	cmd.Flags().Bool("strict", false, "Fail without sending anything if any line is malformed")
+	cmd.Flags().Int("concurrency", 1, "Maximum number of requests in flight")
+	cmd.Flags().Bool("unordered", false, "Print each result as soon as its request completes instead of in file order")
+	cmd.Flags().Int("retry", api.DefaultBatchRetries, "Retry a rate-limited (HTTP 429) request up to N times, waiting for the rate-limit window to reset")
+	cmd.Flags().Duration("retry-max-time", 0, "Give up retrying a request rather than wait more than this in total (e.g. 2m; 0 for no limit)")
+	cmd.Flags().Int("retry-budget", 0, "Total retries of rate-limited requests allowed across the batch (0 for no limit)")
+	cmd.Flags().Bool("force", false, "Retry POST and PATCH requests even without an idempotency key")
+	cmd.Flags().String("output-template", "", "Write each response to a file named by this template, e.g. out/{id}.json with {id} from the line's \"vars\"")
+	cmd.Flags().Bool("fail-fast", false, "Stop sending at the first failed request; requests in flight still complete")
+	cmd.Flags().Bool("continue-on-error", false, "Record a failed request and go on with the rest (the default)")
+	cmd.Flags().String("summary-file", "", "Write the JSON summary of the batch to this file instead of stderr")
+	cmd.Flags().String("failed-output", "", "Write the lines of the failed requests to this file, to run again")
+	cmd.Flags().String("auth", "", "Authentication type (oauth1, oauth2, or app), or a fallback order such as oauth2,oauth1,app")
+	cmd.Flags().StringP("username", "u", "", "Username for OAuth2 authentication")
+	cmd.Flags().CountP("verbose", "v", "Print request/response headers; -vv also logs the bodies to stderr")
+	cmd.Flags().Bool("verbose-body", false, "Same as -vv: also log request/response bodies (secrets redacted) to stderr")
+	cmd.Flags().BoolP("trace", "t", false, "Add trace header to request")
+	cmd.Flags().StringArrayP("header", "H", []string{}, "Request header \"Name: value\", or @file with one per line, sent with every request (repeatable)")
+
+	return cmd
+}
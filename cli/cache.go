@@ -0,0 +1,55 @@
+package cli
+
+import (
+	"fmt"
+
+	"github.com/spf13/cobra"
+
+	"github.com/xdevplatform/xurl/api"
+	"github.com/xdevplatform/xurl/store"
+)
+
+// defaultCacheDirArg is the value of a bare --cache: the cache directory in
+// ~/.xurl.
+const defaultCacheDirArg = "~/.xurl/cache"
+
+// cacheDir resolves a --cache value to a directory.
+func cacheDir(arg string) string {
+	if arg == defaultCacheDirArg {
+		return store.CacheDirPath()
+	}
+	return arg
+}
+
+// CreateCacheCommand creates the cache command
+func CreateCacheCommand() *cobra.Command {
+	cmd := &cobra.Command{
+		Use:   "cache",
+		Short: "Manage the response cache of --cache",
+	}
+
+	cmd.AddCommand(&cobra.Command{
+		Use:   "clear [DIR]",
+		Short: "Remove every cached response",
+		Long: `Remove every response cached with --cache, from ~/.xurl/cache or from DIR
+for a cache kept with --cache=DIR.
+
+Examples:
+  xurl cache clear
+  xurl cache clear ./cache`,
+		Args: cobra.MaximumNArgs(1),
+		Run: func(cmd *cobra.Command, args []string) {
+			dir := cacheDir(defaultCacheDirArg)
+			if len(args) > 0 {
+				dir = args[0]
+			}
+			cleared, err := api.NewResponseCache(dir, api.DefaultCacheMaxAge).Clear()
+			if err != nil {
+				exitWithError(err)
+			}
+			fmt.Printf("Removed %d cached responses from %s\n", cleared, dir)
+		},
+	})
+
+	return cmd
+}
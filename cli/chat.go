@@ -23,6 +23,7 @@ import (
 	"github.com/xdevplatform/chat-xdk/go/chatxdk"
 	"github.com/xdevplatform/xurl/api"
 	"github.com/xdevplatform/xurl/auth"
+	"github.com/xdevplatform/xurl/config"
 	"github.com/xdevplatform/xurl/store"
 	"github.com/xdevplatform/xurl/utils"
 )
@@ -32,7 +33,7 @@ const chatSupported = true
 
 // CreateChatCommand creates the `chat` command family: an end-to-end
 // encrypted XChat client backed by the chat-xdk crypto binding.
-func CreateChatCommand(a *auth.Auth) *cobra.Command {
+func CreateChatCommand(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	chatCmd := &cobra.Command{
 		Use:   "chat",
 		Short: "Send and read end-to-end encrypted XChat messages",
@@ -56,21 +57,21 @@ dm.read and dm.write scopes (run 'xurl auth oauth2' first).`,
 	}
 
 	chatCmd.AddCommand(
-		createChatKeysCommand(a),
-		chatConversationsCmd(a),
-		chatReadCmd(a),
-		chatSendCmd(a),
-		chatListenCmd(a),
-		chatRotateCmd(a),
-		chatDownloadCmd(a),
-		chatMembersCmd(a),
-		chatMarkReadCmd(a),
-		chatTypingCmd(a),
+		createChatKeysCommand(cfg, a),
+		chatConversationsCmd(cfg, a),
+		chatReadCmd(cfg, a),
+		chatSendCmd(cfg, a),
+		chatListenCmd(cfg, a),
+		chatRotateCmd(cfg, a),
+		chatDownloadCmd(cfg, a),
+		chatMembersCmd(cfg, a),
+		chatMarkReadCmd(cfg, a),
+		chatTypingCmd(cfg, a),
 	)
 	return chatCmd
 }
 
-func chatDownloadCmd(a *auth.Auth) *cobra.Command {
+func chatDownloadCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "download CONVERSATION|@USERNAME MEDIA_HASH_KEY",
 		Short: "Download and decrypt a chat media attachment",
@@ -81,7 +82,7 @@ version that was active when the message was sent.`,
 		Args: cobra.ExactArgs(2),
 		Run: func(cmd *cobra.Command, args []string) {
 			out, _ := cmd.Flags().GetString("output")
-			s, err := newChatSession(a, cmd, true)
+			s, err := newChatSession(cfg, a, cmd, true)
 			exitOnError(err)
 			defer s.Close()
 			convID, err := s.resolveConversation(args[0])
@@ -94,7 +95,7 @@ version that was active when the message was sent.`,
 	return cmd
 }
 
-func chatMembersCmd(a *auth.Auth) *cobra.Command {
+func chatMembersCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "add-members GROUP @USER [@USER...]",
 		Short: "Add members to a group conversation",
@@ -105,7 +106,7 @@ sent before they were added.`,
 		Args: cobra.MinimumNArgs(2),
 		Run: func(cmd *cobra.Command, args []string) {
 			yes, _ := cmd.Flags().GetBool("yes")
-			s, err := newChatSession(a, cmd, true)
+			s, err := newChatSession(cfg, a, cmd, true)
 			exitOnError(err)
 			defer s.Close()
 			convID, err := s.resolveConversation(args[0])
@@ -121,13 +122,13 @@ sent before they were added.`,
 	return cmd
 }
 
-func chatMarkReadCmd(a *auth.Auth) *cobra.Command {
+func chatMarkReadCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "mark-read CONVERSATION|@USERNAME",
 		Short: "Mark a conversation read up to its latest message",
 		Args:  cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			s, err := newChatSession(a, cmd, false)
+			s, err := newChatSession(cfg, a, cmd, false)
 			exitOnError(err)
 			defer s.Close()
 			convID, err := s.resolveConversation(args[0])
@@ -139,13 +140,13 @@ func chatMarkReadCmd(a *auth.Auth) *cobra.Command {
 	return cmd
 }
 
-func chatTypingCmd(a *auth.Auth) *cobra.Command {
+func chatTypingCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "typing CONVERSATION|@USERNAME",
 		Short: "Send a typing indicator to a conversation",
 		Args:  cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			s, err := newChatSession(a, cmd, false)
+			s, err := newChatSession(cfg, a, cmd, false)
 			exitOnError(err)
 			defer s.Close()
 			convID, err := s.resolveConversation(args[0])
@@ -159,7 +160,7 @@ func chatTypingCmd(a *auth.Auth) *cobra.Command {
 	return cmd
 }
 
-func chatRotateCmd(a *auth.Auth) *cobra.Command {
+func chatRotateCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "rotate CONVERSATION|@USERNAME",
 		Short: "Rotate a conversation's encryption key",
@@ -174,7 +175,7 @@ under it, and members without the old versions still cannot read old history.`,
 		Args: cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
 			yes, _ := cmd.Flags().GetBool("yes")
-			s, err := newChatSession(a, cmd, true)
+			s, err := newChatSession(cfg, a, cmd, true)
 			exitOnError(err)
 			defer s.Close()
 			convID, err := s.resolveConversation(args[0])
@@ -191,7 +192,7 @@ under it, and members without the old versions still cannot read old history.`,
 // chat keys
 // -----------------------------------------------------------------
 
-func createChatKeysCommand(a *auth.Auth) *cobra.Command {
+func createChatKeysCommand(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	keysCmd := &cobra.Command{
 		Use:   "keys",
 		Short: "Manage XChat encryption keys",
@@ -200,17 +201,17 @@ func createChatKeysCommand(a *auth.Auth) *cobra.Command {
 xurl never generates or registers keys — it only fetches keys that already
 exist for the account (registered by another XChat client).`,
 	}
-	keysCmd.AddCommand(chatKeysStatusCmd(a), chatKeysRestoreCmd(a), chatKeysImportCmd(a))
+	keysCmd.AddCommand(chatKeysStatusCmd(cfg, a), chatKeysRestoreCmd(cfg, a), chatKeysImportCmd(cfg, a))
 	return keysCmd
 }
 
-func chatKeysStatusCmd(a *auth.Auth) *cobra.Command {
+func chatKeysStatusCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "status",
 		Short: "Show local and registered XChat key status",
 		Args:  cobra.NoArgs,
 		Run: func(cmd *cobra.Command, args []string) {
-			s, err := newChatSession(a, cmd, false)
+			s, err := newChatSession(cfg, a, cmd, false)
 			exitOnError(err)
 			defer s.Close()
 			exitOnError(s.keyStatus())
@@ -220,7 +221,7 @@ func chatKeysStatusCmd(a *auth.Auth) *cobra.Command {
 	return cmd
 }
 
-func chatKeysRestoreCmd(a *auth.Auth) *cobra.Command {
+func chatKeysRestoreCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "restore",
 		Short: "Recover XChat keys from Juicebox onto this machine",
@@ -231,7 +232,7 @@ read-only recovery — xurl never writes to Juicebox.`,
 		Args: cobra.NoArgs,
 		Run: func(cmd *cobra.Command, args []string) {
 			pin, _ := cmd.Flags().GetString("pin")
-			s, err := newChatSession(a, cmd, false)
+			s, err := newChatSession(cfg, a, cmd, false)
 			exitOnError(err)
 			defer s.Close()
 			exitOnError(s.restoreKeys(pin))
@@ -242,7 +243,7 @@ read-only recovery — xurl never writes to Juicebox.`,
 	return cmd
 }
 
-func chatKeysImportCmd(a *auth.Auth) *cobra.Command {
+func chatKeysImportCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "import [PRIVATE_KEYS_B64]",
 		Short: "Import an exported XChat private-key blob",
@@ -259,7 +260,7 @@ is rejected.`,
 			if len(args) == 1 {
 				blob = args[0]
 			}
-			s, err := newChatSession(a, cmd, false)
+			s, err := newChatSession(cfg, a, cmd, false)
 			exitOnError(err)
 			defer s.Close()
 			exitOnError(s.importKeys(blob))
@@ -273,7 +274,7 @@ is rejected.`,
 // chat conversations / read / send / listen
 // -----------------------------------------------------------------
 
-func chatConversationsCmd(a *auth.Auth) *cobra.Command {
+func chatConversationsCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "conversations",
 		Short: "List your XChat inbox",
@@ -281,7 +282,7 @@ func chatConversationsCmd(a *auth.Auth) *cobra.Command {
 		Run: func(cmd *cobra.Command, args []string) {
 			maxResults, _ := cmd.Flags().GetInt("max-results")
 			asJSON, _ := cmd.Flags().GetBool("json")
-			s, err := newChatSession(a, cmd, false)
+			s, err := newChatSession(cfg, a, cmd, false)
 			exitOnError(err)
 			defer s.Close()
 			exitOnError(s.listConversations(maxResults, asJSON))
@@ -293,7 +294,7 @@ func chatConversationsCmd(a *auth.Auth) *cobra.Command {
 	return cmd
 }
 
-func chatReadCmd(a *auth.Auth) *cobra.Command {
+func chatReadCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "read CONVERSATION|@USERNAME",
 		Short: "Read decrypted messages from a conversation",
@@ -302,7 +303,7 @@ func chatReadCmd(a *auth.Auth) *cobra.Command {
 			maxResults, _ := cmd.Flags().GetInt("max-results")
 			asJSON, _ := cmd.Flags().GetBool("json")
 			noMarkRead, _ := cmd.Flags().GetBool("no-mark-read")
-			s, err := newChatSession(a, cmd, true)
+			s, err := newChatSession(cfg, a, cmd, true)
 			exitOnError(err)
 			defer s.Close()
 			convID, err := s.resolveConversation(args[0])
@@ -317,7 +318,7 @@ func chatReadCmd(a *auth.Auth) *cobra.Command {
 	return cmd
 }
 
-func chatSendCmd(a *auth.Auth) *cobra.Command {
+func chatSendCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "send CONVERSATION|@USERNAME \"TEXT\"",
 		Short: "Send an encrypted message",
@@ -337,7 +338,7 @@ participants automatically.`,
 			if text == "" && file == "" {
 				exitOnError(fmt.Errorf("provide message text, --file, or both"))
 			}
-			s, err := newChatSession(a, cmd, true)
+			s, err := newChatSession(cfg, a, cmd, true)
 			exitOnError(err)
 			defer s.Close()
 			convID, err := s.resolveConversation(args[0])
@@ -353,7 +354,7 @@ participants automatically.`,
 	return cmd
 }
 
-func chatListenCmd(a *auth.Auth) *cobra.Command {
+func chatListenCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "listen CONVERSATION|@USERNAME",
 		Short: "Print new messages as they arrive (Ctrl-C to stop)",
@@ -361,7 +362,7 @@ func chatListenCmd(a *auth.Auth) *cobra.Command {
 		Run: func(cmd *cobra.Command, args []string) {
 			interval, _ := cmd.Flags().GetInt("interval")
 			noMarkRead, _ := cmd.Flags().GetBool("no-mark-read")
-			s, err := newChatSession(a, cmd, true)
+			s, err := newChatSession(cfg, a, cmd, true)
 			exitOnError(err)
 			defer s.Close()
 			convID, err := s.resolveConversation(args[0])
@@ -403,8 +404,8 @@ type chatSession struct {
 // newChatSession resolves the authenticated user and creates a chat-xdk
 // instance. When requireKeys is true the local private keys are imported and
 // the session identity is set, erroring if no keys exist yet.
-func newChatSession(a *auth.Auth, cmd *cobra.Command, requireKeys bool) (*chatSession, error) {
-	client := newClient(a)
+func newChatSession(cfg *config.Config, a *auth.Auth, cmd *cobra.Command, requireKeys bool) (*chatSession, error) {
+	client := newClient(cmd, cfg, a)
 	opts := baseOpts(cmd)
 
 	userID, err := resolveMyUserID(client, opts)
@@ -1934,7 +1935,7 @@ func promptSecret(prompt string) (string, error) {
 // exitOnError prints an error to stderr and exits non-zero.
 func exitOnError(err error) {
 	if err != nil {
-		fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+		printError(err)
 		os.Exit(1)
 	}
 }
@@ -9,6 +9,7 @@ import (
 	"github.com/spf13/cobra"
 
 	"github.com/xdevplatform/xurl/auth"
+	"github.com/xdevplatform/xurl/config"
 )
 
 // chatSupported reports whether this build includes the XChat client.
@@ -17,7 +18,7 @@ const chatSupported = false
 // CreateChatCommand returns a stub on platforms where the chat-xdk crypto
 // binding is unavailable (it requires cgo and prebuilt static libraries for
 // darwin/amd64, darwin/arm64, or linux/amd64).
-func CreateChatCommand(a *auth.Auth) *cobra.Command {
+func CreateChatCommand(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "chat",
 		Short: "Send and read end-to-end encrypted XChat messages (unavailable in this build)",
@@ -0,0 +1,62 @@
+package cli
+
+import (
+	"fmt"
+	"io"
+	"os"
+
+	"github.com/spf13/cobra"
+
+	"github.com/xdevplatform/xurl/auth"
+	"github.com/xdevplatform/xurl/config"
+)
+
+// CreateConfigCommand creates the config command
+func CreateConfigCommand(cfg *config.Config, a *auth.Auth) *cobra.Command {
+	cmd := &cobra.Command{
+		Use:   "config",
+		Short: "Inspect xurl's configuration",
+	}
+
+	cmd.AddCommand(&cobra.Command{
+		Use:   "show",
+		Short: "Print the effective configuration and where each value comes from",
+		Long: `Print the configuration in effect for the active app (or --app): the OAuth2
+client, redirect URI, endpoint URLs, and token timing settings, each with its
+source (environment variable, app config, or built-in default). The client
+secret is redacted.
+
+Examples:
+  xurl config show
+  xurl --app my-app config show`,
+		Args: cobra.NoArgs,
+		Run: func(cmd *cobra.Command, args []string) {
+			var baseURL string
+			if flag, _ := cmd.Flags().GetString("base-url"); flag != "" {
+				baseURL = cfg.APIBaseURL
+			}
+			printSettings(os.Stdout, config.EffectiveSettings(a.AppName(), baseURL))
+		},
+	})
+
+	return cmd
+}
+
+// printSettings writes settings as aligned "name  value  [source]" lines.
+func printSettings(w io.Writer, settings []config.Setting) {
+	nameWidth, valueWidth := 0, 0
+	for _, s := range settings {
+		nameWidth = max(nameWidth, len(s.Name))
+		valueWidth = max(valueWidth, len(displaySettingValue(s.Value)))
+	}
+	for _, s := range settings {
+		fmt.Fprintf(w, "%-*s  %-*s  [%s]\n", nameWidth, s.Name, valueWidth, displaySettingValue(s.Value), s.Source)
+	}
+}
+
+func displaySettingValue(value string) string {
+	if value == "" {
+		return "(unset)"
+	}
+	return value
+}
@@ -0,0 +1,231 @@
+package cli
+
+import (
+	"encoding/json"
+	"fmt"
+	"os"
+	"strings"
+
+	"github.com/spf13/cobra"
+
+	"github.com/xdevplatform/xurl/api"
+	"github.com/xdevplatform/xurl/auth"
+	"github.com/xdevplatform/xurl/config"
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+	"github.com/xdevplatform/xurl/utils"
+)
+
+// CreateDiffCommand creates the diff command
+func CreateDiffCommand(cfg *config.Config, a *auth.Auth) *cobra.Command {
+	var specA, specB string
+
+	cmd := &cobra.Command{
+		Use:   "diff [flags] [URL] --a ARGS --b ARGS",
+		Short: "Send two variants of a request and diff the responses",
+		Long: `Send a request twice, once with the --a arguments and once with the --b
+arguments, and print a JSON diff of the two response bodies:
+
+  {"equal": false, "differences": [{"path": "/data/name", "a": "X", "b": "Y"}]}
+
+Each path is a JSON pointer; "a" or "b" is missing where only the other body
+has it. xurl exits with status 1 when the bodies differ, as diff(1) does.
+
+--a and --b each hold request flags, quoted as in a shell: --base-url, --auth,
+-u/--username, -X/--method, -H/--header, -P/--param, -d/--data, and a URL that
+replaces the shared one. An API error response (e.g. a 404) is compared like
+any other body.
+
+Examples:
+  xurl diff /2/users/me --a "--base-url https://api.x.com" --b "--base-url http://localhost:8080"
+  xurl diff /2/users/me --a "-u alice" --b "-u bob"
+  xurl diff --a "/2/tweets/1 --auth app" --b "/2/tweets/1 --auth oauth2"`,
+		Args: cobra.MaximumNArgs(1),
+		Run: func(cmd *cobra.Command, args []string) {
+			var url string
+			if len(args) > 0 {
+				url = args[0]
+			}
+			var sides [2]diffSide
+			for i, spec := range []string{specA, specB} {
+				side, err := parseDiffSide(spec, url)
+				if err != nil {
+					exitWithError(fmt.Errorf("--%c: %w", 'a'+i, err))
+				}
+				sides[i] = side
+			}
+
+			flags := readClientFlags(cmd)
+			report, err := runDiff(cfg, sides, func(cfg *config.Config) *api.ApiClient {
+				return flags.apply(api.NewApiClient(cfg, a))
+			})
+			if err != nil {
+				exitWithError(err)
+			}
+			out, err := json.Marshal(report)
+			if err != nil {
+				exitWithError(err)
+			}
+			utils.FormatAndPrintResponse(out)
+			if !report.Equal {
+				os.Exit(1)
+			}
+		},
+	}
+
+	cmd.Flags().StringVar(&specA, "a", "", "Flags (and optionally the URL) of the first request")
+	cmd.Flags().StringVar(&specB, "b", "", "Flags (and optionally the URL) of the second request")
+	return cmd
+}
+
+// diffSide is one of the two requests of xurl diff.
+type diffSide struct {
+	baseURL string
+	options api.RequestOptions
+}
+
+// diffReport is what xurl diff prints.
+type diffReport struct {
+	Equal       bool                 `json:"equal"`
+	Differences []api.JSONDifference `json:"differences"`
+}
+
+// parseDiffSide parses the flags of a --a or --b argument. url is the shared
+// URL, used unless spec gives its own.
+func parseDiffSide(spec, url string) (diffSide, error) {
+	args, err := splitArgs(spec)
+	if err != nil {
+		return diffSide{}, err
+	}
+
+	flags := (&cobra.Command{}).Flags()
+	baseURL := flags.String("base-url", "", "")
+	authType := flags.String("auth", "", "")
+	username := flags.StringP("username", "u", "", "")
+	method := flags.StringP("method", "X", "", "")
+	headers := flags.StringArrayP("header", "H", nil, "")
+	params := flags.StringArrayP("param", "P", nil, "")
+	data := flags.StringP("data", "d", "", "")
+	if err := flags.Parse(args); err != nil {
+		return diffSide{}, err
+	}
+
+	switch rest := flags.Args(); len(rest) {
+	case 0:
+	case 1:
+		url = rest[0]
+	default:
+		return diffSide{}, fmt.Errorf("more than one URL: %s", strings.Join(rest, " "))
+	}
+	if url == "" {
+		return diffSide{}, fmt.Errorf("no URL: give one before --a/--b or inside both")
+	}
+
+	var side diffSide
+	if *baseURL != "" {
+		if side.baseURL, err = config.NormalizeAPIBaseURL(*baseURL); err != nil {
+			return diffSide{}, err
+		}
+	}
+	expanded, err := api.ExpandHeaderArgs(*headers)
+	if err != nil {
+		return diffSide{}, err
+	}
+	if *method == "" {
+		*method = "GET"
+		if flags.Changed("data") {
+			*method = "POST"
+		}
+	}
+	side.options = api.RequestOptions{
+		Method:      *method,
+		Endpoint:    url,
+		Headers:     expanded,
+		Data:        *data,
+		AuthType:    *authType,
+		Username:    *username,
+		QueryParams: *params,
+	}
+	return side, nil
+}
+
+// runDiff sends both requests, each with a client newClient makes from a copy
+// of cfg, and compares their response bodies.
+func runDiff(cfg *config.Config, sides [2]diffSide, newClient func(*config.Config) *api.ApiClient) (diffReport, error) {
+	var bodies [2]json.RawMessage
+	for i, side := range sides {
+		sideCfg := *cfg
+		if side.baseURL != "" {
+			sideCfg.APIBaseURL = side.baseURL
+		}
+		body, err := newClient(&sideCfg).SendRequest(side.options)
+		if err != nil {
+			// An API error body is a response like any other here.
+			if !xurlErrors.IsAPIError(err) || !json.Valid([]byte(err.Error())) {
+				return diffReport{}, fmt.Errorf("request %c: %w", 'a'+i, err)
+			}
+			body = json.RawMessage(err.Error())
+		}
+		bodies[i] = body
+	}
+
+	differences, err := api.DiffJSON(bodies[0], bodies[1])
+	if err != nil {
+		return diffReport{}, err
+	}
+	if differences == nil {
+		differences = []api.JSONDifference{}
+	}
+	return diffReport{Equal: len(differences) == 0, Differences: differences}, nil
+}
+
+// splitArgs splits s into arguments the way a POSIX shell would, for the
+// quoting it needs: whitespace separates arguments, single quotes keep text
+// as is, and double quotes keep text but let a backslash escape a quote or a
+// backslash. Outside quotes a backslash escapes any character.
+func splitArgs(s string) ([]string, error) {
+	var args []string
+	var current strings.Builder
+	inArg := false
+	for i := 0; i < len(s); i++ {
+		c := s[i]
+		switch {
+		case c == ' ' || c == '\t' || c == '\n':
+			if inArg {
+				args = append(args, current.String())
+				current.Reset()
+				inArg = false
+			}
+		case c == '\'':
+			end := strings.IndexByte(s[i+1:], '\'')
+			if end < 0 {
+				return nil, fmt.Errorf("unterminated ' in %q", s)
+			}
+			current.WriteString(s[i+1 : i+1+end])
+			i += end + 1
+			inArg = true
+		case c == '"':
+			i++
+			for ; i < len(s) && s[i] != '"'; i++ {
+				if s[i] == '\\' && i+1 < len(s) && (s[i+1] == '"' || s[i+1] == '\\') {
+					i++
+				}
+				current.WriteByte(s[i])
+			}
+			if i >= len(s) {
+				return nil, fmt.Errorf("unterminated \" in %q", s)
+			}
+			inArg = true
+		case c == '\\' && i+1 < len(s):
+			i++
+			current.WriteByte(s[i])
+			inArg = true
+		default:
+			current.WriteByte(c)
+			inArg = true
+		}
+	}
+	if inArg {
+		args = append(args, current.String())
+	}
+	return args, nil
+}
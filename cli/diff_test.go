@@ -0,0 +1,100 @@
+package cli
+
+import (
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/xdevplatform/xurl/api"
+	"github.com/xdevplatform/xurl/config"
+)
+
+func TestSplitArgs(t *testing.T) {
+	tests := []struct {
+		in   string
+		want []string
+	}{
+		{"", nil},
+		{"  -u alice  --auth\toauth2 ", []string{"-u", "alice", "--auth", "oauth2"}},
+		{`-H 'X-Note: it"s' -d "{\"text\":\"a b\"}"`, []string{"-H", `X-Note: it"s`, "-d", `{"text":"a b"}`}},
+		{`a\ b c''d ""`, []string{"a b", "cd", ""}},
+	}
+	for _, tt := range tests {
+		got, err := splitArgs(tt.in)
+		require.NoError(t, err, tt.in)
+		assert.Equal(t, tt.want, got, tt.in)
+	}
+
+	_, err := splitArgs(`-H 'open`)
+	assert.ErrorContains(t, err, "unterminated '")
+	_, err = splitArgs(`-d "open`)
+	assert.ErrorContains(t, err, `unterminated "`)
+}
+
+func TestParseDiffSide(t *testing.T) {
+	side, err := parseDiffSide(`--base-url http://localhost:8080/ -u alice -H "X-A: 1" -P max_results=5`, "/2/users/me")
+	require.NoError(t, err)
+	assert.Equal(t, "http://localhost:8080", side.baseURL)
+	assert.Equal(t, api.RequestOptions{
+		Method:      "GET",
+		Endpoint:    "/2/users/me",
+		Headers:     []string{"X-A: 1"},
+		Username:    "alice",
+		QueryParams: []string{"max_results=5"},
+	}, side.options)
+
+	side, err = parseDiffSide(`/2/tweets -d '{"text":"hi"}' --auth oauth1`, "/2/users/me")
+	require.NoError(t, err)
+	assert.Equal(t, "/2/tweets", side.options.Endpoint, "a URL in the spec replaces the shared one")
+	assert.Equal(t, "POST", side.options.Method, "-d implies POST")
+
+	_, err = parseDiffSide("-u alice", "")
+	assert.ErrorContains(t, err, "no URL")
+	_, err = parseDiffSide("--base-url ftp://example.com", "/2/users/me")
+	assert.ErrorContains(t, err, "invalid base URL")
+	_, err = parseDiffSide("--nope", "/2/users/me")
+	assert.Error(t, err)
+}
+
+func TestRunDiff(t *testing.T) {
+	serve := func(body string, status int) *httptest.Server {
+		return httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			w.Header().Set("Content-Type", "application/json")
+			w.WriteHeader(status)
+			w.Write([]byte(body))
+		}))
+	}
+	prod := serve(`{"data":{"id":"1","name":"Alice"}}`, http.StatusOK)
+	defer prod.Close()
+	staging := serve(`{"data":{"id":"1","name":"Alicia"}}`, http.StatusOK)
+	defer staging.Close()
+	missing := serve(`{"title":"Not Found Error","status":404}`, http.StatusNotFound)
+	defer missing.Close()
+
+	cfg := config.NewConfig()
+	a := mcpTestAuth(t, "tok")
+	newDiffClient := func(cfg *config.Config) *api.ApiClient { return api.NewApiClient(cfg, a) }
+	side := func(baseURL string) diffSide {
+		return diffSide{baseURL: baseURL, options: api.RequestOptions{Method: "GET", Endpoint: "/2/users/me"}}
+	}
+
+	report, err := runDiff(cfg, [2]diffSide{side(prod.URL), side(prod.URL)}, newDiffClient)
+	require.NoError(t, err)
+	assert.True(t, report.Equal)
+	assert.NotNil(t, report.Differences, "printed as [] rather than null")
+
+	report, err = runDiff(cfg, [2]diffSide{side(prod.URL), side(staging.URL)}, newDiffClient)
+	require.NoError(t, err)
+	assert.False(t, report.Equal)
+	require.Len(t, report.Differences, 1)
+	assert.Equal(t, "/data/name", report.Differences[0].Path)
+	assert.JSONEq(t, `"Alice"`, string(report.Differences[0].A))
+	assert.JSONEq(t, `"Alicia"`, string(report.Differences[0].B))
+
+	report, err = runDiff(cfg, [2]diffSide{side(prod.URL), side(missing.URL)}, newDiffClient)
+	require.NoError(t, err, "an API error body is compared, not fatal")
+	assert.False(t, report.Equal)
+}
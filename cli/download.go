@@ -0,0 +1,104 @@
+package cli
+
+import (
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"time"
+
+	"github.com/spf13/cobra"
+
+	"github.com/xdevplatform/xurl/api"
+	"github.com/xdevplatform/xurl/auth"
+	"github.com/xdevplatform/xurl/config"
+	"github.com/xdevplatform/xurl/store"
+)
+
+// CreateDownloadCommand creates the download command
+func CreateDownloadCommand(cfg *config.Config, auth *auth.Auth) *cobra.Command {
+	var output string
+
+	cmd := &cobra.Command{
+		Use:   "download [flags] URL",
+		Short: "Download a file (e.g. a data export) to disk",
+		Long: `Download a file to disk, streaming it without holding it in memory.
+
+URL may be an API path or the absolute URL returned by an export/job endpoint.
+Redirects are followed. Credentials are sent only when the URL is on the API
+host or another X host such as upload.x.com; files served from other hosts are
+fetched without them unless --force-auth is given. A gzip
+Content-Encoding is always undone; --decompress also inflates gzip files such
+as .gz exports.
+
+Examples:
+  xurl download -o archive.zip https://example.com/exports/123.zip
+  xurl download -o - /2/some/export/file | gunzip
+  xurl download --decompress -o tweets.jsonl https://example.com/exports/123.jsonl.gz`,
+		Args: cobra.ExactArgs(1),
+		Run: func(cmd *cobra.Command, args []string) {
+			authType, _ := cmd.Flags().GetString("auth")
+			username, _ := cmd.Flags().GetString("username")
+			verbose, verboseBody := verbosity(cmd)
+			trace, _ := cmd.Flags().GetBool("trace")
+			headers := headerArgs(cmd)
+			decompress, _ := cmd.Flags().GetBool("decompress")
+			outputAppend, _ := cmd.Flags().GetBool("output-append")
+			forceAuth, _ := cmd.Flags().GetBool("force-auth")
+			timeCond, _ := cmd.Flags().GetString("time-cond")
+			var ifModifiedSince time.Time
+			if timeCond != "" {
+				var err error
+				if ifModifiedSince, err = api.ParseTimeCondition(timeCond); err != nil {
+					exitWithError(err)
+				}
+			}
+			client := readClientFlags(cmd).apply(api.NewApiClient(cfg, auth).WithRateLimitStore(store.NewRateLimitStore()))
+
+			var progress io.Writer
+			if isTerminal(os.Stderr) {
+				progress = os.Stderr
+			}
+			warnOAuth2Expiry(auth, authType, username, headers)
+
+			n, err := client.DownloadFile(api.RequestOptions{
+				Endpoint:        args[0],
+				Headers:         headers,
+				AuthType:        authType,
+				Username:        username,
+				Verbose:         verbose,
+				VerboseBody:     verboseBody,
+				Trace:           trace,
+				Decompress:      decompress,
+				AppendOutput:    outputAppend,
+				ForceAuth:       forceAuth,
+				IfModifiedSince: ifModifiedSince,
+			}, output, progress)
+			if errors.Is(err, api.ErrNotModified) {
+				exitWithError(err)
+			}
+			if err != nil {
+				fmt.Fprintf(os.Stderr, "\033[31m%s\033[0m\n", scrubbedError(err))
+				os.Exit(1)
+			}
+			if output != "-" {
+				fmt.Fprintf(os.Stderr, "Saved %d bytes to %s\n", n, output)
+			}
+		},
+	}
+
+	cmd.Flags().StringVarP(&output, "output", "o", "", "File to write the download to ('-' for stdout)")
+	cmd.Flags().String("auth", "", "Authentication type (oauth1, oauth2, or app), or a fallback order such as oauth2,oauth1,app")
+	cmd.Flags().StringP("username", "u", "", "Username for OAuth2 authentication")
+	cmd.Flags().CountP("verbose", "v", "Print request/response headers; -vv also logs the bodies to stderr")
+	cmd.Flags().Bool("verbose-body", false, "Same as -vv: also log request/response bodies (secrets redacted) to stderr")
+	cmd.Flags().BoolP("trace", "t", false, "Add trace header to request")
+	cmd.Flags().StringArrayP("header", "H", []string{}, "Request header \"Name: value\", or @file with one per line (repeatable; a later header replaces an earlier one of the same name)")
+	cmd.Flags().String("time-cond", "", "Download only if modified since this RFC 3339 time or date, or the mtime of @file, e.g. @photo.jpg (a 304 leaves the file alone and exits 4)")
+	cmd.Flags().Bool("decompress", false, "Inflate a gzip-compressed file (e.g. a .gz export) while saving it")
+	cmd.Flags().Bool("output-append", false, "Append to the -o file instead of replacing it")
+	cmd.Flags().Bool("force-auth", false, "Send credentials even when the URL is not on an X API host")
+	cmd.MarkFlagRequired("output")
+
+	return cmd
+}
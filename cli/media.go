@@ -12,21 +12,21 @@ import (
 )
 
 // CreateMediaCommand creates the media command and its subcommands
-func CreateMediaCommand(auth *auth.Auth) *cobra.Command {
+func CreateMediaCommand(cfg *config.Config, auth *auth.Auth) *cobra.Command {
 	// Create media command
 	var mediaCmd = &cobra.Command{
 		Use:   "media",
 		Short: "Media upload operations",
 	}
 
-	mediaCmd.AddCommand(createMediaUploadCmd(auth))
-	mediaCmd.AddCommand(createMediaStatusCmd(auth))
+	mediaCmd.AddCommand(createMediaUploadCmd(cfg, auth))
+	mediaCmd.AddCommand(createMediaStatusCmd(cfg, auth))
 
 	return mediaCmd
 }
 
 // Create media upload subcommand
-func createMediaUploadCmd(auth *auth.Auth) *cobra.Command {
+func createMediaUploadCmd(cfg *config.Config, auth *auth.Auth) *cobra.Command {
 	var mediaType, mediaCategory string
 	var waitForProcessing bool
 
@@ -39,15 +39,15 @@ func createMediaUploadCmd(auth *auth.Auth) *cobra.Command {
 			filePath := args[0]
 			authType, _ := cmd.Flags().GetString("auth")
 			username, _ := cmd.Flags().GetString("username")
-			verbose, _ := cmd.Flags().GetBool("verbose")
-			headers, _ := cmd.Flags().GetStringArray("header")
+			verbose, verboseBody := verbosity(cmd)
+			headers := headerArgs(cmd)
 			trace, _ := cmd.Flags().GetBool("trace")
-			config := config.NewConfig()
-			client := api.NewApiClient(config, auth)
+			client := readClientFlags(cmd).apply(api.NewApiClient(cfg, auth))
+			warnOAuth2Expiry(auth, authType, username, headers)
 
-			err := api.ExecuteMediaUpload(filePath, mediaType, mediaCategory, authType, username, verbose, waitForProcessing, trace, headers, client)
+			err := api.ExecuteMediaUpload(filePath, mediaType, mediaCategory, authType, username, verbose, verboseBody, waitForProcessing, trace, headers, client)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31m%v\033[0m\n", err)
+				fmt.Fprintf(os.Stderr, "\033[31m%s\033[0m\n", scrubbedError(err))
 				os.Exit(1)
 			}
 		},
@@ -56,17 +56,18 @@ func createMediaUploadCmd(auth *auth.Auth) *cobra.Command {
 	cmd.Flags().StringVar(&mediaType, "media-type", "", "Media MIME type (auto-detected from the file extension if omitted)")
 	cmd.Flags().StringVar(&mediaCategory, "category", "", "Media category (derived from the media type if omitted)")
 	cmd.Flags().BoolVar(&waitForProcessing, "wait", true, "Wait for media processing to complete")
-	cmd.Flags().String("auth", "", "Authentication type (oauth1, oauth2, or app)")
+	cmd.Flags().String("auth", "", "Authentication type (oauth1, oauth2, or app), or a fallback order such as oauth2,oauth1,app")
 	cmd.Flags().StringP("username", "u", "", "Username for OAuth2 authentication")
-	cmd.Flags().BoolP("verbose", "v", false, "Print verbose information")
+	cmd.Flags().CountP("verbose", "v", "Print request/response headers; -vv also logs the bodies to stderr")
+	cmd.Flags().Bool("verbose-body", false, "Same as -vv: also log request/response bodies (secrets redacted) to stderr")
 	cmd.Flags().BoolP("trace", "t", false, "Add trace header to request")
-	cmd.Flags().StringArrayP("header", "H", []string{}, "Request headers")
+	cmd.Flags().StringArrayP("header", "H", []string{}, "Request header \"Name: value\", or @file with one per line (repeatable; a later header replaces an earlier one of the same name)")
 
 	return cmd
 }
 
 // Create media status subcommand
-func createMediaStatusCmd(auth *auth.Auth) *cobra.Command {
+func createMediaStatusCmd(cfg *config.Config, auth *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "status [flags] MEDIA_ID",
 		Short: "Check media upload status",
@@ -76,26 +77,26 @@ func createMediaStatusCmd(auth *auth.Auth) *cobra.Command {
 			mediaID := args[0]
 			authType, _ := cmd.Flags().GetString("auth")
 			username, _ := cmd.Flags().GetString("username")
-			verbose, _ := cmd.Flags().GetBool("verbose")
+			verbose, verboseBody := verbosity(cmd)
 			wait, _ := cmd.Flags().GetBool("wait")
 			trace, _ := cmd.Flags().GetBool("trace")
-			headers, _ := cmd.Flags().GetStringArray("header")
-			config := config.NewConfig()
-			client := api.NewApiClient(config, auth)
+			headers := headerArgs(cmd)
+			client := readClientFlags(cmd).apply(api.NewApiClient(cfg, auth))
 
-			err := api.ExecuteMediaStatus(mediaID, authType, username, verbose, wait, trace, headers, client)
+			err := api.ExecuteMediaStatus(mediaID, authType, username, verbose, verboseBody, wait, trace, headers, client)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31m%v\033[0m\n", err)
+				fmt.Fprintf(os.Stderr, "\033[31m%s\033[0m\n", scrubbedError(err))
 				os.Exit(1)
 			}
 		},
 	}
 
-	cmd.Flags().String("auth", "", "Authentication type (oauth1, oauth2, or app)")
+	cmd.Flags().String("auth", "", "Authentication type (oauth1, oauth2, or app), or a fallback order such as oauth2,oauth1,app")
 	cmd.Flags().StringP("username", "u", "", "Username for OAuth2 authentication")
-	cmd.Flags().BoolP("verbose", "v", false, "Print verbose information")
+	cmd.Flags().CountP("verbose", "v", "Print request/response headers; -vv also logs the bodies to stderr")
+	cmd.Flags().Bool("verbose-body", false, "Same as -vv: also log request/response bodies (secrets redacted) to stderr")
 	cmd.Flags().BoolP("wait", "w", false, "Wait for media processing to complete")
 	cmd.Flags().BoolP("trace", "t", false, "Add trace header to request")
-	cmd.Flags().StringArrayP("header", "H", []string{}, "Request headers")
+	cmd.Flags().StringArrayP("header", "H", []string{}, "Request header \"Name: value\", or @file with one per line (repeatable; a later header replaces an earlier one of the same name)")
 	return cmd
 }
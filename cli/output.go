@@ -0,0 +1,30 @@
+package cli
+
+import (
+	"fmt"
+	"os"
+
+	"github.com/fatih/color"
+)
+
+// redirectBodyOutput makes fd, an open file descriptor, the destination of
+// response bodies (--raw-output-to-fd). Only bodies are written to stdout,
+// diagnostics (-v, progress, warnings) going to stderr, so pointing stdout at
+// fd moves the bodies and nothing else.
+func redirectBodyOutput(fd int) error {
+	if fd < 0 {
+		return fmt.Errorf("--raw-output-to-fd %d: not a file descriptor", fd)
+	}
+	f := os.NewFile(uintptr(fd), fmt.Sprintf("fd %d", fd))
+	if f == nil {
+		return fmt.Errorf("--raw-output-to-fd %d: not a file descriptor", fd)
+	}
+	if _, err := f.Stat(); err != nil {
+		return fmt.Errorf("--raw-output-to-fd %d: not an open file descriptor (open it in the shell, e.g. 3>body.json): %v", fd, err)
+	}
+	os.Stdout = f
+	// fatih/color captured stdout at init.
+	color.Output = f
+	color.NoColor = color.NoColor || !isTerminal(f)
+	return nil
+}
@@ -0,0 +1,158 @@
+package cli
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/spf13/cobra"
+
+	"github.com/xdevplatform/xurl/api"
+	"github.com/xdevplatform/xurl/auth"
+	"github.com/xdevplatform/xurl/config"
+	"github.com/xdevplatform/xurl/store"
+)
+
+// CreateSaveCommand creates the save command
+func CreateSaveCommand() *cobra.Command {
+	var preset store.Preset
+	var remove bool
+
+	cmd := &cobra.Command{
+		Use:   "save NAME [flags] URL",
+		Short: "Save a request as a named preset for 'xurl run'",
+		Long: `Save a request (its method, URL, headers, query parameters, body, and auth)
+under a name in ~/.xurl/presets.yml, to send it again with 'xurl run NAME'.
+Saving under an existing name replaces that preset.
+
+A "@file" body or "-H @file" header file is read each time the preset runs,
+not when it is saved. Presets store bodies and headers as given, secrets
+included; the file is readable by you only.
+
+Examples:
+  xurl save me /2/users/me -P user.fields=created_at,public_metrics
+  xurl save hello -X POST /2/tweets -d '{"text":"Hello!"}' --auth oauth2 -u alice
+  xurl save --delete hello`,
+		Run: func(cmd *cobra.Command, args []string) {
+			presets := store.NewPresetStore()
+			if remove {
+				if len(args) != 1 {
+					exitWithError(fmt.Errorf("--delete takes the name of the preset only"))
+				}
+				deleted, err := presets.DeletePreset(args[0])
+				if err != nil {
+					exitWithError(err)
+				}
+				if !deleted {
+					exitWithError(fmt.Errorf("no preset named %q", args[0]))
+				}
+				fmt.Printf("Deleted preset %q\n", args[0])
+				return
+			}
+
+			if len(args) != 2 {
+				exitWithError(fmt.Errorf("usage: xurl save NAME [flags] URL"))
+			}
+			preset.URL = args[1]
+			preset.Method = strings.ToUpper(preset.Method)
+			if preset.Method == "" {
+				preset.Method = "GET"
+				if cmd.Flags().Changed("data") {
+					preset.Method = "POST"
+				}
+			}
+			if err := presets.SavePreset(args[0], &preset); err != nil {
+				exitWithError(err)
+			}
+			fmt.Printf("Saved preset %q: %s %s\n", args[0], preset.Method, preset.URL)
+		},
+	}
+
+	cmd.Flags().StringVarP(&preset.Method, "method", "X", "", "HTTP method (GET by default, POST when -d is given)")
+	cmd.Flags().StringArrayVarP(&preset.Headers, "header", "H", nil, "Request header \"Name: value\", or @file with one per line (repeatable)")
+	cmd.Flags().StringArrayVarP(&preset.Params, "param", "P", nil, "Query parameter key=value to add to the URL (repeatable)")
+	cmd.Flags().StringVarP(&preset.Data, "data", "d", "", "Request body data (@file reads it from a file when the preset runs)")
+	cmd.Flags().StringVar(&preset.Accept, "accept", "", "Accept header to send (default application/json)")
+	cmd.Flags().StringVar(&preset.Auth, "auth", "", "Authentication type (oauth1, oauth2, or app), or a fallback order such as oauth2,oauth1,app")
+	cmd.Flags().StringVarP(&preset.Username, "username", "u", "", "Username for OAuth2 authentication")
+	cmd.Flags().BoolVar(&remove, "delete", false, "Delete the named preset instead of saving one")
+	return cmd
+}
+
+// CreateRunCommand creates the run command
+func CreateRunCommand(cfg *config.Config, a *auth.Auth) *cobra.Command {
+	cmd := &cobra.Command{
+		Use:   "run [NAME]",
+		Short: "Send a request saved with 'xurl save'",
+		Long: `Send the request saved as NAME with 'xurl save'. Without NAME, list the
+saved presets.
+
+--auth and -u/--username given here replace the preset's own.
+
+Examples:
+  xurl run
+  xurl run me
+  xurl run hello -u bob -v`,
+		Args: cobra.MaximumNArgs(1),
+		Run: func(cmd *cobra.Command, args []string) {
+			presets := store.NewPresetStore()
+			if err := presets.LoadErr(); err != nil {
+				exitWithError(err)
+			}
+			if len(args) == 0 {
+				names := presets.PresetNames()
+				if len(names) == 0 {
+					fmt.Println("No presets saved. Use 'xurl save NAME URL' to save one.")
+				}
+				for _, name := range names {
+					preset := presets.GetPreset(name)
+					fmt.Printf("%s  %s %s\n", name, preset.Method, preset.URL)
+				}
+				return
+			}
+
+			preset := presets.GetPreset(args[0])
+			if preset == nil {
+				exitWithError(fmt.Errorf("no preset named %q (list them with 'xurl run')", args[0]))
+			}
+			options, err := presetOptions(preset, baseOpts(cmd))
+			if err != nil {
+				exitWithError(err)
+			}
+			if err := api.HandleRequest(options, false, "", newClient(cmd, cfg, a)); err != nil {
+				exitWithError(err)
+			}
+		},
+	}
+
+	addCommonFlags(cmd)
+	return cmd
+}
+
+// presetOptions builds the options of preset's request on top of opts, which
+// hold the flags of xurl run: their --auth and --username win over the
+// preset's, and their headers (e.g. --basic-auth) follow its own.
+func presetOptions(preset *store.Preset, opts api.RequestOptions) (api.RequestOptions, error) {
+	headers, err := api.ExpandHeaderArgs(append(append([]string{}, preset.Headers...), opts.Headers...))
+	if err != nil {
+		return api.RequestOptions{}, err
+	}
+	opts.Method = preset.Method
+	opts.Endpoint = preset.URL
+	opts.Headers = headers
+	opts.QueryParams = preset.Params
+	opts.Accept = preset.Accept
+	if strings.HasPrefix(preset.Data, "@") {
+		if opts.Body, err = api.ReadDataArg(preset.Data); err != nil {
+			return api.RequestOptions{}, err
+		}
+	} else {
+		opts.Data = preset.Data
+	}
+	if opts.AuthType == "" {
+		opts.AuthType = preset.Auth
+	}
+	if opts.Username == "" {
+		opts.Username = preset.Username
+	}
+	return opts, nil
+}
@@ -0,0 +1,56 @@
+package cli
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/xdevplatform/xurl/api"
+	"github.com/xdevplatform/xurl/store"
+)
+
+func TestPresetOptions(t *testing.T) {
+	preset := &store.Preset{
+		Method:   "POST",
+		URL:      "/2/tweets",
+		Headers:  []string{"X-A: 1", "X-B: 2"},
+		Params:   []string{"max_results=5"},
+		Data:     `{"text":"hi"}`,
+		Auth:     "oauth2",
+		Username: "alice",
+	}
+
+	options, err := presetOptions(preset, api.RequestOptions{Verbose: true})
+	require.NoError(t, err)
+	assert.Equal(t, api.RequestOptions{
+		Method:      "POST",
+		Endpoint:    "/2/tweets",
+		Headers:     []string{"X-A: 1", "X-B: 2"},
+		QueryParams: []string{"max_results=5"},
+		Data:        `{"text":"hi"}`,
+		AuthType:    "oauth2",
+		Username:    "alice",
+		Verbose:     true,
+	}, options)
+
+	// The flags of xurl run win over the preset's.
+	options, err = presetOptions(preset, api.RequestOptions{AuthType: "oauth1", Username: "bob", Headers: []string{"X-B: 3"}})
+	require.NoError(t, err)
+	assert.Equal(t, "oauth1", options.AuthType)
+	assert.Equal(t, "bob", options.Username)
+	assert.Equal(t, []string{"X-A: 1", "X-B: 3"}, options.Headers)
+
+	// A @file body is read when the preset runs.
+	path := filepath.Join(t.TempDir(), "body.json")
+	require.NoError(t, os.WriteFile(path, []byte(`{"text":"from file"}`), 0600))
+	options, err = presetOptions(&store.Preset{Method: "POST", URL: "/2/tweets", Data: "@" + path}, api.RequestOptions{})
+	require.NoError(t, err)
+	assert.Equal(t, []byte(`{"text":"from file"}`), options.Body)
+	assert.Empty(t, options.Data)
+
+	_, err = presetOptions(&store.Preset{Method: "POST", URL: "/2/tweets", Data: "@" + path + ".missing"}, api.RequestOptions{})
+	assert.Error(t, err)
+}
@@ -1,14 +1,21 @@
 package cli
 
 import (
+	"errors"
 	"fmt"
+	"io"
 	"os"
+	"strings"
+	"text/template"
+	"time"
 
 	"github.com/spf13/cobra"
 
 	"github.com/xdevplatform/xurl/api"
 	"github.com/xdevplatform/xurl/auth"
 	"github.com/xdevplatform/xurl/config"
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+	"github.com/xdevplatform/xurl/store"
 	"github.com/xdevplatform/xurl/version"
 )
 
@@ -22,6 +29,7 @@ const (
 
 // CreateRootCommand creates the root command for the xurl CLI
 func CreateRootCommand(cfg *config.Config, a *auth.Auth) *cobra.Command {
+	storedSecrets = a.StoredSecrets
 	var rootCmd = &cobra.Command{
 		Use:     "xurl [flags] URL",
 		Short:   "Auth enabled curl-like interface for the X API",
@@ -43,24 +51,104 @@ Authentication:
 
 Commands are grouped by purpose below. Run 'xurl <command> --help' for details.`,
 		PersistentPreRun: func(cmd *cobra.Command, args []string) {
+			if baseURL, _ := cmd.Flags().GetString("base-url"); baseURL != "" {
+				normalized, err := config.NormalizeAPIBaseURL(baseURL)
+				if err != nil {
+					exitWithError(err)
+				}
+				cfg.APIBaseURL = normalized
+				if _, ok := os.LookupEnv("INFO_URL"); !ok {
+					cfg.InfoURL = normalized + "/2/users/me"
+				}
+				a.WithInfoURL(cfg.InfoURL)
+			}
+			if rules, _ := cmd.Flags().GetStringArray("url-rewrite"); len(rules) > 0 {
+				for _, rule := range rules {
+					rewrite, err := config.ParseURLRewrite(rule)
+					if err != nil {
+						exitWithError(err)
+					}
+					cfg.URLRewrites = append(cfg.URLRewrites, rewrite)
+				}
+			}
+			if cmd.Flags().Changed("raw-output-to-fd") {
+				fd, _ := cmd.Flags().GetInt("raw-output-to-fd")
+				if err := redirectBodyOutput(fd); err != nil {
+					exitWithError(err)
+				}
+			}
+			readClientFlags(cmd) // exits on invalid flags before any request
 			// Apply --app override if provided
 			appOverride, _ := cmd.Flags().GetString("app")
 			if appOverride != "" {
 				a.WithAppName(appOverride)
 			}
+			if signatureMethod, _ := cmd.Flags().GetString("oauth1-signature-method"); signatureMethod != "" {
+				a.WithOAuth1SignatureMethod(signatureMethod)
+			}
+			if realm, _ := cmd.Flags().GetString("oauth1-realm"); realm != "" {
+				a.WithOAuth1Realm(realm)
+			}
+			if bodyHash, _ := cmd.Flags().GetBool("oauth1-body-hash"); bodyHash {
+				a.WithOAuth1BodyHash(true)
+			}
+			if asQuery, _ := cmd.Flags().GetBool("oauth1-as-query"); asQuery {
+				a.WithOAuth1AsQuery(true)
+			}
+			if debugSignature, _ := cmd.Flags().GetBool("debug-signature"); debugSignature {
+				a.WithDebugSignature(os.Stderr)
+			}
+			if noSaveToken, _ := cmd.Flags().GetBool("no-save-token"); noSaveToken {
+				a.WithNoSaveToken(true)
+			}
+			if nonInteractive, _ := cmd.Flags().GetBool("non-interactive"); nonInteractive {
+				a.WithNonInteractive(true)
+			}
+			if requireUser, _ := cmd.Flags().GetBool("require-user-context"); requireUser {
+				a.WithRequireUserContext(true)
+			}
+			if token, _ := cmd.Flags().GetString("oauth2-token"); token != "" {
+				a.WithOAuth2AccessToken(token)
+			}
+			if method, _ := cmd.Flags().GetString("token-auth-method"); method != "" {
+				if _, err := a.WithTokenAuthMethod(method); err != nil {
+					exitWithError(err)
+				}
+			}
+			if certFile, _ := cmd.Flags().GetString("cert"); certFile != "" {
+				keyFile, _ := cmd.Flags().GetString("key")
+				if _, err := a.WithClientCertificate(certFile, keyFile); err != nil {
+					exitWithError(err)
+				}
+			}
+			if err := selectOAuth2Account(cmd, a); err != nil {
+				exitWithError(err)
+			}
 		},
 		Args: func(cmd *cobra.Command, args []string) error {
 			return nil
 		},
 		Run: func(cmd *cobra.Command, args []string) {
-			headers, _ := cmd.Flags().GetStringArray("header")
+			headers := headerArgs(cmd)
 			data, _ := cmd.Flags().GetString("data")
+			// --data-binary is -d sent as application/octet-stream, whatever
+			// its content looks like.
+			binaryData := cmd.Flags().Changed("data-binary")
+			if binaryData {
+				if cmd.Flags().Changed("data") {
+					exitWithError(fmt.Errorf("-d and --data-binary cannot be combined"))
+				}
+				data, _ = cmd.Flags().GetString("data-binary")
+			}
 
 			method, _ := cmd.Flags().GetString("method")
+			if head, _ := cmd.Flags().GetBool("head"); head {
+				method = "HEAD"
+			}
 			if method == "" {
 				// Mirror curl: providing a request body (-d/--data) implies POST
 				// unless -X says otherwise — even for an explicitly empty body.
-				if cmd.Flags().Changed("data") {
+				if cmd.Flags().Changed("data") || binaryData {
 					method = "POST"
 				} else {
 					method = "GET"
@@ -69,52 +157,307 @@ Commands are grouped by purpose below. Run 'xurl <command> --help' for details.`
 
 			authType, _ := cmd.Flags().GetString("auth")
 			username, _ := cmd.Flags().GetString("username")
-			verbose, _ := cmd.Flags().GetBool("verbose")
+			verbose, verboseBody := verbosity(cmd)
 			trace, _ := cmd.Flags().GetBool("trace")
 			forceStream, _ := cmd.Flags().GetBool("stream")
 			mediaFile, _ := cmd.Flags().GetString("file")
+			chunked, _ := cmd.Flags().GetBool("chunked")
+			acceptStatus, _ := cmd.Flags().GetIntSlice("accept-status")
+			inputCSV, _ := cmd.Flags().GetString("input-csv")
+			concurrency, _ := cmd.Flags().GetInt("concurrency")
+			retries, _ := cmd.Flags().GetInt("retry")
+			retryMaxTime, _ := cmd.Flags().GetDuration("retry-max-time")
+			retryBudgetSize, _ := cmd.Flags().GetInt("retry-budget")
+			forceRetry, _ := cmd.Flags().GetBool("force")
+			respectRateLimit, _ := cmd.Flags().GetBool("respect-rate-limit")
+			noAutoRefresh, _ := cmd.Flags().GetBool("no-auto-refresh")
+			csvOutput, _ := cmd.Flags().GetBool("csv")
+			csvColumns, _ := cmd.Flags().GetStringSlice("csv-columns")
+			arraySeparator, _ := cmd.Flags().GetString("array-separator")
+			bodyFileOutput, _ := cmd.Flags().GetString("body-file-output")
+			decompress, _ := cmd.Flags().GetBool("decompress")
+			compressRequest, _ := cmd.Flags().GetBool("compress-request")
+			outputAppend, _ := cmd.Flags().GetBool("output-append")
+			forceAuth, _ := cmd.Flags().GetBool("force-auth")
+			params, _ := cmd.Flags().GetStringArray("param")
+			fields := map[string][]string{}
+			for _, field := range api.FieldParams {
+				fields[field.Param], _ = cmd.Flags().GetStringSlice(field.Flag)
+			}
+			maxResponseSize, _ := cmd.Flags().GetInt64("max-response-size")
+			truncate, _ := cmd.Flags().GetInt("truncate")
+			idempotencyKey, _ := cmd.Flags().GetString("idempotency-key")
+			outputTemplate, _ := cmd.Flags().GetString("output-template")
+			jsonPointer, _ := cmd.Flags().GetString("json-pointer")
+			templateFile, _ := cmd.Flags().GetString("template-file")
+			accept, _ := cmd.Flags().GetString("accept")
+			timeCond, _ := cmd.Flags().GetString("time-cond")
+			interval, _ := cmd.Flags().GetDuration("interval")
+			count, _ := cmd.Flags().GetInt("count")
+			until, _ := cmd.Flags().GetString("until")
+			discard, _ := cmd.Flags().GetBool("discard")
+			stdinURLs, _ := cmd.Flags().GetBool("stdin-urls")
+			onFailure := failurePolicy(cmd)
+			paginate, _ := cmd.Flags().GetBool("paginate")
+			cacheArg, _ := cmd.Flags().GetString("cache")
+			cacheMaxAge, _ := cmd.Flags().GetDuration("cache-max-age")
+			noCache, _ := cmd.Flags().GetBool("no-cache")
 
-			if len(args) == 0 {
+			if stdinURLs && len(args) > 0 {
+				exitWithError(fmt.Errorf("--stdin-urls reads the URLs from stdin; it cannot be combined with a URL argument"))
+			}
+			if (stdinURLs || inputCSV != "") && !cmd.Flags().Changed("retry") {
+				retries = api.DefaultBatchRetries
+			}
+			if onFailure != api.DefaultFailurePolicy && !stdinURLs && inputCSV == "" && interval <= 0 && !paginate {
+				exitWithError(fmt.Errorf("--fail-fast and --continue-on-error apply to --stdin-urls, --input-csv, --interval, and --paginate"))
+			}
+			if onFailure == api.ContinueOnError && paginate {
+				exitWithError(fmt.Errorf("--paginate cannot continue past a failed page, since the next page comes from its response"))
+			}
+			if len(args) == 0 && !stdinURLs {
 				fmt.Fprintln(os.Stderr, "No URL provided")
 				fmt.Fprintln(os.Stderr, "Usage: xurl [OPTIONS] [URL] [COMMAND]")
 				fmt.Fprintln(os.Stderr, "Try 'xurl --help' for more information.")
 				os.Exit(1)
 			}
 
-			url := args[0]
+			var url string
+			if len(args) > 0 {
+				url = args[0]
+			}
+
+			var ifModifiedSince time.Time
+			if timeCond != "" {
+				var err error
+				if ifModifiedSince, err = api.ParseTimeCondition(timeCond); err != nil {
+					exitWithError(err)
+				}
+			}
+
+			if outputAppend && bodyFileOutput == "" {
+				exitWithError(fmt.Errorf("--output-append appends to the --body-file-output file; it needs --body-file-output"))
+			}
+			if outputTemplate != "" && inputCSV == "" {
+				exitWithError(fmt.Errorf("--output-template names the files of an --input-csv batch; it needs --input-csv"))
+			}
+			var responseTemplate *template.Template
+			if templateFile != "" {
+				if csvOutput || len(csvColumns) > 0 || inputCSV != "" || bodyFileOutput != "" {
+					exitWithError(fmt.Errorf("--template-file cannot be combined with --csv, --input-csv, or --body-file-output"))
+				}
+				var err error
+				if responseTemplate, err = api.ParseTemplateFile(templateFile); err != nil {
+					exitWithError(err)
+				}
+			}
+
+			var untilPredicate *api.Predicate
+			if interval <= 0 && (count != 0 || until != "") {
+				exitWithError(fmt.Errorf("--count and --until control polling; they need --interval"))
+			}
+			if interval > 0 {
+				if count < 0 {
+					exitWithError(fmt.Errorf("--count must not be negative"))
+				}
+				if inputCSV != "" || bodyFileOutput != "" || mediaFile != "" || forceStream || api.IsStreamingEndpoint(url) || csvOutput || len(csvColumns) > 0 || responseTemplate != nil || timeCond != "" {
+					exitWithError(fmt.Errorf("--interval cannot be combined with --input-csv, --body-file-output, --file, streaming, --csv, --template-file, or --time-cond"))
+				}
+				if until != "" {
+					var err error
+					if untilPredicate, err = api.ParsePredicate(until); err != nil {
+						exitWithError(err)
+					}
+				}
+			}
+
+			if discard && (interval > 0 || inputCSV != "" || bodyFileOutput != "" || mediaFile != "" || forceStream || api.IsStreamingEndpoint(url) || csvOutput || len(csvColumns) > 0 || responseTemplate != nil) {
+				exitWithError(fmt.Errorf("--discard cannot be combined with --interval, --input-csv, --body-file-output, --file, streaming, --csv, or --template-file"))
+			}
+
+			if stdinURLs && (interval > 0 || discard || inputCSV != "" || bodyFileOutput != "" || mediaFile != "" || forceStream || csvOutput || len(csvColumns) > 0 || responseTemplate != nil || data == "@-") {
+				exitWithError(fmt.Errorf("--stdin-urls cannot be combined with --interval, --discard, --input-csv, --body-file-output, --file, --stream, --csv, --template-file, or -d @-"))
+			}
 
-			client := api.NewApiClient(cfg, a)
+			if paginate && (interval > 0 || discard || stdinURLs || inputCSV != "" || bodyFileOutput != "" || mediaFile != "" || forceStream || api.IsStreamingEndpoint(url) || csvOutput || len(csvColumns) > 0 || responseTemplate != nil || timeCond != "") {
+				exitWithError(fmt.Errorf("--paginate cannot be combined with --interval, --discard, --stdin-urls, --input-csv, --body-file-output, --file, streaming, --csv, --template-file, or --time-cond"))
+			}
+
+			// curl-style "@file" / "@-" bodies are read as raw bytes so binary
+			// content (e.g. media chunks) is sent unmodified.
+			var body []byte
+			if strings.HasPrefix(data, "@") {
+				var err error
+				body, err = api.ReadDataArg(data)
+				if err != nil {
+					printError(err)
+					os.Exit(1)
+				}
+				data = ""
+			} else if binaryData {
+				body = []byte(data)
+				data = ""
+			}
+
+			client := readClientFlags(cmd).apply(api.NewApiClient(cfg, a).WithRateLimitStore(store.NewRateLimitStore()))
+			if cacheArg != "" && !noCache {
+				client.WithResponseCache(api.NewResponseCache(cacheDir(cacheArg), cacheMaxAge))
+			}
+
+			var retryBudget *api.RetryBudget
+			if retryBudgetSize > 0 {
+				retryBudget = api.NewRetryBudget(retryBudgetSize)
+			}
 
 			requestOptions := api.RequestOptions{
-				Method:   method,
-				Endpoint: url,
-				Headers:  headers,
-				Data:     data,
-				AuthType: authType,
-				Username: username,
-				Verbose:  verbose,
-				Trace:    trace,
-			}
-			err := api.HandleRequest(requestOptions, forceStream, mediaFile, client)
+				Method:           method,
+				Endpoint:         url,
+				Headers:          headers,
+				Data:             data,
+				Body:             body,
+				AuthType:         authType,
+				Username:         username,
+				Verbose:          verbose,
+				VerboseBody:      verboseBody,
+				Trace:            trace,
+				Chunked:          chunked,
+				BinaryBody:       binaryData,
+				AcceptStatus:     acceptStatus,
+				Retry:            api.RetryPolicy{MaxRetries: retries, MaxTime: retryMaxTime, Budget: retryBudget},
+				ForceRetry:       forceRetry,
+				RespectRateLimit: respectRateLimit,
+				NoAutoRefresh:    noAutoRefresh,
+				Decompress:       decompress,
+				CompressRequest:  compressRequest,
+				AppendOutput:     outputAppend,
+				ForceAuth:        forceAuth,
+				QueryParams:      params,
+				Fields:           fields,
+				MaxResponseSize:  maxResponseSize,
+				IdempotencyKey:   idempotencyKey,
+				Accept:           accept,
+				IfModifiedSince:  ifModifiedSince,
+				Output: api.OutputOptions{
+					CSV:            csvOutput || len(csvColumns) > 0,
+					CSVColumns:     csvColumns,
+					ArraySeparator: arraySeparator,
+					Truncate:       truncate,
+					OutputTemplate: outputTemplate,
+					JSONPointer:    jsonPointer,
+					Template:       responseTemplate,
+				},
+			}
+
+			if inputCSV != "" || bodyFileOutput != "" || mediaFile != "" || forceStream || api.IsStreamingEndpoint(url) || interval > 0 || stdinURLs {
+				warnOAuth2Expiry(a, authType, username, headers)
+			}
+
+			var err error
+			if stdinURLs {
+				err = api.ExecuteURLList(requestOptions, os.Stdin, onFailure, client, os.Stdout)
+			} else if inputCSV != "" {
+				err = api.ExecuteCSVRequests(requestOptions, inputCSV, concurrency, onFailure, client, os.Stdout)
+			} else if interval > 0 {
+				err = api.Poll(requestOptions, api.PollOptions{
+					Interval:  interval,
+					Count:     count,
+					Until:     untilPredicate,
+					OnFailure: onFailure,
+				}, client, os.Stdout)
+			} else if paginate {
+				err = api.Paginate(requestOptions, client, os.Stdout)
+			} else if discard {
+				err = api.ExecuteDiscardRequest(os.Stdout, requestOptions, client)
+			} else if bodyFileOutput != "" {
+				err = sendToFile(client, requestOptions, bodyFileOutput)
+			} else {
+				err = api.HandleRequest(requestOptions, forceStream, mediaFile, client)
+			}
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
-				os.Exit(1)
+				exitWithError(err)
 			}
 		},
 	}
 
 	// Global persistent flag: --app
 	rootCmd.PersistentFlags().String("app", "", "Use a specific registered app (overrides default)")
+	rootCmd.PersistentFlags().String("base-url", "", "API base URL for this invocation, e.g. a mock server (overrides API_BASE_URL)")
+	rootCmd.PersistentFlags().StringArray("url-rewrite", nil, "Send requests for one host to another, e.g. 'api.x.com=>sandbox.internal' or 'api.x.com=>http://localhost:8080' (repeatable)")
+	rootCmd.PersistentFlags().String("oauth1-realm", "", "Realm to send in the OAuth1 Authorization header (overrides the stored setting)")
+	rootCmd.PersistentFlags().String("oauth1-signature-method", "", "OAuth1 signature method: HMAC-SHA1 (default) or HMAC-SHA256, or SHA1/SHA256 for short (overrides the stored setting)")
+	rootCmd.PersistentFlags().Bool("oauth1-body-hash", false, "Sign an oauth_body_hash of non-form request bodies (OAuth body hash extension)")
+	rootCmd.PersistentFlags().Bool("oauth1-as-query", false, "Send OAuth1 parameters (including the signature) in the query string instead of the Authorization header")
+	rootCmd.PersistentFlags().Bool("debug-signature", false, "Print the base string, masked signing key, and signature of OAuth1-signed requests to stderr")
+	rootCmd.PersistentFlags().String("token-auth-method", "", "How to send client credentials to the OAuth2 token endpoint: basic (default with a client secret) or body (default without one)")
+	rootCmd.PersistentFlags().String("cert", "", "PEM client certificate to present for mutual TLS (may also hold the private key)")
+	rootCmd.PersistentFlags().String("key", "", "PEM private key for --cert, when it is not in the certificate file")
+	rootCmd.PersistentFlags().Bool("interactive-auth-select", false, "Ask which stored OAuth2 account to use when --auth oauth2 is given without --username and several match (default when stdin is a terminal)")
+	rootCmd.PersistentFlags().Bool("no-save-token", false, "Keep tokens from an OAuth2 login or refresh in memory for this run only; ~/.xurl is not written")
+	rootCmd.PersistentFlags().String("basic-auth", "", "Send user:password as HTTP Basic auth to a gateway in front of the API, in --basic-auth-header (X's Authorization header is unaffected)")
+	rootCmd.PersistentFlags().String("basic-auth-header", api.DefaultBasicAuthHeader, "Header that carries the --basic-auth credentials")
+	rootCmd.PersistentFlags().Int("raw-output-to-fd", 1, "Write response bodies to this open file descriptor instead of stdout, e.g. 3 with 3>body.json; diagnostics always go to stderr")
+	rootCmd.PersistentFlags().String("throttle", "", "Send at most N requests per window across the whole invocation, e.g. 10/15m or 5/s; -v reports the waits")
+	rootCmd.PersistentFlags().String("record", "", "Record every request and its response as a JSON fixture in this directory (credentials are left out)")
+	rootCmd.PersistentFlags().String("playback", "", "Answer requests from the fixtures --record wrote to this directory, without network access or credentials")
+	rootCmd.PersistentFlags().Bool("respect-rate-limit", false, "Wait before sending when the rate limit last seen for the endpoint (saved in ~/.xurl/ratelimits.yml) is used up until its reset")
+	rootCmd.PersistentFlags().Bool("non-interactive", false, "Never open a browser or prompt; fail with exit code 3 instead (also XURL_NON_INTERACTIVE=1)")
+	rootCmd.PersistentFlags().String("oauth2-token", "", "OAuth2 user access token to send instead of a stored one; it is never stored or refreshed")
+	rootCmd.PersistentFlags().Bool("require-user-context", false, "Fail before sending unless the request is authorized as a user (OAuth2 or OAuth1), never with the app-only bearer token")
 
 	rootCmd.Flags().StringP("method", "X", "", "HTTP method (GET by default, POST when -d is given)")
-	rootCmd.Flags().StringArrayP("header", "H", []string{}, "Request headers")
-	rootCmd.Flags().StringP("data", "d", "", "Request body data")
-	rootCmd.Flags().String("auth", "", "Authentication type (oauth1, oauth2, or app)")
+	rootCmd.Flags().BoolP("head", "I", false, "Send a HEAD request and print only the response status and headers (same as -X HEAD)")
+	rootCmd.Flags().StringArrayP("header", "H", []string{}, "Request header \"Name: value\", or @file with one per line (repeatable; a later header replaces an earlier one of the same name)")
+	rootCmd.Flags().String("time-cond", "", "Fetch only if modified since this RFC 3339 time or date, or the mtime of @file (sends If-Modified-Since; a 304 prints nothing and exits 4)")
+	rootCmd.Flags().String("cache", "", "Keep GET responses in this directory (~/.xurl/cache for a bare --cache) and revalidate them with their ETag; an unchanged one is served from it, marked X-Xurl-Cache: hit under -v")
+	rootCmd.Flags().Lookup("cache").NoOptDefVal = defaultCacheDirArg
+	rootCmd.Flags().Duration("cache-max-age", api.DefaultCacheMaxAge, "With --cache, do not use a cached response older than this")
+	rootCmd.Flags().Bool("no-cache", false, "Ignore --cache for this request, e.g. when a wrapper always passes it")
+	rootCmd.Flags().String("accept", "", "Accept header to send, e.g. text/csv (default application/json; an Accept header given with -H wins)")
+	rootCmd.Flags().StringP("data", "d", "", "Request body data (@file or @- reads raw bytes from a file or stdin)")
+	rootCmd.Flags().String("data-binary", "", "Like -d, but always sent as application/octet-stream, e.g. --data-binary @- for bytes piped from another process")
+	for _, field := range api.FieldParams {
+		rootCmd.Flags().StringSlice(field.Flag, nil, fmt.Sprintf("Comma-separated %s to request (repeatable; -P %s=... wins)", field.Param, field.Param))
+	}
+	rootCmd.Flags().StringArrayP("param", "P", []string{}, "Query parameter key=value to add to the URL, percent-encoded (repeatable)")
+	rootCmd.Flags().String("auth", "", "Authentication type (oauth1, oauth2, or app), or a fallback order such as oauth2,oauth1,app")
 	rootCmd.Flags().StringP("username", "u", "", "Username for OAuth2 authentication")
-	rootCmd.Flags().BoolP("verbose", "v", false, "Print verbose information")
+	rootCmd.Flags().CountP("verbose", "v", "Print request/response headers; -vv also logs the bodies to stderr")
+	rootCmd.Flags().Bool("verbose-body", false, "Same as -vv: also log request/response bodies (secrets redacted) to stderr")
 	rootCmd.Flags().BoolP("trace", "t", false, "Add trace header to request")
 	rootCmd.Flags().BoolP("stream", "s", false, "Force streaming mode for non-streaming endpoints")
-	rootCmd.Flags().StringP("file", "F", "", "File to upload (for multipart requests)")
+	rootCmd.Flags().StringP("file", "F", "", "File to upload (for multipart requests): PATH or field=@PATH, optionally with ;type=MIME to declare the part's content type")
+	rootCmd.Flags().IntSlice("accept-status", nil, "HTTP error statuses to treat as success (e.g. 404,409): the body is printed and xurl exits 0")
+	rootCmd.Flags().Int("retry", 0, "Retry a rate-limited (HTTP 429) request up to N times, waiting for the rate-limit window to reset (default 3 with --input-csv or --stdin-urls)")
+	rootCmd.Flags().Duration("retry-max-time", 0, "Give up retrying a request rather than wait more than this in total (e.g. 2m; 0 for no limit)")
+	rootCmd.Flags().Int("retry-budget", 0, "Total retries allowed across all the requests of --input-csv, --stdin-urls, --paginate, or --interval (0 for no limit)")
+	rootCmd.Flags().Bool("force", false, "With --retry, retry POST and PATCH requests even without an idempotency key")
+	rootCmd.Flags().String("idempotency-key", "", "Send this Idempotency-Key header, the same on every retry; alone, a random key per request")
+	rootCmd.Flags().Lookup("idempotency-key").NoOptDefVal = api.IdempotencyKeyAuto
+	rootCmd.Flags().Bool("no-auto-refresh", false, "Do not refresh the OAuth2 token and retry once when a request is rejected with HTTP 401")
+	rootCmd.Flags().Bool("chunked", false, "Send the request body with chunked transfer encoding instead of Content-Length")
+	rootCmd.Flags().Bool("csv", false, "Print the response's records (its \"data\") as CSV, flattening nested fields into dotted columns")
+	rootCmd.Flags().StringSlice("csv-columns", nil, "CSV columns to print, in order (e.g. id,text,public_metrics.like_count); implies --csv")
+	rootCmd.Flags().String("array-separator", api.DefaultArraySeparator, "Separator joining array values within a CSV cell")
+	rootCmd.Flags().String("body-file-output", "", "Stream the response body to this file ('-' for stdout) without buffering or reformatting it")
+	rootCmd.Flags().Bool("decompress", false, "Inflate a gzip-compressed response (e.g. a .gz export) written with --body-file-output")
+	rootCmd.Flags().Bool("compress-request", false, "Gzip request bodies of 1 KiB or more and send them with Content-Encoding: gzip")
+	rootCmd.Flags().Bool("force-auth", false, "Send credentials with an absolute URL even when its host is not the API host or another X host")
+	rootCmd.Flags().Bool("output-append", false, "Append the response to the --body-file-output file instead of replacing it (e.g. to collect NDJSON across runs)")
+	rootCmd.Flags().Int64("max-response-size", 0, "Fail instead of reading a response body larger than this many bytes (0 for no limit)")
+	rootCmd.Flags().Int("truncate", 0, "Print only the first N records of the response's \"data\" (or top-level array), or else its first N bytes")
+	rootCmd.Flags().String("input-csv", "", "Send one request per CSV row, filling {column} placeholders in the URL and body; prints NDJSON")
+	rootCmd.Flags().String("json-pointer", "", "Print only the value at this RFC 6901 JSON pointer, e.g. /data/0/id (strings are printed unquoted)")
+	rootCmd.Flags().Bool("discard", false, "Read the whole response body but print only the status, body size, and timing (for throughput checks)")
+	rootCmd.Flags().String("template-file", "", "Render the response with this Go text/template file instead of printing JSON, e.g. a Markdown report over {{range .data}}")
+	rootCmd.Flags().String("output-template", "", "With --input-csv, write each response to a file named by this template, e.g. resp-{index}-{status}.json or {response.data.id}.json")
+	rootCmd.Flags().Int("concurrency", 1, "Maximum number of requests in flight (with --input-csv)")
+	rootCmd.Flags().Bool("paginate", false, "Follow the response's Link rel=\"next\" header, or its meta.next_token, to fetch every page; prints one NDJSON line per page")
+	rootCmd.Flags().Bool("stdin-urls", false, "Send one request per URL read from stdin (one per line; blank lines and # comments skipped) with the other flags; prints NDJSON")
+	rootCmd.Flags().Bool("fail-fast", false, "With --stdin-urls, --input-csv, --interval, or --paginate, stop at the first failed request (default for --interval and --paginate)")
+	rootCmd.Flags().Bool("continue-on-error", false, "With --stdin-urls, --input-csv, or --interval, record a failed request and go on (default for --stdin-urls and --input-csv)")
+	rootCmd.Flags().Duration("interval", 0, "Repeat the request at this interval (e.g. 30s), printing each response as a line of NDJSON")
+	rootCmd.Flags().Int("count", 0, "With --interval, stop after this many responses (0 to poll until --until holds or indefinitely)")
+	rootCmd.Flags().String("until", "", "With --interval, stop once the response satisfies this jq-style predicate, e.g. '.data.state == \"done\"'; exits 4 if --count runs out first")
 
 	// Organise subcommands into scannable help sections.
 	rootCmd.AddGroup(
@@ -124,17 +467,24 @@ Commands are grouped by purpose below. Run 'xurl <command> --help' for details.`
 		&cobra.Group{ID: groupManage, Title: "Management:"},
 	)
 
-	chatCmd := CreateChatCommand(a)
+	chatCmd := CreateChatCommand(cfg, a)
 	chatCmd.GroupID = groupWrite
 	rootCmd.AddCommand(chatCmd)
 
-	authCmd := CreateAuthCommand(a)
-	mediaCmd := CreateMediaCommand(a)
+	authCmd := CreateAuthCommand(cfg, a)
+	mediaCmd := CreateMediaCommand(cfg, a)
 	versionCmd := CreateVersionCommand()
 	webhookCmd := CreateWebhookCommand(a)
 	tokenCmd := CreateTokenCommand(a)
 	mcpCmd := CreateMCPCommand(a)
-	for _, c := range []*cobra.Command{authCmd, mediaCmd, tokenCmd, mcpCmd, versionCmd, webhookCmd} {
+	downloadCmd := CreateDownloadCommand(cfg, a)
+	diffCmd := CreateDiffCommand(cfg, a)
+	batchCmd := CreateBatchCommand(cfg, a)
+	configCmd := CreateConfigCommand(cfg, a)
+	saveCmd := CreateSaveCommand()
+	runCmd := CreateRunCommand(cfg, a)
+	cacheCmd := CreateCacheCommand()
+	for _, c := range []*cobra.Command{authCmd, configCmd, mediaCmd, downloadCmd, diffCmd, batchCmd, saveCmd, runCmd, cacheCmd, tokenCmd, mcpCmd, versionCmd, webhookCmd} {
 		c.GroupID = groupManage
 		rootCmd.AddCommand(c)
 	}
@@ -145,7 +495,219 @@ Commands are grouped by purpose below. Run 'xurl <command> --help' for details.`
 	rootCmd.SetCompletionCommandGroupID(groupManage)
 
 	// Register streamlined shortcut commands (post, reply, read, search, etc.)
-	CreateShortcutCommands(rootCmd, a)
+	CreateShortcutCommands(rootCmd, cfg, a)
 
 	return rootCmd
 }
+
+// verbosity reads the -v counter and --verbose-body: -v prints headers, and -vv
+// or --verbose-body adds the bodies.
+func verbosity(cmd *cobra.Command) (verbose, verboseBody bool) {
+	level, _ := cmd.Flags().GetCount("verbose")
+	verboseBody, _ = cmd.Flags().GetBool("verbose-body")
+	verboseBody = verboseBody || level >= 2
+	return level > 0 || verboseBody, verboseBody
+}
+
+// clientFlags are the root flags that every ApiClient of an invocation is
+// created with.
+type clientFlags struct {
+	throttle *api.Throttle
+	fixtures *api.Fixtures
+}
+
+// readClientFlags reads --throttle, and --record or --playback, exiting when
+// they are invalid.
+func readClientFlags(cmd *cobra.Command) clientFlags {
+	var flags clientFlags
+	if spec, _ := cmd.Flags().GetString("throttle"); spec != "" {
+		throttle, err := api.ParseThrottle(spec)
+		if err != nil {
+			exitWithError(err)
+		}
+		flags.throttle = throttle
+	}
+	record, _ := cmd.Flags().GetString("record")
+	playback, _ := cmd.Flags().GetString("playback")
+	switch {
+	case record != "" && playback != "":
+		exitWithError(fmt.Errorf("--record and --playback cannot be used together"))
+	case record != "":
+		flags.fixtures = &api.Fixtures{Dir: record}
+	case playback != "":
+		flags.fixtures = &api.Fixtures{Dir: playback, Playback: true}
+	}
+	return flags
+}
+
+// apply sets the flags on client; clients given the same flags share their
+// throttle.
+func (f clientFlags) apply(client *api.ApiClient) *api.ApiClient {
+	return client.WithThrottle(f.throttle).WithFixtures(f.fixtures)
+}
+
+// failurePolicy reads --fail-fast and --continue-on-error, exiting when both
+// are given.
+func failurePolicy(cmd *cobra.Command) api.FailurePolicy {
+	failFast, _ := cmd.Flags().GetBool("fail-fast")
+	continueOnError, _ := cmd.Flags().GetBool("continue-on-error")
+	switch {
+	case failFast && continueOnError:
+		exitWithError(fmt.Errorf("--fail-fast and --continue-on-error cannot be combined"))
+	case failFast:
+		return api.FailFast
+	case continueOnError:
+		return api.ContinueOnError
+	}
+	return api.DefaultFailurePolicy
+}
+
+// headerArgs returns cmd's -H headers with @file arguments expanded (see
+// api.ExpandHeaderArgs) and the --basic-auth header, exiting when a header file
+// cannot be read or the credentials are invalid.
+func headerArgs(cmd *cobra.Command) []string {
+	args, _ := cmd.Flags().GetStringArray("header")
+	// --basic-auth goes first, so that a -H for the same header replaces it.
+	if credentials, _ := cmd.Flags().GetString("basic-auth"); credentials != "" {
+		name, _ := cmd.Flags().GetString("basic-auth-header")
+		basic, err := api.BasicAuthHeaderArg(name, credentials)
+		if err != nil {
+			exitWithError(err)
+		}
+		args = append([]string{basic}, args...)
+	}
+	headers, err := api.ExpandHeaderArgs(args)
+	if err != nil {
+		exitWithError(err)
+	}
+	return headers
+}
+
+// exitInteractionRequired is the exit code for an operation that needed a
+// browser or prompt in non-interactive mode.
+const exitInteractionRequired = 3
+
+// exitNotModified is the exit code for a 304 Not Modified response, e.g. to
+// --time-cond.
+const exitNotModified = 4
+
+// exitConditionNotMet is the exit code for polling that ran out of --count
+// before --until held. It shares its value with exitNotModified: either way
+// the awaited change was not seen, and the two flags cannot be combined.
+const exitConditionNotMet = 4
+
+// exitWithError reports err on stderr and exits. An interaction-required error
+// is printed as bare JSON, without colour, and exits with
+// exitInteractionRequired so that scripts can tell it apart.
+func exitWithError(err error) {
+	if errors.Is(err, api.ErrNotModified) {
+		fmt.Fprintln(os.Stderr, "Not modified")
+		os.Exit(exitNotModified)
+	}
+	if errors.Is(err, api.ErrConditionNotMet) {
+		printError(err)
+		os.Exit(exitConditionNotMet)
+	}
+	if xurlErrors.IsInteractionRequiredError(err) {
+		fmt.Fprintln(os.Stderr, scrubbedError(err))
+		os.Exit(exitInteractionRequired)
+	}
+	printError(err)
+	os.Exit(1)
+}
+
+// storedSecrets returns the values scrubbedError masks besides those that look
+// like credentials; CreateRootCommand points it at the token store.
+var storedSecrets = func() []string { return nil }
+
+// printError writes err to stderr in red.
+func printError(err error) {
+	fmt.Fprintf(os.Stderr, "\033[31mError: %s\033[0m\n", scrubbedError(err))
+}
+
+// scrubbedError is err's message with tokens and stored secrets masked (see
+// api.ScrubSecrets), so that output pasted into an issue does not leak them.
+func scrubbedError(err error) string {
+	return api.ScrubSecrets(err.Error(), storedSecrets())
+}
+
+// pickAccountFunc is the account prompt used by selectOAuth2Account; tests stub
+// it.
+var pickAccountFunc = RunPicker
+
+// selectOAuth2Account asks which stored OAuth2 account to use when cmd is about
+// to make an oauth2 request (--auth oauth2, or oauth2 first in a fallback list)
+// without --username and several accounts could be meant. The choice is set as
+// cmd's --username, so every request of this invocation uses it. Prompting is
+// on by default when stdin is a terminal and off in non-interactive mode;
+// --interactive-auth-select overrides that. Without a prompt, the first account
+// is used as before.
+func selectOAuth2Account(cmd *cobra.Command, a *auth.Auth) error {
+	if cmd.Flags().Lookup("username") == nil || cmd.Flags().Lookup("auth") == nil {
+		return nil
+	}
+	username, _ := cmd.Flags().GetString("username")
+	authType, _ := cmd.Flags().GetString("auth")
+	first, _, _ := strings.Cut(authType, ",")
+	if username != "" || !strings.EqualFold(strings.TrimSpace(first), "oauth2") || a.OAuth2AccessToken() != "" {
+		return nil
+	}
+	// Playback sends no credentials, so no account is used.
+	if playback, _ := cmd.Flags().GetString("playback"); playback != "" {
+		return nil
+	}
+	// An Authorization header from -H is sent as is, so no account is used.
+	// A bad -H @file is reported when the request is built.
+	if args, err := cmd.Flags().GetStringArray("header"); err == nil {
+		if headers, err := api.ExpandHeaderArgs(args); err == nil && api.HasHeaderArg(headers, "Authorization") {
+			return nil
+		}
+	}
+
+	interactive := isTerminal(os.Stdin) && !a.NonInteractive()
+	if cmd.Flags().Changed("interactive-auth-select") {
+		interactive, _ = cmd.Flags().GetBool("interactive-auth-select")
+	}
+	choices := a.OAuth2AccountChoices()
+	if !interactive || len(choices) == 0 {
+		return nil
+	}
+
+	choice, err := pickAccountFunc("Select OAuth2 account", choices)
+	if err != nil {
+		return err
+	}
+	if choice == "" {
+		return fmt.Errorf("no account selected")
+	}
+	return cmd.Flags().Set("username", choice)
+}
+
+// warnOAuth2Expiry warns on stderr, before a long-running operation that may
+// use OAuth2, when the token about to be used is close to expiring. headers are
+// the request's -H arguments: with an Authorization header no token is used.
+func warnOAuth2Expiry(a *auth.Auth, authType, username string, headers []string) {
+	if authType != "" && !strings.Contains(strings.ToLower(authType), "oauth2") || api.HasHeaderArg(headers, "Authorization") {
+		return
+	}
+	if warning := a.OAuth2ExpiryWarning(username); warning != "" {
+		fmt.Fprintf(os.Stderr, "\033[33mWarning: %s\033[0m\n", warning)
+	}
+}
+
+// sendToFile streams the response to path for --body-file-output, with
+// progress when stderr is a terminal.
+func sendToFile(client *api.ApiClient, options api.RequestOptions, path string) error {
+	var progress io.Writer
+	if isTerminal(os.Stderr) {
+		progress = os.Stderr
+	}
+	n, err := client.SendRequestToFile(options, path, progress)
+	if err != nil {
+		return err
+	}
+	if path != "-" {
+		fmt.Fprintf(os.Stderr, "Saved %d bytes to %s\n", n, path)
+	}
+	return nil
+}
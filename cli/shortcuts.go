@@ -4,12 +4,15 @@ import (
 	"encoding/json"
 	"fmt"
 	"os"
+	"time"
 
 	"github.com/spf13/cobra"
 
 	"github.com/xdevplatform/xurl/api"
 	"github.com/xdevplatform/xurl/auth"
 	"github.com/xdevplatform/xurl/config"
+	xurlErrors "github.com/xdevplatform/xurl/errors"
+	"github.com/xdevplatform/xurl/store"
 	"github.com/xdevplatform/xurl/utils"
 )
 
@@ -21,21 +24,25 @@ import (
 func baseOpts(cmd *cobra.Command) api.RequestOptions {
 	authType, _ := cmd.Flags().GetString("auth")
 	username, _ := cmd.Flags().GetString("username")
-	verbose, _ := cmd.Flags().GetBool("verbose")
+	verbose, verboseBody := verbosity(cmd)
 	trace, _ := cmd.Flags().GetBool("trace")
+	respectRateLimit, _ := cmd.Flags().GetBool("respect-rate-limit")
 
 	return api.RequestOptions{
-		AuthType: authType,
-		Username: username,
-		Verbose:  verbose,
-		Trace:    trace,
+		Headers:          headerArgs(cmd),
+		AuthType:         authType,
+		Username:         username,
+		Verbose:          verbose,
+		VerboseBody:      verboseBody,
+		Trace:            trace,
+		RespectRateLimit: respectRateLimit,
 	}
 }
 
-// newClient creates an ApiClient from the auth object.
-func newClient(a *auth.Auth) *api.ApiClient {
-	cfg := config.NewConfig()
-	return api.NewApiClient(cfg, a)
+// newClient creates an ApiClient from the invocation's config and the auth
+// object, with the root flags of cmd.
+func newClient(cmd *cobra.Command, cfg *config.Config, a *auth.Auth) *api.ApiClient {
+	return readClientFlags(cmd).apply(api.NewApiClient(cfg, a).WithRateLimitStore(store.NewRateLimitStore()))
 }
 
 // printResult pretty‑prints a JSON response or exits on error.
@@ -44,12 +51,15 @@ func newClient(a *auth.Auth) *api.ApiClient {
 // be piped/parsed the same way as a successful response; only non-JSON errors
 // (network/auth failures) go to stderr.
 func printResult(resp json.RawMessage, err error) {
+	if xurlErrors.IsInteractionRequiredError(err) {
+		exitWithError(err)
+	}
 	if err != nil {
 		var raw json.RawMessage
 		if json.Unmarshal([]byte(err.Error()), &raw) == nil {
 			utils.FormatAndPrintResponse(raw)
 		} else {
-			fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+			printError(err)
 		}
 		os.Exit(1)
 	}
@@ -104,11 +114,13 @@ func resolveUserID(client api.Client, username string, opts api.RequestOptions)
 	return user.Data.ID, nil
 }
 
-// addCommonFlags adds --auth, --username, --verbose, --trace to a command.
+// addCommonFlags adds --auth, --username, --verbose, --verbose-body, --trace to
+// a command.
 func addCommonFlags(cmd *cobra.Command) {
-	cmd.Flags().String("auth", "", "Authentication type (oauth1, oauth2, app)")
+	cmd.Flags().String("auth", "", "Authentication type (oauth1, oauth2, app), or a fallback order such as oauth2,oauth1,app")
 	cmd.Flags().StringP("username", "u", "", "OAuth2 username to act as")
-	cmd.Flags().BoolP("verbose", "v", false, "Print verbose request/response info")
+	cmd.Flags().CountP("verbose", "v", "Print request/response headers; -vv also logs the bodies to stderr")
+	cmd.Flags().Bool("verbose-body", false, "Same as -vv: also log request/response bodies (secrets redacted) to stderr")
 	cmd.Flags().BoolP("trace", "t", false, "Add X-B3-Flags trace header")
 }
 
@@ -117,7 +129,7 @@ func addCommonFlags(cmd *cobra.Command) {
 // on the given root command.
 // -----------------------------------------------------------------
 
-func CreateShortcutCommands(rootCmd *cobra.Command, a *auth.Auth) {
+func CreateShortcutCommands(rootCmd *cobra.Command, cfg *config.Config, a *auth.Auth) {
 	add := func(groupID string, cmds ...*cobra.Command) {
 		for _, c := range cmds {
 			c.GroupID = groupID
@@ -126,18 +138,18 @@ func CreateShortcutCommands(rootCmd *cobra.Command, a *auth.Auth) {
 	}
 
 	add(groupWrite,
-		postCmd(a), replyCmd(a), quoteCmd(a), deleteCmd(a), dmCmd(a),
-		likeCmd(a), unlikeCmd(a), repostCmd(a), unrepostCmd(a),
-		bookmarkCmd(a), unbookmarkCmd(a),
+		postCmd(cfg, a), replyCmd(cfg, a), quoteCmd(cfg, a), deleteCmd(cfg, a), deleteTweetCmd(cfg, a), tweetCmd(cfg, a), dmCmd(cfg, a),
+		likeCmd(cfg, a), unlikeCmd(cfg, a), repostCmd(cfg, a), unrepostCmd(cfg, a),
+		bookmarkCmd(cfg, a), unbookmarkCmd(cfg, a),
 	)
 	add(groupSocial,
-		whoamiCmd(a), userCmd(a),
-		followCmd(a), unfollowCmd(a), followingCmd(a), followersCmd(a),
-		blockCmd(a), unblockCmd(a), muteCmd(a), unmuteCmd(a),
+		whoamiCmd(cfg, a), userCmd(cfg, a),
+		followCmd(cfg, a), unfollowCmd(cfg, a), followingCmd(cfg, a), followersCmd(cfg, a),
+		blockCmd(cfg, a), unblockCmd(cfg, a), muteCmd(cfg, a), unmuteCmd(cfg, a),
 	)
 	add(groupRead,
-		readCmd(a), searchCmd(a), postsCmd(a), timelineCmd(a), mentionsCmd(a),
-		dmsCmd(a), bookmarksCmd(a), likesCmd(a),
+		readCmd(cfg, a), searchCmd(cfg, a), postsCmd(cfg, a), timelineCmd(cfg, a), mentionsCmd(cfg, a),
+		dmsCmd(cfg, a), bookmarksCmd(cfg, a), likesCmd(cfg, a),
 	)
 }
 
@@ -145,7 +157,7 @@ func CreateShortcutCommands(rootCmd *cobra.Command, a *auth.Auth) {
 //  POSTING
 // =================================================================
 
-func postCmd(a *auth.Auth) *cobra.Command {
+func postCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	var mediaIDs []string
 	cmd := &cobra.Command{
 		Use:   `post "TEXT"`,
@@ -158,7 +170,7 @@ Examples:
   xurl post "Multiple images" --media-id 111 --media-id 222`,
 		Args: cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			printResult(api.CreatePost(client, args[0], mediaIDs, opts))
 		},
@@ -168,7 +180,7 @@ Examples:
 	return cmd
 }
 
-func replyCmd(a *auth.Auth) *cobra.Command {
+func replyCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	var mediaIDs []string
 	cmd := &cobra.Command{
 		Use:   `reply POST_ID_OR_URL "TEXT"`,
@@ -180,7 +192,7 @@ Examples:
   xurl reply https://x.com/user/status/1234567890 "Nice post!"`,
 		Args: cobra.ExactArgs(2),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			printResult(api.ReplyToPost(client, args[0], args[1], mediaIDs, opts))
 		},
@@ -190,7 +202,7 @@ Examples:
 	return cmd
 }
 
-func quoteCmd(a *auth.Auth) *cobra.Command {
+func quoteCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   `quote POST_ID_OR_URL "TEXT"`,
 		Short: "Quote a post",
@@ -201,7 +213,7 @@ Examples:
   xurl quote https://x.com/user/status/1234567890 "Interesting take"`,
 		Args: cobra.ExactArgs(2),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			printResult(api.QuotePost(client, args[0], args[1], opts))
 		},
@@ -210,7 +222,7 @@ Examples:
 	return cmd
 }
 
-func deleteCmd(a *auth.Auth) *cobra.Command {
+func deleteCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "delete POST_ID_OR_URL",
 		Short: "Delete a post",
@@ -221,7 +233,7 @@ Examples:
   xurl delete https://x.com/user/status/1234567890`,
 		Args: cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			printResult(api.DeletePost(client, args[0], opts))
 		},
@@ -230,11 +242,173 @@ Examples:
 	return cmd
 }
 
+// deleteTweetCmd is delete with a plain deleted/not-deleted result, acting
+// as the OAuth2 user unless --auth says otherwise.
+func deleteTweetCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
+	cmd := &cobra.Command{
+		Use:   "delete-tweet POST_ID_OR_URL",
+		Short: "Delete one of your posts and report whether it was deleted",
+		Long: `Send DELETE /2/tweets/POST_ID with OAuth2 user-context auth (override with
+--auth) and print whether the API deleted the post. Exits 1 if it did not.
+
+Examples:
+  xurl delete-tweet 1234567890
+  xurl delete-tweet https://x.com/user/status/1234567890 --auth oauth1`,
+		Args: cobra.ExactArgs(1),
+		Run: func(cmd *cobra.Command, args []string) {
+			client := newClient(cmd, cfg, a)
+			opts := baseOpts(cmd)
+			deleted, err := deleteTweet(client, args[0], opts)
+			if err != nil {
+				printResult(nil, err)
+			}
+			fmt.Printf("deleted: %t\n", deleted)
+			if !deleted {
+				os.Exit(1)
+			}
+		},
+	}
+	addCommonFlags(cmd)
+	return cmd
+}
+
+// deleteTweet deletes a post, as the OAuth2 user when opts names no auth
+// type, and returns the response's data.deleted.
+func deleteTweet(client api.Client, postID string, opts api.RequestOptions) (bool, error) {
+	if opts.AuthType == "" {
+		opts.AuthType = "oauth2"
+	}
+	resp, err := api.DeletePost(client, postID, opts)
+	if err != nil {
+		return false, err
+	}
+	var result struct {
+		Data struct {
+			Deleted bool `json:"deleted"`
+		} `json:"data"`
+	}
+	if err := json.Unmarshal(resp, &result); err != nil {
+		return false, xurlErrors.NewJSONError(err)
+	}
+	return result.Data.Deleted, nil
+}
+
+// tweetCmd groups flag-driven equivalents of post/delete/like, for scripts that
+// prefer named flags to positional arguments.
+func tweetCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
+	cmd := &cobra.Command{
+		Use:   "tweet [TEXT]",
+		Short: "Create, delete, or like a post using named flags",
+		Long: `Typed wrappers for common post operations. Each one sends the right method,
+path, and body for you; use a raw request (xurl -X METHOD /2/...) for anything
+not covered here.
+
+Given TEXT, tweet creates a post from it, the same as tweet create --text.
+Text that matches a subcommand name (create, delete, like) must use --text.
+
+Examples:
+  xurl tweet "Hello world!"
+  xurl tweet "Agreed" --reply-to 1234567890 --quote https://x.com/user/status/1234567891
+  xurl tweet create --text "Hello world!"
+  xurl tweet delete --id 1234567890
+  xurl tweet like --id https://x.com/user/status/1234567890`,
+		Args: cobra.MaximumNArgs(1),
+		Run: func(cmd *cobra.Command, args []string) {
+			if len(args) == 0 {
+				cmd.Help()
+				return
+			}
+			createTweet(cmd, cfg, a, args[0])
+		},
+	}
+	addTweetCreateFlags(cmd)
+	addCommonFlags(cmd)
+	cmd.AddCommand(tweetCreateCmd(cfg, a), tweetDeleteCmd(cfg, a), tweetLikeCmd(cfg, a))
+	return cmd
+}
+
+// addTweetCreateFlags registers the optional post body flags shared by tweet
+// and tweet create.
+func addTweetCreateFlags(cmd *cobra.Command) {
+	cmd.Flags().StringArray("media-id", nil, "Media ID(s) to attach (repeatable)")
+	cmd.Flags().String("reply-to", "", "Post ID or URL to reply to")
+	cmd.Flags().String("quote", "", "Post ID or URL to quote")
+}
+
+// createTweet posts text with the body flags from addTweetCreateFlags.
+func createTweet(cmd *cobra.Command, cfg *config.Config, a *auth.Auth, text string) {
+	mediaIDs, _ := cmd.Flags().GetStringArray("media-id")
+	replyTo, _ := cmd.Flags().GetString("reply-to")
+	quote, _ := cmd.Flags().GetString("quote")
+
+	client := newClient(cmd, cfg, a)
+	opts := baseOpts(cmd)
+	printResult(api.SendPost(client, api.NewPostBody(text, replyTo, quote, mediaIDs), opts))
+}
+
+func tweetCreateCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
+	var text string
+	cmd := &cobra.Command{
+		Use:   "create --text TEXT",
+		Short: "Create a post and print it",
+		Args:  cobra.NoArgs,
+		Run: func(cmd *cobra.Command, args []string) {
+			createTweet(cmd, cfg, a, text)
+		},
+	}
+	cmd.Flags().StringVar(&text, "text", "", "Text of the post")
+	addTweetCreateFlags(cmd)
+	cmd.MarkFlagRequired("text")
+	addCommonFlags(cmd)
+	return cmd
+}
+
+func tweetDeleteCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
+	var id string
+	cmd := &cobra.Command{
+		Use:   "delete --id POST_ID_OR_URL",
+		Short: "Delete one of your posts",
+		Args:  cobra.NoArgs,
+		Run: func(cmd *cobra.Command, args []string) {
+			client := newClient(cmd, cfg, a)
+			opts := baseOpts(cmd)
+			printResult(api.DeletePost(client, id, opts))
+		},
+	}
+	cmd.Flags().StringVar(&id, "id", "", "Post ID or URL")
+	cmd.MarkFlagRequired("id")
+	addCommonFlags(cmd)
+	return cmd
+}
+
+func tweetLikeCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
+	var id string
+	cmd := &cobra.Command{
+		Use:   "like --id POST_ID_OR_URL",
+		Short: "Like a post",
+		Args:  cobra.NoArgs,
+		Run: func(cmd *cobra.Command, args []string) {
+			client := newClient(cmd, cfg, a)
+			opts := baseOpts(cmd)
+			userID, err := resolveMyUserID(client, opts)
+			if err != nil {
+				printError(err)
+				os.Exit(1)
+			}
+			printResult(api.LikePost(client, userID, id, opts))
+		},
+	}
+	cmd.Flags().StringVar(&id, "id", "", "Post ID or URL")
+	cmd.MarkFlagRequired("id")
+	addCommonFlags(cmd)
+	return cmd
+}
+
 // =================================================================
 //  READING
 // =================================================================
 
-func readCmd(a *auth.Auth) *cobra.Command {
+func readCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "read POST_ID_OR_URL",
 		Short: "Read a post",
@@ -245,7 +419,7 @@ Examples:
   xurl read https://x.com/user/status/1234567890`,
 		Args: cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			printResult(api.ReadPost(client, args[0], opts))
 		},
@@ -254,30 +428,66 @@ Examples:
 	return cmd
 }
 
-func searchCmd(a *auth.Auth) *cobra.Command {
+func searchCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	var maxResults int
+	var sinceExpr, untilExpr string
 	cmd := &cobra.Command{
 		Use:   `search "QUERY"`,
 		Short: "Search recent posts",
 		Long: `Search recent posts matching a query.
 
+--since and --until bound the results by creation time. They accept "now",
+"today", "yesterday", "2 days ago", "6h", a YYYY-MM-DD date or an RFC 3339
+timestamp, and are sent as start_time/end_time.
+
 Examples:
   xurl search "golang"
   xurl search "from:elonmusk" -n 20
-  xurl search "#buildinpublic" -n 15`,
+  xurl search "#buildinpublic" -n 15
+  xurl search "golang" --since "2 days ago" --until "1 day ago"`,
 		Args: cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			since, until, err := searchTimeRange(sinceExpr, untilExpr, time.Now())
+			if err != nil {
+				printError(err)
+				os.Exit(1)
+			}
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
-			printResult(api.SearchPosts(client, args[0], maxResults, opts))
+			printResult(api.SearchPostsBetween(client, args[0], maxResults, since, until, opts))
 		},
 	}
 	cmd.Flags().IntVarP(&maxResults, "max-results", "n", 10, "Number of results (min 10, max 100)")
+	cmd.Flags().StringVar(&sinceExpr, "since", "", `Only posts created at or after this time (e.g. "2 days ago", "yesterday", 2024-05-01)`)
+	cmd.Flags().StringVar(&untilExpr, "until", "", `Only posts created before this time (e.g. "now", "6h")`)
 	addCommonFlags(cmd)
 	return cmd
 }
 
-func postsCmd(a *auth.Auth) *cobra.Command {
+// searchTimeRange parses --since/--until relative to now. An --until within the
+// last 10 seconds is left open: the API rejects an end_time that recent, and
+// leaving it out already means "up to now".
+func searchTimeRange(sinceExpr, untilExpr string, now time.Time) (since, until time.Time, err error) {
+	if sinceExpr != "" {
+		if since, err = utils.ParseTimeExpression(sinceExpr, now); err != nil {
+			return time.Time{}, time.Time{}, fmt.Errorf("--since: %v", err)
+		}
+	}
+	if untilExpr != "" {
+		if until, err = utils.ParseTimeExpression(untilExpr, now); err != nil {
+			return time.Time{}, time.Time{}, fmt.Errorf("--until: %v", err)
+		}
+		if until.After(now.Add(-10 * time.Second)) {
+			until = time.Time{}
+		}
+	}
+	if !since.IsZero() && !until.IsZero() && !since.Before(until) {
+		return time.Time{}, time.Time{}, fmt.Errorf("--since (%s) must be before --until (%s)", since.Format(time.RFC3339), until.Format(time.RFC3339))
+	}
+	return since, until, nil
+}
+
+func postsCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	var maxResults int
 	cmd := &cobra.Command{
 		Use:   "posts USERNAME",
@@ -289,11 +499,11 @@ Examples:
   xurl posts @XDevelopers -n 50`,
 		Args: cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			userID, err := resolveUserID(client, args[0], opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.GetUserPosts(client, userID, maxResults, opts))
@@ -308,7 +518,7 @@ Examples:
 //  USER INFO
 // =================================================================
 
-func whoamiCmd(a *auth.Auth) *cobra.Command {
+func whoamiCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "whoami",
 		Short: "Show the authenticated user's profile",
@@ -318,7 +528,7 @@ Examples:
   xurl whoami`,
 		Args: cobra.NoArgs,
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			if opts.Username != "" {
 				printResult(api.LookupUser(client, opts.Username, opts))
@@ -331,7 +541,7 @@ Examples:
 	return cmd
 }
 
-func userCmd(a *auth.Auth) *cobra.Command {
+func userCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "user USERNAME",
 		Short: "Look up a user by username",
@@ -342,7 +552,7 @@ Examples:
   xurl user @XDevelopers`,
 		Args: cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			printResult(api.LookupUser(client, args[0], opts))
 		},
@@ -355,7 +565,7 @@ Examples:
 //  TIMELINE & MENTIONS
 // =================================================================
 
-func timelineCmd(a *auth.Auth) *cobra.Command {
+func timelineCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	var maxResults int
 	cmd := &cobra.Command{
 		Use:   "timeline",
@@ -367,11 +577,11 @@ Examples:
   xurl timeline -n 25`,
 		Args: cobra.NoArgs,
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			userID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.GetTimeline(client, userID, maxResults, opts))
@@ -382,7 +592,7 @@ Examples:
 	return cmd
 }
 
-func mentionsCmd(a *auth.Auth) *cobra.Command {
+func mentionsCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	var maxResults int
 	cmd := &cobra.Command{
 		Use:   "mentions",
@@ -394,11 +604,11 @@ Examples:
   xurl mentions -n 25`,
 		Args: cobra.NoArgs,
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			userID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.GetMentions(client, userID, maxResults, opts))
@@ -413,7 +623,7 @@ Examples:
 //  ENGAGEMENT — Like / Repost / Bookmark
 // =================================================================
 
-func likeCmd(a *auth.Auth) *cobra.Command {
+func likeCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "like POST_ID_OR_URL",
 		Short: "Like a post",
@@ -424,11 +634,11 @@ Examples:
   xurl like https://x.com/user/status/1234567890`,
 		Args: cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			userID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.LikePost(client, userID, args[0], opts))
@@ -438,17 +648,17 @@ Examples:
 	return cmd
 }
 
-func unlikeCmd(a *auth.Auth) *cobra.Command {
+func unlikeCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "unlike POST_ID_OR_URL",
 		Short: "Unlike a post",
 		Args:  cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			userID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.UnlikePost(client, userID, args[0], opts))
@@ -458,7 +668,7 @@ func unlikeCmd(a *auth.Auth) *cobra.Command {
 	return cmd
 }
 
-func repostCmd(a *auth.Auth) *cobra.Command {
+func repostCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "repost POST_ID_OR_URL",
 		Short: "Repost a post",
@@ -469,11 +679,11 @@ Examples:
   xurl repost https://x.com/user/status/1234567890`,
 		Args: cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			userID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.Repost(client, userID, args[0], opts))
@@ -483,17 +693,17 @@ Examples:
 	return cmd
 }
 
-func unrepostCmd(a *auth.Auth) *cobra.Command {
+func unrepostCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "unrepost POST_ID_OR_URL",
 		Short: "Undo a repost",
 		Args:  cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			userID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.Unrepost(client, userID, args[0], opts))
@@ -503,7 +713,7 @@ func unrepostCmd(a *auth.Auth) *cobra.Command {
 	return cmd
 }
 
-func bookmarkCmd(a *auth.Auth) *cobra.Command {
+func bookmarkCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "bookmark POST_ID_OR_URL",
 		Short: "Bookmark a post",
@@ -514,11 +724,11 @@ Examples:
   xurl bookmark https://x.com/user/status/1234567890`,
 		Args: cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			userID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.Bookmark(client, userID, args[0], opts))
@@ -528,17 +738,17 @@ Examples:
 	return cmd
 }
 
-func unbookmarkCmd(a *auth.Auth) *cobra.Command {
+func unbookmarkCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "unbookmark POST_ID_OR_URL",
 		Short: "Remove a bookmark",
 		Args:  cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			userID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.Unbookmark(client, userID, args[0], opts))
@@ -548,7 +758,7 @@ func unbookmarkCmd(a *auth.Auth) *cobra.Command {
 	return cmd
 }
 
-func bookmarksCmd(a *auth.Auth) *cobra.Command {
+func bookmarksCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	var maxResults int
 	cmd := &cobra.Command{
 		Use:   "bookmarks",
@@ -560,11 +770,11 @@ Examples:
   xurl bookmarks -n 25`,
 		Args: cobra.NoArgs,
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			userID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.GetBookmarks(client, userID, maxResults, opts))
@@ -575,7 +785,7 @@ Examples:
 	return cmd
 }
 
-func likesCmd(a *auth.Auth) *cobra.Command {
+func likesCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	var maxResults int
 	cmd := &cobra.Command{
 		Use:   "likes",
@@ -587,11 +797,11 @@ Examples:
   xurl likes -n 25`,
 		Args: cobra.NoArgs,
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			userID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.GetLikedPosts(client, userID, maxResults, opts))
@@ -606,7 +816,7 @@ Examples:
 //  SOCIAL GRAPH — Follow / Block / Mute
 // =================================================================
 
-func followCmd(a *auth.Auth) *cobra.Command {
+func followCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "follow USERNAME",
 		Short: "Follow a user",
@@ -617,16 +827,16 @@ Examples:
   xurl follow @XDevelopers`,
 		Args: cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			myID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			targetID, err := resolveUserID(client, args[0], opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.FollowUser(client, myID, targetID, opts))
@@ -636,22 +846,22 @@ Examples:
 	return cmd
 }
 
-func unfollowCmd(a *auth.Auth) *cobra.Command {
+func unfollowCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "unfollow USERNAME",
 		Short: "Unfollow a user",
 		Args:  cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			myID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			targetID, err := resolveUserID(client, args[0], opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.UnfollowUser(client, myID, targetID, opts))
@@ -661,7 +871,7 @@ func unfollowCmd(a *auth.Auth) *cobra.Command {
 	return cmd
 }
 
-func followingCmd(a *auth.Auth) *cobra.Command {
+func followingCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	var maxResults int
 	var targetUser string
 	cmd := &cobra.Command{
@@ -674,7 +884,7 @@ Examples:
   xurl following --of elonmusk -n 50`,
 		Args: cobra.NoArgs,
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			var userID string
 			var err error
@@ -684,7 +894,7 @@ Examples:
 				userID, err = resolveMyUserID(client, opts)
 			}
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.GetFollowing(client, userID, maxResults, opts))
@@ -696,7 +906,7 @@ Examples:
 	return cmd
 }
 
-func followersCmd(a *auth.Auth) *cobra.Command {
+func followersCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	var maxResults int
 	var targetUser string
 	cmd := &cobra.Command{
@@ -709,7 +919,7 @@ Examples:
   xurl followers --of elonmusk -n 50`,
 		Args: cobra.NoArgs,
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			var userID string
 			var err error
@@ -719,7 +929,7 @@ Examples:
 				userID, err = resolveMyUserID(client, opts)
 			}
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.GetFollowers(client, userID, maxResults, opts))
@@ -731,22 +941,22 @@ Examples:
 	return cmd
 }
 
-func blockCmd(a *auth.Auth) *cobra.Command {
+func blockCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "block USERNAME",
 		Short: "Block a user",
 		Args:  cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			myID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			targetID, err := resolveUserID(client, args[0], opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.BlockUser(client, myID, targetID, opts))
@@ -756,22 +966,22 @@ func blockCmd(a *auth.Auth) *cobra.Command {
 	return cmd
 }
 
-func unblockCmd(a *auth.Auth) *cobra.Command {
+func unblockCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "unblock USERNAME",
 		Short: "Unblock a user",
 		Args:  cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			myID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			targetID, err := resolveUserID(client, args[0], opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.UnblockUser(client, myID, targetID, opts))
@@ -781,22 +991,22 @@ func unblockCmd(a *auth.Auth) *cobra.Command {
 	return cmd
 }
 
-func muteCmd(a *auth.Auth) *cobra.Command {
+func muteCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "mute USERNAME",
 		Short: "Mute a user",
 		Args:  cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			myID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			targetID, err := resolveUserID(client, args[0], opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.MuteUser(client, myID, targetID, opts))
@@ -806,22 +1016,22 @@ func muteCmd(a *auth.Auth) *cobra.Command {
 	return cmd
 }
 
-func unmuteCmd(a *auth.Auth) *cobra.Command {
+func unmuteCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	cmd := &cobra.Command{
 		Use:   "unmute USERNAME",
 		Short: "Unmute a user",
 		Args:  cobra.ExactArgs(1),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			myID, err := resolveMyUserID(client, opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			targetID, err := resolveUserID(client, args[0], opts)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
 			printResult(api.UnmuteUser(client, myID, targetID, opts))
@@ -835,32 +1045,95 @@ func unmuteCmd(a *auth.Auth) *cobra.Command {
 //  DIRECT MESSAGES
 // =================================================================
 
-func dmCmd(a *auth.Auth) *cobra.Command {
+func dmCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
+	var to, conversation, text string
 	cmd := &cobra.Command{
 		Use:   `dm USERNAME "TEXT"`,
 		Short: "Send a direct message",
-		Long: `Send a direct message to a user.
+		Long: `Send a direct message to a user, or to an existing conversation.
+
+A message to a user (USERNAME, or --to with a user ID) goes to
+POST /2/dm_conversations/with/USER_ID/messages, which starts the one-to-one
+conversation if there is none yet. --conversation posts to an existing
+conversation (one-to-one or group) by its ID instead. DMs need user-context
+auth with the dm.write scope; see xurl auth oauth2 --scope-preset dm.
 
 Examples:
   xurl dm @elonmusk "Hey, great post!"
-  xurl dm someuser "Hello there"`,
-		Args: cobra.ExactArgs(2),
+  xurl dm someuser "Hello there"
+  xurl dm --to 1234567890 --text "Hello there"
+  xurl dm --conversation 1234567890-9876543210 --text "Hello again"`,
+		Args: cobra.MaximumNArgs(2),
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
-			opts := baseOpts(cmd)
-			targetID, err := resolveUserID(client, args[0], opts)
+			target, err := dmTarget(args, to, conversation, text)
 			if err != nil {
-				fmt.Fprintf(os.Stderr, "\033[31mError: %v\033[0m\n", err)
+				printError(err)
 				os.Exit(1)
 			}
-			printResult(api.SendDM(client, targetID, args[1], opts))
+			client := newClient(cmd, cfg, a)
+			opts := baseOpts(cmd)
+			if target.conversationID != "" {
+				printResult(api.SendDMToConversation(client, target.conversationID, target.text, opts))
+				return
+			}
+			if target.userID == "" {
+				target.userID, err = resolveUserID(client, target.username, opts)
+				if err != nil {
+					printError(err)
+					os.Exit(1)
+				}
+			}
+			printResult(api.SendDM(client, target.userID, target.text, opts))
 		},
 	}
+	cmd.Flags().StringVar(&to, "to", "", "Recipient user ID")
+	cmd.Flags().StringVar(&conversation, "conversation", "", "ID of an existing conversation to post to instead of a user")
+	cmd.Flags().StringVar(&text, "text", "", "Message text")
 	addCommonFlags(cmd)
 	return cmd
 }
 
-func dmsCmd(a *auth.Auth) *cobra.Command {
+// dmMessage is where dm sends a message: exactly one of username (to look
+// up), userID, and conversationID is set.
+type dmMessage struct {
+	username       string
+	userID         string
+	conversationID string
+	text           string
+}
+
+// dmTarget works out dm's message from its arguments and flags. Positional
+// arguments are USERNAME TEXT, or just TEXT when --to or --conversation names
+// the target.
+func dmTarget(args []string, to, conversation, text string) (dmMessage, error) {
+	var target dmMessage
+	switch len(args) {
+	case 2:
+		if to != "" || conversation != "" || text != "" {
+			return target, fmt.Errorf("give the recipient and text either as arguments or with --to/--conversation and --text, not both")
+		}
+		target = dmMessage{username: args[0], text: args[1]}
+	case 1:
+		if text != "" {
+			return target, fmt.Errorf("give the text either as an argument or with --text, not both")
+		}
+		target = dmMessage{userID: to, conversationID: conversation, text: args[0]}
+	default:
+		target = dmMessage{userID: to, conversationID: conversation, text: text}
+	}
+	if target.userID != "" && target.conversationID != "" {
+		return target, fmt.Errorf("--to and --conversation are mutually exclusive")
+	}
+	if target.username == "" && target.userID == "" && target.conversationID == "" {
+		return target, fmt.Errorf("no recipient: give a USERNAME, --to, or --conversation")
+	}
+	if target.text == "" {
+		return target, fmt.Errorf("no message text: give TEXT or --text")
+	}
+	return target, nil
+}
+
+func dmsCmd(cfg *config.Config, a *auth.Auth) *cobra.Command {
 	var maxResults int
 	cmd := &cobra.Command{
 		Use:   "dms",
@@ -872,7 +1145,7 @@ Examples:
   xurl dms -n 25`,
 		Args: cobra.NoArgs,
 		Run: func(cmd *cobra.Command, args []string) {
-			client := newClient(a)
+			client := newClient(cmd, cfg, a)
 			opts := baseOpts(cmd)
 			printResult(api.GetDMEvents(client, maxResults, opts))
 		},
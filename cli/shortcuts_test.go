@@ -3,13 +3,18 @@ package cli
 import (
 	"encoding/json"
 	"fmt"
+	"io"
 	"net/http"
 	"testing"
+	"time"
 
+	"github.com/spf13/cobra"
 	"github.com/stretchr/testify/assert"
 	"github.com/stretchr/testify/require"
 
 	"github.com/xdevplatform/xurl/api"
+	"github.com/xdevplatform/xurl/auth"
+	"github.com/xdevplatform/xurl/config"
 )
 
 type fakeClient struct {
@@ -61,3 +66,140 @@ func TestResolveMyUserIDReturnsHelpfulErrorWhenGetMeFails(t *testing.T) {
 	require.Error(t, err)
 	assert.Contains(t, err.Error(), "try --username")
 }
+
+func TestSearchTimeRange(t *testing.T) {
+	now := time.Date(2024, 5, 10, 12, 0, 0, 0, time.UTC)
+
+	since, until, err := searchTimeRange("2 days ago", "1 day ago", now)
+	require.NoError(t, err)
+	assert.Equal(t, now.Add(-48*time.Hour), since)
+	assert.Equal(t, now.Add(-24*time.Hour), until)
+
+	_, until, err = searchTimeRange("", "now", now)
+	require.NoError(t, err)
+	assert.True(t, until.IsZero(), "--until now leaves end_time open")
+
+	_, _, err = searchTimeRange("next tuesday", "", now)
+	assert.ErrorContains(t, err, "--since")
+
+	_, _, err = searchTimeRange("1 day ago", "2 days ago", now)
+	assert.ErrorContains(t, err, "must be before")
+}
+
+func TestDeleteTweet(t *testing.T) {
+	var got api.RequestOptions
+	client := fakeClient{
+		sendRequest: func(options api.RequestOptions) (json.RawMessage, error) {
+			got = options
+			return json.RawMessage(`{"data":{"deleted":true}}`), nil
+		},
+	}
+
+	deleted, err := deleteTweet(client, "https://x.com/user/status/123", api.RequestOptions{})
+	require.NoError(t, err)
+	assert.True(t, deleted)
+	assert.Equal(t, "DELETE", got.Method)
+	assert.Equal(t, "/2/tweets/123", got.Endpoint)
+	assert.Equal(t, "oauth2", got.AuthType, "user-context auth by default")
+
+	_, err = deleteTweet(client, "123", api.RequestOptions{AuthType: "oauth1"})
+	require.NoError(t, err)
+	assert.Equal(t, "oauth1", got.AuthType, "--auth wins")
+
+	client.sendRequest = func(options api.RequestOptions) (json.RawMessage, error) {
+		return json.RawMessage(`{"data":{"deleted":false}}`), nil
+	}
+	deleted, err = deleteTweet(client, "123", api.RequestOptions{})
+	require.NoError(t, err)
+	assert.False(t, deleted)
+}
+
+func TestDMTarget(t *testing.T) {
+	tests := []struct {
+		name         string
+		args         []string
+		to           string
+		conversation string
+		text         string
+		want         dmMessage
+		wantErr      string
+	}{
+		{name: "username and text", args: []string{"@alice", "hi"}, want: dmMessage{username: "@alice", text: "hi"}},
+		{name: "user ID flags", to: "42", text: "hi", want: dmMessage{userID: "42", text: "hi"}},
+		{name: "conversation with text argument", args: []string{"hi"}, conversation: "1-42", want: dmMessage{conversationID: "1-42", text: "hi"}},
+		{name: "both targets", to: "42", conversation: "1-42", text: "hi", wantErr: "mutually exclusive"},
+		{name: "arguments and flags", args: []string{"@alice", "hi"}, to: "42", wantErr: "not both"},
+		{name: "text twice", args: []string{"hi"}, to: "42", text: "hi", wantErr: "not both"},
+		{name: "no recipient", text: "hi", wantErr: "no recipient"},
+		{name: "no text", to: "42", wantErr: "no message text"},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			got, err := dmTarget(tt.args, tt.to, tt.conversation, tt.text)
+			if tt.wantErr != "" {
+				assert.ErrorContains(t, err, tt.wantErr)
+				return
+			}
+			require.NoError(t, err)
+			assert.Equal(t, tt.want, got)
+		})
+	}
+}
+
+func TestTweetSubcommandsRequireFlags(t *testing.T) {
+	tests := []struct {
+		args []string
+		flag string
+	}{
+		{[]string{"create"}, "text"},
+		{[]string{"delete"}, "id"},
+		{[]string{"like"}, "id"},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.args[0], func(t *testing.T) {
+			cmd := tweetCmd(nil, nil)
+			cmd.SetArgs(tt.args)
+			cmd.SetOut(io.Discard)
+			cmd.SetErr(io.Discard)
+
+			err := cmd.Execute()
+			require.Error(t, err)
+			assert.Contains(t, err.Error(), fmt.Sprintf(`required flag(s) "%s" not set`, tt.flag))
+		})
+	}
+}
+
+func TestVerbosity(t *testing.T) {
+	tests := []struct {
+		args        []string
+		verbose     bool
+		verboseBody bool
+	}{
+		{nil, false, false},
+		{[]string{"-v"}, true, false},
+		{[]string{"-vv"}, true, true},
+		{[]string{"-v", "-v"}, true, true},
+		{[]string{"--verbose-body"}, true, true},
+	}
+
+	for _, tt := range tests {
+		cmd := &cobra.Command{Use: "x", Run: func(*cobra.Command, []string) {}}
+		addCommonFlags(cmd)
+		require.NoError(t, cmd.ParseFlags(tt.args))
+
+		verbose, verboseBody := verbosity(cmd)
+		assert.Equal(t, tt.verbose, verbose, "%v", tt.args)
+		assert.Equal(t, tt.verboseBody, verboseBody, "%v", tt.args)
+	}
+
+	// Commands with their own flags count -v the same way.
+	cfg := &config.Config{}
+	a := auth.NewAuth(cfg)
+	for _, cmd := range []*cobra.Command{CreateBatchCommand(cfg, a), CreateDownloadCommand(cfg, a), createMediaUploadCmd(cfg, a), createMediaStatusCmd(cfg, a)} {
+		require.NoError(t, cmd.ParseFlags([]string{"-vv"}))
+		verbose, verboseBody := verbosity(cmd)
+		assert.True(t, verbose, cmd.Name())
+		assert.True(t, verboseBody, cmd.Name())
+	}
+}
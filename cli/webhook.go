@@ -68,11 +68,15 @@ func CreateWebhookCommand(authInstance *auth.Auth) *cobra.Command {
 				color.Green("Logging POST request bodies to: %s", outputFileName)
 			}
 
-			// Prompt for ngrok authtoken
-			color.Yellow("Enter your ngrok authtoken (leave empty to try NGROK_AUTHTOKEN env var): ")
-			reader := bufio.NewReader(os.Stdin)
-			ngrokAuthToken, _ := reader.ReadString('\n')
-			ngrokAuthToken = strings.TrimSpace(ngrokAuthToken)
+			// Prompt for ngrok authtoken; non-interactive runs go straight to
+			// NGROK_AUTHTOKEN.
+			var ngrokAuthToken string
+			if !authInstance.NonInteractive() {
+				color.Yellow("Enter your ngrok authtoken (leave empty to try NGROK_AUTHTOKEN env var): ")
+				reader := bufio.NewReader(os.Stdin)
+				ngrokAuthToken, _ = reader.ReadString('\n')
+				ngrokAuthToken = strings.TrimSpace(ngrokAuthToken)
+			}
 
 			ctx := context.Background()
 			var tunnelOpts []ngrok.ConnectOption
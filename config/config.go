@@ -2,13 +2,27 @@ package config
 
 import (
 	"fmt"
+	"net"
+	"net/url"
 	"os"
+	"strconv"
+	"strings"
+	"time"
 
 	"github.com/xdevplatform/xurl/store"
 )
 
 const DefaultRedirectURI = "http://localhost:8080/callback"
 
+// DefaultTokenRefreshMargin is how long before its expiry an OAuth2 access token
+// is refreshed, so a token is not handed to a long request or upload that will
+// outlive it.
+const DefaultTokenRefreshMargin = 300 * time.Second
+
+// DefaultTokenExpiryWarning is how close to its expiry an OAuth2 access token
+// must be for a long-running command to warn that it may outlive the token.
+const DefaultTokenExpiryWarning = 10 * time.Minute
+
 // Config holds the application configuration
 type Config struct {
 	// OAuth2 client tokens (may come from env vars or the active app in .xurl)
@@ -26,6 +40,19 @@ type Config struct {
 	InfoURL string
 	// AppName is the explicit --app override; empty means "use default".
 	AppName string
+	// TokenRefreshMargin refreshes OAuth2 tokens this long before they expire
+	// (XURL_TOKEN_REFRESH_MARGIN); zero means DefaultTokenRefreshMargin.
+	TokenRefreshMargin time.Duration
+	// TokenExpiryWarning is the remaining lifetime below which long-running
+	// commands warn about the OAuth2 token (XURL_TOKEN_EXPIRY_WARNING); zero
+	// means DefaultTokenExpiryWarning.
+	TokenExpiryWarning time.Duration
+	// NonInteractive makes anything that would open a browser or prompt fail
+	// instead (XURL_NON_INTERACTIVE).
+	NonInteractive bool
+	// URLRewrites redirect API requests from one host to another
+	// (--url-rewrite).
+	URLRewrites []URLRewrite
 }
 
 // NewConfig creates a new Config from environment variables
@@ -42,6 +69,9 @@ func NewConfigForApp(appName string) *Config {
 	tokenURL := getEnvOrDefault("TOKEN_URL", "https://api.x.com/2/oauth2/token")
 	apiBaseURL := getEnvOrDefault("API_BASE_URL", "https://api.x.com")
 	infoURL := getEnvOrDefault("INFO_URL", fmt.Sprintf("%s/2/users/me", apiBaseURL))
+	refreshMargin := parsePositiveDuration(getEnvOrDefault("XURL_TOKEN_REFRESH_MARGIN", ""), DefaultTokenRefreshMargin)
+	expiryWarning := parsePositiveDuration(getEnvOrDefault("XURL_TOKEN_EXPIRY_WARNING", ""), DefaultTokenExpiryWarning)
+	nonInteractive, _ := strconv.ParseBool(getEnvOrDefault("XURL_NON_INTERACTIVE", "false"))
 
 	return &Config{
 		ClientID:           clientID,
@@ -53,6 +83,154 @@ func NewConfigForApp(appName string) *Config {
 		APIBaseURL:         apiBaseURL,
 		InfoURL:            infoURL,
 		AppName:            appName,
+		TokenRefreshMargin: refreshMargin,
+		TokenExpiryWarning: expiryWarning,
+		NonInteractive:     nonInteractive,
+	}
+}
+
+// NormalizeAPIBaseURL checks that baseURL is an absolute http(s) URL without a
+// query or fragment, and returns it without trailing slashes so that joining it
+// with an endpoint never doubles them.
+func NormalizeAPIBaseURL(baseURL string) (string, error) {
+	parsed, err := url.Parse(baseURL)
+	if err != nil || (parsed.Scheme != "http" && parsed.Scheme != "https") || parsed.Host == "" {
+		return "", fmt.Errorf("invalid base URL %q: expected an absolute http:// or https:// URL", baseURL)
+	}
+	if parsed.RawQuery != "" || parsed.Fragment != "" {
+		return "", fmt.Errorf("invalid base URL %q: it cannot have a query or fragment", baseURL)
+	}
+	return strings.TrimRight(baseURL, "/"), nil
+}
+
+// URLRewrite sends the requests for one host to another, e.g. from api.x.com
+// to an environment's gateway.
+type URLRewrite struct {
+	// From is the host to rewrite, with a port if only that port matches.
+	From string
+	// Scheme replaces the URL's scheme; empty keeps it.
+	Scheme string
+	// Host is the host to send the request to instead; without a port, the
+	// request keeps its own.
+	Host string
+}
+
+// ParseURLRewrite parses a --url-rewrite rule, "FROM=>TO", where FROM is a
+// host (optionally with a port) and TO is a host, optionally with a port and
+// preceded by an http:// or https:// scheme: "api.x.com=>sandbox.internal" or
+// "api.x.com=>http://localhost:8080".
+func ParseURLRewrite(rule string) (URLRewrite, error) {
+	from, to, ok := strings.Cut(rule, "=>")
+	from, to = strings.TrimSpace(from), strings.TrimSpace(to)
+	if !ok || from == "" || to == "" || strings.ContainsAny(from, "/?#") {
+		return URLRewrite{}, fmt.Errorf("invalid URL rewrite %q: expected FROM=>TO, e.g. api.x.com=>sandbox.internal", rule)
+	}
+	rewrite := URLRewrite{From: strings.ToLower(from), Host: to}
+	if scheme, host, ok := strings.Cut(to, "://"); ok {
+		if scheme != "http" && scheme != "https" {
+			return URLRewrite{}, fmt.Errorf("invalid URL rewrite %q: the target scheme must be http or https", rule)
+		}
+		rewrite.Scheme, rewrite.Host = scheme, host
+	}
+	if rewrite.Host == "" || strings.ContainsAny(rewrite.Host, "/?#") {
+		return URLRewrite{}, fmt.Errorf("invalid URL rewrite %q: the target must be a host, with no path", rule)
+	}
+	return rewrite, nil
+}
+
+// Apply rewrites u when its host matches r.From, reporting whether it did. As
+// with curl's --connect-to, u keeps its port when r.Host has none.
+func (r URLRewrite) Apply(u *url.URL) bool {
+	if !strings.EqualFold(u.Host, r.From) && !strings.EqualFold(u.Hostname(), r.From) {
+		return false
+	}
+	if r.Scheme != "" {
+		u.Scheme = r.Scheme
+	}
+	host := r.Host
+	if target := (&url.URL{Host: host}); target.Port() == "" && u.Port() != "" {
+		host = net.JoinHostPort(target.Hostname(), u.Port())
+	}
+	u.Host = host
+	return true
+}
+
+// parsePositiveDuration accepts a number of seconds ("600") or a Go duration
+// ("10m"). Empty, invalid, or non-positive values yield fallback.
+func parsePositiveDuration(value string, fallback time.Duration) time.Duration {
+	if value == "" {
+		return fallback
+	}
+	if seconds, err := strconv.Atoi(value); err == nil {
+		if seconds > 0 {
+			return time.Duration(seconds) * time.Second
+		}
+		return fallback
+	}
+	if d, err := time.ParseDuration(value); err == nil && d > 0 {
+		return d
+	}
+	return fallback
+}
+
+// Setting is one effective configuration value and where it came from.
+type Setting struct {
+	Name   string
+	Value  string
+	Source string
+}
+
+// EffectiveSettings lists the configuration in effect for appName (empty for
+// the default app), resolved the same way as NewConfigForApp and the app's
+// stored credentials, with the client secret redacted. baseURL is the
+// invocation's --base-url, already normalized, or empty without the flag.
+func EffectiveSettings(appName, baseURL string) []Setting {
+	ts := store.NewTokenStore()
+	resolvedName := ts.GetActiveAppName(appName)
+	app := ts.GetApp(resolvedName)
+
+	fromEnvOrApp := func(name string, appValue func(*store.App) string) Setting {
+		if value, ok := os.LookupEnv(name); ok {
+			return Setting{name, value, "environment variable"}
+		}
+		if app != nil && appValue(app) != "" {
+			return Setting{name, appValue(app), "app config"}
+		}
+		return Setting{name, "", "not set"}
+	}
+	fromEnvOrDefault := func(name, defaultValue string) Setting {
+		if value, ok := os.LookupEnv(name); ok {
+			return Setting{name, value, "environment variable"}
+		}
+		return Setting{name, defaultValue, "built-in default"}
+	}
+
+	appSource := "default app"
+	if appName != "" {
+		appSource = "--app flag"
+	}
+	clientSecret := fromEnvOrApp("CLIENT_SECRET", func(a *store.App) string { return a.ClientSecret })
+	if clientSecret.Value != "" {
+		clientSecret.Value = "(redacted)"
+	}
+	redirectURI, _, redirectSource := ResolveRedirectURI(resolvedName)
+	apiBaseURL := fromEnvOrDefault("API_BASE_URL", "https://api.x.com")
+	if baseURL != "" {
+		apiBaseURL = Setting{"API_BASE_URL", baseURL, "--base-url flag"}
+	}
+
+	return []Setting{
+		{"app", resolvedName, appSource},
+		fromEnvOrApp("CLIENT_ID", func(a *store.App) string { return a.ClientID }),
+		clientSecret,
+		{"REDIRECT_URI", redirectURI, redirectSource},
+		fromEnvOrDefault("AUTH_URL", "https://x.com/i/oauth2/authorize"),
+		fromEnvOrDefault("TOKEN_URL", "https://api.x.com/2/oauth2/token"),
+		apiBaseURL,
+		fromEnvOrDefault("INFO_URL", fmt.Sprintf("%s/2/users/me", apiBaseURL.Value)),
+		fromEnvOrDefault("XURL_TOKEN_REFRESH_MARGIN", DefaultTokenRefreshMargin.String()),
+		fromEnvOrDefault("XURL_TOKEN_EXPIRY_WARNING", DefaultTokenExpiryWarning.String()),
+		fromEnvOrDefault("XURL_NON_INTERACTIVE", "false"),
 	}
 }
 
@@ -1,9 +1,11 @@
 package config
 
 import (
+	"net/url"
 	"os"
 	"path/filepath"
 	"testing"
+	"time"
 
 	"github.com/stretchr/testify/assert"
 	"github.com/stretchr/testify/require"
@@ -75,3 +77,107 @@ func TestNewConfigForAppUsesRedirectURIResolution(t *testing.T) {
 	assert.Equal(t, "http://127.0.0.1:8080/callback", cfg.RedirectURI)
 	assert.True(t, cfg.RedirectURIFromEnv)
 }
+
+func TestParsePositiveDuration(t *testing.T) {
+	assert.Equal(t, DefaultTokenRefreshMargin, parsePositiveDuration("", DefaultTokenRefreshMargin))
+	assert.Equal(t, 600*time.Second, parsePositiveDuration("600", DefaultTokenRefreshMargin))
+	assert.Equal(t, 10*time.Minute, parsePositiveDuration("10m", DefaultTokenRefreshMargin))
+	assert.Equal(t, DefaultTokenRefreshMargin, parsePositiveDuration("0", DefaultTokenRefreshMargin))
+	assert.Equal(t, DefaultTokenRefreshMargin, parsePositiveDuration("-5", DefaultTokenRefreshMargin))
+	assert.Equal(t, DefaultTokenRefreshMargin, parsePositiveDuration("soon", DefaultTokenRefreshMargin))
+
+	t.Setenv("XURL_TOKEN_REFRESH_MARGIN", "90")
+	t.Setenv("XURL_TOKEN_EXPIRY_WARNING", "30m")
+	cfg := NewConfig()
+	assert.Equal(t, 90*time.Second, cfg.TokenRefreshMargin)
+	assert.Equal(t, 30*time.Minute, cfg.TokenExpiryWarning)
+}
+
+func TestNonInteractiveFromEnv(t *testing.T) {
+	for value, want := range map[string]bool{"1": true, "true": true, "0": false, "": false, "nope": false} {
+		t.Setenv("XURL_NON_INTERACTIVE", value)
+		assert.Equal(t, want, NewConfig().NonInteractive, "XURL_NON_INTERACTIVE=%q", value)
+	}
+}
+
+func TestEffectiveSettings(t *testing.T) {
+	tempDir, err := os.MkdirTemp("", "xurl-config-test")
+	require.NoError(t, err)
+	defer os.RemoveAll(tempDir)
+
+	t.Setenv("HOME", tempDir)
+	ts := store.NewTokenStore()
+	require.NoError(t, ts.AddApp("my-app", "stored-id", "stored-secret"))
+	require.NoError(t, ts.SetAppRedirectURI("my-app", "http://localhost:9090/callback"))
+
+	for _, name := range []string{"CLIENT_ID", "CLIENT_SECRET", "REDIRECT_URI", "AUTH_URL", "API_BASE_URL", "INFO_URL", "XURL_TOKEN_REFRESH_MARGIN", "XURL_TOKEN_EXPIRY_WARNING"} {
+		t.Setenv(name, "")
+		os.Unsetenv(name)
+	}
+	t.Setenv("TOKEN_URL", "http://127.0.0.1:9999/token")
+
+	settings := map[string]Setting{}
+	for _, s := range EffectiveSettings("my-app", "") {
+		settings[s.Name] = s
+	}
+
+	assert.Equal(t, Setting{"app", "my-app", "--app flag"}, settings["app"])
+	assert.Equal(t, Setting{"CLIENT_ID", "stored-id", "app config"}, settings["CLIENT_ID"])
+	assert.Equal(t, Setting{"CLIENT_SECRET", "(redacted)", "app config"}, settings["CLIENT_SECRET"])
+	assert.Equal(t, Setting{"REDIRECT_URI", "http://localhost:9090/callback", "app config"}, settings["REDIRECT_URI"])
+	assert.Equal(t, Setting{"TOKEN_URL", "http://127.0.0.1:9999/token", "environment variable"}, settings["TOKEN_URL"])
+	assert.Equal(t, Setting{"AUTH_URL", "https://x.com/i/oauth2/authorize", "built-in default"}, settings["AUTH_URL"])
+	assert.Equal(t, "https://api.x.com/2/users/me", settings["INFO_URL"].Value)
+
+	for _, s := range EffectiveSettings("my-app", "http://127.0.0.1:8000/mock") {
+		settings[s.Name] = s
+	}
+	assert.Equal(t, Setting{"API_BASE_URL", "http://127.0.0.1:8000/mock", "--base-url flag"}, settings["API_BASE_URL"])
+	assert.Equal(t, "http://127.0.0.1:8000/mock/2/users/me", settings["INFO_URL"].Value)
+}
+
+func TestNormalizeAPIBaseURL(t *testing.T) {
+	normalized, err := NormalizeAPIBaseURL("http://127.0.0.1:8000/mock//")
+	require.NoError(t, err)
+	assert.Equal(t, "http://127.0.0.1:8000/mock", normalized, "trailing slashes are dropped")
+
+	for _, invalid := range []string{"api.x.com", "/2", "ftp://api.x.com", "https://", "https://api.x.com?x=1"} {
+		_, err := NormalizeAPIBaseURL(invalid)
+		assert.Error(t, err, invalid)
+	}
+}
+
+func TestURLRewrite(t *testing.T) {
+	var rewrites []URLRewrite
+	for _, rule := range []string{"API.x.com=>sandbox.internal", "upload.x.com:443 => http://localhost:8080"} {
+		rewrite, err := ParseURLRewrite(rule)
+		require.NoError(t, err)
+		rewrites = append(rewrites, rewrite)
+	}
+	assert.Equal(t, []URLRewrite{
+		{From: "api.x.com", Host: "sandbox.internal"},
+		{From: "upload.x.com:443", Scheme: "http", Host: "localhost:8080"},
+	}, rewrites)
+
+	for raw, want := range map[string]string{
+		"https://api.x.com/2/users/me?x=1":    "https://sandbox.internal/2/users/me?x=1",
+		"https://API.X.COM:8443/2/tweets":     "https://sandbox.internal:8443/2/tweets",
+		"https://upload.x.com:443/1.1/media":  "http://localhost:8080/1.1/media",
+		"https://upload.x.com/1.1/media":      "https://upload.x.com/1.1/media",
+		"https://api.x.com.evil.example.com/": "https://api.x.com.evil.example.com/",
+	} {
+		u, err := url.Parse(raw)
+		require.NoError(t, err)
+		for _, rewrite := range rewrites {
+			if rewrite.Apply(u) {
+				break
+			}
+		}
+		assert.Equal(t, want, u.String(), raw)
+	}
+
+	for _, invalid := range []string{"api.x.com", "=>b", "a=>", "a/b=>c", "a=>ftp://b", "a=>https://b/path", "a=>https://"} {
+		_, err := ParseURLRewrite(invalid)
+		assert.Error(t, err, invalid)
+	}
+}
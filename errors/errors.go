@@ -14,6 +14,9 @@ const (
 	ErrTypeJSON          = "JSON Error"
 	ErrTypeAuth          = "Auth Error"
 	ErrTypeTokenStore    = "Token Store Error"
+	// ErrTypeInteractionRequired marks an operation that needed a browser or
+	// a prompt while running non-interactively.
+	ErrTypeInteractionRequired = "Interaction Required"
 )
 
 type Error struct {
@@ -82,6 +85,18 @@ func NewTokenStoreError(message string) *Error {
 	return NewError(ErrTypeTokenStore, message, nil)
 }
 
+// NewInteractionRequiredError reports that action needed a browser or prompt
+// in non-interactive mode. The message is a JSON object so that scripts can
+// parse it; hint says how to do the step without interaction.
+func NewInteractionRequiredError(action, hint string) *Error {
+	message, _ := json.Marshal(map[string]string{
+		"error":  "interaction_required",
+		"action": action,
+		"hint":   hint,
+	})
+	return NewError(ErrTypeInteractionRequired, string(message), nil)
+}
+
 func IsErrorType(err error, errorType string) bool {
 	var e *Error
 	if ok := errors.As(err, &e); ok {
@@ -95,3 +110,9 @@ func IsIOError(err error) bool   { return IsErrorType(err, ErrTypeIO) }
 func IsAPIError(err error) bool  { return IsErrorType(err, ErrTypeAPI) }
 func IsJSONError(err error) bool { return IsErrorType(err, ErrTypeJSON) }
 func IsAuthError(err error) bool { return IsErrorType(err, ErrTypeAuth) }
+
+// IsInteractionRequiredError reports whether err is, or wraps at any depth, an
+// interaction-required error.
+func IsInteractionRequiredError(err error) bool {
+	return errors.Is(err, &Error{Type: ErrTypeInteractionRequired})
+}
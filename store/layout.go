@@ -0,0 +1,180 @@
+package store
+
+import (
+	"bytes"
+	"fmt"
+	"net/url"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/xdevplatform/xurl/errors"
+
+	"gopkg.in/yaml.v3"
+)
+
+// ─── Token layouts ──────────────────────────────────────────────────
+
+// Token store layouts. With TokenLayoutFile (the default) every token lives in
+// auth.yml. With TokenLayoutPerAccount each OAuth2 account's token has a file
+// of its own, tokens/<app>/<username>.yml next to auth.yml, which keeps the
+// apps, OAuth1 and bearer tokens, and settings.
+const (
+	TokenLayoutFile       = "file"
+	TokenLayoutPerAccount = "per-account"
+)
+
+// tokenDirName is the directory of per-account token files, next to auth.yml.
+const tokenDirName = "tokens"
+
+// SetTokenLayout switches the store to layout and saves it in that layout,
+// moving every OAuth2 token into its own file or back into auth.yml.
+func (s *TokenStore) SetTokenLayout(layout string) error {
+	switch layout {
+	case TokenLayoutFile, "":
+		s.TokenLayout = ""
+	case TokenLayoutPerAccount:
+		s.TokenLayout = TokenLayoutPerAccount
+	default:
+		return errors.NewTokenStoreError(fmt.Sprintf("unknown token layout %q (use %s or %s)", layout, TokenLayoutFile, TokenLayoutPerAccount))
+	}
+	return s.saveToFile()
+}
+
+// GetTokenLayout returns the store's layout, TokenLayoutFile by default.
+func (s *TokenStore) GetTokenLayout() string {
+	if s.TokenLayout == "" {
+		return TokenLayoutFile
+	}
+	return s.TokenLayout
+}
+
+// TokenDir returns the directory of per-account token files.
+func (s *TokenStore) TokenDir() string {
+	return filepath.Join(filepath.Dir(s.FilePath), tokenDirName)
+}
+
+// accountTokenPath returns the file of username's OAuth2 token for appName.
+func (s *TokenStore) accountTokenPath(appName, username string) string {
+	return filepath.Join(s.TokenDir(), escapeFileName(appName), escapeFileName(username)+".yml")
+}
+
+// escapeFileName makes name safe as a single path element: path separators
+// and other special characters are percent-encoded, and so is a leading dot,
+// so that "." and ".." cannot leave the token directory.
+func escapeFileName(name string) string {
+	escaped := url.PathEscape(name)
+	if strings.HasPrefix(escaped, ".") {
+		escaped = "%2E" + escaped[1:]
+	}
+	return escaped
+}
+
+// loadAccountTokens reads the per-account token files into the apps already
+// loaded from auth.yml. A file that cannot be read or parsed is skipped with a
+// warning, so one corrupt account does not take the others down with it; it
+// is left on disk until that account's token is saved again.
+func (s *TokenStore) loadAccountTokens() {
+	s.tokenFiles = make(map[string][]byte)
+	appDirs, err := os.ReadDir(s.TokenDir())
+	if err != nil {
+		return
+	}
+	for _, appDir := range appDirs {
+		appName, err := url.PathUnescape(appDir.Name())
+		app := s.Apps[appName]
+		if err != nil || !appDir.IsDir() || app == nil {
+			continue
+		}
+		files, err := os.ReadDir(filepath.Join(s.TokenDir(), appDir.Name()))
+		if err != nil {
+			fmt.Fprintf(os.Stderr, "Skipping unreadable token directory %s: %v\n", appDir.Name(), err)
+			continue
+		}
+		for _, file := range files {
+			name, isToken := strings.CutSuffix(file.Name(), ".yml")
+			if !isToken || file.IsDir() {
+				continue
+			}
+			path := filepath.Join(s.TokenDir(), appDir.Name(), file.Name())
+			username, err := url.PathUnescape(name)
+			if err != nil {
+				continue
+			}
+			data, err := os.ReadFile(path)
+			if err != nil {
+				fmt.Fprintf(os.Stderr, "Skipping unreadable token file %s: %v\n", path, err)
+				continue
+			}
+			var token Token
+			if err := yaml.Unmarshal(data, &token); err != nil || token.OAuth2 == nil {
+				fmt.Fprintf(os.Stderr, "Skipping corrupt token file %s\n", path)
+				continue
+			}
+			app.OAuth2Tokens[username] = token
+			s.tokenFiles[path] = data
+		}
+	}
+}
+
+// saveAccountTokens writes the per-account token files. Only files whose
+// content changed are written, and the files of accounts no longer in the
+// store are removed.
+func (s *TokenStore) saveAccountTokens() error {
+	if s.tokenFiles == nil {
+		s.tokenFiles = make(map[string][]byte)
+	}
+	current := make(map[string]bool)
+	for appName, app := range s.Apps {
+		for username, token := range app.OAuth2Tokens {
+			path := s.accountTokenPath(appName, username)
+			current[path] = true
+			data, err := yaml.Marshal(&token)
+			if err != nil {
+				return errors.NewJSONError(err)
+			}
+			if written, ok := s.tokenFiles[path]; ok && bytes.Equal(written, data) {
+				continue
+			}
+			if err := os.MkdirAll(filepath.Dir(path), 0700); err != nil {
+				return errors.NewIOError(err)
+			}
+			if err := os.WriteFile(path, data, 0600); err != nil {
+				return errors.NewIOError(err)
+			}
+			s.tokenFiles[path] = data
+		}
+	}
+	for path := range s.tokenFiles {
+		if !current[path] {
+			if err := os.Remove(path); err != nil && !os.IsNotExist(err) {
+				return errors.NewIOError(err)
+			}
+			delete(s.tokenFiles, path)
+		}
+	}
+	return nil
+}
+
+// removeAccountTokens deletes the per-account token files once their tokens
+// are back in auth.yml.
+func (s *TokenStore) removeAccountTokens() {
+	for path := range s.tokenFiles {
+		_ = os.Remove(path)
+		_ = os.Remove(filepath.Dir(path))
+	}
+	_ = os.Remove(s.TokenDir())
+	s.tokenFiles = nil
+}
+
+// withoutOAuth2Tokens returns copies of apps without their OAuth2 tokens, for
+// auth.yml in the per-account layout.
+func withoutOAuth2Tokens(apps map[string]*App) map[string]*App {
+	stripped := make(map[string]*App, len(apps))
+	for name, app := range apps {
+		copied := *app
+		copied.OAuth2Tokens = nil
+		stripped[name] = &copied
+	}
+	return stripped
+}
@@ -0,0 +1,103 @@
+package store
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// reloadTokenStore reads store's auth.yml (and token files) into a new store.
+func reloadTokenStore(t *testing.T, store *TokenStore) *TokenStore {
+	t.Helper()
+	data, err := os.ReadFile(store.FilePath)
+	require.NoError(t, err)
+	reloaded := &TokenStore{Apps: make(map[string]*App), FilePath: store.FilePath}
+	reloaded.loadFromData(data)
+	return reloaded
+}
+
+func TestPerAccountTokenLayout(t *testing.T) {
+	store, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+	require.NoError(t, store.SaveOAuth2Token("alice", "a1", "ar", 1))
+	require.NoError(t, store.SaveOAuth2Token("bob", "b1", "br", 1))
+	require.NoError(t, store.SaveBearerToken("bearer"))
+	assert.Equal(t, TokenLayoutFile, store.GetTokenLayout())
+
+	require.NoError(t, store.SetTokenLayout(TokenLayoutPerAccount))
+	alicePath := filepath.Join(tempDir, "tokens", "default", "alice.yml")
+	bobPath := filepath.Join(tempDir, "tokens", "default", "bob.yml")
+	assert.FileExists(t, alicePath)
+	assert.FileExists(t, bobPath)
+	info, err := os.Stat(alicePath)
+	require.NoError(t, err)
+	assert.Equal(t, os.FileMode(0600), info.Mode().Perm())
+
+	authFile, err := os.ReadFile(store.FilePath)
+	require.NoError(t, err)
+	assert.NotContains(t, string(authFile), "a1", "OAuth2 tokens leave auth.yml")
+	assert.Contains(t, string(authFile), "token_layout: per-account")
+	assert.Contains(t, string(authFile), "bearer", "other tokens stay in auth.yml")
+
+	reloaded := reloadTokenStore(t, store)
+	assert.Equal(t, TokenLayoutPerAccount, reloaded.GetTokenLayout())
+	assert.Equal(t, "a1", reloaded.GetOAuth2Token("alice").OAuth2.AccessToken)
+	assert.Equal(t, "b1", reloaded.GetOAuth2Token("bob").OAuth2.AccessToken)
+	assert.Equal(t, "bearer", reloaded.GetBearerToken().Bearer)
+
+	t.Run("saving one account leaves the other files alone", func(t *testing.T) {
+		// A marker in bob's file survives only if it is not rewritten.
+		bobData, err := os.ReadFile(bobPath)
+		require.NoError(t, err)
+		marked := append([]byte("# untouched\n"), bobData...)
+		require.NoError(t, os.WriteFile(bobPath, marked, 0600))
+
+		require.NoError(t, reloaded.SaveOAuth2Token("alice", "a2", "ar", 2))
+		got, err := os.ReadFile(bobPath)
+		require.NoError(t, err)
+		assert.Equal(t, marked, got)
+		assert.Equal(t, "a2", reloadTokenStore(t, store).GetOAuth2Token("alice").OAuth2.AccessToken)
+	})
+
+	t.Run("a corrupt file loses only its account", func(t *testing.T) {
+		require.NoError(t, os.WriteFile(bobPath, []byte("{not yaml"), 0600))
+		loaded := reloadTokenStore(t, store)
+		assert.Nil(t, loaded.GetOAuth2Token("bob"))
+		assert.Equal(t, "a2", loaded.GetOAuth2Token("alice").OAuth2.AccessToken)
+
+		require.NoError(t, loaded.SaveOAuth2Token("bob", "b2", "br", 2))
+		assert.Equal(t, "b2", reloadTokenStore(t, store).GetOAuth2Token("bob").OAuth2.AccessToken, "a new login replaces the corrupt file")
+	})
+
+	t.Run("clearing an account removes its file", func(t *testing.T) {
+		loaded := reloadTokenStore(t, store)
+		require.NoError(t, loaded.ClearOAuth2Token("bob"))
+		assert.NoFileExists(t, bobPath)
+		assert.FileExists(t, alicePath)
+	})
+
+	t.Run("switching back moves the tokens into auth.yml", func(t *testing.T) {
+		loaded := reloadTokenStore(t, store)
+		require.NoError(t, loaded.SetTokenLayout(TokenLayoutFile))
+		assert.NoDirExists(t, filepath.Join(tempDir, "tokens"))
+
+		authFile, err := os.ReadFile(store.FilePath)
+		require.NoError(t, err)
+		assert.NotContains(t, string(authFile), "token_layout")
+		assert.Equal(t, "a2", reloadTokenStore(t, store).GetOAuth2Token("alice").OAuth2.AccessToken)
+	})
+
+	assert.Error(t, store.SetTokenLayout("sqlite"))
+}
+
+func TestAccountTokenPathStaysInTokenDir(t *testing.T) {
+	store := &TokenStore{FilePath: filepath.Join("home", ".xurl", "auth.yml")}
+	dir := filepath.Join("home", ".xurl", "tokens")
+
+	assert.Equal(t, filepath.Join(dir, "default", "alice.yml"), store.accountTokenPath("default", "alice"))
+	assert.Equal(t, filepath.Join(dir, "%2E.", "a%2Fb.yml"), store.accountTokenPath("..", "a/b"))
+	assert.Equal(t, filepath.Join(dir, "my%20app", "%2Ehidden.yml"), store.accountTokenPath("my app", ".hidden"))
+}
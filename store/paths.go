@@ -8,8 +8,11 @@ import (
 
 // Names of the files inside the ~/.xurl directory.
 const (
-	authFileName = "auth.yml"
-	keysFileName = "keys.yml"
+	authFileName       = "auth.yml"
+	keysFileName       = "keys.yml"
+	rateLimitsFileName = "ratelimits.yml"
+	presetsFileName    = "presets.yml"
+	cacheDirName       = "cache"
 )
 
 // resolveStoreDir returns ~/.xurl as a directory, creating it if needed and
@@ -91,3 +94,22 @@ func AuthFilePath() string {
 func KeysFilePath() string {
 	return filepath.Join(resolveStoreDir(), keysFileName)
 }
+
+// RateLimitsFilePath returns the rate-limit state file inside the resolved
+// ~/.xurl directory.
+func RateLimitsFilePath() string {
+	return filepath.Join(resolveStoreDir(), rateLimitsFileName)
+}
+
+// PresetsFilePath returns the request preset file inside the resolved ~/.xurl
+// directory.
+func PresetsFilePath() string {
+	return filepath.Join(resolveStoreDir(), presetsFileName)
+}
+
+// CacheDirPath returns the default response cache directory (--cache) inside
+// the resolved ~/.xurl directory. It is created when the first response is
+// cached.
+func CacheDirPath() string {
+	return filepath.Join(resolveStoreDir(), cacheDirName)
+}
@@ -0,0 +1,138 @@
+package store
+
+import (
+	"fmt"
+	"os"
+	"slices"
+
+	"github.com/xdevplatform/xurl/errors"
+
+	"gopkg.in/yaml.v3"
+)
+
+// ─── Preset types ───────────────────────────────────────────────────
+
+// Preset is a saved request (xurl save), re-sent by name with xurl run. Data
+// and headers are kept as given, so a "@file" body or header file is read
+// when the preset runs, not when it is saved.
+type Preset struct {
+	Method   string   `yaml:"method"`
+	URL      string   `yaml:"url"`
+	Headers  []string `yaml:"headers,omitempty"`
+	Params   []string `yaml:"params,omitempty"`
+	Data     string   `yaml:"data,omitempty"`
+	Accept   string   `yaml:"accept,omitempty"`
+	Auth     string   `yaml:"auth,omitempty"`
+	Username string   `yaml:"username,omitempty"`
+}
+
+// PresetStore persists named presets in a YAML file (~/.xurl/presets.yml by
+// default), following the same conventions as the chat key store: 0600
+// permissions, and a corrupt file is an error rather than an empty store.
+type PresetStore struct {
+	Presets  map[string]*Preset `yaml:"presets"`
+	filePath string
+	// loadErr records a failed load of an existing file; it gates writes
+	// so that a corrupt file is never silently overwritten.
+	loadErr error
+}
+
+// NewPresetStore loads (or initializes) the preset store at
+// ~/.xurl/presets.yml.
+func NewPresetStore() *PresetStore {
+	return NewPresetStoreWithPath(PresetsFilePath())
+}
+
+// NewPresetStoreWithPath loads (or initializes) a preset store at the given path.
+func NewPresetStoreWithPath(path string) *PresetStore {
+	s := &PresetStore{
+		Presets:  make(map[string]*Preset),
+		filePath: path,
+	}
+	s.loadErr = s.loadFromFile()
+	return s
+}
+
+// LoadErr reports whether an existing preset file failed to load.
+func (s *PresetStore) LoadErr() error {
+	return s.loadErr
+}
+
+// GetPreset returns the preset called name, or nil if there is none.
+func (s *PresetStore) GetPreset(name string) *Preset {
+	return s.Presets[name]
+}
+
+// PresetNames returns the names of the stored presets, sorted.
+func (s *PresetStore) PresetNames() []string {
+	names := make([]string, 0, len(s.Presets))
+	for name := range s.Presets {
+		names = append(names, name)
+	}
+	slices.Sort(names)
+	return names
+}
+
+// SavePreset stores preset as name, replacing any preset of that name, and
+// persists the store.
+func (s *PresetStore) SavePreset(name string, preset *Preset) error {
+	if s.loadErr != nil {
+		return errors.NewTokenStoreError(fmt.Sprintf("refusing to overwrite %s, which exists but could not be loaded (fix or remove it first): %v", s.filePath, s.loadErr))
+	}
+	if name == "" {
+		return errors.NewTokenStoreError("cannot save a preset without a name")
+	}
+	s.Presets[name] = preset
+	return s.saveToFile()
+}
+
+// DeletePreset removes the preset called name and persists the store. It
+// reports whether there was one.
+func (s *PresetStore) DeletePreset(name string) (bool, error) {
+	if s.loadErr != nil {
+		return false, errors.NewTokenStoreError(fmt.Sprintf("refusing to overwrite %s, which exists but could not be loaded (fix or remove it first): %v", s.filePath, s.loadErr))
+	}
+	if _, ok := s.Presets[name]; !ok {
+		return false, nil
+	}
+	delete(s.Presets, name)
+	return true, s.saveToFile()
+}
+
+// ─── Persistence ────────────────────────────────────────────────────
+
+func (s *PresetStore) loadFromFile() error {
+	data, err := os.ReadFile(s.filePath)
+	if err != nil {
+		if os.IsNotExist(err) {
+			return nil
+		}
+		return errors.NewIOError(err)
+	}
+	var loaded PresetStore
+	if err := yaml.Unmarshal(data, &loaded); err != nil {
+		return errors.NewTokenStoreError(fmt.Sprintf("failed to parse preset store %s: %v", s.filePath, err))
+	}
+	if loaded.Presets != nil {
+		s.Presets = loaded.Presets
+	}
+	return nil
+}
+
+func (s *PresetStore) saveToFile() error {
+	data, err := yaml.Marshal(s)
+	if err != nil {
+		return errors.NewTokenStoreError(fmt.Sprintf("failed to serialize preset store: %v", err))
+	}
+	// Write-then-rename so a crash mid-write never leaves a truncated file.
+	// Presets may hold bodies and headers with secrets: owner only.
+	tmp := s.filePath + ".tmp"
+	if err := os.WriteFile(tmp, data, 0600); err != nil {
+		return errors.NewIOError(err)
+	}
+	if err := os.Rename(tmp, s.filePath); err != nil {
+		_ = os.Remove(tmp)
+		return errors.NewIOError(err)
+	}
+	return nil
+}
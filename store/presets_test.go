@@ -0,0 +1,49 @@
+package store
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestPresetStore(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "presets.yml")
+	s := NewPresetStoreWithPath(path)
+	require.NoError(t, s.LoadErr())
+	assert.Nil(t, s.GetPreset("me"))
+
+	me := &Preset{Method: "GET", URL: "/2/users/me", Params: []string{"user.fields=created_at"}, Auth: "oauth2", Username: "alice"}
+	post := &Preset{Method: "POST", URL: "/2/tweets", Headers: []string{"X-A: 1"}, Data: `{"text":"hi"}`}
+	require.NoError(t, s.SavePreset("me", me))
+	require.NoError(t, s.SavePreset("post", post))
+
+	info, err := os.Stat(path)
+	require.NoError(t, err)
+	assert.Equal(t, os.FileMode(0600), info.Mode().Perm())
+
+	reloaded := NewPresetStoreWithPath(path)
+	assert.Equal(t, me, reloaded.GetPreset("me"))
+	assert.Equal(t, post, reloaded.GetPreset("post"))
+	assert.Equal(t, []string{"me", "post"}, reloaded.PresetNames())
+
+	deleted, err := reloaded.DeletePreset("me")
+	require.NoError(t, err)
+	assert.True(t, deleted)
+	deleted, err = reloaded.DeletePreset("me")
+	require.NoError(t, err)
+	assert.False(t, deleted)
+	assert.Equal(t, []string{"post"}, NewPresetStoreWithPath(path).PresetNames())
+
+	t.Run("a corrupt file is not overwritten", func(t *testing.T) {
+		require.NoError(t, os.WriteFile(path, []byte("{not yaml"), 0600))
+		corrupt := NewPresetStoreWithPath(path)
+		require.Error(t, corrupt.LoadErr())
+		require.Error(t, corrupt.SavePreset("me", me))
+		data, err := os.ReadFile(path)
+		require.NoError(t, err)
+		assert.Equal(t, "{not yaml", string(data))
+	})
+}
@@ -0,0 +1,111 @@
+package store
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"time"
+
+	"github.com/xdevplatform/xurl/errors"
+
+	"gopkg.in/yaml.v3"
+)
+
+// ─── Rate-limit state ───────────────────────────────────────────────
+
+// RateLimit is the last-seen rate-limit state of one endpoint family, from
+// the x-rate-limit-limit, x-rate-limit-remaining, and x-rate-limit-reset
+// response headers.
+type RateLimit struct {
+	Limit     int   `yaml:"limit"`
+	Remaining int   `yaml:"remaining"`
+	Reset     int64 `yaml:"reset"`
+}
+
+// Exhausted reports whether the bucket was empty when last seen and its
+// window has not yet reset at now.
+func (r *RateLimit) Exhausted(now time.Time) bool {
+	return r != nil && r.Remaining <= 0 && time.Unix(r.Reset, 0).After(now)
+}
+
+// RateLimitStore persists rate-limit state across invocations in a YAML file
+// (~/.xurl/ratelimits.yml by default), keyed by host, method, and endpoint
+// family, e.g. "api.x.com GET /2/tweets/:id".
+type RateLimitStore struct {
+	Buckets  map[string]*RateLimit `yaml:"buckets"`
+	filePath string
+}
+
+// NewRateLimitStore loads (or initializes) the rate-limit store at
+// ~/.xurl/ratelimits.yml.
+func NewRateLimitStore() *RateLimitStore {
+	return NewRateLimitStoreWithPath(RateLimitsFilePath())
+}
+
+// NewRateLimitStoreWithPath loads (or initializes) a rate-limit store at the
+// given path. An unreadable or corrupt file is treated as empty: the state is
+// only a hint, and is rewritten by the next response.
+func NewRateLimitStoreWithPath(path string) *RateLimitStore {
+	s := &RateLimitStore{filePath: path}
+	s.load()
+	return s
+}
+
+// Get returns the stored state of bucket, or nil if there is none.
+func (s *RateLimitStore) Get(bucket string) *RateLimit {
+	return s.Buckets[bucket]
+}
+
+// Record stores limit for bucket and persists the store. The file is read
+// again first, so that buckets recorded meanwhile by another xurl process are
+// kept, and entries whose window reset long ago are dropped.
+func (s *RateLimitStore) Record(bucket string, limit RateLimit) error {
+	s.load()
+	cutoff := time.Now().Add(-24 * time.Hour).Unix()
+	for name, existing := range s.Buckets {
+		if existing.Reset < cutoff {
+			delete(s.Buckets, name)
+		}
+	}
+	s.Buckets[bucket] = &limit
+	return s.save()
+}
+
+// ─── Persistence ────────────────────────────────────────────────────
+
+func (s *RateLimitStore) load() {
+	s.Buckets = make(map[string]*RateLimit)
+	data, err := os.ReadFile(s.filePath)
+	if err != nil {
+		return
+	}
+	var loaded RateLimitStore
+	if yaml.Unmarshal(data, &loaded) == nil && loaded.Buckets != nil {
+		s.Buckets = loaded.Buckets
+	}
+}
+
+func (s *RateLimitStore) save() error {
+	data, err := yaml.Marshal(s)
+	if err != nil {
+		return errors.NewTokenStoreError(fmt.Sprintf("failed to serialize rate-limit store: %v", err))
+	}
+	// Write a temp file of our own and rename it into place, so concurrent
+	// xurl processes never interleave their writes.
+	tmp, err := os.CreateTemp(filepath.Dir(s.filePath), ".ratelimits-*.tmp")
+	if err != nil {
+		return errors.NewIOError(err)
+	}
+	defer os.Remove(tmp.Name())
+	if _, err := tmp.Write(data); err != nil {
+		tmp.Close()
+		return errors.NewIOError(err)
+	}
+	if err := tmp.Close(); err != nil {
+		return errors.NewIOError(err)
+	}
+	if err := os.Rename(tmp.Name(), s.filePath); err != nil {
+		return errors.NewIOError(err)
+	}
+	return nil
+}
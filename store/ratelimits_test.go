@@ -0,0 +1,37 @@
+package store
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestRateLimitStore(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "ratelimits.yml")
+	now := time.Now()
+	reset := now.Add(10 * time.Minute).Unix()
+
+	first := NewRateLimitStoreWithPath(path)
+	second := NewRateLimitStoreWithPath(path)
+	require.NoError(t, first.Record("api.x.com GET /2/tweets/:id", RateLimit{Limit: 300, Remaining: 0, Reset: reset}))
+	require.NoError(t, second.Record("api.x.com GET /2/users/me", RateLimit{Limit: 75, Remaining: 74, Reset: reset}))
+
+	info, err := os.Stat(path)
+	require.NoError(t, err)
+	assert.Equal(t, os.FileMode(0600), info.Mode().Perm())
+
+	loaded := NewRateLimitStoreWithPath(path)
+	assert.True(t, loaded.Get("api.x.com GET /2/tweets/:id").Exhausted(now), "a record by another process is kept")
+	assert.False(t, loaded.Get("api.x.com GET /2/users/me").Exhausted(now))
+	assert.False(t, loaded.Get("api.x.com GET /2/tweets/:id").Exhausted(time.Unix(reset, 0)), "the bucket refills at its reset")
+	assert.False(t, loaded.Get("api.x.com GET /2/unknown").Exhausted(now))
+
+	t.Run("a corrupt file counts as empty", func(t *testing.T) {
+		require.NoError(t, os.WriteFile(path, []byte("{not yaml"), 0600))
+		assert.Nil(t, NewRateLimitStoreWithPath(path).Get("api.x.com GET /2/tweets/:id"))
+	})
+}
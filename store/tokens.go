@@ -6,6 +6,7 @@ import (
 	"os"
 	"path/filepath"
 	"sort"
+	"time"
 
 	"github.com/xdevplatform/xurl/errors"
 
@@ -20,6 +21,11 @@ type OAuth1Token struct {
 	TokenSecret    string `yaml:"token_secret" json:"token_secret"`
 	ConsumerKey    string `yaml:"consumer_key" json:"consumer_key"`
 	ConsumerSecret string `yaml:"consumer_secret" json:"consumer_secret"`
+	// SignatureMethod is the OAuth1 signature method to sign with (empty means
+	// HMAC-SHA1).
+	SignatureMethod string `yaml:"signature_method,omitempty" json:"signature_method,omitempty"`
+	// Realm is sent as the Authorization header's realm parameter when set.
+	Realm string `yaml:"realm,omitempty" json:"realm,omitempty"`
 }
 
 // Represents OAuth2 authentication tokens
@@ -29,6 +35,18 @@ type OAuth2Token struct {
 	ExpirationTime uint64 `yaml:"expiration_time" json:"expiration_time"`
 }
 
+// ExpiresIn returns how long until the access token expires, negative once it
+// has.
+func (t *OAuth2Token) ExpiresIn(now time.Time) time.Duration {
+	return time.Unix(int64(t.ExpirationTime), 0).Sub(now)
+}
+
+// ExpiresWithin reports whether the access token has expired or will within d
+// of now.
+func (t *OAuth2Token) ExpiresWithin(d time.Duration, now time.Time) bool {
+	return t.ExpiresIn(now) <= d
+}
+
 // Represents the type of token
 type TokenType string
 
@@ -57,14 +75,39 @@ type App struct {
 	OAuth2Tokens map[string]Token `yaml:"oauth2_tokens,omitempty"`
 	OAuth1Token  *Token           `yaml:"oauth1_token,omitempty"`
 	BearerToken  *Token           `yaml:"bearer_token,omitempty"`
+	// PendingOAuth2Login holds an authorization started in one invocation and
+	// finished in another (`auth oauth2 --open-url-only`, then `--code`).
+	PendingOAuth2Login *PendingOAuth2Login `yaml:"pending_oauth2_login,omitempty"`
+}
+
+// PendingOAuth2Login is the PKCE/state material of an authorization-code login
+// whose URL has been handed out but whose code has not been exchanged yet.
+type PendingOAuth2Login struct {
+	State        string `yaml:"state"`
+	CodeVerifier string `yaml:"code_verifier"`
+	RedirectURI  string `yaml:"redirect_uri"`
+	Username     string `yaml:"username,omitempty"`
 }
 
 // ─── On-disk YAML structure ─────────────────────────────────────────
 
 // storeFile is the serialised YAML layout of ~/.xurl
 type storeFile struct {
-	Apps       map[string]*App `yaml:"apps"`
-	DefaultApp string          `yaml:"default_app"`
+	Apps             map[string]*App `yaml:"apps"`
+	DefaultApp       string          `yaml:"default_app"`
+	ClockSkewSeconds int64           `yaml:"clock_skew_seconds,omitempty"`
+	AuthRoutes       []AuthRoute     `yaml:"auth_routes,omitempty"`
+	TokenLayout      string          `yaml:"token_layout,omitempty"`
+}
+
+// AuthRoute is a user-configured entry of the route-aware auth table: requests
+// whose path matches Path, and whose method is Method when one is set, use the
+// first auth type in Auth (e.g. "app" or "oauth1,oauth2") that has stored
+// credentials, when --auth is not given.
+type AuthRoute struct {
+	Method string `yaml:"method,omitempty"`
+	Path   string `yaml:"path"`
+	Auth   string `yaml:"auth"`
 }
 
 // ─── Legacy JSON structure (for migration) ──────────────────────────
@@ -83,7 +126,23 @@ type legacyStore struct {
 type TokenStore struct {
 	Apps       map[string]*App `yaml:"apps"`
 	DefaultApp string          `yaml:"default_app"`
-	FilePath   string          `yaml:"-"`
+	// ClockSkewSeconds is the measured offset of the API server's clock from
+	// the local one, applied to OAuth1 timestamps.
+	ClockSkewSeconds int64 `yaml:"clock_skew_seconds,omitempty"`
+	// AuthRoutes are consulted before the built-in route table when choosing
+	// an auth type automatically. They are edited by hand in the file; xurl
+	// only carries them through saves.
+	AuthRoutes []AuthRoute `yaml:"auth_routes,omitempty"`
+	// TokenLayout is TokenLayoutPerAccount when OAuth2 tokens are kept in a
+	// file per account rather than in auth.yml (empty = TokenLayoutFile).
+	TokenLayout string `yaml:"-"`
+	FilePath    string `yaml:"-"`
+	// tokenFiles holds the content of each per-account token file as last
+	// read or written, so that a save only rewrites the files that changed.
+	tokenFiles map[string][]byte
+	// InMemory keeps every change (new tokens, refreshes, clock skew) in this
+	// process only: nothing is written to FilePath.
+	InMemory bool `yaml:"-"`
 }
 
 func resolveHomeDir() string {
@@ -166,12 +225,18 @@ func (s *TokenStore) loadFromData(data []byte) {
 	if err := yaml.Unmarshal(data, &sf); err == nil && len(sf.Apps) > 0 {
 		s.Apps = sf.Apps
 		s.DefaultApp = sf.DefaultApp
+		s.ClockSkewSeconds = sf.ClockSkewSeconds
+		s.AuthRoutes = sf.AuthRoutes
+		s.TokenLayout = sf.TokenLayout
 		// Ensure all apps have initialised maps
 		for _, app := range s.Apps {
 			if app.OAuth2Tokens == nil {
 				app.OAuth2Tokens = make(map[string]Token)
 			}
 		}
+		if s.TokenLayout == TokenLayoutPerAccount {
+			s.loadAccountTokens()
+		}
 		return
 	}
 
@@ -467,6 +532,43 @@ func (s *TokenStore) SaveOAuth1TokensForApp(appName, accessToken, tokenSecret, c
 	return s.saveToFile()
 }
 
+// RestoreBearerTokenForApp puts back a bearer token previously read with
+// GetBearerTokenForApp; nil clears the app's bearer token.
+func (s *TokenStore) RestoreBearerTokenForApp(appName string, token *Token) error {
+	app := s.ResolveApp(appName)
+	app.BearerToken = token
+	return s.saveToFile()
+}
+
+// RestoreOAuth1TokensForApp puts back an OAuth1 token previously read with
+// GetOAuth1TokensForApp; nil clears the app's OAuth1 tokens.
+func (s *TokenStore) RestoreOAuth1TokensForApp(appName string, token *Token) error {
+	app := s.ResolveApp(appName)
+	app.OAuth1Token = token
+	return s.saveToFile()
+}
+
+// SetOAuth1SignatureMethodForApp sets the signature method stored with the
+// named app's OAuth1 tokens.
+func (s *TokenStore) SetOAuth1SignatureMethodForApp(appName, signatureMethod string) error {
+	app := s.ResolveApp(appName)
+	if app.OAuth1Token == nil || app.OAuth1Token.OAuth1 == nil {
+		return errors.NewTokenStoreError("no OAuth1 tokens stored")
+	}
+	app.OAuth1Token.OAuth1.SignatureMethod = signatureMethod
+	return s.saveToFile()
+}
+
+// SetOAuth1RealmForApp sets the realm stored with the named app's OAuth1 tokens.
+func (s *TokenStore) SetOAuth1RealmForApp(appName, realm string) error {
+	app := s.ResolveApp(appName)
+	if app.OAuth1Token == nil || app.OAuth1Token.OAuth1 == nil {
+		return errors.NewTokenStoreError("no OAuth1 tokens stored")
+	}
+	app.OAuth1Token.OAuth1.Realm = realm
+	return s.saveToFile()
+}
+
 // GetOAuth2Token gets an OAuth2 token for a username from the resolved app.
 func (s *TokenStore) GetOAuth2Token(username string) *Token {
 	return s.GetOAuth2TokenForApp("", username)
@@ -518,6 +620,27 @@ func (s *TokenStore) GetFirstOAuth2TokenForApp(appName string) *Token {
 	return token
 }
 
+// SetPendingOAuth2LoginForApp stores the named app's pending OAuth2 login; nil
+// clears it.
+func (s *TokenStore) SetPendingOAuth2LoginForApp(appName string, pending *PendingOAuth2Login) error {
+	app := s.ResolveApp(appName)
+	app.PendingOAuth2Login = pending
+	return s.saveToFile()
+}
+
+// GetPendingOAuth2LoginForApp gets the named app's pending OAuth2 login, if any.
+func (s *TokenStore) GetPendingOAuth2LoginForApp(appName string) *PendingOAuth2Login {
+	app := s.ResolveApp(appName)
+	return app.PendingOAuth2Login
+}
+
+// SetClockSkew stores the measured offset of the server clock from the local
+// clock, in seconds.
+func (s *TokenStore) SetClockSkew(seconds int64) error {
+	s.ClockSkewSeconds = seconds
+	return s.saveToFile()
+}
+
 // GetOAuth1Tokens gets OAuth1 tokens from the resolved app.
 func (s *TokenStore) GetOAuth1Tokens() *Token {
 	return s.GetOAuth1TokensForApp("")
@@ -618,13 +741,63 @@ func (s *TokenStore) HasBearerToken() bool {
 	return app != nil && app.BearerToken != nil
 }
 
+// Secrets returns every secret value held in the store, across all apps:
+// client secrets, tokens, and token secrets. Error output is scrubbed of them.
+func (s *TokenStore) Secrets() []string {
+	var secrets []string
+	add := func(values ...string) {
+		for _, value := range values {
+			if value != "" {
+				secrets = append(secrets, value)
+			}
+		}
+	}
+	addToken := func(token *Token) {
+		if token == nil {
+			return
+		}
+		add(token.Bearer)
+		if token.OAuth2 != nil {
+			add(token.OAuth2.AccessToken, token.OAuth2.RefreshToken)
+		}
+		if token.OAuth1 != nil {
+			add(token.OAuth1.AccessToken, token.OAuth1.TokenSecret, token.OAuth1.ConsumerSecret)
+		}
+	}
+	for _, app := range s.Apps {
+		add(app.ClientSecret)
+		addToken(app.BearerToken)
+		addToken(app.OAuth1Token)
+		for _, token := range app.OAuth2Tokens {
+			addToken(&token)
+		}
+		if app.PendingOAuth2Login != nil {
+			add(app.PendingOAuth2Login.CodeVerifier)
+		}
+	}
+	return secrets
+}
+
 // ─── Persistence ────────────────────────────────────────────────────
 
-// Saves the token store to ~/.xurl in YAML format.
+// Saves the token store to ~/.xurl in YAML format. In the per-account layout
+// the OAuth2 tokens go to their own files instead (see saveAccountTokens).
 func (s *TokenStore) saveToFile() error {
+	if s.InMemory {
+		return nil
+	}
 	sf := storeFile{
-		Apps:       s.Apps,
-		DefaultApp: s.DefaultApp,
+		Apps:             s.Apps,
+		DefaultApp:       s.DefaultApp,
+		ClockSkewSeconds: s.ClockSkewSeconds,
+		AuthRoutes:       s.AuthRoutes,
+		TokenLayout:      s.TokenLayout,
+	}
+	if s.TokenLayout == TokenLayoutPerAccount {
+		if err := s.saveAccountTokens(); err != nil {
+			return err
+		}
+		sf.Apps = withoutOAuth2Tokens(s.Apps)
 	}
 	data, err := yaml.Marshal(&sf)
 	if err != nil {
@@ -636,5 +809,9 @@ func (s *TokenStore) saveToFile() error {
 		return errors.NewIOError(err)
 	}
 
+	// Tokens moved back into auth.yml no longer need their files.
+	if s.TokenLayout != TokenLayoutPerAccount && s.tokenFiles != nil {
+		s.removeAccountTokens()
+	}
 	return nil
 }
@@ -5,6 +5,7 @@ import (
 	"os"
 	"path/filepath"
 	"testing"
+	"time"
 
 	"github.com/stretchr/testify/assert"
 	"github.com/stretchr/testify/require"
@@ -654,3 +655,74 @@ configuration:
 		assert.Error(t, err, "Expected error when importing from malformed .twurlrc")
 	})
 }
+
+func TestOAuth2TokenExpiry(t *testing.T) {
+	now := time.Unix(1_700_000_000, 0)
+	token := &OAuth2Token{ExpirationTime: 1_700_000_180}
+
+	assert.Equal(t, 3*time.Minute, token.ExpiresIn(now))
+	assert.True(t, token.ExpiresWithin(5*time.Minute, now))
+	assert.True(t, token.ExpiresWithin(3*time.Minute, now))
+	assert.False(t, token.ExpiresWithin(time.Minute, now))
+
+	expired := &OAuth2Token{ExpirationTime: 1_699_999_000}
+	assert.Negative(t, expired.ExpiresIn(now))
+	assert.True(t, expired.ExpiresWithin(0, now))
+}
+
+func TestAuthRoutesSurviveSave(t *testing.T) {
+	store, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+
+	store.loadFromData([]byte(`apps:
+  default:
+    client_id: id
+    client_secret: secret
+default_app: default
+auth_routes:
+  - path: /2/tweets/search/**
+    auth: oauth1,app
+  - method: POST
+    path: /2/tweets
+    auth: oauth1
+`))
+	require.Equal(t, []AuthRoute{{Path: "/2/tweets/search/**", Auth: "oauth1,app"}, {Method: "POST", Path: "/2/tweets", Auth: "oauth1"}}, store.AuthRoutes)
+
+	require.NoError(t, store.SaveBearerToken("bearer"))
+	data, err := os.ReadFile(store.FilePath)
+	require.NoError(t, err)
+
+	reloaded := &TokenStore{Apps: make(map[string]*App)}
+	reloaded.loadFromData(data)
+	assert.Equal(t, store.AuthRoutes, reloaded.AuthRoutes)
+}
+
+func TestSecrets(t *testing.T) {
+	store, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+
+	require.NoError(t, store.AddApp("other", "other-id", "other-client-secret"))
+	require.NoError(t, store.SaveBearerToken("bearer-token"))
+	require.NoError(t, store.SaveOAuth2TokenForApp("other", "alice", "alice-access", "alice-refresh", 0))
+	require.NoError(t, store.SaveOAuth1Tokens("oauth1-access", "oauth1-token-secret", "consumer-key", "consumer-secret"))
+
+	secrets := store.Secrets()
+	for _, secret := range []string{"other-client-secret", "bearer-token", "alice-access", "alice-refresh", "oauth1-access", "oauth1-token-secret", "consumer-secret"} {
+		assert.Contains(t, secrets, secret)
+	}
+	assert.NotContains(t, secrets, "other-id", "client IDs are not secret")
+	assert.NotContains(t, secrets, "consumer-key", "consumer keys are not secret")
+	assert.NotContains(t, secrets, "")
+}
+
+func TestInMemoryStore(t *testing.T) {
+	store, tempDir := createTempTokenStore(t)
+	defer os.RemoveAll(tempDir)
+	store.InMemory = true
+
+	require.NoError(t, store.SaveOAuth2Token("alice", "access", "refresh", 0))
+	require.NoError(t, store.SetClockSkew(5))
+
+	assert.NoFileExists(t, store.FilePath)
+	assert.Equal(t, "access", store.GetOAuth2Token("alice").OAuth2.AccessToken)
+}
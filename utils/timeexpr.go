@@ -0,0 +1,60 @@
+package utils
+
+import (
+	"fmt"
+	"regexp"
+	"strconv"
+	"strings"
+	"time"
+)
+
+var relativeTimePattern = regexp.MustCompile(`^(\d+)\s*([a-z]+?)s?(\s+ago)?$`)
+
+var relativeTimeUnits = map[string]time.Duration{
+	"s": time.Second, "sec": time.Second, "second": time.Second,
+	"m": time.Minute, "min": time.Minute, "minute": time.Minute,
+	"h": time.Hour, "hr": time.Hour, "hour": time.Hour,
+	"d": 24 * time.Hour, "day": 24 * time.Hour,
+	"w": 7 * 24 * time.Hour, "wk": 7 * 24 * time.Hour, "week": 7 * 24 * time.Hour,
+}
+
+// ParseTimeExpression resolves a human-friendly time relative to now:
+// "now", "today", "yesterday", "<n> <unit> ago" or "<n><unit>" (seconds through
+// weeks, e.g. "2 days ago", "90m", "1w"), an RFC 3339 timestamp, or a
+// YYYY-MM-DD date (midnight in now's location).
+func ParseTimeExpression(expr string, now time.Time) (time.Time, error) {
+	s := strings.ToLower(strings.TrimSpace(expr))
+
+	switch s {
+	case "now":
+		return now, nil
+	case "today":
+		return startOfDay(now), nil
+	case "yesterday":
+		return startOfDay(now).AddDate(0, 0, -1), nil
+	}
+
+	if t, err := time.Parse(time.RFC3339, strings.TrimSpace(expr)); err == nil {
+		return t, nil
+	}
+	if t, err := time.ParseInLocation("2006-01-02", s, now.Location()); err == nil {
+		return t, nil
+	}
+
+	if m := relativeTimePattern.FindStringSubmatch(s); m != nil {
+		unit, ok := relativeTimeUnits[m[2]]
+		if ok {
+			n, err := strconv.Atoi(m[1])
+			if err == nil {
+				return now.Add(-time.Duration(n) * unit), nil
+			}
+		}
+	}
+
+	return time.Time{}, fmt.Errorf("cannot parse time %q (try \"now\", \"yesterday\", \"2 days ago\", \"6h\", 2024-05-01 or an RFC 3339 timestamp)", expr)
+}
+
+func startOfDay(t time.Time) time.Time {
+	y, m, d := t.Date()
+	return time.Date(y, m, d, 0, 0, 0, 0, t.Location())
+}
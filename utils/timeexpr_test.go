@@ -0,0 +1,44 @@
+package utils
+
+import (
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseTimeExpression(t *testing.T) {
+	now := time.Date(2024, 5, 10, 15, 30, 0, 0, time.UTC)
+
+	tests := []struct {
+		expr     string
+		expected time.Time
+	}{
+		{"now", now},
+		{"NOW", now},
+		{"today", time.Date(2024, 5, 10, 0, 0, 0, 0, time.UTC)},
+		{"yesterday", time.Date(2024, 5, 9, 0, 0, 0, 0, time.UTC)},
+		{"2 days ago", now.Add(-48 * time.Hour)},
+		{"1 day ago", now.Add(-24 * time.Hour)},
+		{"3 hours ago", now.Add(-3 * time.Hour)},
+		{"90m", now.Add(-90 * time.Minute)},
+		{"6h", now.Add(-6 * time.Hour)},
+		{"1w", now.Add(-7 * 24 * time.Hour)},
+		{"45 seconds ago", now.Add(-45 * time.Second)},
+		{"2024-05-01", time.Date(2024, 5, 1, 0, 0, 0, 0, time.UTC)},
+		{"2024-05-01T12:00:00Z", time.Date(2024, 5, 1, 12, 0, 0, 0, time.UTC)},
+	}
+	for _, tc := range tests {
+		t.Run(tc.expr, func(t *testing.T) {
+			got, err := ParseTimeExpression(tc.expr, now)
+			require.NoError(t, err)
+			assert.True(t, tc.expected.Equal(got), "got %s, want %s", got, tc.expected)
+		})
+	}
+
+	for _, expr := range []string{"", "soon", "2 fortnights ago", "ago", "2024-13-01"} {
+		_, err := ParseTimeExpression(expr, now)
+		assert.Error(t, err, expr)
+	}
+}